@@ -0,0 +1,245 @@
+//! # Keymap diagnostics
+//!
+//! ## Overview
+//!
+//! Mappings added to a [ModalMachine] can interfere with each other without any error: mapping the
+//! same keys twice replaces the first [Step], mapping a prefix of an existing sequence to a [Step]
+//! that runs right away cuts off everything that used to follow it, and an
+//! [Any](EdgeEvent::Any) edge will always be taken before a [Fallthrough](EdgeEvent::Fallthrough)
+//! edge out of the same node.
+//!
+//! [ModalMachine::validate] looks for these problems after the keymap has been built, and
+//! [ModalMachine::debug_assert_valid] can be used to catch them in debug builds and tests.
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{self, Debug, Display};
+use std::sync::Arc;
+
+use super::{
+    EdgeEvent,
+    EdgePathPart,
+    EdgeRepeat,
+    InputKey,
+    InputKeyClass,
+    ModalMachine,
+    NodeAction,
+    Step,
+};
+
+/// A problem found in the mappings of a [ModalMachine].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MappingDiagnostic<Key, M, C: InputKeyClass<Key>> {
+    /// The key sequence was mapped more than once in this mode, and only the most recent
+    /// [Step] is kept.
+    Overwritten {
+        /// The mode the sequence was mapped in.
+        mode: M,
+        /// The key sequence.
+        path: Vec<EdgePathPart<Key, C>>,
+    },
+
+    /// The key sequence no longer leads to its [Step], because a later mapping or prefix
+    /// replaced one of the edges along its path.
+    Unreachable {
+        /// The mode the sequence was mapped in.
+        mode: M,
+        /// The key sequence.
+        path: Vec<EdgePathPart<Key, C>>,
+    },
+
+    /// The key sequence starts with a shorter sequence that is mapped to a [Step] that doesn't
+    /// wait for more input, so it's likely to never be reached.
+    PrefixConflict {
+        /// The mode the sequences were mapped in.
+        mode: M,
+        /// The shorter key sequence.
+        prefix: Vec<EdgePathPart<Key, C>>,
+        /// The longer key sequence.
+        path: Vec<EdgePathPart<Key, C>>,
+    },
+
+    /// An edge can never be followed, because another edge out of the same node always takes
+    /// priority over it.
+    Shadowed {
+        /// The mode the node belongs to.
+        mode: M,
+        /// A key sequence that reaches the node.
+        path: Vec<EdgePathPart<Key, C>>,
+        /// The edge that never gets followed.
+        event: EdgeEvent<Key, C>,
+        /// The edge that gets followed instead.
+        by: EdgeEvent<Key, C>,
+    },
+}
+
+fn fmt_event<Key, C>(f: &mut fmt::Formatter<'_>, ev: &EdgeEvent<Key, C>) -> fmt::Result
+where
+    Key: InputKey,
+    C: InputKeyClass<Key>,
+{
+    match ev {
+        EdgeEvent::Key(key) => write!(f, "{}", key.name().unwrap_or_else(|| "?".into())),
+        EdgeEvent::Class(class) => write!(f, "{}", class.name().unwrap_or_else(|| "?".into())),
+        EdgeEvent::Any => write!(f, "{{any}}"),
+        EdgeEvent::Fallthrough => write!(f, "{{fallthrough}}"),
+    }
+}
+
+fn fmt_path<Key, C>(f: &mut fmt::Formatter<'_>, path: &[EdgePathPart<Key, C>]) -> fmt::Result
+where
+    Key: InputKey,
+    C: InputKeyClass<Key>,
+{
+    write!(f, "\"")?;
+
+    for (rep, ev) in path {
+        fmt_event(f, ev)?;
+
+        match rep {
+            EdgeRepeat::Once => {},
+            EdgeRepeat::Min(n) => write!(f, ">={n}")?,
+            EdgeRepeat::Max(n) => write!(f, "<={n}")?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+impl<Key, M, C> Display for MappingDiagnostic<Key, M, C>
+where
+    Key: InputKey,
+    M: Debug,
+    C: InputKeyClass<Key>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingDiagnostic::Overwritten { mode, path } => {
+                write!(f, "{mode:?}: ")?;
+                fmt_path(f, path)?;
+                write!(f, " is mapped more than once")
+            },
+            MappingDiagnostic::Unreachable { mode, path } => {
+                write!(f, "{mode:?}: ")?;
+                fmt_path(f, path)?;
+                write!(f, " can no longer be reached")
+            },
+            MappingDiagnostic::PrefixConflict { mode, prefix, path } => {
+                write!(f, "{mode:?}: ")?;
+                fmt_path(f, path)?;
+                write!(f, " is cut off by the mapping for ")?;
+                fmt_path(f, prefix)
+            },
+            MappingDiagnostic::Shadowed { mode, path, event, by } => {
+                write!(f, "{mode:?}: ")?;
+                fmt_event(f, event)?;
+                write!(f, " after ")?;
+                fmt_path(f, path)?;
+                write!(f, " is always shadowed by ")?;
+                fmt_event(f, by)
+            },
+        }
+    }
+}
+
+impl<Key, S> ModalMachine<Key, S>
+where
+    Key: InputKey,
+    S: Step<Key>,
+{
+    /// Check the mappings in each mode for sequences that are overwritten, unreachable, cut off
+    /// by a shorter sequence, or shadowed by a higher priority edge.
+    ///
+    /// A [Step] is assumed to wait for more input if [Step::is_unmapped] or [Step::can_wait] is
+    /// true, or if it falls through to another mode. Prefixes mapped to any other [Step] are
+    /// reported as conflicts.
+    pub fn validate(&self) -> Vec<MappingDiagnostic<Key, S::M, S::Class>> {
+        let im = &self.im;
+        let graph = &im.graph;
+        let mut diags = vec![];
+
+        for (mode, path) in im.remapped.iter() {
+            diags.push(MappingDiagnostic::Overwritten { mode: *mode, path: path.clone() });
+        }
+
+        // Visit every node that can be reached from the top of a mode, and remember the first
+        // sequence of keys that gets there.
+        let mut steps = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<_> = graph.modes.values().map(|id| (*id, vec![])).collect();
+
+        while let Some((id, path)) = queue.pop_front() {
+            if !visited.insert(id) {
+                continue;
+            }
+
+            if let NodeAction::Step(step) = &graph.get_node(id).action {
+                steps.insert(Arc::as_ptr(step));
+            }
+
+            let edges = match graph.edges.get(&id) {
+                Some(edges) => edges,
+                None => continue,
+            };
+
+            if edges.contains_key(&EdgeEvent::Fallthrough) && edges.contains_key(&EdgeEvent::Any) {
+                diags.push(MappingDiagnostic::Shadowed {
+                    mode: graph.get_node(id).mode,
+                    path: path.clone(),
+                    event: EdgeEvent::Fallthrough,
+                    by: EdgeEvent::Any,
+                });
+            }
+
+            for (ev, edge) in edges.iter() {
+                if let EdgeEvent::Fallthrough = ev {
+                    queue.push_back((edge.end, path.clone()));
+                } else {
+                    let mut next = path.clone();
+                    next.push((EdgeRepeat::Once, ev.clone()));
+                    queue.push_back((edge.end, next));
+                }
+            }
+        }
+
+        for (mode, path, step) in im.mappings.iter() {
+            if !steps.contains(&Arc::as_ptr(step)) {
+                diags.push(MappingDiagnostic::Unreachable { mode: *mode, path: path.clone() });
+            }
+
+            for n in 1..path.len() {
+                let key = (*mode, path[..n].to_vec());
+                let prefix = match im.mapping_idx.get(&key) {
+                    Some(idx) => &im.mappings[*idx].2,
+                    None => continue,
+                };
+
+                if prefix.is_unmapped() || prefix.can_wait() || prefix.fallthrough().is_some() {
+                    continue;
+                }
+
+                diags.push(MappingDiagnostic::PrefixConflict {
+                    mode: *mode,
+                    prefix: key.1,
+                    path: path.clone(),
+                });
+            }
+        }
+
+        return diags;
+    }
+
+    /// Panic with a list of the problems found by [ModalMachine::validate], if there are any.
+    ///
+    /// This does nothing in release builds.
+    #[track_caller]
+    pub fn debug_assert_valid(&self) {
+        if cfg!(debug_assertions) {
+            let diags = self.validate();
+
+            if !diags.is_empty() {
+                let msgs: Vec<String> = diags.iter().map(ToString::to_string).collect();
+
+                panic!("invalid keybindings:\n{}", msgs.join("\n"));
+            }
+        }
+    }
+}
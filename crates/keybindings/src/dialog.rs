@@ -7,6 +7,7 @@
 //! show them a message and fetch the `y` or `Y` keypresses.
 use std::borrow::Cow;
 use std::fmt::Debug;
+use std::sync::{Mutex, PoisonError};
 
 use textwrap::wrap;
 use unicode_segmentation::UnicodeSegmentation;
@@ -283,6 +284,34 @@ where
     }
 }
 
+impl<A, D> Dialog<A> for Box<D>
+where
+    D: Dialog<A> + ?Sized,
+{
+    fn render(&mut self, max_rows: usize, max_cols: usize) -> Vec<Cow<'_, str>> {
+        self.as_mut().render(max_rows, max_cols)
+    }
+
+    fn input(&mut self, c: char) -> Option<Vec<A>> {
+        self.as_mut().input(c)
+    }
+}
+
+/// Wrapping a dialog in a [Mutex] makes it [Sync], so that a dialog that is only [Send] can be
+/// passed to [BindingMachine::run_dialog](crate::BindingMachine::run_dialog).
+impl<A, D> Dialog<A> for Mutex<D>
+where
+    D: Dialog<A> + ?Sized,
+{
+    fn render(&mut self, max_rows: usize, max_cols: usize) -> Vec<Cow<'_, str>> {
+        self.get_mut().unwrap_or_else(PoisonError::into_inner).render(max_rows, max_cols)
+    }
+
+    fn input(&mut self, c: char) -> Option<Vec<A>> {
+        self.get_mut().unwrap_or_else(PoisonError::into_inner).input(c)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -394,4 +423,15 @@ mod tests {
         let mut dialog = MultiChoice::new(choices.clone());
         assert_eq!(dialog.input('5'), Some(vec![3]));
     }
+
+    #[test]
+    fn test_mutex_dialog() {
+        let dialog: Box<dyn Dialog<usize>> = Box::new(PromptYesNo::new("Continue?", vec![1]));
+        let mut dialog = Mutex::new(dialog);
+
+        let lines = dialog.render(1, 100);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].as_ref(), "Continue? (y/N)");
+        assert_eq!(dialog.input('y'), Some(vec![1]));
+    }
 }
@@ -168,13 +168,16 @@
 #![allow(clippy::needless_return)]
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::type_complexity)]
+use std::any::{Any, TypeId};
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
+pub mod diagnostics;
 pub mod dialog;
 
 use self::dialog::Dialog;
@@ -192,6 +195,10 @@ pub trait InputState {
 
     /// Copy any overriding values into an `Output` object.
     fn merge(original: Self::Output, overrides: &Self::Output) -> Self::Output;
+
+    /// Update whether the keys being processed come from an executing macro.
+    #[allow(unused_variables)]
+    fn set_macro_executing(&mut self, executing: bool) {}
 }
 
 /// Trait for keys that can be used with [ModalMachine].
@@ -212,6 +219,11 @@ pub trait InputKey: Clone + Hash + Eq {
 
     /// Return this key's representation as a single, printable codepoint, if it exists.
     fn get_char(&self) -> Option<char>;
+
+    /// Return a printable name for this key, written the way it would be in a key sequence.
+    fn name(&self) -> Option<String> {
+        None
+    }
 }
 
 impl InputKey for char {
@@ -228,6 +240,10 @@ impl InputKey for char {
     fn get_char(&self) -> Option<char> {
         Some(*self)
     }
+
+    fn name(&self) -> Option<String> {
+        Some(self.to_string())
+    }
 }
 
 /// Trait for context objects used within [ModalMachine].
@@ -299,6 +315,11 @@ pub trait InputKeyClass<T>: Clone + Hash + Eq {
     /// The order returned here is the order of priority for which [EdgeEvent::Class] edge to
     /// follow.
     fn memberships(ke: &T) -> Vec<Self>;
+
+    /// Return a printable name for this class, written the way it would be in a key sequence.
+    fn name(&self) -> Option<String> {
+        None
+    }
 }
 
 /// Different ways to include an action in the current action sequence.
@@ -359,6 +380,21 @@ pub trait Step<Key>: Clone {
     /// doing so, otherwise it waits for the next key to indicate the register for starting macro
     /// recording).
     fn step(&self, ctx: &mut Self::State) -> (Vec<Self::A>, Option<Self::M>);
+
+    /// Indicates whether [Step::step] may return zero actions and no mode to go to, so that
+    /// longer key sequences that start with this Step can still be reached.
+    ///
+    /// This is only used by [ModalMachine::validate] to decide which prefixes conflict with
+    /// longer mappings.
+    fn can_wait(&self) -> bool {
+        false
+    }
+
+    /// A short, human-readable description of what this Step does, for use in places like
+    /// pending-key hints.
+    fn describe(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// A collection of bindings that can be added to a [ModalMachine].
@@ -402,6 +438,138 @@ where
 
     /// Start an interactive user dialog.
     fn run_dialog(&mut self, dialog: Box<dyn Dialog<A> + Send + Sync>);
+
+    /// List the inputs that can follow a partially typed key sequence, and what each of them
+    /// does. This is empty when no key sequence is pending.
+    fn pending_hints(&self) -> Vec<KeyHint> {
+        vec![]
+    }
+
+    /// Update what currently has focus, so that mappings added for those scopes get used.
+    ///
+    /// Earlier scopes take priority over later ones, and all of them take priority over mappings
+    /// that apply everywhere. Any partially typed key sequence is discarded when the focus
+    /// changes.
+    #[allow(unused_variables)]
+    fn set_focus(&mut self, focus: &[MappingScope]) {}
+
+    /// Update whether the keys passed to [BindingMachine::input_key] are being replayed from a
+    /// macro instead of typed by the user.
+    #[allow(unused_variables)]
+    fn set_macro_executing(&mut self, executing: bool) {}
+}
+
+/// Identifies a part of an application that can have its own mappings, which only apply while it
+/// has focus.
+///
+/// Scopes can be created from any identifier type that implements [Eq] and [Hash], so that
+/// applications can use their own window and content identifiers. Identifiers of different types
+/// never refer to the same scope.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum MappingScope {
+    /// Mappings that apply while some content has focus, regardless of which window shows it.
+    Content(ScopeId),
+
+    /// Mappings that apply while a specific window has focus.
+    Window(ScopeId),
+}
+
+impl MappingScope {
+    /// Create a scope for the content identified by `id`.
+    pub fn content<T>(id: &T) -> Self
+    where
+        T: Clone + Debug + Eq + Hash + Send + Sync + 'static,
+    {
+        MappingScope::Content(ScopeId::new(id))
+    }
+
+    /// Create a scope for the window identified by `id`.
+    pub fn window<T>(id: &T) -> Self
+    where
+        T: Clone + Debug + Eq + Hash + Send + Sync + 'static,
+    {
+        MappingScope::Window(ScopeId::new(id))
+    }
+}
+
+/// An application identifier used within a [MappingScope].
+pub struct ScopeId(Box<dyn ScopeKey>);
+
+impl ScopeId {
+    fn new<T>(id: &T) -> Self
+    where
+        T: Clone + Debug + Eq + Hash + Send + Sync + 'static,
+    {
+        ScopeId(Box::new(id.clone()))
+    }
+}
+
+impl Clone for ScopeId {
+    fn clone(&self) -> Self {
+        ScopeId(self.0.clone_key())
+    }
+}
+
+impl Debug for ScopeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq for ScopeId {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_key(other.0.as_ref())
+    }
+}
+
+impl Eq for ScopeId {}
+
+impl Hash for ScopeId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash_key(state)
+    }
+}
+
+/// Object-safe versions of the traits needed to compare and hash a [ScopeId].
+trait ScopeKey: Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn clone_key(&self) -> Box<dyn ScopeKey>;
+    fn eq_key(&self, other: &dyn ScopeKey) -> bool;
+    fn hash_key(&self, state: &mut dyn Hasher);
+}
+
+impl<T> ScopeKey for T
+where
+    T: Clone + Debug + Eq + Hash + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn clone_key(&self) -> Box<dyn ScopeKey> {
+        Box::new(self.clone())
+    }
+
+    fn eq_key(&self, other: &dyn ScopeKey) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+
+    fn hash_key(&self, mut state: &mut dyn Hasher) {
+        TypeId::of::<T>().hash(&mut state);
+        self.hash(&mut state);
+    }
+}
+
+/// An input that can follow a pending key sequence, and a description of what it does.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyHint {
+    /// The name of the input, from [InputKey::name] or [InputKeyClass::name].
+    pub key: String,
+
+    /// A description of what the input does, from [Step::describe].
+    ///
+    /// Inputs that lead to further pending input are described as "+prefix".
+    pub desc: String,
 }
 
 /// A default [InputKeyClass] with no members.
@@ -471,7 +639,7 @@ where
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 struct IdGenerator {
     next_id: u64,
 }
@@ -515,6 +683,7 @@ enum InputResult<S> {
     Unmapped,
 }
 
+#[derive(Clone)]
 enum NodeAction<S> {
     Empty,
     Root,
@@ -537,6 +706,7 @@ impl<S> From<Option<S>> for NodeAction<S> {
     }
 }
 
+#[derive(Clone)]
 struct Node<M, S> {
     mode: M,
     action: NodeAction<S>,
@@ -570,7 +740,7 @@ pub enum EdgeEvent<Key, K: InputKeyClass<Key>> {
 }
 
 /// Specifies how many times an [EdgeEvent] is allowed to be repeated.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum EdgeRepeat {
     /// Allow [EdgeEvent] to occur once before continuing to the next part of the [EdgePath].
     Once,
@@ -594,6 +764,7 @@ struct Edge<Key, S: Step<Key>> {
     end: NodeId,
 }
 
+#[derive(Clone)]
 struct Graph<Key: InputKey, S: Step<Key>> {
     idgen: IdGenerator,
     modes: HashMap<S::M, NodeId>,
@@ -702,6 +873,69 @@ impl<Key: InputKey, S: Step<Key>> Graph<Key, S> {
         return FollowResult::NoSuccessor;
     }
 
+    /// Find the [Step] that a node will run, following any [EdgeEvent::Fallthrough] edges out of
+    /// nodes that are still waiting on more input.
+    fn resolve_step(&self, id: NodeId) -> Option<&S> {
+        let mut id = id;
+        let mut visited = HashSet::new();
+
+        while visited.insert(id) {
+            match &self.get_node(id).action {
+                NodeAction::Step(step) => return Some(step.as_ref()),
+                NodeAction::Root => return None,
+                NodeAction::Empty => {
+                    id = self.get_edge(id, &EdgeEvent::Fallthrough)?.end;
+                },
+            }
+        }
+
+        return None;
+    }
+
+    /// List the events that can be followed from a node, including those reachable through
+    /// [EdgeEvent::Fallthrough] edges.
+    fn successors(&self, id: NodeId) -> Vec<(EdgeEvent<Key, S::Class>, Option<&S>)> {
+        let mut res = vec![];
+        let mut seen = HashSet::new();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([id]);
+
+        while let Some(id) = queue.pop_front() {
+            if !visited.insert(id) {
+                continue;
+            }
+
+            for (ev, edge) in self.edges.get(&id).into_iter().flatten() {
+                if let EdgeEvent::Fallthrough = ev {
+                    queue.push_back(edge.end);
+                } else if seen.insert(ev.clone()) {
+                    // Events at closer nodes take priority over ones reached by falling through.
+                    res.push((ev.clone(), self.resolve_step(edge.end)));
+                }
+            }
+        }
+
+        return res;
+    }
+
+    /// Find the node that a sequence of keys leads to, without following any
+    /// [EdgeEvent::Fallthrough] edges or updating the step number.
+    fn walk(&self, id: NodeId, keys: &[Key]) -> Option<NodeId> {
+        keys.iter().try_fold(id, |id, ke| {
+            let m = self.edges.get(&id)?;
+            let e = m
+                .get(&EdgeEvent::Key(ke.clone()))
+                .or_else(|| {
+                    S::Class::memberships(ke)
+                        .into_iter()
+                        .find_map(|class| m.get(&EdgeEvent::Class(class)))
+                })
+                .or_else(|| m.get(&EdgeEvent::Any))?;
+
+            Some(e.end)
+        })
+    }
+
     fn update_step_number(&mut self, id: NodeId) {
         if let Some(prev_node) = self.prev_node {
             if id != prev_node {
@@ -760,6 +994,9 @@ where
 struct InputMachine<Key: InputKey, S: Step<Key>> {
     graph: Graph<Key, S>,
     curr: NodeId,
+    mappings: Vec<(S::M, Vec<EdgePathPart<Key, S::Class>>, Arc<S>)>,
+    mapping_idx: HashMap<(S::M, Vec<EdgePathPart<Key, S::Class>>), usize>,
+    remapped: Vec<(S::M, Vec<EdgePathPart<Key, S::Class>>)>,
 }
 
 impl<Key: InputKey, S: Step<Key>> InputMachine<Key, S> {
@@ -767,11 +1004,11 @@ impl<Key: InputKey, S: Step<Key>> InputMachine<Key, S> {
         &mut self,
         mode: S::M,
         ev: &EdgeEvent<Key, S::Class>,
-        step: Option<S>,
+        action: NodeAction<S>,
         prev: &mut NodeId,
         fallthrough: &mut bool,
     ) -> NodeId {
-        let id = self.graph.upsert_node(mode, *prev, ev, step.into());
+        let id = self.graph.upsert_node(mode, *prev, ev, action);
 
         if *fallthrough {
             self.graph.add_edge(*prev, id, EdgeEvent::Fallthrough);
@@ -790,7 +1027,7 @@ impl<Key: InputKey, S: Step<Key>> InputMachine<Key, S> {
         prev: &mut NodeId,
         fallthrough: &mut bool,
     ) -> NodeId {
-        self.add_simple_step(mode, ev, None, prev, fallthrough)
+        self.add_simple_step(mode, ev, NodeAction::Empty, prev, fallthrough)
     }
 
     fn add_intermediate(
@@ -929,6 +1166,7 @@ impl<Key: InputKey, S: Step<Key>> InputMachine<Key, S> {
     }
 
     fn add_mapping(&mut self, mode: S::M, evs: &EdgePath<Key, S::Class>, step: &S) {
+        let step = Arc::new(step.clone());
         let mut prev = self.graph.get_mode(mode);
         let mut fallthrough = false;
         let prev = &mut prev;
@@ -941,11 +1179,11 @@ impl<Key: InputKey, S: Step<Key>> InputMachine<Key, S> {
 
             match last {
                 (EdgeRepeat::Once, ev) => {
-                    let step = Some(step.clone());
-                    *prev = self.add_simple_step(mode, ev, step, prev, fallthrough);
+                    let action = NodeAction::Step(step.clone());
+                    *prev = self.add_simple_step(mode, ev, action, prev, fallthrough);
                 },
                 (EdgeRepeat::Min(n), ev) => {
-                    let end = self.graph.add_node(mode, NodeAction::new(step.clone()));
+                    let end = self.graph.add_node(mode, NodeAction::Step(step.clone()));
                     let many = self.graph.add_node(mode, NodeAction::Empty);
 
                     self.graph.add_edge(many, end, EdgeEvent::Fallthrough);
@@ -964,7 +1202,7 @@ impl<Key: InputKey, S: Step<Key>> InputMachine<Key, S> {
                     *prev = end;
                 },
                 (EdgeRepeat::Max(n), ev) => {
-                    let end = self.graph.add_node(mode, NodeAction::new(step.clone()));
+                    let end = self.graph.add_node(mode, NodeAction::Step(step.clone()));
 
                     self.graph.add_edge(*prev, end, EdgeEvent::Fallthrough);
 
@@ -989,14 +1227,34 @@ impl<Key: InputKey, S: Step<Key>> InputMachine<Key, S> {
 
                 self.graph.add_edge(*prev, mid, EdgeEvent::Fallthrough);
             }
+
+            self.record_mapping(mode, evs, step);
         }
     }
 
-    fn input(&mut self, ke: &Key, ctx: &mut S::State) -> InputResult<S> {
+    fn record_mapping(&mut self, mode: S::M, evs: &EdgePath<Key, S::Class>, step: Arc<S>) {
+        let key = (mode, evs.to_vec());
+
+        if let Some(idx) = self.mapping_idx.get(&key) {
+            self.mappings[*idx].2 = step;
+
+            if !self.remapped.contains(&key) {
+                self.remapped.push(key);
+            }
+        } else {
+            self.mapping_idx.insert(key, self.mappings.len());
+            self.mappings.push((mode, evs.to_vec(), step));
+        }
+    }
+
+    fn input<F>(&mut self, ke: &Key, mut event: F) -> InputResult<S>
+    where
+        F: FnMut(&EdgeEvent<Key, S::Class>, &Key, usize),
+    {
         loop {
             match self.graph.follow_edge(self.curr, ke) {
                 FollowResult::Successor(e, step_number) => {
-                    ctx.event(&e.evt, ke, step_number);
+                    event(&e.evt, ke, step_number);
                     self.curr = e.end;
 
                     let node = self.graph.get_node(self.curr);
@@ -1047,6 +1305,10 @@ impl<Key: InputKey, S: Step<Key>> InputMachine<Key, S> {
     fn mode(&self) -> S::M {
         self.graph.get_node(self.curr).mode
     }
+
+    fn is_pending(&self) -> bool {
+        !matches!(self.graph.get_node(self.curr).action, NodeAction::Root)
+    }
 }
 
 impl<Key: InputKey, S: Step<Key>> Default for InputMachine<Key, S> {
@@ -1054,7 +1316,13 @@ impl<Key: InputKey, S: Step<Key>> Default for InputMachine<Key, S> {
         let mut graph = Graph::<Key, S>::default();
         let curr = graph.get_mode(S::M::default());
 
-        InputMachine { graph, curr }
+        InputMachine {
+            graph,
+            curr,
+            mappings: Vec::new(),
+            mapping_idx: HashMap::new(),
+            remapped: Vec::new(),
+        }
     }
 }
 
@@ -1123,6 +1391,19 @@ pub struct ModalMachine<Key: InputKey, S: Step<Key>> {
     sequences: HashMap<S::Sequence, SequenceTracker<S::A, <S::State as InputState>::Output>>,
     dialogs: Vec<Box<dyn Dialog<S::A> + Send + Sync>>,
 
+    /// Mappings that only apply while their scope has focus.
+    scoped: HashMap<MappingScope, Vec<(S::M, Vec<EdgePathPart<Key, S::Class>>, S)>>,
+
+    /// The scopes that currently have focus, in order of priority.
+    focus: Vec<MappingScope>,
+
+    /// The mappings for the focused scopes, if any of them have mappings. These get consulted
+    /// before the global mappings whenever no global key sequence is pending.
+    local: Option<InputMachine<Key, S>>,
+
+    /// Context updates from the scoped mappings, which only get applied once they reach a [Step].
+    deferred: Vec<(EdgeEvent<Key, S::Class>, Key, usize)>,
+
     /// Previously en
     key_trail: Vec<Key>,
 }
@@ -1162,6 +1443,10 @@ where
             actions: VecDeque::new(),
             sequences: HashMap::new(),
             dialogs: Vec::new(),
+            scoped: HashMap::new(),
+            focus: Vec::new(),
+            local: None,
+            deferred: Vec::new(),
             key_trail: Vec::new(),
         }
     }
@@ -1176,6 +1461,92 @@ where
         self.im.add_mapping(mode, evs, action);
     }
 
+    /// Map a sequence of keys to an action in the given mode, but only while `scope` has focus.
+    ///
+    /// These mappings take priority over the ones added with [ModalMachine::add_mapping], while
+    /// still following any prefixes added with [ModalMachine::add_prefix]. If the keys typed stop
+    /// matching any scoped mapping, they get processed using the global mappings instead.
+    pub fn add_scoped_mapping(
+        &mut self,
+        scope: MappingScope,
+        mode: S::M,
+        evs: &EdgePath<Key, S::Class>,
+        action: &S,
+    ) {
+        let focused = self.focus.contains(&scope);
+        let mappings = self.scoped.entry(scope).or_default();
+
+        if let Some(m) = mappings.iter_mut().find(|(m, p, _)| *m == mode && p == evs) {
+            m.2 = action.clone();
+        } else {
+            mappings.push((mode, evs.to_vec(), action.clone()));
+        }
+
+        if focused {
+            self.refocus();
+        }
+    }
+
+    /// Remove all of the mappings added for `scope` with [ModalMachine::add_scoped_mapping],
+    /// like when the content it identifies gets closed.
+    pub fn clear_scope(&mut self, scope: &MappingScope) {
+        if self.scoped.remove(scope).is_some() && self.focus.contains(scope) {
+            self.refocus();
+        }
+    }
+
+    /// Returns the scopes that currently have focus, in order of priority.
+    pub fn focus(&self) -> &[MappingScope] {
+        self.focus.as_slice()
+    }
+
+    /// Rebuild the mappings for the focused scopes, and return to the top of the current mode.
+    fn refocus(&mut self) {
+        let mode = self.state;
+
+        // Add the highest priority mappings last, so that they replace any others.
+        let scoped: Vec<_> = self
+            .focus
+            .iter()
+            .rev()
+            .filter_map(|scope| self.scoped.get(scope))
+            .collect();
+
+        self.local = if scoped.is_empty() {
+            None
+        } else {
+            let mut im = InputMachine::default();
+
+            for (mode, evs, step) in scoped.into_iter().flatten() {
+                im.add_mapping(*mode, evs, step);
+            }
+
+            Some(im)
+        };
+
+        self.deferred.clear();
+        self.goto_machines(mode);
+
+        if !self.key_trail.is_empty() {
+            self.key_trail.clear();
+            self.ctx.reset();
+        }
+    }
+
+    fn goto_machines(&mut self, mode: S::M) {
+        self.im.goto_mode(mode);
+
+        if let Some(local) = &mut self.local {
+            local.goto_mode(mode);
+        }
+    }
+
+    fn apply_deferred(&mut self) {
+        for (ev, ke, n) in self.deferred.drain(..) {
+            self.ctx.event(&ev, &ke, n);
+        }
+    }
+
     fn unmapped(&mut self, ke: Key) {
         let (mut acts, ms) = self.im.mode().unmapped(&ke, &mut self.ctx);
         let res = self.ctx.take();
@@ -1217,7 +1588,7 @@ where
     fn goto_mode(&mut self, mode: S::M) {
         let prev = self.state;
 
-        self.im.goto_mode(mode);
+        self.goto_machines(mode);
         self.state = mode;
 
         let acts = self.state.enter(prev, &mut self.ctx);
@@ -1285,6 +1656,70 @@ where
     pub fn state(&mut self) -> &mut S::State {
         &mut self.ctx
     }
+
+    /// Iterate over the key sequences mapped in a mode using [ModalMachine::add_mapping], and the
+    /// [Step] that each one reaches, in the order that they were first mapped.
+    ///
+    /// Remapping a key sequence replaces its [Step] without changing its position.
+    pub fn mappings(
+        &self,
+        mode: S::M,
+    ) -> impl Iterator<Item = (&EdgePath<Key, S::Class>, &S)> + '_ {
+        self.im
+            .mappings
+            .iter()
+            .filter(move |(m, _, _)| *m == mode)
+            .map(|(_, evs, step)| (evs.as_slice(), step.as_ref()))
+    }
+
+    /// Indicates whether the keys typed so far are waiting on further input to complete a
+    /// sequence, instead of being at the top of the current mode.
+    pub fn is_pending(&self) -> bool {
+        self.im.is_pending() || self.local.as_ref().is_some_and(InputMachine::is_pending)
+    }
+
+    /// List the inputs that can follow the keys typed so far, and the [Step] that each one
+    /// reaches. Inputs that lead to a node waiting on more input have no [Step].
+    ///
+    /// When no keys are pending, this lists the inputs available at the top of the current mode.
+    /// The order of the returned inputs is unspecified.
+    pub fn continuations(&self) -> Vec<(EdgeEvent<Key, S::Class>, Option<&S>)> {
+        let local = match &self.local {
+            Some(local) if !self.im.is_pending() => local,
+            _ => return self.im.graph.successors(self.im.curr),
+        };
+
+        let mut res = local.graph.successors(local.curr);
+
+        // Global mappings that continue the keys typed so far can still be reached.
+        let global = if local.is_pending() {
+            self.im.graph.walk(self.im.curr, &self.key_trail)
+        } else {
+            Some(self.im.curr)
+        };
+
+        for (ev, step) in global.into_iter().flat_map(|id| self.im.graph.successors(id)) {
+            if !res.iter().any(|(prev, _)| prev == &ev) {
+                res.push((ev, step));
+            }
+        }
+
+        return res;
+    }
+
+    /// Find the mappings added with [ModalMachine::add_mapping] whose [Step] satisfies a
+    /// predicate, and return the mode and key sequence of each one.
+    pub fn find_mappings<F>(&self, f: F) -> Vec<(S::M, &EdgePath<Key, S::Class>)>
+    where
+        F: Fn(&S) -> bool,
+    {
+        self.im
+            .mappings
+            .iter()
+            .filter(|(_, _, step)| f(step))
+            .map(|(mode, evs, _)| (*mode, evs.as_slice()))
+            .collect()
+    }
 }
 
 impl<Key, S> BindingMachine<Key, S::A, S::Sequence, <S::State as InputState>::Output>
@@ -1311,11 +1746,56 @@ where
             return;
         }
 
-        let mut stack = vec![input];
+        // Each key is paired with whether it can be matched against the scoped mappings.
+        let mut stack = vec![(input, true)];
 
-        while let Some(mut ke) = stack.pop() {
+        while let Some((mut ke, mut scoped)) = stack.pop() {
             loop {
-                match self.im.input(&ke, &mut self.ctx) {
+                let res = match &mut self.local {
+                    Some(local) if scoped && !self.im.is_pending() => {
+                        if !local.is_pending() {
+                            local.goto_mode(self.im.mode());
+                        }
+
+                        let deferred = &mut self.deferred;
+                        let res =
+                            local.input(&ke, |ev, ke, n| deferred.push((ev.clone(), ke.clone(), n)));
+
+                        match res {
+                            InputResult::Unmapped => {
+                                // The scoped mappings don't match, so discard their updates to
+                                // the context and process the keys using the global mappings.
+                                local.goto_mode(self.im.mode());
+                                self.deferred.clear();
+
+                                let mut key_trail = std::mem::take(&mut self.key_trail);
+                                key_trail.push(ke);
+                                ke = key_trail.remove(0);
+                                scoped = false;
+                                key_trail.into_iter().rev().for_each(|k| stack.push((k, true)));
+                                continue;
+                            },
+                            InputResult::NeedMore => res,
+                            InputResult::ClearTrail => {
+                                // Stay in sync with mode changes made by falling through.
+                                self.im.goto_mode(local.mode());
+                                self.apply_deferred();
+                                res
+                            },
+                            _ => {
+                                self.apply_deferred();
+                                res
+                            },
+                        }
+                    },
+                    _ => {
+                        let ctx = &mut self.ctx;
+
+                        self.im.input(&ke, |ev, ke, n| ctx.event(ev, ke, n))
+                    },
+                };
+
+                match res {
                     InputResult::Consumed => {
                         self.key_trail.clear();
                         break;
@@ -1327,7 +1807,8 @@ where
                     InputResult::Unmapped => {
                         if let Some(mut ke2) = ke.decompose() {
                             std::mem::swap(&mut ke, &mut ke2);
-                            stack.push(ke2);
+                            stack.push((ke2, true));
+                            scoped = true;
                             continue;
                         }
 
@@ -1344,16 +1825,18 @@ where
                         let mut key_trail = std::mem::take(&mut self.key_trail);
                         key_trail.push(ke);
                         self.unmapped(key_trail.remove(0));
-                        key_trail.into_iter().rev().for_each(|k| stack.push(k));
+                        key_trail.into_iter().rev().for_each(|k| stack.push((k, true)));
                         break;
                     },
                     InputResult::ClearTrail => {
                         self.key_trail.clear();
+                        scoped = true;
                         continue;
                     },
                     InputResult::RetryAfter(step) => {
                         self.key_trail.clear();
                         self.step(step.as_ref());
+                        scoped = true;
                         continue;
                     },
                     InputResult::Step(step) => {
@@ -1412,11 +1895,53 @@ where
     fn run_dialog(&mut self, dialog: Box<dyn Dialog<S::A> + Send + Sync>) {
         self.dialogs.push(dialog);
     }
+
+    fn set_focus(&mut self, focus: &[MappingScope]) {
+        if self.focus != focus {
+            self.focus = focus.to_vec();
+            self.refocus();
+        }
+    }
+
+    fn set_macro_executing(&mut self, executing: bool) {
+        self.ctx.set_macro_executing(executing);
+    }
+
+    fn pending_hints(&self) -> Vec<KeyHint> {
+        if !self.dialogs.is_empty() || !self.is_pending() {
+            return vec![];
+        }
+
+        let mut hints: Vec<KeyHint> = self
+            .continuations()
+            .into_iter()
+            .filter_map(|(ev, step)| {
+                let key = match ev {
+                    EdgeEvent::Key(key) => key.name()?,
+                    EdgeEvent::Class(class) => class.name()?,
+                    EdgeEvent::Any | EdgeEvent::Fallthrough => return None,
+                };
+
+                // Inputs that wait for more keys don't have a Step to describe them.
+                let desc = match step {
+                    Some(step) => step.describe()?.to_string(),
+                    None => "+prefix".to_string(),
+                };
+
+                Some(KeyHint { key, desc })
+            })
+            .collect();
+
+        hints.sort_by(|a, b| a.key.cmp(&b.key));
+
+        return hints;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::diagnostics::MappingDiagnostic;
 
     #[derive(Clone, Debug, Eq, Hash, PartialEq)]
     enum TestKey {
@@ -1460,6 +1985,14 @@ mod tests {
                 None
             }
         }
+
+        fn name(&self) -> Option<String> {
+            match self {
+                TestKey::Char(c) => Some(c.to_string()),
+                TestKey::Ctl(c) => Some(format!("^{c}")),
+                _ => None,
+            }
+        }
     }
 
     macro_rules! alt {
@@ -1661,6 +2194,13 @@ mod tests {
 
             return kcs;
         }
+
+        fn name(&self) -> Option<String> {
+            match self {
+                TestKeyClass::Register => Some("{register}".into()),
+                _ => None,
+            }
+        }
     }
 
     impl Mode<TestAction, TestContext> for TestMode {
@@ -1818,6 +2358,10 @@ mod tests {
             self.fall_mode
         }
 
+        fn can_wait(&self) -> bool {
+            self.action.is_none() && self.goto_mode.is_none()
+        }
+
         fn step(&self, ctx: &mut Self::State) -> (Vec<Self::A>, Option<Self::M>) {
             let actions: Vec<Self::A> = self.action.clone().into_iter().collect();
 
@@ -1827,6 +2371,15 @@ mod tests {
 
             (actions, self.goto_mode)
         }
+
+        fn describe(&self) -> Option<&str> {
+            match self.action.as_ref()? {
+                TestAction::EditLine => Some("edit line"),
+                TestAction::EditWord => Some("edit word"),
+                TestAction::Paste => Some("paste"),
+                _ => None,
+            }
+        }
     }
 
     impl Default for TestBindings {
@@ -3136,4 +3689,365 @@ mod tests {
         assert_eq!(tm.mode(), TestMode::Insert);
         assert_eq!(tm.get_cursor_indicator(), None);
     }
+
+    #[test]
+    fn test_mappings() {
+        let mut tm = TestMachine::default();
+        let mapped = |tm: &TestMachine, mode| {
+            tm.mappings(mode)
+                .map(|(evs, step)| (evs.to_vec(), step.action.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        // Mappings are listed per mode in the order they were added, without prefixes.
+        assert_eq!(mapped(&tm, TestMode::Suffix), vec![
+            (keys!('w'), Some(TestAction::EditWord)),
+            (
+                vec![
+                    once!(EdgeEvent::Key(key!('t'))),
+                    once!(EdgeEvent::Class(TestKeyClass::TillChar))
+                ],
+                Some(TestAction::EditTillChar)
+            ),
+            (keys!(';'), Some(TestAction::EditTillChar)),
+        ]);
+        assert_eq!(mapped(&tm, TestMode::Normal).len(), 6);
+
+        // Remapping a sequence updates it in place.
+        let yy = keys!('y', 'y');
+        tm.add_mapping(TestMode::Normal, &yy, &action!(TestAction::Palaver));
+
+        let normal = mapped(&tm, TestMode::Normal);
+        assert_eq!(normal.len(), 6);
+        assert_eq!(normal[4], (yy, Some(TestAction::Palaver)));
+    }
+
+    #[test]
+    fn test_continuations() {
+        let mut tm = TestMachine::default();
+        let ctx = TestContext::default();
+        let next = |tm: &TestMachine| {
+            tm.continuations()
+                .into_iter()
+                .map(|(ev, step)| (ev, step.map(|s| s.action.clone())))
+                .collect::<HashMap<_, _>>()
+        };
+
+        // At the top of Insert mode, all of its mappings are available.
+        let res = next(&tm);
+        assert_eq!(res.len(), 7);
+        assert_eq!(res[&EdgeEvent::Key(TestKey::Left)], Some(Some(TestAction::MoveLeft)));
+
+        // After ^R, only a register can follow.
+        assert!(!tm.is_pending());
+        tm.input_key(ctl!('r'));
+        assert_eq!(tm.pop(), None);
+        assert!(tm.is_pending());
+
+        let res = next(&tm);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[&EdgeEvent::Class(TestKeyClass::Register)], Some(Some(TestAction::Paste)));
+
+        tm.input_key(key!('a'));
+        let _ = tm.pop();
+        assert!(!tm.is_pending());
+
+        // Go to Normal mode.
+        tm.input_key(ctl!('l'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+
+        // A count prefix needs more input, and can be followed by any of Normal mode's keys.
+        let res = next(&tm);
+        assert_eq!(res[&EdgeEvent::Class(TestKeyClass::Count)], None);
+
+        tm.input_key(key!('2'));
+        assert_eq!(tm.pop(), None);
+
+        let res = next(&tm);
+        assert_eq!(res.len(), 6);
+        assert_eq!(res[&EdgeEvent::Key(key!('n'))], Some(Some(TestAction::NoOp)));
+
+        // After "d", we can finish "dd" or fall through to Suffix mode.
+        tm.input_key(key!('d'));
+        assert_eq!(tm.pop(), None);
+
+        let res = next(&tm);
+        assert_eq!(res.len(), 4);
+        assert_eq!(res[&EdgeEvent::Key(key!('d'))], Some(Some(TestAction::EditLine)));
+        assert_eq!(res[&EdgeEvent::Key(key!('w'))], Some(Some(TestAction::EditWord)));
+        assert_eq!(res[&EdgeEvent::Key(key!('t'))], None);
+    }
+
+    #[test]
+    fn test_pending_hints() {
+        let mut tm = TestMachine::default();
+        let ctx = TestContext::default();
+        let hint = |key: &str, desc: &str| KeyHint { key: key.into(), desc: desc.into() };
+
+        // Nothing is pending at the top of Insert mode.
+        assert_eq!(tm.pending_hints(), vec![]);
+
+        // After ^R, a register pastes.
+        tm.input_key(ctl!('r'));
+        assert_eq!(tm.pending_hints(), vec![hint("{register}", "paste")]);
+
+        tm.input_key(key!('a'));
+        let _ = tm.pop();
+        assert_eq!(tm.pending_hints(), vec![]);
+
+        // Go to Normal mode.
+        tm.input_key(ctl!('l'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+
+        // After "d", inputs without descriptions are left out, and "t" waits for more input.
+        tm.input_key(key!('d'));
+        assert_eq!(tm.pending_hints(), vec![
+            hint("d", "edit line"),
+            hint("t", "+prefix"),
+            hint("w", "edit word"),
+        ]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut tm = TestMachine::default();
+        assert_eq!(tm.validate(), vec![]);
+        tm.debug_assert_valid();
+
+        // Mapping "n" again replaces the previous step.
+        let n = keys!('n');
+        tm.add_mapping(TestMode::Normal, &n, &action!(TestAction::Query));
+        assert_eq!(tm.validate(), vec![MappingDiagnostic::Overwritten {
+            mode: TestMode::Normal,
+            path: n,
+        }]);
+
+        // "n" doesn't wait for more input, so "nx" gets cut off.
+        let mut tm = TestMachine::default();
+        let nx = keys!('n', 'x');
+        tm.add_mapping(TestMode::Normal, &nx, &action!(TestAction::EditWord));
+
+        let diags = tm.validate();
+        assert_eq!(diags, vec![MappingDiagnostic::PrefixConflict {
+            mode: TestMode::Normal,
+            prefix: keys!('n'),
+            path: nx,
+        }]);
+        assert_eq!(diags[0].to_string(), "Normal: \"nx\" is cut off by the mapping for \"n\"");
+
+        // Making "q" go back to the root leaves "qw" unreachable.
+        let mut tm = TestMachine::default();
+        let qw = keys!('q', 'w');
+        tm.add_mapping(TestMode::Normal, &qw, &action!(TestAction::EditWord));
+        tm.add_prefix(TestMode::Normal, &qw[..1], &None);
+        assert_eq!(tm.validate(), vec![MappingDiagnostic::Unreachable {
+            mode: TestMode::Normal,
+            path: qw,
+        }]);
+
+        // The zero-repeat case of a count after ^V is always taken by the {any} edge.
+        let mut tm = TestMachine::default();
+        let any = vec![once!(EdgeEvent::Key(ctl!('v'))), once!(EdgeEvent::Any)];
+        let count = vec![
+            once!(EdgeEvent::Key(ctl!('v'))),
+            (EdgeRepeat::Max(3), EdgeEvent::Class(TestKeyClass::Count)),
+        ];
+        tm.add_mapping(TestMode::Insert, &any, &action!(TestAction::Type('x')));
+        tm.add_mapping(TestMode::Insert, &count, &action!(TestAction::Type('y')));
+
+        let diags = tm.validate();
+        assert_eq!(diags, vec![MappingDiagnostic::Shadowed {
+            mode: TestMode::Insert,
+            path: vec![once!(EdgeEvent::Key(ctl!('v')))],
+            event: EdgeEvent::Fallthrough,
+            by: EdgeEvent::Any,
+        }]);
+        assert_eq!(
+            diags[0].to_string(),
+            "Insert: {fallthrough} after \"^v\" is always shadowed by {any}"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_debug_assert_valid() {
+        let mut tm = TestMachine::default();
+        let nx = keys!('n', 'x');
+
+        tm.add_mapping(TestMode::Normal, &nx, &action!(TestAction::EditWord));
+        tm.debug_assert_valid();
+    }
+
+    #[test]
+    fn test_find_mappings() {
+        let tm = TestMachine::default();
+
+        let res = tm.find_mappings(|step| step.action == Some(TestAction::EditLine));
+        assert_eq!(res, vec![
+            (TestMode::Normal, keys!('d', 'd').as_slice()),
+            (TestMode::Normal, keys!('y', 'y').as_slice()),
+        ]);
+
+        let res = tm.find_mappings(|step| step.goto_mode == Some(TestMode::Insert));
+        assert_eq!(res, vec![(TestMode::Normal, keys!(TestKey::Esc).as_slice())]);
+
+        let res = tm.find_mappings(|step| step.action == Some(TestAction::Query));
+        assert!(res.is_empty());
+    }
+
+    #[test]
+    fn test_scoped_mapping() {
+        let mut tm = TestMachine::default();
+        let mut ctx = TestContext::default();
+        let help = MappingScope::window(&1usize);
+        let dir = MappingScope::content(&"dir".to_string());
+
+        tm.add_scoped_mapping(
+            help.clone(),
+            TestMode::Normal,
+            keys!('a').as_slice(),
+            &action!(TestAction::Query),
+        );
+        tm.add_scoped_mapping(
+            help.clone(),
+            TestMode::Normal,
+            keys!('n').as_slice(),
+            &action!(TestAction::Palaver),
+        );
+        tm.add_scoped_mapping(
+            dir.clone(),
+            TestMode::Normal,
+            keys!('a').as_slice(),
+            &action!(TestAction::Inveigle),
+        );
+        tm.add_scoped_mapping(
+            dir.clone(),
+            TestMode::Normal,
+            keys!('b', 'c').as_slice(),
+            &action!(TestAction::Query),
+        );
+        tm.add_scoped_mapping(
+            help.clone(),
+            TestMode::Normal,
+            keys!('d', 'x').as_slice(),
+            &action!(TestAction::Query),
+        );
+
+        // Scopes only match identifiers of the same type and kind.
+        assert_eq!(help, MappingScope::window(&1usize));
+        assert_ne!(help, MappingScope::window(&1u32));
+        assert_ne!(help, MappingScope::content(&1usize));
+        assert_ne!(dir, MappingScope::content(&"dir"));
+
+        // Go to Normal mode.
+        tm.input_key(ctl!('l'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+        assert_eq!(tm.mode(), TestMode::Normal);
+
+        // Scoped mappings don't apply until their scope has focus.
+        tm.input_key(key!('a'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+        tm.input_key(key!('n'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+
+        tm.set_focus(std::slice::from_ref(&help));
+        assert_eq!(tm.focus(), std::slice::from_ref(&help));
+        assert_eq!(tm.mode(), TestMode::Normal);
+
+        tm.input_key(key!('a'));
+        assert_pop2!(tm, TestAction::Query, ctx);
+
+        // Scoped mappings replace global ones.
+        tm.input_key(key!('n'));
+        assert_pop2!(tm, TestAction::Palaver, ctx);
+
+        // Global mappings and prefixes are still available.
+        ctx.temp.operation = Some(TestOperation::Delete);
+        tm.input_key(key!('d'));
+        tm.input_key(key!('d'));
+        assert_pop2!(tm, TestAction::EditLine, ctx);
+
+        ctx.temp.operation = None;
+        ctx.temp.count = Some(12);
+        tm.input_key(key!('1'));
+        tm.input_key(key!('2'));
+        tm.input_key(key!('a'));
+        assert_pop2!(tm, TestAction::Query, ctx);
+        ctx.temp.count = None;
+
+        // Keys that stop matching the scoped mappings get processed by the global ones.
+        tm.input_key(key!('d'));
+        tm.input_key(key!('x'));
+        assert_pop2!(tm, TestAction::Query, ctx);
+
+        ctx.temp.operation = Some(TestOperation::Delete);
+        tm.input_key(key!('d'));
+        assert!(tm.is_pending());
+        tm.input_key(key!('d'));
+        assert_pop2!(tm, TestAction::EditLine, ctx);
+
+        ctx.temp.count = Some(12);
+        tm.input_key(key!('1'));
+        tm.input_key(key!('2'));
+        tm.input_key(key!('d'));
+        tm.input_key(key!('w'));
+        assert_pop2!(tm, TestAction::EditWord, ctx);
+        ctx.temp.count = None;
+        ctx.temp.operation = None;
+
+        // Scoped mappings apply after falling through to another mode.
+        tm.input_key(key!(TestKey::Esc));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+        assert_eq!(tm.mode(), TestMode::Insert);
+
+        tm.input_key(ctl!('o'));
+        tm.input_key(key!('a'));
+        assert_pop2!(tm, TestAction::Query, ctx);
+        assert_eq!(tm.mode(), TestMode::Insert);
+
+        tm.input_key(ctl!('l'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+        assert_eq!(tm.mode(), TestMode::Normal);
+
+        // Earlier scopes take priority over later ones.
+        tm.set_focus(&[dir.clone(), help.clone()]);
+        tm.input_key(key!('a'));
+        assert_pop2!(tm, TestAction::Inveigle, ctx);
+        tm.input_key(key!('n'));
+        assert_pop2!(tm, TestAction::Palaver, ctx);
+
+        // Changing focus discards pending keys.
+        tm.input_key(key!('b'));
+        assert_eq!(tm.pop(), None);
+        assert!(tm.is_pending());
+
+        tm.set_focus(std::slice::from_ref(&help));
+        assert!(!tm.is_pending());
+        tm.input_key(key!('c'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+
+        // Remapping in a focused scope takes effect immediately.
+        tm.add_scoped_mapping(
+            help.clone(),
+            TestMode::Normal,
+            keys!('a').as_slice(),
+            &action!(TestAction::MoveUp),
+        );
+        tm.input_key(key!('a'));
+        assert_pop2!(tm, TestAction::MoveUp, ctx);
+
+        // Clearing the scope removes its mappings.
+        tm.clear_scope(&help);
+        tm.input_key(key!('a'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+        tm.input_key(key!('n'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+
+        // Global mappings are unaffected.
+        tm.set_focus(&[dir]);
+        tm.set_focus(&[]);
+        tm.input_key(key!('a'));
+        assert_pop2!(tm, TestAction::NoOp, ctx);
+        assert_eq!(tm.mappings(TestMode::Normal).count(), 6);
+    }
 }
@@ -3,12 +3,12 @@ use std::collections::VecDeque;
 use std::fs::{DirEntry, File, FileType};
 use std::io::{stdout, Stdout};
 use std::path::Path;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 
 use modalkit::crossterm::{
     self,
-    event::{poll, read, Event, KeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
+    event::{poll, read, Event, KeyboardEnhancementFlags},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -25,6 +25,7 @@ use ratatui::{
 use modalkit::{
     actions::{
         Action,
+        CommandAction,
         Commandable,
         Editable,
         EditorAction,
@@ -51,10 +52,11 @@ use modalkit::{
         cursor::Cursor,
         key::KeyManager,
         rope::EditRope,
-        store::Store,
+        store::{RegisterStore, Store},
     },
+    env::emacs::command::{self as emacs, EmacsCommandMachine},
     env::mixed::{MixedBindings, MixedChoice},
-    env::vim::command::{complete_cmdbar, VimCommandMachine},
+    env::vim::command::{self as vim, VimCommandMachine},
     errors::{EditError, EditResult, UIError, UIResult},
     key::TerminalKey,
     keybindings::{dialog::Pager, BindingMachine},
@@ -142,9 +144,51 @@ impl ListItem<EditorInfo> for DirectoryItem {
     }
 }
 
+/// The commands typed after `:` in Vim, or after `M-x` in Emacs.
+enum EditorCommands {
+    Emacs(EmacsCommandMachine<EditorInfo>),
+    Vim(VimCommandMachine<EditorInfo>),
+}
+
+impl EditorCommands {
+    fn complete(&self, text: &EditRope, cursor: &mut Cursor) -> Vec<String> {
+        match self {
+            EditorCommands::Emacs(cmds) => emacs::complete_cmdbar(text, cursor, cmds),
+            EditorCommands::Vim(cmds) => vim::complete_cmdbar(text, cursor, cmds),
+        }
+    }
+
+    fn command(
+        &mut self,
+        act: &CommandAction,
+        ctx: &EditContext,
+        rstore: &mut RegisterStore,
+    ) -> UIResult<Vec<(Action<EditorInfo>, EditContext)>, EditorInfo> {
+        match self {
+            EditorCommands::Emacs(cmds) => cmds.command(act, ctx, rstore),
+            EditorCommands::Vim(cmds) => cmds.command(act, ctx, rstore),
+        }
+    }
+}
+
+impl Default for EditorCommands {
+    fn default() -> Self {
+        EditorCommands::Vim(VimCommandMachine::default())
+    }
+}
+
+impl From<MixedChoice> for EditorCommands {
+    fn from(env: MixedChoice) -> Self {
+        match env {
+            MixedChoice::Emacs => EditorCommands::Emacs(EmacsCommandMachine::default()),
+            _ => EditorCommands::Vim(VimCommandMachine::default()),
+        }
+    }
+}
+
 #[derive(Default)]
 struct EditorStore {
-    cmds: VimCommandMachine<EditorInfo>,
+    cmds: EditorCommands,
     filenames: HashMap<String, usize>,
     fileindex: usize,
 }
@@ -337,6 +381,7 @@ impl Window<EditorInfo> for EditorWindow {
         match self.id() {
             EditorContentId::Command(CommandType::Command) => Line::from("[Command Line]"),
             EditorContentId::Command(CommandType::Search) => Line::from("[Search Bar]"),
+            EditorContentId::Command(_) => Line::from("[Prompt]"),
             EditorContentId::Scratch => Line::from("[Scratch]"),
             EditorContentId::File(index) => Line::from(format!("Buffer {index}")),
             EditorContentId::Directory(name) => Line::from(name),
@@ -488,9 +533,9 @@ impl ApplicationInfo for EditorInfo {
     ) -> Vec<String> {
         match content {
             EditorContentId::Command(CommandType::Command) => {
-                complete_cmdbar(text, cursor, &store.application.cmds)
+                store.application.cmds.complete(text, cursor)
             },
-            EditorContentId::Command(CommandType::Search) => vec![],
+            EditorContentId::Command(_) => vec![],
             EditorContentId::Directory(_) => vec![],
             EditorContentId::File(_) => vec![],
             EditorContentId::Scratch => vec![],
@@ -518,18 +563,16 @@ impl Editor {
 
         crossterm::execute!(stdout, EnterAlternateScreen)?;
 
-        if crossterm::terminal::supports_keyboard_enhancement()? {
-            // Enable the Kitty keyboard enhancement protocol for improved keypresses.
-            crossterm::execute!(
-                stdout,
-                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
-            )?;
-        }
-
         let backend = CrosstermBackend::new(stdout);
-        let terminal = Terminal::new(backend)?;
+        let mut terminal = Terminal::new(backend)?;
+
+        // Enable the Kitty keyboard enhancement protocol for improved keypresses.
+        terminal
+            .enable_keyboard_enhancement(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)?;
+
+        let mut store = Store::<EditorInfo>::default();
+        store.application.cmds = EditorCommands::from(env);
 
-        let mut store = Store::default();
         let bindings = MixedBindings::<TerminalKey, EditorInfo>::from(env);
         let bindings = KeyManager::new(bindings);
 
@@ -549,6 +592,7 @@ impl Editor {
         while self.screen.tabs() != 0 {
             let key = self.step()?;
 
+            self.bindings.set_focus(&self.screen.focused_scopes());
             self.bindings.input_key(key);
 
             let mut keyskip = false;
@@ -572,6 +616,13 @@ impl Editor {
                         // Continue processing; we'll redraw later.
                         continue;
                     },
+                    Err(UIError::EditingFailure(EditError::NeedConfirm(dialog))) |
+                    Err(UIError::NeedConfirm(dialog)) => {
+                        self.bindings.run_dialog(Box::new(Mutex::new(dialog)));
+
+                        // Continue processing; we'll redraw later.
+                        continue;
+                    },
                     Err(e) => {
                         self.screen.push_error(e);
 
@@ -581,8 +632,11 @@ impl Editor {
                     },
                 }
             }
+
+            self.screen.which_key().update(&self.bindings);
         }
 
+        self.terminal.disable_keyboard_enhancement()?;
         crossterm::terminal::disable_raw_mode()?;
         execute!(self.terminal.backend_mut(), LeaveAlternateScreen)?;
         self.terminal.show_cursor()?;
@@ -594,13 +648,18 @@ impl Editor {
         loop {
             self.redraw(false)?;
 
-            if !poll(Duration::from_millis(500))? {
+            // Wake up in time to show the hints for any pending keys.
+            let timeout = self.screen.which_key().time_left().unwrap_or(Duration::from_millis(500));
+
+            if !poll(timeout)? {
                 continue;
             }
 
             match read()? {
                 Event::Key(ke) => {
-                    return Ok(TerminalKey::from(ke));
+                    if let Some(key) = TerminalKey::from_event(ke) {
+                        return Ok(key);
+                    }
                 },
                 Event::Mouse(_) => {
                     // Do nothing for now.
@@ -672,6 +731,11 @@ impl Editor {
 
                 None
             },
+            Action::ClearSearchHighlight => {
+                self.store.registers.set_search_highlight(false);
+
+                None
+            },
 
             // Actions that create more Actions.
             Action::Prompt(act) => {
@@ -746,6 +810,8 @@ fn main() -> Result<(), std::io::Error> {
         Some(arg) => {
             match arg.as_str().trim() {
                 "e" | "emacs" => MixedChoice::Emacs,
+                "h" | "helix" => MixedChoice::Helix,
+                "k" | "kakoune" => MixedChoice::Kakoune,
                 "v" | "vim" => MixedChoice::Vim,
                 m => panic!("Unknown environment: {:?}", m),
             }
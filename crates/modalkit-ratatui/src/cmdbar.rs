@@ -13,7 +13,7 @@ use std::ops::{Deref, DerefMut};
 
 use ratatui::{buffer::Buffer, layout::Rect, style::Style, text::Span, widgets::StatefulWidget};
 
-use modalkit::actions::{Action, CommandBarAction, PromptAction, Promptable};
+use modalkit::actions::{Action, CommandBarAction, EditorAction, PromptAction, Promptable};
 use modalkit::editing::{
     application::ApplicationInfo,
     completion::CompletionList,
@@ -40,6 +40,7 @@ pub struct CommandBarState<I: ApplicationInfo> {
     cmdtype: CommandType,
     tbox_cmd: TextBoxState<I>,
     tbox_search: TextBoxState<I>,
+    tbox_prompt: TextBoxState<I>,
 }
 
 impl<I> CommandBarState<I>
@@ -50,6 +51,7 @@ where
     pub fn new(store: &mut Store<I>) -> Self {
         let buffer_cmd = store.load_buffer(I::content_of_command(CommandType::Command));
         let buffer_search = store.load_buffer(I::content_of_command(CommandType::Search));
+        let buffer_prompt = store.load_buffer(I::content_of_command(CommandType::Prompt));
 
         CommandBarState {
             scrollback: ScrollbackState::Pending,
@@ -58,6 +60,7 @@ where
             cmdtype: CommandType::Command,
             tbox_cmd: TextBoxState::new(buffer_cmd),
             tbox_search: TextBoxState::new(buffer_search),
+            tbox_prompt: TextBoxState::new(buffer_prompt),
         }
     }
 
@@ -73,6 +76,45 @@ where
         self.cmdtype = ct;
     }
 
+    /// Indicates whether the bar is being used to enter a search.
+    pub fn is_search(&self) -> bool {
+        self.cmdtype == CommandType::Search
+    }
+
+    /// Indicates whether the bar is being used to enter an incremental search that moves the
+    /// cursor, which should then move to the first match from [Mark::SearchStart] as the search
+    /// gets typed.
+    pub fn is_incremental_search(&self) -> bool {
+        if !self.is_search() {
+            return false;
+        }
+
+        match &self.action {
+            Some((
+                Action::Editor(EditorAction::Edit(ea, EditTarget::Search(SearchType::Regex, _, _))),
+                ctx,
+            )) => ctx.is_search_incremental() && ctx.resolve(ea).is_motion(),
+            _ => false,
+        }
+    }
+
+    /// Get the search pattern currently being typed into the bar, and the direction that it will
+    /// be searched in once submitted.
+    pub fn get_search_preview(&self) -> Option<(EditRope, MoveDir1D)> {
+        if !self.is_search() {
+            return None;
+        }
+
+        let (_, ctx) = self.action.as_ref()?;
+        let needle = self.tbox_search.get().trim_end_matches(|c| c == '\n');
+
+        if needle.is_empty() {
+            return None;
+        }
+
+        return Some((needle, ctx.get_search_regex_dir()));
+    }
+
     /// Reset the contents of the bar, and return the contents as an [EditRope].
     pub fn reset(&mut self) -> EditRope {
         self.scrollback = ScrollbackState::Pending;
@@ -96,6 +138,7 @@ where
         match self.cmdtype {
             CommandType::Command => &self.tbox_cmd,
             CommandType::Search => &self.tbox_search,
+            _ => &self.tbox_prompt,
         }
     }
 }
@@ -108,6 +151,7 @@ where
         match self.cmdtype {
             CommandType::Command => &mut self.tbox_cmd,
             CommandType::Search => &mut self.tbox_search,
+            _ => &mut self.tbox_prompt,
         }
     }
 }
@@ -121,11 +165,17 @@ where
         ctx: &EditContext,
         store: &mut Store<I>,
     ) -> EditResult<Vec<(Action<I>, EditContext)>, I> {
+        let incremental = self.is_incremental_search();
         let rope = self.reset().trim_end_matches(|c| c == '\n');
         store.registers.set_last_command(self.cmdtype, rope);
 
         let mut acts = vec![(CommandBarAction::Unfocus.into(), ctx.clone())];
-        acts.extend(self.action.take());
+        let act = self.action.take();
+
+        if !incremental {
+            // Incremental searches have already moved the cursor to the match.
+            acts.extend(act);
+        }
 
         Ok(acts)
     }
@@ -183,6 +233,10 @@ where
                 self.recall(dir, count, *prefixed, ctx, store)
             },
             PromptAction::Submit => self.submit(ctx, store),
+            PromptAction::InsertCursorWord(_) => {
+                // The bar doesn't know which window has the cursor, so Screen handles this.
+                Ok(vec![])
+            },
         }
     }
 }
@@ -255,6 +309,7 @@ where
             let tbox_state = match state.cmdtype {
                 CommandType::Command => &mut state.tbox_cmd,
                 CommandType::Search => &mut state.tbox_search,
+                _ => &mut state.tbox_prompt,
             };
 
             tbox.render(area, buf, tbox_state);
@@ -276,6 +331,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use modalkit::actions::EditAction;
     use modalkit::editing::application::EmptyInfo;
     use modalkit::editing::context::EditContextBuilder;
 
@@ -311,4 +367,36 @@ mod tests {
         assert_eq!(res[1].0, act2);
         assert_eq!(res[1].1, ctx2);
     }
+
+    #[test]
+    fn test_incremental_search_submit() {
+        let mut store = Store::<EmptyInfo>::default();
+        let mut cmdbar = CommandBarState::new(&mut store);
+
+        let target = EditTarget::Search(SearchType::Regex, MoveDirMod::Same, Count::Contextual);
+        let act = Action::from(EditorAction::Edit(Specifier::Contextual, target.clone()));
+
+        // Non-incremental searches get performed when the bar is submitted.
+        let ctx = EditContextBuilder::default().search_incremental(false).build();
+        cmdbar.set_type("/", CommandType::Search, &act, &ctx);
+        assert!(!cmdbar.is_incremental_search());
+
+        let res = cmdbar.submit(&EditContext::default(), &mut store).unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[1].0, act);
+
+        // Incremental searches have already moved the cursor, so only the bar gets unfocused.
+        let ctx = EditContextBuilder::default().search_incremental(true).build();
+        cmdbar.set_type("I-search: ", CommandType::Search, &act, &ctx);
+        assert!(cmdbar.is_incremental_search());
+
+        let res = cmdbar.submit(&EditContext::default(), &mut store).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].0, Action::from(CommandBarAction::Unfocus));
+
+        // Incremental searches that don't move the cursor still get performed.
+        let act = Action::from(EditorAction::Edit(EditAction::Yank.into(), target));
+        cmdbar.set_type("I-search: ", CommandType::Search, &act, &ctx);
+        assert!(!cmdbar.is_incremental_search());
+    }
 }
@@ -90,6 +90,7 @@
 #![allow(clippy::type_complexity)]
 use std::io::{stdout, Stdout};
 use std::process;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 use ratatui::{
     backend::CrosstermBackend,
@@ -103,6 +104,7 @@ use ratatui::{
 };
 
 use crossterm::{
+    event::{KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -110,12 +112,14 @@ use crossterm::{
 use modalkit::actions::Action;
 use modalkit::editing::{application::ApplicationInfo, completion::CompletionList, store::Store};
 use modalkit::errors::{EditResult, UIResult};
+use modalkit::keybindings::MappingScope;
 use modalkit::prelude::*;
 
 pub mod cmdbar;
 pub mod list;
 pub mod screen;
 pub mod textbox;
+pub mod whichkey;
 pub mod windows;
 
 mod util;
@@ -227,6 +231,17 @@ pub trait Window<I: ApplicationInfo>: WindowOps<I> + Sized {
     /// Get the identifier for this window.
     fn id(&self) -> I::WindowId;
 
+    /// Get the scopes whose mappings apply while this window has focus, in order of priority.
+    ///
+    /// By default, this is only the scope for the window's identifier. Windows can also return
+    /// scopes for the content they show, or one shared by every window of the same kind.
+    fn mapping_scopes(&self) -> Vec<MappingScope>
+    where
+        I::WindowId: Sync + 'static,
+    {
+        vec![MappingScope::window(&self.id())]
+    }
+
     /// Get the title to show in the window layout.
     fn get_win_title(&self, store: &mut Store<I>) -> Line;
 
@@ -264,6 +279,13 @@ pub fn render_cursor<T: TerminalCursor>(f: &mut Frame, widget: &T, cursor: Optio
     }
 }
 
+/// The flags pushed by [TerminalExtOps::enable_keyboard_enhancement], so that they can be
+/// restored after the process gets suspended.
+///
+/// This is shared by the whole process, since every [Terminal] using [Stdout] writes to the same
+/// terminal.
+static KEYBOARD_FLAGS: AtomicU8 = AtomicU8::new(0);
+
 /// Extended operations for [Terminal].
 pub trait TerminalExtOps {
     /// Result type for terminal operations.
@@ -271,6 +293,37 @@ pub trait TerminalExtOps {
 
     /// Suspend the process.
     fn program_suspend(&mut self) -> Self::Result;
+
+    /// Ask the terminal to report keypresses using the [Kitty keyboard protocol] with the given
+    /// flags, and return whether it supports doing so.
+    ///
+    /// With [KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES], keys like `<C-Enter>` and
+    /// `<S-Enter>` are reported differently from `<Enter>`, and the Super, Meta and Hyper
+    /// modifiers are reported as well. Key events should be converted with
+    /// [TerminalKey::from_event](modalkit::key::TerminalKey::from_event), so that any key
+    /// releases requested by the other flags get ignored.
+    ///
+    /// The enabled flags are tracked for the whole process rather than for each [Terminal], since
+    /// they apply to the terminal that the process is attached to. They get restored when resuming
+    /// after [TerminalExtOps::program_suspend].
+    ///
+    /// By default, this does nothing and returns `false`.
+    ///
+    /// [Kitty keyboard protocol]: https://sw.kovidgoyal.net/kitty/keyboard-protocol/
+    #[allow(unused_variables)]
+    fn enable_keyboard_enhancement(
+        &mut self,
+        flags: KeyboardEnhancementFlags,
+    ) -> Result<bool, std::io::Error> {
+        Ok(false)
+    }
+
+    /// Stop using the flags set by [TerminalExtOps::enable_keyboard_enhancement].
+    ///
+    /// By default, this does nothing.
+    fn disable_keyboard_enhancement(&mut self) -> Result<(), std::io::Error> {
+        Ok(())
+    }
 }
 
 impl TerminalExtOps for Terminal<CrosstermBackend<Stdout>> {
@@ -278,8 +331,14 @@ impl TerminalExtOps for Terminal<CrosstermBackend<Stdout>> {
 
     fn program_suspend(&mut self) -> Self::Result {
         let mut stdout = stdout();
+        let flags =
+            KeyboardEnhancementFlags::from_bits_truncate(KEYBOARD_FLAGS.load(Ordering::SeqCst));
 
         // Restore old terminal state.
+        if !flags.is_empty() {
+            execute!(self.backend_mut(), PopKeyboardEnhancementFlags)?;
+        }
+
         crossterm::terminal::disable_raw_mode()?;
         execute!(self.backend_mut(), LeaveAlternateScreen)?;
         self.show_cursor()?;
@@ -295,8 +354,37 @@ impl TerminalExtOps for Terminal<CrosstermBackend<Stdout>> {
         // Restore application terminal state.
         crossterm::terminal::enable_raw_mode()?;
         crossterm::execute!(stdout, EnterAlternateScreen)?;
+
+        if !flags.is_empty() {
+            crossterm::execute!(stdout, PushKeyboardEnhancementFlags(flags))?;
+        }
+
         self.clear()?;
 
         Ok(None)
     }
+
+    fn enable_keyboard_enhancement(
+        &mut self,
+        flags: KeyboardEnhancementFlags,
+    ) -> Result<bool, std::io::Error> {
+        if !crossterm::terminal::supports_keyboard_enhancement()? {
+            return Ok(false);
+        }
+
+        self.disable_keyboard_enhancement()?;
+
+        execute!(self.backend_mut(), PushKeyboardEnhancementFlags(flags))?;
+        KEYBOARD_FLAGS.store(flags.bits(), Ordering::SeqCst);
+
+        Ok(true)
+    }
+
+    fn disable_keyboard_enhancement(&mut self) -> Result<(), std::io::Error> {
+        if KEYBOARD_FLAGS.swap(0, Ordering::SeqCst) != 0 {
+            execute!(self.backend_mut(), PopKeyboardEnhancementFlags)?;
+        }
+
+        Ok(())
+    }
 }
@@ -738,6 +738,13 @@ where
                 return Ok(info);
             },
 
+            EditAction::Fold => {
+                let msg = "Lists cannot be folded".to_string();
+                let err = EditError::Unimplemented(msg);
+
+                return Err(err);
+            },
+
             // Everything else is a modifying action.
             EditAction::ChangeCase(_) => Err(EditError::ReadOnly),
             EditAction::ChangeNumber(_, _) => Err(EditError::ReadOnly),
@@ -746,6 +753,12 @@ where
             EditAction::Indent(_) => Err(EditError::ReadOnly),
             EditAction::Join(_) => Err(EditError::ReadOnly),
             EditAction::Replace(_) => Err(EditError::ReadOnly),
+            EditAction::Surround(_) => Err(EditError::ReadOnly),
+            EditAction::Rectangle(_) => Err(EditError::ReadOnly),
+            EditAction::Transpose(_, _) => Err(EditError::ReadOnly),
+            EditAction::SortLines => Err(EditError::ReadOnly),
+            EditAction::TrimTrailingWhitespace => Err(EditError::ReadOnly),
+            EditAction::Application(_) => Err(EditError::ReadOnly),
         }
     }
 
@@ -1368,7 +1381,9 @@ mod tests {
 
                     return Ok(vec![(act.into(), ctx.clone())]);
                 },
-                PromptAction::Abort(_) | PromptAction::Recall(_, _, _) => {
+                PromptAction::Abort(_) |
+                PromptAction::Recall(_, _, _) |
+                PromptAction::InsertCursorWord(_) => {
                     let msg = "";
                     let err = EditError::Unimplemented(msg.into());
 
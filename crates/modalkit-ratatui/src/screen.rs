@@ -22,6 +22,7 @@ use ratatui::{
 use super::{
     cmdbar::{CommandBar, CommandBarState},
     util::{rect_down, rect_zero_height},
+    whichkey::{WhichKey, WhichKeyState},
     windows::{WindowActions, WindowLayout, WindowLayoutRoot, WindowLayoutState},
     TerminalCursor,
     Window,
@@ -30,6 +31,7 @@ use super::{
 
 use modalkit::actions::*;
 use modalkit::errors::{EditResult, UIError, UIResult};
+use modalkit::keybindings::MappingScope;
 use modalkit::prelude::*;
 use modalkit::ui::FocusList;
 
@@ -293,6 +295,7 @@ where
     focused: CurrentFocus,
     cmdbar: CommandBarState<I>,
     tabs: FocusList<WindowLayoutState<W, I>>,
+    which_key: WhichKeyState,
 
     messages: Vec<(String, Style)>,
     last_message: bool,
@@ -317,6 +320,7 @@ where
             focused: CurrentFocus::Window,
             cmdbar,
             tabs,
+            which_key: WhichKeyState::default(),
 
             messages: vec![],
             last_message: false,
@@ -390,6 +394,12 @@ where
         }
     }
 
+    /// Get a mutable reference to the state for the pending key hints shown above the command
+    /// bar.
+    pub fn which_key(&mut self) -> &mut WhichKeyState {
+        &mut self.which_key
+    }
+
     /// Get a reference to the window layout for the current tab.
     pub fn current_tab(&self) -> UIResult<&WindowLayoutState<W, I>, I> {
         self.tabs.get().ok_or(UIError::NoTab)
@@ -405,6 +415,22 @@ where
         self.tabs.get().and_then(WindowLayoutState::get)
     }
 
+    /// Get the scopes whose mappings should currently apply, to pass along to
+    /// [BindingMachine::set_focus](modalkit::keybindings::BindingMachine::set_focus).
+    ///
+    /// While the command bar has focus, only global mappings apply.
+    pub fn focused_scopes(&self) -> Vec<MappingScope>
+    where
+        I::WindowId: Sync + 'static,
+    {
+        match self.focused {
+            CurrentFocus::Command => vec![],
+            CurrentFocus::Window => {
+                self.current_window().map(Window::mapping_scopes).unwrap_or_default()
+            },
+        }
+    }
+
     /// Get a mutable reference to the currently focused window.
     pub fn current_window_mut(&mut self) -> UIResult<&mut W, I> {
         self.current_tab_mut()?.get_mut().ok_or(UIError::NoWindow)
@@ -594,6 +620,41 @@ macro_rules! delegate_focus {
     };
 }
 
+impl<W, I> ScreenState<W, I>
+where
+    W: Window<I> + Editable<EditContext, Store<I>, I>,
+    I: ApplicationInfo,
+{
+    /// Move the cursor in the current window to the first match for an incremental search
+    /// that's being typed.
+    fn incsearch(&mut self, ctx: &EditContext, store: &mut Store<I>) -> EditResult<EditInfo, I> {
+        if !self.cmdbar.is_incremental_search() {
+            return Ok(None);
+        }
+
+        let preview = self.cmdbar.get_search_preview();
+        let found = preview.is_some();
+        store.registers.set_search_preview(preview);
+
+        let Ok(window) = self.current_window_mut() else {
+            return Ok(None);
+        };
+
+        let ret = EditTarget::CharJump(Specifier::Exact(Mark::SearchStart));
+        let ret = EditorAction::Edit(Specifier::Exact(EditAction::Motion), ret);
+        window.editor_command(&ret, ctx, store)?;
+
+        if found {
+            let target = EditTarget::Search(SearchType::Regex, MoveDirMod::Same, Count::Exact(1));
+            let search = EditorAction::Edit(Specifier::Exact(EditAction::Motion), target);
+
+            window.editor_command(&search, ctx, store)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 impl<W, I> Editable<EditContext, Store<I>, I> for ScreenState<W, I>
 where
     W: Window<I> + Editable<EditContext, Store<I>, I>,
@@ -605,7 +666,17 @@ where
         ctx: &EditContext,
         store: &mut Store<I>,
     ) -> EditResult<EditInfo, I> {
-        delegate_focus!(self, f => f.editor_command(act, ctx, store))
+        match self.focused {
+            CurrentFocus::Command => {
+                let info = self.cmdbar.editor_command(act, ctx, store)?;
+
+                // Move the cursor to the match as the search gets typed.
+                self.incsearch(ctx, store)?;
+
+                Ok(info)
+            },
+            CurrentFocus::Window => delegate_focus!(self, f => f.editor_command(act, ctx, store)),
+        }
     }
 }
 
@@ -646,7 +717,7 @@ where
 
 impl<W, I> Promptable<EditContext, Store<I>, I> for ScreenState<W, I>
 where
-    W: Window<I> + Promptable<EditContext, Store<I>, I>,
+    W: Window<I> + Editable<EditContext, Store<I>, I> + Promptable<EditContext, Store<I>, I>,
     I: ApplicationInfo,
 {
     fn prompt(
@@ -655,7 +726,32 @@ where
         ctx: &EditContext,
         store: &mut Store<I>,
     ) -> EditResult<Vec<(Action<I>, EditContext)>, I> {
-        delegate_focus!(self, f => f.prompt(act, ctx, store))
+        if self.focused != CurrentFocus::Command {
+            return delegate_focus!(self, f => f.prompt(act, ctx, store));
+        }
+
+        match act {
+            PromptAction::InsertCursorWord(style) => {
+                let Some(word) = self.current_window().and_then(|w| w.get_cursor_word(style)) else {
+                    return Ok(vec![]);
+                };
+
+                let act = InsertTextAction::Transcribe(word, MoveDir1D::Previous, 1.into());
+                let _ = self.cmdbar.editor_command(&act.into(), ctx, store)?;
+                let _ = self.incsearch(ctx, store)?;
+
+                Ok(vec![])
+            },
+            PromptAction::Abort(_) | PromptAction::Submit => {
+                let acts = self.cmdbar.prompt(act, ctx, store)?;
+
+                // Stop highlighting and searching for what was being typed.
+                store.registers.set_search_preview(None);
+
+                Ok(acts)
+            },
+            _ => self.cmdbar.prompt(act, ctx, store),
+        }
     }
 }
 
@@ -708,6 +804,7 @@ where
     cmdbar_prompt_style: Option<Style>,
     tab_style: Style,
     tab_style_focused: Style,
+    which_key_style: Style,
     divider: Span<'a>,
     focused: bool,
 
@@ -733,6 +830,7 @@ where
             cmdbar_prompt_style: None,
             tab_style: Style::default(),
             tab_style_focused: Style::default(),
+            which_key_style: Style::default(),
             divider: Span::raw("|"),
             focused: true,
             _p: PhantomData,
@@ -787,6 +885,12 @@ where
         self
     }
 
+    /// What [Style] should be used when showing the keys that can follow a pending key sequence.
+    pub fn which_key_style(mut self, style: Style) -> Self {
+        self.which_key_style = style;
+        self
+    }
+
     /// Set the divider [Span] to place in between tab names.
     ///
     /// This defaults to an unstyled "|".
@@ -894,9 +998,14 @@ where
             .select(state.tabs.pos())
             .render(tabarea, buf);
 
+        // While a search is being typed, preview its next match in the current window.
+        let searching = focused == CurrentFocus::Command && state.cmdbar.is_search();
+        let preview = searching.then(|| state.cmdbar.get_search_preview()).flatten();
+        self.store.registers.set_search_preview(preview);
+
         if let Ok(tab) = state.current_tab_mut() {
             WindowLayout::new(self.store)
-                .focus(self.focused && focused == CurrentFocus::Window)
+                .focus(self.focused && (focused == CurrentFocus::Window || searching))
                 .border_style(self.border_style)
                 .border_style_focused(self.border_style_focused)
                 .border_type(self.border_type)
@@ -931,6 +1040,18 @@ where
             .prompt_style(self.cmdbar_prompt_style.unwrap_or(self.cmdbar_style))
             .render(cmdarea, buf, &mut state.cmdbar);
 
+        // Show the keys that can follow a pending sequence over the bottom of the windows.
+        let hintsh = WhichKey::height(&state.which_key, area.width).min(winarea.height);
+
+        if hintsh > 0 {
+            let y = winarea.bottom() - hintsh;
+            let hintarea = Rect::new(winarea.x, y, winarea.width, hintsh);
+
+            WhichKey::new()
+                .style(self.which_key_style)
+                .render(hintarea, buf, &mut state.which_key);
+        }
+
         // Render completion list last so it's drawn on top of the windows.
         if let Some(ref mut completions) = compls {
             match completions.display {
@@ -8,6 +8,8 @@
 //! - Toggling wrapped and non-wrapped views of the buffer's
 //! - Scrolling through the buffer's contents
 //! - Rendering line annotations in left and right gutters
+//! - Collapsing closed folds into a single summary line
+//! - Highlighting search matches and misspelled words
 //!
 //! [EditBuffer]: modalkit::editing::buffer::EditBuffer
 //!
@@ -36,11 +38,14 @@
 use std::convert::TryInto;
 use std::iter::Iterator;
 use std::marker::PhantomData;
+use std::ops::Range;
+
+use regex::Regex;
 
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Modifier, Style},
+    style::{Color, Modifier, Style},
     text::Span,
     widgets::{Block, StatefulWidget, Widget},
 };
@@ -48,12 +53,12 @@ use ratatui::{
 use modalkit::actions::*;
 use modalkit::editing::{
     application::{ApplicationInfo, EmptyInfo},
-    buffer::{CursorGroupId, FollowersInfo, HighlightInfo},
+    buffer::{CursorGroupId, EditBuffer, FoldInfo, FoldMethod, FollowersInfo, HighlightInfo},
     completion::CompletionList,
     context::{EditContext, Resolve},
     cursor::Cursor,
     rope::{CharOff, EditRope},
-    store::{SharedBuffer, Store},
+    store::{SharedBuffer, SpellStore, Store},
 };
 use modalkit::errors::{EditError, EditResult, UIResult};
 use modalkit::prelude::*;
@@ -114,6 +119,14 @@ pub struct TextBox<'a, I: ApplicationInfo = EmptyInfo> {
     oneline: bool,
     style: Style,
 
+    search: Option<Regex>,
+    search_preview: Option<(Regex, MoveDir1D)>,
+    search_style: Style,
+    incsearch_style: Style,
+    spell: Option<&'a SpellStore>,
+    spell_style: Style,
+    fold_style: Style,
+
     lgutter_width: u16,
     rgutter_width: u16,
 
@@ -157,6 +170,25 @@ fn shift_corner_oneline(cursor: &Cursor, corner: &mut Cursor) {
     }
 }
 
+/*
+ * If the viewport corner is inside of a closed fold, move it to the start of the fold so that the
+ * fold's summary line gets shown.
+ */
+fn shift_corner_folds(corner: &mut Cursor, folds: &FoldInfo) {
+    if let Some((start, _)) = closed_fold(folds, corner.y) {
+        corner.set_y(start);
+        corner.set_x(0);
+    }
+}
+
+fn closed_fold(folds: &FoldInfo, line: usize) -> Option<(usize, usize)> {
+    folds.query_point(line).next().map(|fold| fold.value)
+}
+
+fn fold_summary(first: &str, start: usize, end: usize) -> String {
+    format!("+--{:>3} lines: {}", end - start + 1, first.trim())
+}
+
 fn shift_corner(
     viewctx: &mut ViewportContext<Cursor>,
     cursor: &Cursor,
@@ -265,6 +297,11 @@ where
         self.buffer.write().unwrap().set_line_info(line, info);
     }
 
+    /// Change how folds get created in this text box.
+    pub fn set_fold_method(&mut self, method: FoldMethod) {
+        self.buffer.write().unwrap().set_fold_method(self.group_id, method);
+    }
+
     /// Control whether the text box should wrap long lines when displaying them.
     pub fn set_wrap(&mut self, wrap: bool) {
         self.viewctx.set_wrap(wrap);
@@ -599,8 +636,11 @@ where
         }
     }
 
-    fn draw(&mut self, area: Rect, buf: &mut Buffer, _: bool, _: &mut Store<I>) {
-        TextBox::new().render(area, buf, self);
+    fn draw(&mut self, area: Rect, buf: &mut Buffer, focused: bool, store: &mut Store<I>) {
+        TextBox::new()
+            .highlight_search(store, focused)
+            .highlight_spelling(&store.spell)
+            .render(area, buf, self);
     }
 
     fn get_completions(&self) -> Option<CompletionList> {
@@ -628,6 +668,14 @@ where
             oneline: false,
             style: Style::default(),
 
+            search: None,
+            search_preview: None,
+            search_style: Style::default().fg(Color::Black).bg(Color::Yellow),
+            incsearch_style: Style::default().add_modifier(Modifier::REVERSED),
+            spell: None,
+            spell_style: Style::default().fg(Color::Red).add_modifier(Modifier::UNDERLINED),
+            fold_style: Style::default().fg(Color::Blue).bg(Color::Gray),
+
             lgutter_width: 0,
             rgutter_width: 0,
 
@@ -674,6 +722,64 @@ where
         self
     }
 
+    /// Highlight the matches of the last search pattern saved in the [Store].
+    ///
+    /// If `preview` is true, then the next match of a search that is currently being typed into
+    /// the command bar will also be highlighted.
+    pub fn highlight_search(mut self, store: &Store<I>, preview: bool) -> Self {
+        self.search = store
+            .registers
+            .get_search_highlight()
+            .and_then(|needle| Regex::new(needle.to_string().as_str()).ok());
+
+        self.search_preview = store.registers.get_search_preview().and_then(|(needle, dir)| {
+            let needle = Regex::new(needle.to_string().as_str()).ok()?;
+
+            preview.then_some((needle, *dir))
+        });
+
+        self
+    }
+
+    /// Set the style to use for highlighting matches of the last search.
+    pub fn search_style(mut self, style: Style) -> Self {
+        self.search_style = style;
+        self
+    }
+
+    /// Set the style to use for highlighting the next match of an in-progress search.
+    pub fn incsearch_style(mut self, style: Style) -> Self {
+        self.incsearch_style = style;
+        self
+    }
+
+    /// Highlight the words that the [SpellStore] considers misspelled.
+    ///
+    /// Nothing will be highlighted while spell checking is disabled.
+    pub fn highlight_spelling(mut self, spell: &'a SpellStore) -> Self {
+        self.spell = Some(spell);
+        self
+    }
+
+    /// Set the style to use for highlighting misspelled words.
+    pub fn spell_style(mut self, style: Style) -> Self {
+        self.spell_style = style;
+        self
+    }
+
+    /// Set the style to use for the summary lines of closed folds.
+    pub fn fold_style(mut self, style: Style) -> Self {
+        self.fold_style = style;
+        self
+    }
+
+    #[inline]
+    fn _render_fold(&self, summary: String, (x, y): (u16, u16), width: u16, buf: &mut Buffer) {
+        buf.set_style(Rect::new(x, y, width, 1), self.fold_style);
+
+        let _ = buf.set_stringn(x, y, summary, width as usize, self.fold_style);
+    }
+
     #[inline]
     fn _highlight_followers(
         &self,
@@ -696,11 +802,18 @@ where
     }
 
     #[inline]
-    fn _set_style(&self, start: usize, h1: usize, h2: usize, (x, y): (u16, u16), buf: &mut Buffer) {
+    fn _set_style(
+        &self,
+        start: usize,
+        h1: usize,
+        h2: usize,
+        (x, y): (u16, u16),
+        hlstyled: Style,
+        buf: &mut Buffer,
+    ) {
         let tx: u16 = x + (h1 - start) as u16;
         let selwidth: u16 = (h2 - h1 + 1).try_into().unwrap();
 
-        let hlstyled = self.style.add_modifier(Modifier::REVERSED);
         let selarea = Rect::new(tx, y, selwidth, 1);
 
         buf.set_style(selarea, hlstyled);
@@ -714,6 +827,7 @@ where
         end: usize,
         (x, y): (u16, u16),
         hls: &HighlightInfo,
+        hlstyled: Style,
         buf: &mut Buffer,
     ) {
         for selection in hls.query_point(line) {
@@ -732,11 +846,10 @@ where
                     };
 
                     if range.contains(&x1) && range.contains(&x2) {
-                        self._set_style(start, x1, x2, (x, y), buf);
+                        self._set_style(start, x1, x2, (x, y), hlstyled, buf);
                     }
                 },
                 TargetShape::LineWise => {
-                    let hlstyled = self.style.add_modifier(Modifier::REVERSED);
                     let selwidth: u16 = (end - start).try_into().unwrap();
                     let selarea = Rect::new(x, y, selwidth, 1);
 
@@ -750,7 +863,7 @@ where
                     let x2 = rx.min(maxcol);
 
                     if range.contains(&x1) && range.contains(&x2) {
-                        self._set_style(start, x1, x2, (x, y), buf);
+                        self._set_style(start, x1, x2, (x, y), hlstyled, buf);
                     }
                 },
             }
@@ -764,6 +877,10 @@ where
         buf: &mut Buffer,
         hinfo: HighlightInfo,
         finfo: FollowersInfo,
+        sinfo: HighlightInfo,
+        pinfo: HighlightInfo,
+        cinfo: HighlightInfo,
+        folds: FoldInfo,
         state: &mut TextBoxState<I>,
     ) {
         let bot = area.bottom();
@@ -781,11 +898,13 @@ where
          */
         let cursor = state.get_cursor();
         shift_corner_wrap(&cursor, &mut state.viewctx.corner, height);
+        shift_corner_folds(&mut state.viewctx.corner, &folds);
 
         let cby = state.viewctx.corner.y;
         let cbx = state.viewctx.corner.x;
 
         let text = state.buffer.read().unwrap();
+        let selstyle = self.style.add_modifier(Modifier::REVERSED);
 
         let mut wrapped = Vec::new();
         let mut sawcursor = false;
@@ -795,8 +914,21 @@ where
                 break;
             }
 
-            let base = if loff == 0 { cbx } else { 0 };
             let line = cby + loff;
+
+            if let Some((fstart, fend)) = closed_fold(&folds, line) {
+                if line == fstart {
+                    let cursor_line = (fstart..=fend).contains(&cursor.y);
+                    let summary = fold_summary(&s.to_string(), fstart, fend);
+
+                    wrapped.push((line, 0, 0, summary, cursor_line, true, true));
+                    sawcursor |= cursor_line;
+                }
+
+                continue;
+            }
+
+            let base = if loff == 0 { cbx } else { 0 };
             let mut first = true;
             let mut off = 0;
             let slen = s.len();
@@ -816,10 +948,10 @@ where
                 let cursor_line = line == cursor.y && ((start..end).contains(&cursor.x) || last);
 
                 if cursor_line && full && last {
-                    wrapped.push((line, start, end, swrapped, false, first));
-                    wrapped.push((line, end, end, " ".to_string(), true, first));
+                    wrapped.push((line, start, end, swrapped, false, first, false));
+                    wrapped.push((line, end, end, " ".to_string(), true, first, false));
                 } else {
-                    wrapped.push((line, start, end, swrapped, cursor_line, first));
+                    wrapped.push((line, start, end, swrapped, cursor_line, first, false));
                 }
 
                 sawcursor |= cursor_line;
@@ -829,7 +961,7 @@ where
 
             if slen == 0 {
                 let cursor_line = line == cursor.y;
-                wrapped.push((line, base, base, s.to_string(), cursor_line, true));
+                wrapped.push((line, base, base, s.to_string(), cursor_line, true, false));
                 sawcursor |= cursor_line;
             }
         }
@@ -837,12 +969,12 @@ where
         if wrapped.len() > height {
             let n = wrapped.len() - height;
             let _ = wrapped.drain(..n);
-            let (line, start, _, _, _, _) = wrapped.first().unwrap();
+            let (line, start, _, _, _, _, _) = wrapped.first().unwrap();
             state.viewctx.corner.set_y(*line);
             state.viewctx.corner.set_x(*start);
         }
 
-        for (line, start, end, s, cursor_line, first) in wrapped.into_iter() {
+        for (line, start, end, s, cursor_line, first, folded) in wrapped.into_iter() {
             if y >= bot {
                 break;
             }
@@ -862,6 +994,17 @@ where
                 }
             }
 
+            if folded {
+                if cursor_line {
+                    state.term_cursor = (x, y);
+                }
+
+                self._render_fold(s, (x, y), area.width, buf);
+
+                y += 1;
+                continue;
+            }
+
             if cursor_line {
                 let coff = s[..s
                     .char_indices()
@@ -876,7 +1019,10 @@ where
             let _ = buf.set_stringn(x, y, s, width, self.style);
 
             self._highlight_followers(line, start, end, (x, y), &finfo, buf);
-            self._highlight_line(line, start, end, (x, y), &hinfo, buf);
+            self._highlight_line(line, start, end, (x, y), &cinfo, self.spell_style, buf);
+            self._highlight_line(line, start, end, (x, y), &sinfo, self.search_style, buf);
+            self._highlight_line(line, start, end, (x, y), &pinfo, self.incsearch_style, buf);
+            self._highlight_line(line, start, end, (x, y), &hinfo, selstyle, buf);
 
             y += 1;
         }
@@ -888,6 +1034,9 @@ where
         buf: &mut Buffer,
         hinfo: HighlightInfo,
         finfo: FollowersInfo,
+        sinfo: HighlightInfo,
+        pinfo: HighlightInfo,
+        cinfo: HighlightInfo,
         state: &mut TextBoxState<I>,
     ) {
         let right = area.right();
@@ -904,6 +1053,7 @@ where
         let cbx = state.viewctx.corner.x;
 
         let text = state.buffer.read().unwrap();
+        let selstyle = self.style.add_modifier(Modifier::REVERSED);
 
         let mut joined = Vec::new();
         let mut sawcursor = false;
@@ -1026,7 +1176,10 @@ where
             let (xres, _) = buf.set_stringn(x, y, s, w, self.style);
 
             self._highlight_followers(line, start, end, (x, y), &finfo, buf);
-            self._highlight_line(line, start, end, (x, y), &hinfo, buf);
+            self._highlight_line(line, start, end, (x, y), &cinfo, self.spell_style, buf);
+            self._highlight_line(line, start, end, (x, y), &sinfo, self.search_style, buf);
+            self._highlight_line(line, start, end, (x, y), &pinfo, self.incsearch_style, buf);
+            self._highlight_line(line, start, end, (x, y), &hinfo, selstyle, buf);
 
             x = xres;
         }
@@ -1039,6 +1192,10 @@ where
         buf: &mut Buffer,
         hinfo: HighlightInfo,
         finfo: FollowersInfo,
+        sinfo: HighlightInfo,
+        pinfo: HighlightInfo,
+        cinfo: HighlightInfo,
+        folds: FoldInfo,
         state: &mut TextBoxState<I>,
     ) {
         let bot = area.bottom();
@@ -1051,11 +1208,13 @@ where
         // If the cursor has moved off-screen, update the viewport corner.
         let cursor = state.get_cursor();
         shift_corner_nowrap(&cursor, &mut state.viewctx.corner, width, height);
+        shift_corner_folds(&mut state.viewctx.corner, &folds);
 
         let cby = state.viewctx.corner.y;
         let cbx = state.viewctx.corner.x;
 
         let text = state.buffer.read().unwrap();
+        let selstyle = self.style.add_modifier(Modifier::REVERSED);
         let mut line = cby;
         let mut lines = text.lines(line);
 
@@ -1073,6 +1232,29 @@ where
                     lgi.render(lga, buf);
                 }
 
+                if let Some((fstart, fend)) = closed_fold(&folds, line) {
+                    if (fstart..=fend).contains(&cursor.y) {
+                        state.term_cursor = (x, y);
+                    }
+
+                    if let Some(rgi) = rgutter {
+                        let rga = Rect::new(gutters.1.x, y, gutters.1.width, 0);
+                        rgi.render(rga, buf);
+                    }
+
+                    let summary = fold_summary(&s.to_string(), fstart, fend);
+                    self._render_fold(summary, (x, y), area.width, buf);
+
+                    // Skip over the rest of the lines hidden by the fold.
+                    if fend > line {
+                        let _ = lines.nth(fend - line - 1);
+                    }
+
+                    y += 1;
+                    line = fend + 1;
+                    continue;
+                }
+
                 let s = s.slice(CharOff::from(start)..CharOff::from(end)).to_string();
 
                 if line == cursor.y && (start..=end).contains(&cursor.x) {
@@ -1096,7 +1278,10 @@ where
                 }
 
                 self._highlight_followers(line, start, end, (x, y), &finfo, buf);
-                self._highlight_line(line, start, end, (x, y), &hinfo, buf);
+                self._highlight_line(line, start, end, (x, y), &cinfo, self.spell_style, buf);
+                self._highlight_line(line, start, end, (x, y), &sinfo, self.search_style, buf);
+                self._highlight_line(line, start, end, (x, y), &pinfo, self.incsearch_style, buf);
+                self._highlight_line(line, start, end, (x, y), &hinfo, selstyle, buf);
 
                 y += 1;
                 line += 1;
@@ -1116,13 +1301,76 @@ where
         state.buffer.write().unwrap().follower_intervals(state.group_id)
     }
 
+    #[inline]
+    fn _fold_intervals(&self, state: &mut TextBoxState<I>) -> FoldInfo {
+        state.buffer.write().unwrap().fold_intervals(state.group_id)
+    }
+
+    fn _highlight_window(
+        &self,
+        buffer: &mut EditBuffer<I>,
+        state: &TextBoxState<I>,
+        height: usize,
+    ) -> (usize, usize) {
+        if self.oneline {
+            return (0, buffer.get_lines());
+        }
+
+        // The viewport may shift while rendering to keep the cursor onscreen, so we need to
+        // look at the lines around both the current corner and the cursor.
+        let cursor = buffer.get_leader(state.group_id);
+        let corner = state.viewctx.corner.y;
+        let start = corner.min(cursor.y.saturating_sub(height));
+        let end = corner.max(cursor.y).saturating_add(height);
+
+        (start, end)
+    }
+
+    fn _search_intervals(&self, state: &mut TextBoxState<I>, height: usize) -> HighlightInfo {
+        let Some(needle) = &self.search else {
+            return std::iter::empty::<(Range<usize>, _)>().collect();
+        };
+
+        let mut buffer = state.buffer.write().unwrap();
+        let (start, end) = self._highlight_window(&mut buffer, state, height);
+
+        buffer.search_intervals(needle, start, end)
+    }
+
+    fn _spell_intervals(&self, state: &mut TextBoxState<I>, height: usize) -> HighlightInfo {
+        let Some(spell) = self.spell else {
+            return std::iter::empty::<(Range<usize>, _)>().collect();
+        };
+
+        let mut buffer = state.buffer.write().unwrap();
+        let (start, end) = self._highlight_window(&mut buffer, state, height);
+
+        buffer.spell_intervals(spell, start, end)
+    }
+
+    #[inline]
+    fn _search_preview_intervals(&self, state: &mut TextBoxState<I>) -> HighlightInfo {
+        let Some((needle, dir)) = &self.search_preview else {
+            return std::iter::empty::<(Range<usize>, _)>().collect();
+        };
+
+        state
+            .buffer
+            .write()
+            .unwrap()
+            .search_preview_intervals(state.group_id, needle, *dir)
+    }
+
     fn _render_lines(&mut self, area: Rect, buf: &mut Buffer, state: &mut TextBoxState<I>) {
         let hinfo = self._selection_intervals(state);
         let finfo = self._follower_intervals(state);
+        let sinfo = self._search_intervals(state, area.height as usize);
+        let pinfo = self._search_preview_intervals(state);
+        let cinfo = self._spell_intervals(state, area.height as usize);
 
         if self.oneline {
             state.set_term_info(area);
-            self._render_lines_oneline(area, buf, hinfo, finfo, state);
+            self._render_lines_oneline(area, buf, hinfo, finfo, sinfo, pinfo, cinfo, state);
             return;
         }
 
@@ -1139,10 +1387,16 @@ where
 
         state.set_term_info(texta);
 
+        let folds = self._fold_intervals(state);
+
         if state.viewctx.wrap {
-            self._render_lines_wrap(texta, gutters, buf, hinfo, finfo, state);
+            self._render_lines_wrap(
+                texta, gutters, buf, hinfo, finfo, sinfo, pinfo, cinfo, folds, state,
+            );
         } else {
-            self._render_lines_nowrap(texta, gutters, buf, hinfo, finfo, state);
+            self._render_lines_nowrap(
+                texta, gutters, buf, hinfo, finfo, sinfo, pinfo, cinfo, folds, state,
+            );
         }
     }
 }
@@ -1195,6 +1449,7 @@ mod tests {
     use super::*;
     use modalkit::editing::store::Store;
     use modalkit::env::vim::VimState;
+    use ratatui::buffer::Cell;
 
     macro_rules! mv {
         ($mt: expr) => {
@@ -1581,4 +1836,166 @@ mod tests {
         assert_eq!(tbox.get_cursor(), Cursor::new(0, 7));
         assert_eq!(tbox.get_term_cursor(), (14, 0).into());
     }
+
+    #[test]
+    fn test_render_search_highlight() {
+        let (mut tbox, _, mut store) = mkboxstr("foo bar foo\nbaz foo\n");
+        let search = |cell: &Cell| cell.bg == Color::Yellow;
+        let incsearch = |cell: &Cell| cell.modifier.contains(Modifier::REVERSED);
+
+        let area = Rect::new(0, 0, 12, 2);
+        let mut buffer = Buffer::empty(area);
+
+        let highlighted = |buffer: &Buffer, f: &dyn Fn(&Cell) -> bool| {
+            let mut cells = vec![];
+
+            for y in 0..2 {
+                for x in 0..12 {
+                    if f(&buffer[(x, y)]) {
+                        cells.push((x, y));
+                    }
+                }
+            }
+
+            cells
+        };
+
+        // Nothing is highlighted before searching.
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(highlighted(&buffer, &search), vec![]);
+
+        // Every match of the last search gets highlighted.
+        store.registers.set_last_search("foo");
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(highlighted(&buffer, &search), vec![
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (8, 0),
+            (9, 0),
+            (10, 0),
+            (4, 1),
+            (5, 1),
+            (6, 1),
+        ]);
+
+        // Highlighting stops after clearing it.
+        let mut buffer = Buffer::empty(area);
+        store.registers.set_search_highlight(false);
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(highlighted(&buffer, &search), vec![]);
+
+        // An in-progress search previews its next match in the focused window.
+        let mut buffer = Buffer::empty(area);
+        store.registers.set_search_preview(Some(("ba".into(), MoveDir1D::Next)));
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(highlighted(&buffer, &incsearch), vec![(4, 0), (5, 0)]);
+
+        // But not in windows that aren't focused.
+        let mut buffer = Buffer::empty(area);
+        tbox.draw(area, &mut buffer, false, &mut store);
+        assert_eq!(highlighted(&buffer, &incsearch), vec![]);
+
+        // Searching again turns highlighting back on.
+        let mut buffer = Buffer::empty(area);
+        store.registers.set_search_preview(None);
+        store.registers.set_last_search("baz");
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(highlighted(&buffer, &search), vec![(0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_render_spelling() {
+        let (mut tbox, _, mut store) = mkboxstr("helo world\nwrold\n");
+        let misspelled = |cell: &Cell| cell.modifier.contains(Modifier::UNDERLINED);
+
+        let area = Rect::new(0, 0, 12, 2);
+
+        let highlighted = |buffer: &Buffer| {
+            let mut cells = vec![];
+
+            for y in 0..2 {
+                for x in 0..12 {
+                    if misspelled(&buffer[(x, y)]) {
+                        cells.push((x, y));
+                    }
+                }
+            }
+
+            cells
+        };
+
+        store.spell.add_words(["hello", "world"]);
+
+        // Nothing is highlighted while spell checking is disabled.
+        let mut buffer = Buffer::empty(area);
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(highlighted(&buffer), vec![]);
+
+        // Unknown words get highlighted once it's enabled.
+        let mut buffer = Buffer::empty(area);
+        store.spell.set_enabled(true);
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(highlighted(&buffer), vec![
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (3, 0),
+            (0, 1),
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (4, 1),
+        ]);
+
+        // Words marked as good stop being highlighted.
+        let mut buffer = Buffer::empty(area);
+        store.spell.mark_good("helo", true).unwrap();
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(highlighted(&buffer), vec![(0, 1), (1, 1), (2, 1), (3, 1), (4, 1)]);
+    }
+
+    #[test]
+    fn test_render_folds() {
+        let (mut tbox, ctx, mut store) = mkboxstr("a {\n  b\n  c\n}\nd\n");
+        let area = Rect::new(0, 0, 20, 3);
+
+        let rows = |buffer: &Buffer| {
+            (0..3)
+                .map(|y| (0..20).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+                .map(|row| row.trim_end().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Closed folds collapse into a single summary line.
+        tbox.set_fold_method(FoldMethod::Indent);
+
+        for wrap in [true, false] {
+            let mut buffer = Buffer::empty(area);
+            tbox.set_wrap(wrap);
+            tbox.draw(area, &mut buffer, true, &mut store);
+
+            assert_eq!(rows(&buffer), vec!["a {", "+--  2 lines: b", "}"]);
+            assert_eq!(buffer[(0, 1)].fg, Color::Blue);
+            assert_eq!(buffer[(19, 1)].bg, Color::Gray);
+        }
+
+        // Moving onto the fold places the terminal cursor on its summary.
+        tbox.set_wrap(true);
+        let mov = mv!(MoveType::BufferLineOffset, 3);
+        let act = EditorAction::Edit(EditAction::Motion.into(), mov);
+        tbox.editor_command(&act, &ctx, &mut store).unwrap();
+        assert_eq!(tbox.get_cursor(), Cursor::new(2, 2));
+
+        let mut buffer = Buffer::empty(area);
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(tbox.get_term_cursor(), (0, 1).into());
+
+        // Folds are displayed normally once they've been opened.
+        tbox.set_fold_method(FoldMethod::Manual);
+
+        let mut buffer = Buffer::empty(area);
+        tbox.draw(area, &mut buffer, true, &mut store);
+        assert_eq!(rows(&buffer), vec!["a {", "  b", "  c"]);
+    }
 }
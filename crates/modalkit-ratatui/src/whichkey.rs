@@ -0,0 +1,295 @@
+//! # Pending key hints
+//!
+//! ## Overview
+//!
+//! This widget lists the keys that can follow a partially typed key sequence (like `<C-W>` or
+//! `g` in Vim, or `<C-X>r` in Emacs), along with a short description of what each one does. The
+//! hints come from [BindingMachine::pending_hints].
+//!
+//! Hints only appear once a sequence has been waiting for more input for a configurable delay,
+//! so that quickly typed sequences don't cause the hints to flash on screen. Applications should
+//! call [WhichKeyState::update] after processing input keys, and redraw once
+//! [WhichKeyState::time_left] has elapsed.
+//!
+//! Typically, this widget is used indirectly by consumers through [Screen], which draws it above
+//! the command bar.
+//!
+//! [Screen]: super::screen::Screen
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier as StyleModifier, Style},
+    widgets::{Clear, StatefulWidget, Widget},
+};
+
+use unicode_width::UnicodeWidthStr;
+
+use modalkit::keybindings::{BindingMachine, InputKey, KeyHint};
+
+const MAX_WHICHKEY_H: usize = 10;
+const GAP_WHICHKEY_COL: usize = 3;
+
+/// How long a key sequence needs to be pending before [WhichKeyState] shows its hints.
+pub const DEFAULT_WHICHKEY_DELAY: Duration = Duration::from_millis(500);
+
+/// Persistent state for [WhichKey].
+pub struct WhichKeyState {
+    delay: Duration,
+    hints: Vec<KeyHint>,
+    since: Option<Instant>,
+}
+
+impl WhichKeyState {
+    /// Create state that shows hints once keys have been pending for `delay`.
+    pub fn new(delay: Duration) -> Self {
+        WhichKeyState { delay, hints: vec![], since: None }
+    }
+
+    /// How long keys need to be pending before hints are shown.
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Change how long keys need to be pending before hints are shown.
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay;
+    }
+
+    /// Update the hints to reflect the keys currently pending in a [BindingMachine].
+    pub fn update<K, A, S, C>(&mut self, bindings: &impl BindingMachine<K, A, S, C>)
+    where
+        K: InputKey,
+    {
+        self.set_hints(bindings.pending_hints());
+    }
+
+    /// Set the hints to show directly.
+    ///
+    /// The delay restarts whenever the hints change.
+    pub fn set_hints(&mut self, hints: Vec<KeyHint>) {
+        if hints.is_empty() {
+            self.clear();
+        } else if hints != self.hints || self.since.is_none() {
+            self.hints = hints;
+            self.since = Some(Instant::now());
+        }
+    }
+
+    /// Stop showing any hints.
+    pub fn clear(&mut self) {
+        self.hints = vec![];
+        self.since = None;
+    }
+
+    /// Returns the hints that should currently be shown, which is nothing until the delay has
+    /// passed.
+    pub fn hints(&self) -> &[KeyHint] {
+        match self.since {
+            Some(since) if since.elapsed() >= self.delay => self.hints.as_slice(),
+            _ => &[],
+        }
+    }
+
+    /// Returns how much longer until pending hints get shown, so that the application can redraw
+    /// at the right time.
+    ///
+    /// This is [None] when there is nothing waiting to be shown.
+    pub fn time_left(&self) -> Option<Duration> {
+        let since = self.since?;
+        let left = self.delay.saturating_sub(since.elapsed());
+
+        (!left.is_zero()).then_some(left)
+    }
+}
+
+impl Default for WhichKeyState {
+    fn default() -> Self {
+        WhichKeyState::new(DEFAULT_WHICHKEY_DELAY)
+    }
+}
+
+/// Row and column information for laying out the hints.
+struct WhichKeyLayout {
+    keyw: usize,
+    colw: usize,
+    cols: usize,
+    rows: usize,
+}
+
+impl WhichKeyLayout {
+    fn new(hints: &[KeyHint], width: u16) -> Self {
+        let width = (width as usize).max(1);
+        let keyw = hints.iter().map(|h| h.key.width()).max().unwrap_or(0);
+        let descw = hints.iter().map(|h| h.desc.width()).max().unwrap_or(0);
+        let colw = (keyw + 1 + descw + GAP_WHICHKEY_COL).clamp(1, width);
+        let cols = (width / colw).max(1);
+        let rows = hints.len().div_ceil(cols).min(MAX_WHICHKEY_H);
+
+        WhichKeyLayout { keyw, colw, cols, rows }
+    }
+}
+
+/// Widget for showing the keys that can follow a pending key sequence.
+pub struct WhichKey {
+    style: Style,
+    key_style: Style,
+}
+
+impl WhichKey {
+    /// Create a new widget.
+    pub fn new() -> Self {
+        WhichKey {
+            style: Style::default(),
+            key_style: Style::default().add_modifier(StyleModifier::BOLD),
+        }
+    }
+
+    /// What [Style] should be used for the hints' background and descriptions.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// What [Style] should be used when drawing each hint's key.
+    pub fn key_style(mut self, style: Style) -> Self {
+        self.key_style = style;
+        self
+    }
+
+    /// How many rows are needed to show the current hints at a given width.
+    pub fn height(state: &WhichKeyState, width: u16) -> u16 {
+        WhichKeyLayout::new(state.hints(), width).rows as u16
+    }
+}
+
+impl Default for WhichKey {
+    fn default() -> Self {
+        WhichKey::new()
+    }
+}
+
+impl StatefulWidget for WhichKey {
+    type State = WhichKeyState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let hints = state.hints();
+
+        if area.height == 0 || hints.is_empty() {
+            return;
+        }
+
+        let layout = WhichKeyLayout::new(hints, area.width);
+        let rows = layout.rows.min(area.height as usize);
+
+        Clear.render(area, buf);
+        buf.set_style(area, self.style);
+
+        let mut iter = hints.iter();
+
+        for x in 0..layout.cols {
+            for y in 0..rows {
+                let hint = match iter.next() {
+                    Some(hint) => hint,
+                    None => return,
+                };
+
+                let x = area.x + (x * layout.colw) as u16;
+                let y = area.y + y as u16;
+                let maxw = layout.colw.saturating_sub(GAP_WHICHKEY_COL);
+                let key = format!("{:w$} ", hint.key, w = layout.keyw);
+
+                let (x, _) = buf.set_stringn(x, y, key, maxw, self.key_style);
+                let maxw = maxw.saturating_sub(layout.keyw + 1);
+                buf.set_stringn(x, y, &hint.desc, maxw, self.style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use modalkit::editing::application::EmptyInfo;
+    use modalkit::env::{
+        emacs::keybindings::default_emacs_keys,
+        vim::keybindings::default_vim_keys,
+    };
+
+    fn hint(key: &str, desc: &str) -> KeyHint {
+        KeyHint { key: key.into(), desc: desc.into() }
+    }
+
+    #[test]
+    fn test_pending_hints() {
+        let mut vm = default_vim_keys::<EmptyInfo>();
+
+        vm.input_key("<C-W>".parse().unwrap());
+
+        let hints = vm.pending_hints();
+        assert!(hints.contains(&hint("s", "split window")));
+        assert!(hints.contains(&hint("<C-W>", "next window")));
+        assert!(hints.contains(&hint("g", "+prefix")));
+        assert!(hints.contains(&hint("f", "split window, edit file under cursor")));
+
+        // Unmapped keys aren't listed.
+        assert!(!hints.iter().any(|h| h.key == "d"));
+
+        let mut em = default_emacs_keys::<EmptyInfo>();
+
+        em.input_key("<C-X>".parse().unwrap());
+
+        let hints = em.pending_hints();
+        assert!(hints.contains(&hint("r", "+prefix")));
+        assert!(hints.contains(&hint("o", "other-window")));
+    }
+
+    #[test]
+    fn test_delay() {
+        let mut vm = default_vim_keys::<EmptyInfo>();
+        let mut state = WhichKeyState::new(Duration::from_secs(3600));
+
+        // Nothing is pending yet.
+        state.update(&vm);
+        assert_eq!(state.hints(), &[]);
+        assert_eq!(state.time_left(), None);
+
+        // The hints wait for the delay.
+        vm.input_key("<C-W>".parse().unwrap());
+        state.update(&vm);
+        assert_eq!(state.hints(), &[]);
+        assert!(state.time_left().is_some());
+
+        // Once the delay is over, the hints are shown.
+        state.set_delay(Duration::ZERO);
+        assert!(state.hints().contains(&hint("v", "split window vertically")));
+        assert_eq!(state.time_left(), None);
+
+        // Finishing the sequence hides them.
+        vm.input_key("v".parse().unwrap());
+        state.update(&vm);
+        assert_eq!(state.hints(), &[]);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut state = WhichKeyState::new(Duration::ZERO);
+        state.set_hints(vec![hint("a", "first"), hint("bc", "second"), hint("d", "third")]);
+
+        let area = Rect::new(0, 0, 24, 2);
+        assert_eq!(WhichKey::height(&state, area.width), 2);
+
+        let mut buffer = Buffer::empty(area);
+        WhichKey::new().render(area, &mut buffer, &mut state);
+
+        let line = |y: u16| {
+            (0..area.width)
+                .map(|x| buffer[(x, y)].symbol().to_string())
+                .collect::<String>()
+        };
+
+        assert_eq!(line(0), "a  first    d  third    ");
+        assert_eq!(line(1), "bc second               ");
+    }
+}
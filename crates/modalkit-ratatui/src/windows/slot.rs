@@ -6,6 +6,7 @@ use modalkit::{
     editing::completion::CompletionList,
     editing::store::Store,
     errors::UIResult,
+    keybindings::MappingScope,
     prelude::*,
 };
 
@@ -227,6 +228,13 @@ where
         self.current.id()
     }
 
+    fn mapping_scopes(&self) -> Vec<MappingScope>
+    where
+        I::WindowId: Sync + 'static,
+    {
+        self.current.mapping_scopes()
+    }
+
     fn get_win_title(&self, store: &mut Store<I>) -> Line {
         self.current.get_win_title(store)
     }
@@ -29,6 +29,25 @@
 //! // Scroll the viewport so that line 10 is at the top of the screen.
 //! let _: Action = Action::Scroll(ScrollStyle::LinePos(MovePosition::Beginning, 10.into()));
 //! ```
+//!
+//! ## Serialization
+//!
+//! When the `serde` feature is enabled, [Action] and the types it's built from in this module and
+//! in [prelude](crate::prelude) implement `Serialize` and `Deserialize`, so that actions can be
+//! sent to other processes, saved to disk, or written in configuration files.
+//!
+//! Actions use serde's default representation for enums: a variant without fields is written as
+//! its name, and a variant with fields is written as a map from its name to its field, or to a
+//! list of its fields if it has more than one. Flag sets like [CloseFlags] are written as their
+//! flag names joined by `|`. For example, deleting three lines looks like this in JSON:
+//!
+//! ```json
+//! {"Editor": {"Edit": [{"Exact": "Delete"}, {"Range": ["Line", true, {"Exact": 3}]}]}}
+//! ```
+//!
+//! Since the names and field order of variants make up the format, renaming or reordering them is
+//! treated as a breaking change. [Action::Application] uses the representation of the
+//! application's own [ApplicationAction] type, and [WordStyle::CharSet] can't be serialized.
 use crate::{
     commands::{Command, CommandMachine},
     editing::application::*,
@@ -41,6 +60,7 @@ use crate::{
 
 /// The various actions that can be taken on text.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum EditAction {
     /// Move the cursor.
     ///
@@ -79,12 +99,43 @@ pub enum EditAction {
 
     /// Change the case of the targeted text.
     ChangeCase(Case),
+
+    /// Create a closed fold over the lines of the targeted text.
+    Fold,
+
+    /// Add, remove or replace the delimiters around the targeted text.
+    ///
+    /// New delimiters come from the [replacement character](EditContext::get_replace_char), where
+    /// opening and closing brackets are expanded into a matching pair.
+    Surround(SurroundChange),
+
+    /// Change the text within a rectangle.
+    Rectangle(RectangleChange),
+
+    /// Swap the text object before the end of the targeted text with the one that follows it.
+    ///
+    /// The [contextual count](Count::Contextual) controls how many objects to drag the preceding
+    /// one past, in the given direction. A count of zero instead swaps the objects at the cursor
+    /// and at the selection's anchor.
+    Transpose(TransposeStyle, MoveDir1D),
+
+    /// Sort the lines of the targeted text.
+    SortLines,
+
+    /// Delete the whitespace at the end of each line of the targeted text.
+    TrimTrailingWhitespace,
+
+    /// Replace the targeted text using an application-defined operator with the given name.
+    ///
+    /// The replacement text comes from [ApplicationInfo::operator].
+    Application(String),
 }
 
 impl EditAction {
     /// Returns true if this [EditAction] doesn't modify a buffer's text.
     pub fn is_readonly(&self) -> bool {
         match self {
+            EditAction::Fold => true,
             EditAction::Motion => true,
             EditAction::Yank => true,
 
@@ -95,6 +146,12 @@ impl EditAction {
             EditAction::Indent(_) => false,
             EditAction::Join(_) => false,
             EditAction::Replace(_) => false,
+            EditAction::Surround(_) => false,
+            EditAction::Rectangle(_) => false,
+            EditAction::Transpose(_, _) => false,
+            EditAction::SortLines => false,
+            EditAction::TrimTrailingWhitespace => false,
+            EditAction::Application(_) => false,
         }
     }
 
@@ -186,6 +243,7 @@ where
 
 /// Selection manipulation
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum SelectionAction {
     /// Duplicate selections [*n* times](Count) to adjacent lines in [MoveDir1D] direction.
@@ -235,6 +293,7 @@ pub enum SelectionAction {
 
 /// Text insertion actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum InsertTextAction {
     /// Insert a new line [shape-wise](TargetShape) before or after the current position.
@@ -252,6 +311,7 @@ pub enum InsertTextAction {
 
 /// Editing history actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum HistoryAction {
     /// Create a new editing history checkpoint.
     Checkpoint,
@@ -263,6 +323,125 @@ pub enum HistoryAction {
     Undo(Count),
 }
 
+/// Fold actions
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
+pub enum FoldAction {
+    /// Close [*n* levels](Count) of folds around the cursor.
+    Close(Count),
+
+    /// Close every fold.
+    CloseAll,
+
+    /// Delete the innermost fold around the cursor.
+    Delete,
+
+    /// Delete every fold.
+    DeleteAll,
+
+    /// Open [*n* levels](Count) of folds around the cursor.
+    Open(Count),
+
+    /// Open every fold.
+    OpenAll,
+
+    /// Open [*n* levels](Count) of folds around the cursor if it is on a closed fold, and
+    /// otherwise close them.
+    Toggle(Count),
+}
+
+/// Spell checking actions
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
+pub enum SpellAction {
+    /// Mark the word under the cursor, or the selected text, as misspelled.
+    ///
+    /// If [bool] is `true`, the word is only remembered until the application exits, and
+    /// otherwise it's saved to the user dictionary.
+    Bad(bool),
+
+    /// Mark the word under the cursor, or the selected text, as correctly spelled.
+    ///
+    /// If [bool] is `true`, the word is only remembered until the application exits, and
+    /// otherwise it's saved to the user dictionary.
+    Good(bool),
+
+    /// Replace the word under the cursor with the given text.
+    Replace(String),
+
+    /// Suggest corrections for the word under the cursor.
+    ///
+    /// If a [count](Count) is given, then the word is replaced with that suggestion. Otherwise,
+    /// the suggestions are shown to the user using [EditError::NeedConfirm].
+    ///
+    /// [EditError::NeedConfirm]: crate::errors::EditError::NeedConfirm
+    Suggest(Count),
+
+    /// Undo marking the word under the cursor, or the selected text, as good or bad.
+    ///
+    /// [bool] indicates whether to look at words remembered until the application exits or at
+    /// those saved to the user dictionary.
+    Undo(bool),
+}
+
+impl SpellAction {
+    /// Returns true if this [SpellAction] doesn't modify a buffer's text.
+    pub fn is_readonly(&self) -> bool {
+        match self {
+            SpellAction::Bad(_) => true,
+            SpellAction::Good(_) => true,
+            SpellAction::Undo(_) => true,
+            SpellAction::Replace(_) => false,
+            SpellAction::Suggest(_) => false,
+        }
+    }
+}
+
+/// Interactive search and replace actions
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
+pub enum ReplaceAction {
+    /// Step through the matches of `Register::LastCommand(CommandType::Search)` within the
+    /// selection, or after the cursor, and ask whether to replace each one with
+    /// `Register::LastCommand(CommandType::Prompt)`. An empty replacement deletes the matches.
+    ///
+    /// If [bool] is `true`, then the pattern is a regular expression and the replacement may
+    /// refer to its capture groups (e.g., `$1`). Otherwise, both are treated as literal text.
+    ///
+    /// The prompt is shown to the user using [EditError::NeedConfirm].
+    ///
+    /// [EditError::NeedConfirm]: crate::errors::EditError::NeedConfirm
+    Start(bool),
+
+    /// Like [ReplaceAction::Start], but replace matches of the first [String] with the second
+    /// instead of using the registers.
+    StartWith(bool, String, String),
+
+    /// Replace the current match, and move on to the next one.
+    Replace,
+
+    /// Skip the current match, and move on to the next one.
+    Skip,
+
+    /// Replace the current match and every one after it.
+    ReplaceAll,
+
+    /// Replace the current match, and then stop.
+    ReplaceAndQuit,
+
+    /// Go back to the previously visited match.
+    Back,
+
+    /// Restore the text of the most recently replaced match, and ask about it again.
+    Undo,
+
+    /// Stop replacing.
+    Quit,
+}
+
 impl HistoryAction {
     /// Returns true if this [HistoryAction] doesn't modify a buffer's text.
     pub fn is_readonly(&self) -> bool {
@@ -276,11 +455,20 @@ impl HistoryAction {
 
 /// Cursor group actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum CursorAction {
     /// Close the [targeted cursors](CursorCloseTarget) in the current cursor group.
     Close(CursorCloseTarget),
 
+    /// Move the cursor leader to the most recent position on a [MarkRing], and then rotate that
+    /// position to the back of the ring.
+    PopMark(MarkRing),
+
+    /// Push the cursor leader's position onto the buffer's [MarkRing], and onto the global one
+    /// if the buffer isn't already its most recent entry.
+    PushMark,
+
     /// Restore a saved cursor group.
     ///
     /// If a combining style is specified, then the saved group will be merged with the current one
@@ -305,9 +493,11 @@ impl CursorAction {
     /// error.
     pub fn is_switchable(&self, _: &EditContext) -> bool {
         match self {
+            CursorAction::PopMark(_) => true,
             CursorAction::Restore(_) => true,
 
             CursorAction::Close(_) => false,
+            CursorAction::PushMark => false,
             CursorAction::Rotate(..) => false,
             CursorAction::Save(_) => false,
             CursorAction::Split(_) => false,
@@ -317,6 +507,7 @@ impl CursorAction {
 
 /// Command actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum CommandAction {
     /// Run a command string.
@@ -382,6 +573,14 @@ where
 
 /// Command bar actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "I::Action: serde::Serialize, I::WindowId: serde::Serialize",
+        deserialize = "I::Action: serde::Deserialize<'de>, I::WindowId: serde::Deserialize<'de>"
+    ))
+)]
 pub enum CommandBarAction<I: ApplicationInfo> {
     /// Focus the command bar
     Focus(String, CommandType, Box<Action<I>>),
@@ -392,6 +591,7 @@ pub enum CommandBarAction<I: ApplicationInfo> {
 
 /// Prompt actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum PromptAction {
     /// Abort command entry.
     ///
@@ -407,6 +607,11 @@ pub enum PromptAction {
     /// If [bool] is `true`, then this should only move through entries that share an initially
     /// typed prefix.
     Recall(MoveDir1D, Count, bool),
+
+    /// Insert the word under the cursor in the currently focused window into the prompt.
+    ///
+    /// For example, `C-w` during `isearch` in Emacs.
+    InsertCursorWord(WordStyle),
 }
 
 /// A widget that the user can switch focus of keyboard input to.
@@ -425,6 +630,7 @@ where
 
 /// Macro actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum MacroAction {
     /// Execute the contents of the contextually specified Register [*n* times](Count).
@@ -432,18 +638,36 @@ pub enum MacroAction {
     /// If no register is specified, then this should default to [Register::UnnamedMacro].
     Execute(Count),
 
+    /// Like [MacroAction::Execute], except that typing the key that finished this action's
+    /// keybinding again right afterwards executes the macro another time.
+    ///
+    /// For example, `C-x e e e` in Emacs.
+    ExecuteRepeatable(Count),
+
     /// Run the given macro string [*n* times](Count).
     Run(String, Count),
 
     /// Execute the contents of the previously specified register [*n* times](Count).
     Repeat(Count),
 
+    /// Rotate [Register::MacroRing] [*n* times](Count) so that an older ([MoveDir1D::Previous])
+    /// or newer ([MoveDir1D::Next]) macro is at its front.
+    Rotate(MoveDir1D, Count),
+
     /// Start or stop recording a macro.
     ToggleRecording,
 }
 
 /// Tab actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "I::WindowId: serde::Serialize",
+        deserialize = "I::WindowId: serde::Deserialize<'de>"
+    ))
+)]
 #[non_exhaustive]
 pub enum TabAction<I: ApplicationInfo> {
     /// Close the [TabTarget] tabs with [CloseFlags] options.
@@ -486,6 +710,14 @@ where
 
 /// Window actions
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "I::WindowId: serde::Serialize",
+        deserialize = "I::WindowId: serde::Deserialize<'de>"
+    ))
+)]
 #[non_exhaustive]
 pub enum WindowAction<I: ApplicationInfo> {
     /// Close the [WindowTarget] windows with [CloseFlags] options.
@@ -554,6 +786,7 @@ where
 
 /// Editor manipulation
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum EditorAction {
     /// Complete the text before the cursor group leader.
@@ -565,6 +798,9 @@ pub enum EditorAction {
     /// Perform the specified [action](EditAction) on [a target](EditTarget).
     Edit(Specifier<EditAction>, EditTarget),
 
+    /// Open, close or delete folds.
+    Fold(FoldAction),
+
     /// Perform a history operation.
     History(HistoryAction),
 
@@ -576,6 +812,12 @@ pub enum EditorAction {
 
     /// Modify the current selection.
     Selection(SelectionAction),
+
+    /// Interactively replace matches of a pattern.
+    Replace(ReplaceAction),
+
+    /// Check the spelling of the word under the cursor.
+    Spell(SpellAction),
 }
 
 impl EditorAction {
@@ -585,12 +827,15 @@ impl EditorAction {
             EditorAction::Complete(_, _, _) => false,
             EditorAction::History(act) => act.is_readonly(),
             EditorAction::InsertText(_) => false,
+            EditorAction::Replace(_) => false,
 
             EditorAction::Cursor(_) => true,
+            EditorAction::Fold(_) => true,
             EditorAction::Mark(_) => true,
             EditorAction::Selection(_) => true,
 
             EditorAction::Edit(act, _) => ctx.resolve(act).is_readonly(),
+            EditorAction::Spell(act) => act.is_readonly(),
         }
     }
 
@@ -601,6 +846,9 @@ impl EditorAction {
         match self {
             EditorAction::History(_) => SequenceStatus::Break,
             EditorAction::Mark(_) => SequenceStatus::Break,
+            EditorAction::Fold(_) => SequenceStatus::Ignore,
+            EditorAction::Replace(_) => SequenceStatus::Ignore,
+            EditorAction::Spell(_) => SequenceStatus::Ignore,
             EditorAction::InsertText(_) => SequenceStatus::Track,
             EditorAction::Cursor(_) => SequenceStatus::Track,
             EditorAction::Selection(_) => SequenceStatus::Track,
@@ -609,6 +857,7 @@ impl EditorAction {
                 match ctx.resolve(act) {
                     EditAction::Motion => motion,
                     EditAction::Yank => SequenceStatus::Ignore,
+                    EditAction::Fold => SequenceStatus::Ignore,
                     _ => SequenceStatus::Track,
                 }
             },
@@ -625,9 +874,12 @@ impl EditorAction {
             EditorAction::Complete(_, _, _) => SequenceStatus::Atom,
             EditorAction::Cursor(_) => SequenceStatus::Atom,
             EditorAction::Edit(_, _) => SequenceStatus::Atom,
+            EditorAction::Fold(_) => SequenceStatus::Atom,
             EditorAction::InsertText(_) => SequenceStatus::Atom,
             EditorAction::Mark(_) => SequenceStatus::Atom,
+            EditorAction::Replace(_) => SequenceStatus::Atom,
             EditorAction::Selection(_) => SequenceStatus::Atom,
+            EditorAction::Spell(_) => SequenceStatus::Atom,
         }
     }
 
@@ -635,7 +887,10 @@ impl EditorAction {
     pub fn is_last_selection(&self, ctx: &EditContext) -> SequenceStatus {
         match self {
             EditorAction::History(_) => SequenceStatus::Ignore,
+            EditorAction::Fold(_) => SequenceStatus::Ignore,
             EditorAction::Mark(_) => SequenceStatus::Ignore,
+            EditorAction::Replace(_) => SequenceStatus::Ignore,
+            EditorAction::Spell(_) => SequenceStatus::Ignore,
             EditorAction::InsertText(_) => SequenceStatus::Ignore,
             EditorAction::Cursor(_) => SequenceStatus::Ignore,
             EditorAction::Complete(_, _, _) => SequenceStatus::Ignore,
@@ -663,10 +918,13 @@ impl EditorAction {
             EditorAction::Cursor(act) => act.is_switchable(ctx),
             EditorAction::Edit(act, _) => ctx.resolve(act).is_switchable(ctx),
             EditorAction::Complete(_, _, _) => false,
+            EditorAction::Fold(_) => false,
             EditorAction::History(_) => false,
             EditorAction::InsertText(_) => false,
             EditorAction::Mark(_) => false,
+            EditorAction::Replace(_) => false,
             EditorAction::Selection(_) => false,
+            EditorAction::Spell(_) => false,
         }
     }
 }
@@ -677,6 +935,24 @@ impl From<CursorAction> for EditorAction {
     }
 }
 
+impl From<FoldAction> for EditorAction {
+    fn from(act: FoldAction) -> Self {
+        EditorAction::Fold(act)
+    }
+}
+
+impl From<ReplaceAction> for EditorAction {
+    fn from(act: ReplaceAction) -> Self {
+        EditorAction::Replace(act)
+    }
+}
+
+impl From<SpellAction> for EditorAction {
+    fn from(act: SpellAction) -> Self {
+        EditorAction::Spell(act)
+    }
+}
+
 impl From<HistoryAction> for EditorAction {
     fn from(act: HistoryAction) -> Self {
         EditorAction::History(act)
@@ -697,6 +973,14 @@ impl From<SelectionAction> for EditorAction {
 
 /// The result of either pressing a complete keybinding sequence, or parsing a command.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "I::Action: serde::Serialize, I::WindowId: serde::Serialize",
+        deserialize = "I::Action: serde::Deserialize<'de>, I::WindowId: serde::Deserialize<'de>"
+    ))
+)]
 #[non_exhaustive]
 pub enum Action<I: ApplicationInfo = EmptyInfo> {
     /// Do nothing.
@@ -735,6 +1019,9 @@ pub enum Action<I: ApplicationInfo = EmptyInfo> {
     /// Find the [*n*<sup>th</sup>](Count) occurrence of the current application-level search.
     Search(MoveDirMod, Count),
 
+    /// Stop highlighting matches of the last search until the next search is performed.
+    ClearSearchHighlight,
+
     /// Perform a command-related action.
     Command(CommandAction),
 
@@ -773,6 +1060,7 @@ impl<I: ApplicationInfo> Action<I> {
             Action::Tab(_) => SequenceStatus::Break,
             Action::Window(_) => SequenceStatus::Break,
 
+            Action::ClearSearchHighlight => SequenceStatus::Ignore,
             Action::KeywordLookup => SequenceStatus::Ignore,
             Action::NoOp => SequenceStatus::Ignore,
             Action::RedrawScreen => SequenceStatus::Ignore,
@@ -799,6 +1087,7 @@ impl<I: ApplicationInfo> Action<I> {
             Action::Macro(_) => SequenceStatus::Atom,
             Action::Tab(_) => SequenceStatus::Atom,
             Action::Window(_) => SequenceStatus::Atom,
+            Action::ClearSearchHighlight => SequenceStatus::Atom,
             Action::KeywordLookup => SequenceStatus::Atom,
             Action::NoOp => SequenceStatus::Atom,
             Action::Prompt(_) => SequenceStatus::Atom,
@@ -824,6 +1113,7 @@ impl<I: ApplicationInfo> Action<I> {
             Action::Macro(_) => SequenceStatus::Ignore,
             Action::Tab(_) => SequenceStatus::Ignore,
             Action::Window(_) => SequenceStatus::Ignore,
+            Action::ClearSearchHighlight => SequenceStatus::Ignore,
             Action::KeywordLookup => SequenceStatus::Ignore,
             Action::NoOp => SequenceStatus::Ignore,
             Action::Prompt(_) => SequenceStatus::Ignore,
@@ -842,6 +1132,7 @@ impl<I: ApplicationInfo> Action<I> {
             Action::Editor(act) => act.is_switchable(ctx),
             Action::Jump(..) => true,
 
+            Action::ClearSearchHighlight => false,
             Action::CommandBar(_) => false,
             Action::Command(_) => false,
             Action::KeywordLookup => false,
@@ -999,4 +1290,74 @@ mod tests {
         ctx.operation = EditAction::Delete;
         assert_eq!(act.is_readonly(&ctx), false);
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        let json = |act: &Action| serde_json::to_string(act).unwrap();
+        let from = |s: &str| serde_json::from_str::<Action>(s).unwrap();
+
+        // These representations are part of the documented format, and shouldn't change.
+        let act: Action = EditorAction::Edit(
+            EditAction::Delete.into(),
+            EditTarget::Range(RangeType::Line, true, 3.into()),
+        )
+        .into();
+        let s = r#"{"Editor":{"Edit":[{"Exact":"Delete"},{"Range":["Line",true,{"Exact":3}]}]}}"#;
+        assert_eq!(json(&act), s);
+        assert_eq!(from(s), act);
+
+        let act: Action =
+            WindowAction::Close(WindowTarget::Single(FocusChange::Current), CloseFlags::WQ).into();
+        let s = r#"{"Window":{"Close":[{"Single":"Current"},"WRITE | QUIT"]}}"#;
+        assert_eq!(json(&act), s);
+        assert_eq!(from(s), act);
+        assert_eq!(from(r#"{"Window":{"Close":[{"Single":"Current"},"WQ"]}}"#), act);
+
+        let s = r#""KeywordLookup""#;
+        assert_eq!(json(&Action::KeywordLookup), s);
+        assert_eq!(from(s), Action::KeywordLookup);
+
+        // Everything else round trips.
+        let acts: Vec<Action> = vec![
+            Action::Scroll(ScrollStyle::LinePos(MovePosition::Beginning, 10.into())),
+            WindowAction::Split(
+                OpenTarget::Name("foo".into()),
+                Axis::Vertical,
+                MoveDir1D::Next,
+                Count::Contextual,
+            )
+            .into(),
+            CommandBarAction::Focus(":".into(), CommandType::Command, Box::new(Action::NoOp))
+                .into(),
+            EditorAction::Edit(
+                Specifier::Contextual,
+                EditTarget::Motion(
+                    MoveType::WordBegin(WordStyle::Big, MoveDir1D::Next),
+                    Count::Contextual,
+                ),
+            )
+            .into(),
+            InsertTextAction::Paste(PasteStyle::Side(MoveDir1D::Previous), Count::Exact(2)).into(),
+            EditorAction::Mark(Specifier::Exact(Mark::BufferNamed('a'))).into(),
+            MacroAction::Run("dd".into(), Count::Contextual).into(),
+            Action::ShowInfoMessage(InfoMessage::Message("hello".into())),
+            Action::Application(()),
+        ];
+
+        for act in acts {
+            assert_eq!(from(&json(&act)), act);
+        }
+
+        // Test functions can't be serialized.
+        let act: Action = EditorAction::Edit(
+            Specifier::Contextual,
+            EditTarget::Motion(
+                MoveType::WordBegin(WordStyle::CharSet(|c| c == 'a'), MoveDir1D::Next),
+                Count::Contextual,
+            ),
+        )
+        .into();
+        assert!(serde_json::to_string(&act).is_err());
+    }
 }
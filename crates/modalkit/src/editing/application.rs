@@ -151,8 +151,9 @@ use std::hash::Hash;
 
 use crate::{
     editing::{context::EditContext, cursor::Cursor, rope::EditRope, store::Store},
+    errors::{EditError, EditResult},
     keybindings::SequenceStatus,
-    prelude::CommandType,
+    prelude::{CommandType, EditRange},
 };
 
 /// Trait for objects that describe application-specific actions.
@@ -177,7 +178,7 @@ pub trait ApplicationAction: Clone + Debug + Eq + PartialEq + Send {
 
     /// Allows controlling whether an application-specific action can cause
     /// a buffer switch on an
-    /// [EditError::WrongBuffer](crate::errors::EditError::WrongBuffer).
+    /// [EditError::WrongBuffer].
     fn is_switchable(&self, ctx: &EditContext) -> bool;
 }
 
@@ -261,6 +262,34 @@ pub trait ApplicationInfo: Clone + Debug + Eq + PartialEq {
 
     /// Get the [ApplicationContentId] used to show a given command type.
     fn content_of_command(cmdtype: CommandType) -> Self::ContentId;
+
+    /// Apply the operator named by [EditAction::Application] to the `text` within `range`, and
+    /// return the text to replace it with.
+    ///
+    /// Linewise ranges cover whole lines, but leave out the newline at the end of the last one.
+    /// Blockwise ranges are split up, so that this gets called once for each line in the block.
+    /// The rest of the buffer's text is available in `buffer`.
+    ///
+    /// By default, this fails for every operator name.
+    ///
+    /// With the Vim keybindings, operators can be mapped using [InputStep::operator] so that they
+    /// can be followed by a motion or text object, and using [InputStep::selection_operator] so
+    /// that they can act on the selection in Visual and Select modes. Both of these can be
+    /// repeated with `.`.
+    ///
+    /// [EditAction::Application]: crate::actions::EditAction::Application
+    /// [InputStep::operator]: crate::env::vim::keybindings::InputStep::operator
+    /// [InputStep::selection_operator]: crate::env::vim::keybindings::InputStep::selection_operator
+    fn operator(
+        name: &str,
+        text: &EditRope,
+        range: &EditRange<Cursor>,
+        buffer: &EditRope,
+        content: &Self::ContentId,
+        store: &mut Store<Self>,
+    ) -> EditResult<EditRope, Self> {
+        Err(EditError::Failure(format!("Unknown operator: {name}")))
+    }
 }
 
 /// A default implementor of [ApplicationInfo] for consumers that don't require any customization.
@@ -287,6 +316,7 @@ impl ApplicationInfo for EmptyInfo {
         match cmdtype {
             CommandType::Search => "*search*".into(),
             CommandType::Command => "*command*".into(),
+            CommandType::Prompt => "*prompt*".into(),
         }
     }
 }
@@ -17,6 +17,15 @@ where
         store: &mut S,
     ) -> EditResult<EditInfo, I>;
 
+    fn cursor_pop_mark(
+        &mut self,
+        ring: MarkRing,
+        ctx: &C,
+        store: &mut S,
+    ) -> EditResult<EditInfo, I>;
+
+    fn cursor_push_mark(&mut self, ctx: &C, store: &mut S) -> EditResult<EditInfo, I>;
+
     fn cursor_restore(
         &mut self,
         style: &CursorGroupCombineStyle,
@@ -72,6 +81,31 @@ where
         Ok(None)
     }
 
+    fn cursor_pop_mark(
+        &mut self,
+        ring: MarkRing,
+        ctx: &CursorGroupIdContext<'a>,
+        store: &mut Store<I>,
+    ) -> EditResult<EditInfo, I> {
+        let cursor = store.cursors.pop_mark(self.id.clone(), ring)?;
+
+        self.set_leader(ctx.0, cursor);
+
+        Ok(None)
+    }
+
+    fn cursor_push_mark(
+        &mut self,
+        ctx: &CursorGroupIdContext<'a>,
+        store: &mut Store<I>,
+    ) -> EditResult<EditInfo, I> {
+        let leader = self.get_leader(ctx.0);
+
+        store.cursors.push_mark(self.id.clone(), leader);
+
+        Ok(None)
+    }
+
     fn cursor_restore(
         &mut self,
         style: &CursorGroupCombineStyle,
@@ -168,4 +202,75 @@ mod tests {
         );
         assert_eq!(ebuf.get_leader(curid), Cursor::new(1, 0));
     }
+
+    #[test]
+    fn test_mark_ring() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("hello\nworld\n");
+
+        // Nothing has been pushed yet.
+        let res = ebuf.cursor_pop_mark(MarkRing::Buffer, ctx!(gid, vwctx, vctx), &mut store);
+        assert!(res.is_err(), "{:?}", res);
+
+        // Push a few positions onto the ring.
+        ebuf.set_leader(gid, Cursor::new(0, 1));
+        ebuf.cursor_push_mark(ctx!(gid, vwctx, vctx), &mut store).unwrap();
+        ebuf.set_leader(gid, Cursor::new(0, 3));
+        ebuf.cursor_push_mark(ctx!(gid, vwctx, vctx), &mut store).unwrap();
+        ebuf.set_leader(gid, Cursor::new(1, 2));
+        ebuf.cursor_push_mark(ctx!(gid, vwctx, vctx), &mut store).unwrap();
+
+        // Typing at the start of the line moves the positions after it.
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        type_char!(ebuf, 'a', gid, vwctx, vctx, store);
+        assert_eq!(ebuf.get_text(), "ahello\nworld\n");
+
+        // Popping cycles through the ring, most recent first.
+        ebuf.cursor_pop_mark(MarkRing::Buffer, ctx!(gid, vwctx, vctx), &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 2));
+        ebuf.cursor_pop_mark(MarkRing::Buffer, ctx!(gid, vwctx, vctx), &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 4));
+        ebuf.cursor_pop_mark(MarkRing::Buffer, ctx!(gid, vwctx, vctx), &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 2));
+        ebuf.cursor_pop_mark(MarkRing::Buffer, ctx!(gid, vwctx, vctx), &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 2));
+
+        // Shrinking the ring drops the oldest positions.
+        store.cursors.set_mark_ring_size(1);
+        ebuf.cursor_pop_mark(MarkRing::Buffer, ctx!(gid, vwctx, vctx), &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 4));
+        ebuf.cursor_pop_mark(MarkRing::Buffer, ctx!(gid, vwctx, vctx), &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 4));
+
+        // Only the first position pushed in a row from the same buffer goes on the global ring.
+        let mut other = EditBuffer::new("other".to_string());
+        let ogid = other.create_group();
+        other.set_text("foo bar\n");
+        other.set_leader(ogid, Cursor::new(0, 4));
+        other.cursor_push_mark(ctx!(ogid, vwctx, vctx), &mut store).unwrap();
+
+        ebuf.set_leader(gid, Cursor::new(1, 0));
+        ebuf.cursor_push_mark(ctx!(gid, vwctx, vctx), &mut store).unwrap();
+
+        // Popping the global ring from the wrong buffer leaves it untouched.
+        let res = other.cursor_pop_mark(MarkRing::Global, ctx!(ogid, vwctx, vctx), &mut store);
+        assert!(matches!(res, Err(EditError::WrongBuffer(ref id)) if id.is_empty()), "{:?}", res);
+
+        ebuf.cursor_pop_mark(MarkRing::Global, ctx!(gid, vwctx, vctx), &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 0));
+
+        let res = ebuf.cursor_pop_mark(MarkRing::Global, ctx!(gid, vwctx, vctx), &mut store);
+        assert!(matches!(res, Err(EditError::WrongBuffer(ref id)) if id == "other"), "{:?}", res);
+
+        other
+            .cursor_pop_mark(MarkRing::Global, ctx!(ogid, vwctx, vctx), &mut store)
+            .unwrap();
+        assert_eq!(other.get_leader(ogid), Cursor::new(0, 4));
+    }
 }
@@ -1,13 +1,14 @@
 use std::borrow::Cow;
+use std::ops::Range;
 
 use crate::editing::{
     application::ApplicationInfo,
     context::Resolve,
-    cursor::{Adjustable, Cursor, CursorChoice},
-    rope::EditRope,
+    cursor::{block_cursors, Adjustable, Cursor, CursorChoice},
+    rope::{CharOff, EditRope},
     store::{RegisterCell, RegisterPutFlags, Store},
 };
-use crate::errors::EditResult;
+use crate::errors::{EditError, EditResult};
 use crate::prelude::*;
 use crate::util::into_range;
 
@@ -50,6 +51,7 @@ where
 
     fn format(
         &mut self,
+        cursor: &Cursor,
         range: &CursorRange,
         ctx: &C,
         store: &mut Store<I>,
@@ -64,6 +66,21 @@ where
         store: &mut Store<I>,
     ) -> EditResult<CursorChoice, I>;
 
+    fn sort_lines(
+        &mut self,
+        range: &CursorRange,
+        ctx: &C,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I>;
+
+    fn trim_whitespace(
+        &mut self,
+        cursor: &Cursor,
+        range: &CursorRange,
+        ctx: &C,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I>;
+
     fn join(
         &mut self,
         spaces: JoinStyle,
@@ -79,6 +96,85 @@ where
         ctx: &C,
         store: &mut Store<I>,
     ) -> EditResult<CursorChoice, I>;
+
+    fn surround(
+        &mut self,
+        change: &SurroundChange,
+        c: Option<char>,
+        range: &CursorRange,
+        ctx: &C,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I>;
+
+    fn rectangle(
+        &mut self,
+        change: &RectangleChange,
+        range: &CursorRange,
+        ctx: &C,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I>;
+
+    fn transpose(
+        &mut self,
+        style: &TransposeStyle,
+        dir: MoveDir1D,
+        anchor: &Cursor,
+        range: &CursorRange,
+        ctx: &C,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I>;
+
+    fn application(
+        &mut self,
+        name: &str,
+        range: &CursorRange,
+        ctx: &C,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I>;
+}
+
+/// Get the opening and closing delimiters to use when surrounding text with *c*.
+fn surround_pair(c: char) -> (char, char) {
+    match c {
+        '(' | ')' => ('(', ')'),
+        '[' | ']' => ('[', ']'),
+        '{' | '}' => ('{', '}'),
+        '<' | '>' => ('<', '>'),
+        c => (c, c),
+    }
+}
+
+impl<I> EditBuffer<I>
+where
+    I: ApplicationInfo,
+{
+    /// Swap the text within two non-overlapping ranges.
+    fn _swap(
+        &mut self,
+        a: Range<CharOff>,
+        b: Range<CharOff>,
+        store: &mut Store<I>,
+    ) -> EditResult<(), I> {
+        let (a, b) = if a.start <= b.start { (a, b) } else { (b, a) };
+
+        if a.end > b.start {
+            let msg = "Don't have two things to transpose".to_string();
+
+            return Err(EditError::Failure(msg));
+        }
+
+        let atext = self.text.slice(a.clone());
+        let btext = self.text.slice(b.clone());
+
+        // Replace the later range first, so that the earlier one stays valid.
+        let (_, adjs) = self.text.replace(b, atext);
+        self._adjust_all(adjs, store);
+
+        let (_, adjs) = self.text.replace(a, btext);
+        self._adjust_all(adjs, store);
+
+        return Ok(());
+    }
 }
 
 impl<'a, I> EditActions<CursorMovementsContext<'a, Cursor>, I> for EditBuffer<I>
@@ -145,6 +241,10 @@ where
             flags |= RegisterPutFlags::APPEND
         }
 
+        if ctx.context.get_register_prepend() {
+            flags |= RegisterPutFlags::PREPEND
+        }
+
         store.registers.put(&register, cell, flags)?;
 
         let cursor = self.text.offset_to_cursor(coff);
@@ -187,6 +287,10 @@ where
             flags |= RegisterPutFlags::APPEND;
         }
 
+        if ctx.context.get_register_prepend() {
+            flags |= RegisterPutFlags::PREPEND;
+        }
+
         store.registers.put(&register, cell, flags)?;
 
         let choice = cursors
@@ -308,18 +412,286 @@ where
         return Ok(CursorChoice::Empty);
     }
 
+    fn surround(
+        &mut self,
+        change: &SurroundChange,
+        c: Option<char>,
+        range: &CursorRange,
+        ctx: &CursorMovementsContext<'a, Cursor>,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I> {
+        let (_, ranges) = self._effective(range, ctx.context.get_target_shape());
+        let mut choice = CursorChoice::Empty;
+
+        for (start, end, inclusive) in ranges.into_iter().rev() {
+            let (prefix, text, suffix) = self.text.split(start, end, inclusive);
+            let tlen = text.len();
+
+            if tlen == 0 {
+                continue;
+            }
+
+            let cstart = self.text.offset_to_cursor(start);
+            let cend = self.text.offset_to_cursor(start + tlen.into());
+            let clast = self.text.offset_to_cursor(start + (tlen - 1).into());
+
+            match (change, c.map(surround_pair)) {
+                (SurroundChange::Add, Some((open, close))) => {
+                    self.text =
+                        prefix + EditRope::from(open) + text + EditRope::from(close) + suffix;
+
+                    // Shift everything after each new delimiter over by one column.
+                    self._adjust_columns(cend.y, cend.x, 0, 1, store);
+                    self._adjust_columns(cstart.y, cstart.x, 0, 1, store);
+
+                    let last = start + (tlen + 1).into();
+                    let start = self.text.offset_to_cursor(start);
+                    let end = self.text.offset_to_cursor(last);
+
+                    choice = CursorChoice::Range(start.clone(), end, start);
+                },
+                (SurroundChange::Delete, _) if tlen >= 2 => {
+                    let (_, inner, _) = text.split(1.into(), (tlen - 1).into(), false);
+
+                    self.text = prefix + inner + suffix;
+
+                    // Shift everything after each removed delimiter back by one column.
+                    self._adjust_columns(clast.y, clast.x, 0, -1, store);
+                    self._adjust_columns(cstart.y, cstart.x, 0, -1, store);
+
+                    let last = start + tlen.saturating_sub(3).into();
+                    let start = self.text.offset_to_cursor(start);
+                    let end = self.text.offset_to_cursor(last);
+
+                    choice = CursorChoice::Range(start.clone(), end, start);
+                },
+                (SurroundChange::Replace, Some((open, close))) if tlen >= 2 => {
+                    let (_, inner, _) = text.split(1.into(), (tlen - 1).into(), false);
+
+                    self.text =
+                        prefix + EditRope::from(open) + inner + EditRope::from(close) + suffix;
+
+                    choice = CursorChoice::Range(cstart.clone(), clast, cstart);
+                },
+                _ => {
+                    // Nothing to change.
+                },
+            }
+        }
+
+        Ok(choice)
+    }
+
+    fn rectangle(
+        &mut self,
+        change: &RectangleChange,
+        range: &CursorRange,
+        ctx: &CursorMovementsContext<'a, Cursor>,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I> {
+        let (lc, rc) = block_cursors(&range.start, &range.end);
+        let width = rc.x - lc.x + 1;
+        let (_, ranges) = self._effective(range, Some(TargetShape::BlockWise));
+        let fill = match change {
+            RectangleChange::Fill => {
+                let reg = ctx.context.get_register().unwrap_or(Register::Unnamed);
+
+                store.registers.get(&reg)?.value
+            },
+            RectangleChange::Open | RectangleChange::Clear | RectangleChange::Number => {
+                EditRope::empty()
+            },
+        };
+        let digits = ranges.len().to_string().len();
+        let mut last = None;
+
+        for (idx, (start, end, inclusive)) in ranges.into_iter().enumerate().rev() {
+            // Lines that end inside of the rectangle shouldn't lose their newline.
+            let inclusive = inclusive && self.text.chars(end).next() != Some('\n');
+            let (prefix, text, suffix) = self.text.split(start, end, inclusive);
+            let cstart = self.text.offset_to_cursor(start);
+
+            let (inserted, removed) = match change {
+                RectangleChange::Open => (EditRope::from(" ".repeat(width)), 0),
+                RectangleChange::Fill => (fill.clone(), text.len()),
+                RectangleChange::Clear => (EditRope::from(" ".repeat(text.len())), text.len()),
+                RectangleChange::Number => {
+                    (EditRope::from(format!("{:>width$} ", idx + 1, width = digits)), 0)
+                },
+            };
+            let ilen = inserted.len();
+
+            self.text = match change {
+                RectangleChange::Fill | RectangleChange::Clear => prefix + inserted + suffix,
+                RectangleChange::Open | RectangleChange::Number => {
+                    prefix + inserted + text + suffix
+                },
+            };
+
+            // Shift everything after the changed text on this line.
+            let amt = ilen as isize - removed as isize;
+            self._adjust_columns(cstart.y, cstart.x + removed, 0, amt, store);
+
+            last.get_or_insert(Cursor::new(cstart.y, cstart.x + ilen));
+        }
+
+        let choice = match (change, last) {
+            (_, None) => CursorChoice::Empty,
+            (RectangleChange::Fill, Some(cursor)) => {
+                // Filling leaves the cursor after the new text on the rectangle's last line.
+                CursorChoice::Single(cursor)
+            },
+            (_, Some(_)) => {
+                // Everything else leaves the cursor in the rectangle's upper-left corner.
+                CursorChoice::Single(lc)
+            },
+        };
+
+        return Ok(choice);
+    }
+
+    fn transpose(
+        &mut self,
+        style: &TransposeStyle,
+        dir: MoveDir1D,
+        anchor: &Cursor,
+        range: &CursorRange,
+        ctx: &CursorMovementsContext<'a, Cursor>,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I> {
+        let count = ctx.context.resolve(&Count::Contextual);
+        let mut point = self.text.cursor_to_offset(&range.end);
+        let nothing = || EditError::Failure("Don't have two things to transpose".to_string());
+
+        if count == 0 {
+            // Swap the objects at the cursor and the anchor, leaving the cursor where it is.
+            let aoff = self.text.cursor_to_offset(anchor);
+            let a = self.text.find_transposable(style, point, MoveDir1D::Next);
+            let b = self.text.find_transposable(style, aoff, MoveDir1D::Next);
+            let (a, b) = a.zip(b).ok_or_else(nothing)?;
+
+            self._swap(a, b, store)?;
+
+            return Ok(CursorChoice::Single(self.text.offset_to_cursor(point)));
+        }
+
+        if *style == TransposeStyle::Char && dir == MoveDir1D::Next && count == 1 {
+            // At the end of a line, swap the two characters before the cursor.
+            if point > CharOff::from(0) && self.text.chars(point).next() == Some('\n') {
+                point = point - CharOff::from(1);
+            }
+        }
+
+        let prev = self.text.find_transposable(style, point, MoveDir1D::Previous);
+        let prev = prev.ok_or_else(nothing)?;
+        let mut next: Option<Range<CharOff>> = None;
+        let mut off = match dir {
+            MoveDir1D::Next => prev.end,
+            MoveDir1D::Previous => prev.start,
+        };
+
+        // Find the span of text covering the next *count* objects.
+        for _ in 0..count {
+            let obj = self.text.find_transposable(style, off, dir).ok_or_else(nothing)?;
+
+            off = match dir {
+                MoveDir1D::Next => obj.end,
+                MoveDir1D::Previous => obj.start,
+            };
+
+            next = match next {
+                Some(span) => span.start.min(obj.start)..span.end.max(obj.end),
+                None => obj,
+            }
+            .into();
+        }
+
+        let next = next.ok_or_else(nothing)?;
+
+        let plen = prev.end - prev.start;
+        let end = match dir {
+            MoveDir1D::Next => next.end,
+            MoveDir1D::Previous => next.start + plen,
+        };
+
+        self._swap(prev, next, store)?;
+
+        // Leave the cursor after the dragged object, or at the start of the following line.
+        let end = match (style, dir) {
+            (TransposeStyle::Line, MoveDir1D::Next) => end + CharOff::from(1),
+            _ => end,
+        };
+
+        return Ok(CursorChoice::Single(self.text.offset_to_cursor(end)));
+    }
+
     fn format(
         &mut self,
-        _: &CursorRange,
+        cursor: &Cursor,
+        range: &CursorRange,
         _: &CursorMovementsContext<'a, Cursor>,
-        _: &mut Store<I>,
+        store: &mut Store<I>,
     ) -> EditResult<CursorChoice, I> {
-        /*
-         * Automatically formatting lines requires a whole lot of logic that just doesn't exist
-         * in this codebase yet. At some point, if some kind of filetype detection is added, then
-         * this function can be made to do something useful.
-         */
-        return Ok(CursorChoice::Empty);
+        // Formatting always fills whole lines, and leaves the cursor on the same word.
+        let mut cursor = cursor.clone();
+        let start = self.text.offset_of_line(range.start.y);
+        let end = self.text.offset_of_line(range.end.y) + self.text.get_columns(range.end.y).into();
+
+        cursor.adjust(&self._fill(start, end, store));
+
+        return Ok(CursorChoice::Single(cursor));
+    }
+
+    fn sort_lines(
+        &mut self,
+        range: &CursorRange,
+        _: &CursorMovementsContext<'a, Cursor>,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I> {
+        // Sorting always works on whole lines, and leaves the cursor at the start of the first.
+        let start = self.text.offset_of_line(range.start.y);
+        let end = self.text.offset_of_line(range.end.y) + self.text.get_columns(range.end.y).into();
+
+        let (_, text, _) = self.text.split(start, end, false);
+        let text = Cow::from(&text);
+        let mut lines = text.split('\n').collect::<Vec<_>>();
+        lines.sort_unstable();
+
+        let (_, adjs) = self.text.replace(start..end, EditRope::from(lines.join("\n")));
+        self._adjust_all(adjs, store);
+
+        return Ok(CursorChoice::Single(self.text.offset_to_cursor(start)));
+    }
+
+    fn trim_whitespace(
+        &mut self,
+        cursor: &Cursor,
+        range: &CursorRange,
+        _: &CursorMovementsContext<'a, Cursor>,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I> {
+        // Trimming always works on whole lines, and leaves the cursor where it was.
+        let mut cursor = cursor.clone();
+
+        // Trim the later lines first, so that the earlier offsets stay valid.
+        for y in (range.start.y..=range.end.y).rev() {
+            let start = self.text.offset_of_line(y);
+            let end = start + self.text.get_columns(y).into();
+
+            let (_, line, _) = self.text.split(start, end, false);
+            let line = Cow::from(&line);
+            let n = line.chars().rev().take_while(|c| c.is_whitespace()).count();
+
+            if n == 0 {
+                continue;
+            }
+
+            let (_, adjs) = self.text.replace(end - n.into()..end, EditRope::empty());
+            self._adjust(&adjs, store);
+            cursor.adjust(&adjs);
+        }
+
+        return Ok(CursorChoice::Single(cursor));
     }
 
     fn changenum(
@@ -454,6 +826,43 @@ where
 
         return Ok(cursor.map(CursorChoice::Single).unwrap_or_default());
     }
+
+    fn application(
+        &mut self,
+        name: &str,
+        range: &CursorRange,
+        ctx: &CursorMovementsContext<'a, Cursor>,
+        store: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I> {
+        let (shape, ranges) = self._effective(range, ctx.context.get_target_shape());
+        let mut choice = CursorChoice::Empty;
+
+        for (start, end, inclusive) in ranges.into_iter().rev() {
+            let (shape, inclusive) = match shape {
+                // Leave out the newline that ends the last line.
+                TargetShape::LineWise => (TargetShape::LineWise, false),
+                TargetShape::CharWise => (TargetShape::CharWise, inclusive),
+                TargetShape::BlockWise => (TargetShape::CharWise, inclusive),
+            };
+
+            let (_, text, _) = self.text.split(start, end, inclusive);
+            let cstart = self.text.offset_to_cursor(start);
+            let cend = self.text.offset_to_cursor(end);
+            let erange = EditRange::new(cstart, cend, shape, inclusive);
+            let text = I::operator(name, &text, &erange, &self.text, &self.id, store)?;
+
+            let (c, adjs) = if inclusive {
+                self.text.replace(start..=end, text)
+            } else {
+                self.text.replace(start..end, text)
+            };
+
+            self._adjust_all(adjs, store);
+            choice = c;
+        }
+
+        return Ok(choice);
+    }
 }
 
 #[cfg(test)]
@@ -851,6 +1260,229 @@ mod tests {
         assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 31));
     }
 
+    #[test]
+    fn test_rectangle_open() {
+        let (mut ebuf, curid, vwctx, vctx, mut store) =
+            mkfivestr("hello world\n1 2 3 4 5 6\n  a b c d e f\n");
+
+        // Set cursor to (0, 7).
+        ebuf.set_leader(curid, Cursor::new(0, 7));
+
+        // Open a rectangle from here to the first word of the third line.
+        let act = EditAction::Rectangle(RectangleChange::Open);
+        edit!(ebuf, act, range!(RangeType::Line, 3), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "he      llo world\n1       2 3 4 5 6\n        a b c d e f\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 2));
+    }
+
+    #[test]
+    fn test_rectangle_clear() {
+        let (mut ebuf, curid, vwctx, vctx, mut store) =
+            mkfivestr("hello world\n1 2 3 4 5 6\n  a b c d e f\n");
+
+        // Set cursor to (0, 7).
+        ebuf.set_leader(curid, Cursor::new(0, 7));
+
+        // Blank out the rectangle from here to the first word of the third line.
+        let act = EditAction::Rectangle(RectangleChange::Clear);
+        edit!(ebuf, act, range!(RangeType::Line, 3), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "he      rld\n1       5 6\n        d e f\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 2));
+    }
+
+    #[test]
+    fn test_rectangle_fill() {
+        let (mut ebuf, curid, vwctx, mut vctx, mut store) =
+            mkfivestr("hello world\n1 2 3 4 5 6\n  a b c d e f\n");
+
+        set_named_reg!(store, 'a', TargetShape::CharWise, "xy");
+        vctx.register = Some(Register::Named('a'));
+
+        // Set cursor to (0, 7).
+        ebuf.set_leader(curid, Cursor::new(0, 7));
+
+        // Replace each line of the rectangle with the contents of "a.
+        let act = EditAction::Rectangle(RectangleChange::Fill);
+        edit!(ebuf, act, range!(RangeType::Line, 3), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hexyrld\n1 xy5 6\n  xyd e f\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(2, 4));
+
+        // Lines ending inside of the rectangle keep their newlines.
+        let (mut ebuf, curid, vwctx, vctx, mut store) = mkfivestr("hello\n\nworld\n");
+        set_reg!(store, Register::Unnamed, TargetShape::CharWise, "-");
+        ebuf.set_leader(curid, Cursor::new(0, 3));
+
+        let act = EditAction::Rectangle(RectangleChange::Fill);
+        edit!(ebuf, act, range!(RangeType::Line, 3), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "-o\n-\n-d\n");
+    }
+
+    #[test]
+    fn test_rectangle_number() {
+        let (mut ebuf, curid, vwctx, vctx, mut store) =
+            mkfivestr("hello world\n1 2 3 4 5 6\n  a b c d e f\n");
+
+        // Set cursor to (0, 7).
+        ebuf.set_leader(curid, Cursor::new(0, 7));
+
+        // Number the lines along the rectangle's left edge.
+        let act = EditAction::Rectangle(RectangleChange::Number);
+        edit!(ebuf, act, range!(RangeType::Line, 3), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "he1 llo world\n1 2 2 3 4 5 6\n  3 a b c d e f\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 2));
+    }
+
+    #[test]
+    fn test_transpose_chars() {
+        let (mut ebuf, curid, vwctx, mut vctx, mut store) = mkfivestr("abcd\n");
+        let act = EditAction::Transpose(TransposeStyle::Char, MoveDir1D::Next);
+
+        // Swap the characters on either side of the cursor.
+        ebuf.set_leader(curid, Cursor::new(0, 1));
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "bacd\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 2));
+
+        // At the end of the line, swap the two characters before the cursor.
+        ebuf.set_leader(curid, Cursor::new(0, 4));
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "badc\n");
+
+        // Drag the character before the cursor forward past two others.
+        vctx.count = Some(2);
+        ebuf.set_leader(curid, Cursor::new(0, 1));
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "adbc\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 3));
+
+        // Drag it backwards instead.
+        let act = EditAction::Transpose(TransposeStyle::Char, MoveDir1D::Previous);
+        vctx.count = Some(1);
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "abdc\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 2));
+
+        // There's nothing to swap with at the start of the buffer.
+        ebuf.set_leader(curid, Cursor::new(0, 0));
+        let res =
+            ebuf.edit(&act, &EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), &mut store);
+        assert!(res.is_err());
+        assert_eq!(ebuf.get_text(), "abdc\n");
+    }
+
+    #[test]
+    fn test_transpose_words() {
+        let (mut ebuf, curid, vwctx, mut vctx, mut store) = mkfivestr("aa bb, cc dd\n");
+        let style = TransposeStyle::Word(WordStyle::AlphaNum);
+        let act = EditAction::Transpose(style.clone(), MoveDir1D::Next);
+
+        // Swap the word before the cursor with the one after it.
+        ebuf.set_leader(curid, Cursor::new(0, 2));
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "bb aa, cc dd\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 5));
+
+        // In the middle of a word, that word gets dragged forward past the next two.
+        vctx.count = Some(2);
+        ebuf.set_leader(curid, Cursor::new(0, 4));
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "bb cc dd, aa\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 11));
+
+        // A negative argument drags the word backwards.
+        let act = EditAction::Transpose(style.clone(), MoveDir1D::Previous);
+        vctx.count = Some(1);
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "bb cc aa, dd\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 8));
+
+        // A zero argument swaps the words at the cursor and the selection anchor.
+        ebuf.set_leader(curid, Cursor::new(0, 0));
+        vctx.target_shape = Some(TargetShape::CharWise);
+        let mov = MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next);
+        edit!(ebuf, EditAction::Motion, mv!(mov, 2), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 6));
+
+        vctx.target_shape = None;
+        vctx.count = Some(0);
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "aa cc bb, dd\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 6));
+    }
+
+    #[test]
+    fn test_transpose_lines() {
+        let (mut ebuf, curid, vwctx, mut vctx, mut store) = mkfivestr("1\n2\n3\n4\n");
+        let act = EditAction::Transpose(TransposeStyle::Line, MoveDir1D::Next);
+
+        // Swap the current and previous lines, and move to the next line.
+        ebuf.set_leader(curid, Cursor::new(1, 0));
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "2\n1\n3\n4\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(2, 0));
+
+        // Drag the previous line down past two lines.
+        vctx.count = Some(2);
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "2\n3\n4\n1\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(3, 0));
+    }
+
+    #[test]
+    fn test_transpose_sexps() {
+        let (mut ebuf, curid, vwctx, vctx, mut store) = mkfivestr("(a b) [c (d)] e\n");
+        let act = EditAction::Transpose(TransposeStyle::Sexp, MoveDir1D::Next);
+
+        // Balanced groups get swapped as a unit.
+        ebuf.set_leader(curid, Cursor::new(0, 5));
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "[c (d)] (a b) e\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 13));
+
+        // Atoms can be swapped with groups.
+        edit!(ebuf, act, EditTarget::CurrentPosition, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "[c (d)] e (a b)\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 14));
+    }
+
+    #[test]
+    fn test_surround() {
+        let (mut ebuf, curid, vwctx, mut vctx, mut store) = mkfivestr("hello world\n");
+
+        let mov = MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next);
+
+        // Surround the first word with parentheses.
+        vctx.replace_char = Some(')'.into());
+        let operation = EditAction::Surround(SurroundChange::Add);
+        edit!(ebuf, operation, mv!(mov), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "(hello) world\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 0));
+
+        // Replace the parentheses with brackets.
+        vctx.replace_char = Some('['.into());
+        let operation = EditAction::Surround(SurroundChange::Replace);
+        let target = range!(RangeType::Bracketed('(', ')'));
+        ebuf.set_leader(curid, Cursor::new(0, 2));
+        edit!(ebuf, operation, target, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "[hello] world\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 0));
+
+        // Delete the brackets.
+        vctx.replace_char = None;
+        let operation = EditAction::Surround(SurroundChange::Delete);
+        let target = range!(RangeType::Bracketed('[', ']'));
+        ebuf.set_leader(curid, Cursor::new(0, 2));
+        edit!(ebuf, operation, target, ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello world\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 0));
+
+        // Adding requires a character to surround the text with.
+        let operation = EditAction::Surround(SurroundChange::Add);
+        let res = ebuf.edit(&operation, &mv!(mov), ctx!(curid, vwctx, vctx), &mut store);
+        assert!(res.is_err());
+        assert_eq!(ebuf.get_text(), "hello world\n");
+    }
+
     #[test]
     fn test_forced_motion_char() {
         let (mut ebuf, curid, vwctx, mut vctx, mut store) =
@@ -1202,4 +1834,197 @@ mod tests {
         assert_eq!(ebuf.get_text(), "a 4 b 2 c\nd 9 e 4 f\ng 14 h 6 i\nj 19 k 8 l\n");
         assert_eq!(ebuf.get_leader(curid), Cursor::new(3, 3));
     }
+
+    #[test]
+    fn test_format() {
+        let (mut ebuf, curid, vwctx, vctx, mut store) = mkfivestr(
+            "// one two three four five six\n\
+            // seven eight\n\
+            \n\
+            \x20 - alpha beta gamma\n\
+            \x20   delta   epsilon  \n",
+        );
+
+        ebuf.set_fill_column(16);
+        ebuf.set_leader(curid, Cursor::new(1, 3));
+
+        // Each paragraph is filled separately, keeping the comment leaders and indentation.
+        let operation = EditAction::Format;
+        edit!(ebuf, operation, range!(RangeType::Buffer), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(
+            ebuf.get_text(),
+            "// one two three\n\
+            // four five six\n\
+            // seven eight\n\
+            \n\
+            \x20 - alpha beta\n\
+            \x20   gamma delta\n\
+            \x20   epsilon  \n"
+        );
+
+        // The cursor stays on the same word.
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(2, 3));
+
+        // Filling again doesn't change anything.
+        edit!(ebuf, operation, range!(RangeType::Buffer), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text().lines().count(), 7);
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(2, 3));
+
+        // Lines are joined back together when the fill column grows.
+        ebuf.set_fill_column(70);
+        edit!(ebuf, operation, range!(RangeType::Buffer), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(
+            ebuf.get_text(),
+            "// one two three four five six seven eight\n\
+            \n\
+            \x20 - alpha beta gamma delta epsilon  \n"
+        );
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 31));
+    }
+
+    #[test]
+    fn test_format_bullets() {
+        let (mut ebuf, curid, vwctx, vctx, mut store) = mkfivestr(
+            "* one two three four\n\
+            \n\
+            \x20* one two three\n\
+            \x20* four\n\
+            \n\
+            日本 日本 日本\n",
+        );
+
+        ebuf.set_fill_column(12);
+
+        // Bullets aren't repeated on continuation lines, which get indented past them instead.
+        // Lines in block comments keep their leaders, and wide characters take up two columns.
+        let operation = EditAction::Format;
+        edit!(ebuf, operation, range!(RangeType::Buffer), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(
+            ebuf.get_text(),
+            "* one two\n\
+            \x20 three four\n\
+            \n\
+            \x20* one two\n\
+            \x20* three\n\
+            \x20* four\n\
+            \n\
+            日本 日本\n\
+            日本\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_lines() {
+        let (mut ebuf, curid, vwctx, vctx, mut store) =
+            mkfivestr("pear\nfig\napple\nbanana\ncherry\n");
+        let operation = EditAction::SortLines;
+
+        // Only the targeted lines get sorted.
+        ebuf.set_leader(curid, Cursor::new(1, 2));
+        edit!(ebuf, operation, range!(RangeType::Line, 3), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "pear\napple\nbanana\nfig\ncherry\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(1, 0));
+
+        // Sort the whole buffer.
+        edit!(ebuf, operation, range!(RangeType::Buffer), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "apple\nbanana\ncherry\nfig\npear\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 0));
+    }
+
+    #[test]
+    fn test_trim_whitespace() {
+        let (mut ebuf, curid, vwctx, vctx, mut store) =
+            mkfivestr("a  \n  b\t \n\t\nc d \ne  \n");
+        let operation = EditAction::TrimTrailingWhitespace;
+
+        // Only the targeted lines get trimmed, and the cursor stays where it was.
+        ebuf.set_leader(curid, Cursor::new(1, 1));
+        edit!(ebuf, operation, range!(RangeType::Line, 2), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "a  \n  b\n\nc d \ne  \n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(1, 1));
+
+        // Trim the whole buffer, with the cursor in the trimmed whitespace.
+        ebuf.set_leader(curid, Cursor::new(4, 2));
+        edit!(ebuf, operation, range!(RangeType::Buffer), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "a\n  b\n\nc d\ne\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(4, 0));
+    }
+
+    #[derive(Clone, Debug, Eq, PartialEq)]
+    enum OperatorInfo {}
+
+    impl ApplicationInfo for OperatorInfo {
+        type Error = String;
+        type Action = ();
+        type Store = ();
+        type WindowId = String;
+        type ContentId = String;
+
+        fn content_of_command(ct: CommandType) -> String {
+            EmptyInfo::content_of_command(ct)
+        }
+
+        fn operator(
+            name: &str,
+            text: &EditRope,
+            _: &EditRange<Cursor>,
+            _: &EditRope,
+            _: &String,
+            _: &mut Store<Self>,
+        ) -> EditResult<EditRope, Self> {
+            if name != "urlencode" {
+                return Err(EditError::Failure(format!("Unknown operator: {name}")));
+            }
+
+            let encoded = text
+                .to_string()
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_string()
+                    } else {
+                        format!("%{:02X}", c as u32)
+                    }
+                })
+                .collect::<String>();
+
+            return Ok(encoded.into());
+        }
+    }
+
+    #[test]
+    fn test_application_operator() {
+        let mut ebuf = EditBuffer::<OperatorInfo>::new("".to_string());
+        let curid = ebuf.create_group();
+        let vwctx = ViewportContext::default();
+        let mut vctx = mkctx();
+        let mut store = Store::<OperatorInfo>::default();
+        let op = EditAction::Application("urlencode".into());
+
+        ebuf.set_text("hello world\n1 2 3 4 5 6\n  a b c d e f\n");
+
+        // Encode the first word and the space after it.
+        let mov = MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next);
+        edit!(ebuf, op, mv!(mov), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello%20world\n1 2 3 4 5 6\n  a b c d e f\n");
+        assert_eq!(ebuf.get_leader(curid), Cursor::new(0, 0));
+
+        // Linewise ranges leave the final newline alone.
+        ebuf.set_leader(curid, Cursor::new(1, 0));
+        edit!(ebuf, op, range!(RangeType::Line), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello%20world\n1%202%203%204%205%206\n  a b c d e f\n");
+
+        // Each line in a block gets encoded separately.
+        ebuf.set_text("hello world\n1 2 3 4 5 6\n  a b c d e f\n");
+        ebuf.set_leader(curid, Cursor::new(0, 7));
+        vctx.target_shape = Some(TargetShape::BlockWise);
+        edit!(ebuf, op, range!(RangeType::Line, 3), ctx!(curid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello%20world\n1 2%203%204%205 6\n  a%20b%20c%20d e f\n");
+
+        // Unknown operators fail without changing the text.
+        let op = EditAction::Application("translate".into());
+        let res = ebuf.edit(&op, &range!(RangeType::Line), ctx!(curid, vwctx, vctx), &mut store);
+        assert!(matches!(res, Err(EditError::Failure(_))));
+        assert_eq!(ebuf.get_text(), "hello%20world\n1 2%203%204%205 6\n  a%20b%20c%20d e f\n");
+    }
 }
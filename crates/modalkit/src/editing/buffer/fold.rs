@@ -0,0 +1,919 @@
+use std::borrow::Cow;
+
+use intervaltree::IntervalTree;
+
+use crate::actions::FoldAction;
+use crate::editing::{
+    application::ApplicationInfo,
+    context::{EditContext, Resolve},
+    cursor::{Adjustable, Cursor, CursorAdjustment, CursorChoice},
+    rope::EditRope,
+    store::Store,
+};
+use crate::errors::{EditError, EditResult};
+use crate::prelude::*;
+use crate::util::sort2;
+
+use super::{CursorGroupId, CursorGroupIdContext, CursorRange, EditBuffer};
+
+/// How many columns a tab counts for when comparing indentation for [FoldMethod::Indent].
+const FOLD_TAB_WIDTH: usize = 8;
+
+/// An [IntervalTree] of the outermost closed folds for a cursor group, keyed on line offset.
+///
+/// Each value contains the first and last lines of the fold.
+pub type FoldInfo = IntervalTree<usize, (usize, usize)>;
+
+/// How the folds for a cursor group get created.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum FoldMethod {
+    /// Folds are created using [EditAction::Fold](crate::actions::EditAction::Fold), and removed
+    /// using [FoldAction::Delete] and [FoldAction::DeleteAll].
+    #[default]
+    Manual,
+
+    /// Create a fold for each run of lines indented further than the line before them.
+    Indent,
+
+    /// Create a fold from each line containing the first [String] through the next line
+    /// containing the second [String].
+    Marker(String, String),
+}
+
+impl FoldMethod {
+    /// Create a [FoldMethod::Marker] that uses `{{{` and `}}}` as its markers.
+    pub fn marker() -> Self {
+        FoldMethod::Marker("{{{".into(), "}}}".into())
+    }
+}
+
+/// A range of lines that can be collapsed into a single line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fold {
+    start: usize,
+    end: usize,
+    closed: bool,
+}
+
+impl Fold {
+    fn new(start: usize, end: usize, closed: bool) -> Self {
+        Fold { start, end, closed }
+    }
+
+    /// Get the first line of this fold.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Get the last line of this fold.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Indicates whether this fold is currently closed.
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Indicates whether `line` falls within this fold.
+    pub fn contains(&self, line: usize) -> bool {
+        self.start <= line && line <= self.end
+    }
+
+    fn adjusted(mut self, adjs: &[CursorAdjustment]) -> Option<Self> {
+        for adj in adjs {
+            self.start = adjust_line(self.start, false, adj)?;
+            self.end = adjust_line(self.end, true, adj)?;
+
+            if self.end < self.start {
+                return None;
+            }
+        }
+
+        Some(self)
+    }
+}
+
+fn shift_line(line: usize, amount: isize) -> usize {
+    let abs = amount.unsigned_abs();
+
+    if amount < 0 {
+        line.saturating_sub(abs)
+    } else {
+        line.saturating_add(abs)
+    }
+}
+
+/// Adjust the first or last line of a fold, returning [None] if the fold no longer has any lines.
+fn adjust_line(line: usize, last: bool, adj: &CursorAdjustment) -> Option<usize> {
+    match adj {
+        CursorAdjustment::Line { line_start, line_end, amount, amount_after } => {
+            if line < *line_start {
+                Some(line)
+            } else if line > *line_end {
+                Some(shift_line(line, *amount_after))
+            } else if *amount != isize::MAX {
+                Some(shift_line(line, *amount))
+            } else if last {
+                // The last line was deleted, so the fold now ends before the deleted lines.
+                line_start.checked_sub(1)
+            } else {
+                // The first line was deleted, so the fold now starts after the deleted lines.
+                Some(*line_start)
+            }
+        },
+        CursorAdjustment::Column { line: l, column_start, amt_line, .. } => {
+            // Lines split off of the end of the fold stay inside of it, while splitting the
+            // start of the first line pushes the whole fold down.
+            if line == *l && (last || *column_start == 0) {
+                Some(shift_line(line, *amt_line))
+            } else {
+                Some(line)
+            }
+        },
+    }
+}
+
+fn indent_width(line: &str) -> Option<usize> {
+    let mut width = 0;
+
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += FOLD_TAB_WIDTH - width % FOLD_TAB_WIDTH,
+            '\n' | '\r' => return None,
+            c if c.is_whitespace() => width += 1,
+            _ => return Some(width),
+        }
+    }
+
+    None
+}
+
+fn indent_folds(text: &EditRope) -> Vec<(usize, usize)> {
+    let widths = text
+        .lines(0)
+        .map(|line| indent_width(Cow::from(&line).as_ref()))
+        .collect::<Vec<_>>();
+
+    // Blank lines take on the smaller indentation of the lines around them.
+    let mut after = vec![0; widths.len()];
+    let mut next = 0;
+
+    for (i, width) in widths.iter().enumerate().rev() {
+        after[i] = next;
+        next = width.unwrap_or(next);
+    }
+
+    let mut folds = vec![];
+    let mut stack: Vec<(usize, usize)> = vec![];
+    let mut prev = 0;
+
+    for (i, width) in widths.iter().enumerate() {
+        let width = match width {
+            Some(w) => *w,
+            None => prev.min(after[i]),
+        };
+
+        while let Some((level, start)) = stack.last().cloned() {
+            if level <= width {
+                break;
+            }
+
+            stack.pop();
+            folds.push((start, i - 1));
+        }
+
+        if width > stack.last().map(|(level, _)| *level).unwrap_or(0) {
+            stack.push((width, i));
+        }
+
+        if let Some(w) = widths[i] {
+            prev = w;
+        }
+    }
+
+    let last = widths.len().saturating_sub(1);
+
+    while let Some((_, start)) = stack.pop() {
+        folds.push((start, last));
+    }
+
+    folds.retain(|(start, end)| start < end);
+    folds
+}
+
+fn marker_folds(text: &EditRope, open: &str, close: &str) -> Vec<(usize, usize)> {
+    let mut folds = vec![];
+    let mut stack = vec![];
+    let mut last = 0;
+
+    for (i, line) in text.lines(0).enumerate() {
+        let line = Cow::from(&line);
+        last = i;
+
+        if !open.is_empty() && line.contains(open) {
+            stack.push(i);
+        }
+
+        if !close.is_empty() && line.contains(close) {
+            if let Some(start) = stack.pop() {
+                folds.push((start, i));
+            }
+        }
+    }
+
+    while let Some(start) = stack.pop() {
+        folds.push((start, last));
+    }
+
+    folds.retain(|(start, end)| start < end);
+    folds
+}
+
+/// The folds for a cursor group, and how they get created.
+#[derive(Clone, Debug, Default)]
+pub(super) struct FoldList {
+    method: FoldMethod,
+
+    /// Folds ordered by their first line, with enclosing folds placed before the ones they contain.
+    folds: Vec<Fold>,
+
+    /// The text that the folds were last calculated from.
+    text: Option<EditRope>,
+}
+
+impl FoldList {
+    fn sort(&mut self) {
+        self.folds.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+        self.folds.dedup_by(|a, b| a.start == b.start && a.end == b.end);
+    }
+
+    /// Recalculate the folds from the buffer text when they aren't being managed manually.
+    ///
+    /// Folds that start on the same line as a previous fold keep its open or closed state, and
+    /// any new ones are closed if `closed` is true.
+    fn update(&mut self, text: &EditRope, closed: bool) {
+        if matches!(&self.text, Some(prev) if prev.is_instance(text)) {
+            return;
+        }
+
+        let ranges = match &self.method {
+            FoldMethod::Manual => return,
+            FoldMethod::Indent => indent_folds(text),
+            FoldMethod::Marker(open, close) => marker_folds(text, open, close),
+        };
+
+        let folds = ranges
+            .into_iter()
+            .map(|(start, end)| {
+                let closed = match self.folds.binary_search_by_key(&start, Fold::start) {
+                    Ok(idx) => self.folds[idx].closed,
+                    Err(_) => closed,
+                };
+
+                Fold::new(start, end, closed)
+            })
+            .collect();
+
+        self.folds = folds;
+        self.text = Some(text.clone());
+        self.sort();
+    }
+
+    fn is_manual(&self) -> bool {
+        matches!(self.method, FoldMethod::Manual)
+    }
+
+    fn has_closed(&self) -> bool {
+        self.folds.iter().any(Fold::is_closed)
+    }
+
+    /// The folds that aren't hidden inside of a closed fold.
+    fn visible(&self) -> Vec<&Fold> {
+        let mut visible = vec![];
+        let mut hidden: Option<&Fold> = None;
+
+        for fold in self.folds.iter() {
+            if let Some(h) = hidden {
+                if h.contains(fold.start) && h.contains(fold.end) {
+                    continue;
+                }
+            }
+
+            if fold.closed {
+                hidden = Some(fold);
+            }
+
+            visible.push(fold);
+        }
+
+        visible
+    }
+
+    /// The indexes of the folds containing `line`, from outermost to innermost.
+    fn around(&self, line: usize) -> Vec<usize> {
+        self.folds
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, fold)| fold.contains(line).then_some(idx))
+            .collect()
+    }
+
+    /// The first and last lines of the outermost closed fold containing `line`.
+    fn closed_at(&self, line: usize) -> Option<(usize, usize)> {
+        self.folds
+            .iter()
+            .find(|fold| fold.closed && fold.contains(line))
+            .map(|fold| (fold.start, fold.end))
+    }
+
+    fn next_fold(&self, line: usize, dir: MoveDir1D) -> Option<usize> {
+        let visible = self.visible().into_iter();
+
+        match dir {
+            MoveDir1D::Next => visible.map(Fold::start).filter(|start| *start > line).min(),
+            MoveDir1D::Previous => {
+                let end = visible.map(Fold::end).filter(|end| *end < line).max()?;
+                let end = self.closed_at(end).map(|(start, _)| start).unwrap_or(end);
+
+                Some(end)
+            },
+        }
+    }
+
+    fn create(&mut self, start: usize, end: usize) {
+        self.folds.push(Fold::new(start, end, true));
+        self.sort();
+    }
+
+    fn open(&mut self, line: usize, count: usize) -> bool {
+        let around = self.around(line);
+
+        if around.is_empty() {
+            return false;
+        }
+
+        let first = around
+            .iter()
+            .position(|idx| self.folds[*idx].closed)
+            .unwrap_or(around.len());
+
+        for idx in around[first..].iter().take(count) {
+            self.folds[*idx].closed = false;
+        }
+
+        return true;
+    }
+
+    fn close(&mut self, line: usize, count: usize) -> bool {
+        let around = self.around(line);
+
+        if around.is_empty() {
+            return false;
+        }
+
+        let first = around
+            .iter()
+            .position(|idx| self.folds[*idx].closed)
+            .unwrap_or(around.len());
+
+        for idx in around[..first].iter().rev().take(count) {
+            self.folds[*idx].closed = true;
+        }
+
+        return true;
+    }
+
+    fn toggle(&mut self, line: usize, count: usize) -> bool {
+        if self.closed_at(line).is_some() {
+            self.open(line, count)
+        } else {
+            self.close(line, count)
+        }
+    }
+
+    fn delete(&mut self, line: usize) -> bool {
+        let around = self.around(line);
+        let closed = around.iter().find(|idx| self.folds[**idx].closed);
+
+        if let Some(idx) = closed.or(around.last()) {
+            self.folds.remove(*idx);
+
+            return true;
+        } else {
+            return false;
+        }
+    }
+
+    fn set_all(&mut self, closed: bool) {
+        for fold in self.folds.iter_mut() {
+            fold.closed = closed;
+        }
+    }
+}
+
+impl Adjustable for FoldList {
+    fn zero(&mut self) {
+        self.folds.clear();
+        self.text = None;
+    }
+
+    fn adjust(&mut self, adjs: &[CursorAdjustment]) {
+        self.folds = std::mem::take(&mut self.folds)
+            .into_iter()
+            .filter_map(|fold| fold.adjusted(adjs))
+            .collect();
+        self.sort();
+    }
+}
+
+fn no_fold<I: ApplicationInfo>() -> EditError<I> {
+    EditError::Failure("No fold found".into())
+}
+
+fn not_manual<I: ApplicationInfo>() -> EditError<I> {
+    EditError::Failure("Folds can only be created and deleted with the manual method".into())
+}
+
+impl<I> EditBuffer<I>
+where
+    I: ApplicationInfo,
+{
+    fn _folds(&mut self, gid: CursorGroupId) -> &mut FoldList {
+        let folds = self.folds.entry(gid).or_default();
+        folds.update(&self.text, false);
+        folds
+    }
+
+    /// Rewrite a movement so that it treats each closed fold as a single line, and so that fold
+    /// movements turn into line movements.
+    pub(super) fn _fold_movement(
+        &mut self,
+        gid: CursorGroupId,
+        cursor: &Cursor,
+        mv: &MoveType,
+        count: &Count,
+        ctx: &EditContext,
+    ) -> (MoveType, Count) {
+        match mv {
+            MoveType::Line(dir) | MoveType::FirstWord(dir) => {
+                let max = self.text.get_lines().saturating_sub(1);
+                let folds = self._folds(gid);
+
+                if !folds.has_closed() {
+                    return (mv.clone(), count.clone());
+                }
+
+                let mut y = cursor.y;
+
+                for _ in 0..ctx.resolve(count) {
+                    match dir {
+                        MoveDir1D::Next => {
+                            let end = folds.closed_at(y).map(|(_, end)| end).unwrap_or(y);
+
+                            if end >= max {
+                                break;
+                            }
+
+                            y = end + 1;
+                        },
+                        MoveDir1D::Previous => {
+                            let start = folds.closed_at(y).map(|(start, _)| start).unwrap_or(y);
+
+                            if start == 0 {
+                                break;
+                            }
+
+                            y = start - 1;
+                        },
+                    }
+                }
+
+                let y = folds.closed_at(y).map(|(start, _)| start).unwrap_or(y);
+                let (dir, count) = if y >= cursor.y {
+                    (MoveDir1D::Next, y - cursor.y)
+                } else {
+                    (MoveDir1D::Previous, cursor.y - y)
+                };
+
+                let mv = match mv {
+                    MoveType::Line(_) => MoveType::Line(dir),
+                    _ => MoveType::FirstWord(dir),
+                };
+
+                (mv, Count::Exact(count))
+            },
+            MoveType::Fold(dir) => {
+                let folds = self._folds(gid);
+                let mut y = None;
+
+                for _ in 0..ctx.resolve(count) {
+                    match folds.next_fold(y.unwrap_or(cursor.y), *dir) {
+                        Some(line) => y = Some(line),
+                        None => break,
+                    }
+                }
+
+                match y {
+                    Some(y) => (MoveType::BufferLineOffset, Count::Exact(y + 1)),
+                    None => (mv.clone(), count.clone()),
+                }
+            },
+            _ => (mv.clone(), count.clone()),
+        }
+    }
+
+    /// Expand a line-wise range so that it covers any closed folds at either of its ends.
+    pub(super) fn _fold_range(&mut self, gid: CursorGroupId, range: CursorRange) -> CursorRange {
+        if range.shape != TargetShape::LineWise {
+            return range;
+        }
+
+        let folds = self._folds(gid);
+
+        if !folds.has_closed() {
+            return range;
+        }
+
+        let mut range = range;
+
+        if let Some((start, _)) = folds.closed_at(range.start.y) {
+            range.start = Cursor::new(start, 0);
+        }
+
+        if let Some((_, end)) = folds.closed_at(range.end.y) {
+            range.end = Cursor::new(end, 0);
+        }
+
+        return range;
+    }
+
+    /// Change how folds get created for a cursor group.
+    ///
+    /// Any previous folds are removed. When using [FoldMethod::Indent] or [FoldMethod::Marker],
+    /// the folds found in the current text start out closed.
+    pub fn set_fold_method(&mut self, gid: CursorGroupId, method: FoldMethod) {
+        let folds = self.folds.entry(gid).or_default();
+
+        folds.method = method;
+        folds.folds.clear();
+        folds.text = None;
+        folds.update(&self.text, true);
+    }
+
+    /// Get how folds get created for a cursor group.
+    pub fn get_fold_method(&self, gid: CursorGroupId) -> FoldMethod {
+        self.folds.get(gid).map(|folds| folds.method.clone()).unwrap_or_default()
+    }
+
+    /// Get the folds for a cursor group.
+    ///
+    /// Folds are ordered by their first line, and enclosing folds come before the ones they
+    /// contain.
+    pub fn get_folds(&mut self, gid: CursorGroupId) -> Vec<Fold> {
+        self._folds(gid).folds.clone()
+    }
+
+    /// Returns an [IntervalTree] containing the outermost closed folds for a cursor group.
+    pub fn fold_intervals(&mut self, gid: CursorGroupId) -> FoldInfo {
+        self._folds(gid)
+            .visible()
+            .into_iter()
+            .filter(|fold| fold.closed)
+            .map(|fold| (fold.start..fold.end.saturating_add(1), (fold.start, fold.end)))
+            .collect()
+    }
+}
+
+pub trait FoldActions<C, S, I>
+where
+    I: ApplicationInfo,
+{
+    fn fold_create(
+        &mut self,
+        range: &CursorRange,
+        ctx: &C,
+        store: &mut S,
+    ) -> EditResult<CursorChoice, I>;
+
+    fn fold_command(&mut self, act: &FoldAction, ctx: &C, store: &mut S)
+        -> EditResult<EditInfo, I>;
+}
+
+impl<'a, I> FoldActions<CursorGroupIdContext<'a>, Store<I>, I> for EditBuffer<I>
+where
+    I: ApplicationInfo,
+{
+    fn fold_create(
+        &mut self,
+        range: &CursorRange,
+        ctx: &CursorGroupIdContext<'a>,
+        _: &mut Store<I>,
+    ) -> EditResult<CursorChoice, I> {
+        let folds = self._folds(ctx.0);
+
+        if !folds.is_manual() {
+            return Err(not_manual());
+        }
+
+        let (start, end) = sort2(range.start.clone(), range.end.clone());
+        folds.create(start.y, end.y);
+
+        Ok(CursorChoice::Single(start))
+    }
+
+    fn fold_command(
+        &mut self,
+        act: &FoldAction,
+        ctx: &CursorGroupIdContext<'a>,
+        _: &mut Store<I>,
+    ) -> EditResult<EditInfo, I> {
+        let line = self.get_leader(ctx.0).y;
+        let folds = self._folds(ctx.0);
+
+        let found = match act {
+            FoldAction::Close(count) => folds.close(line, ctx.2.resolve(count)),
+            FoldAction::Open(count) => folds.open(line, ctx.2.resolve(count)),
+            FoldAction::Toggle(count) => folds.toggle(line, ctx.2.resolve(count)),
+            FoldAction::CloseAll => {
+                folds.set_all(true);
+                true
+            },
+            FoldAction::OpenAll => {
+                folds.set_all(false);
+                true
+            },
+            FoldAction::Delete => {
+                if !folds.is_manual() {
+                    return Err(not_manual());
+                }
+
+                folds.delete(line)
+            },
+            FoldAction::DeleteAll => {
+                if !folds.is_manual() {
+                    return Err(not_manual());
+                }
+
+                folds.folds.clear();
+                true
+            },
+        };
+
+        if found {
+            Ok(None)
+        } else {
+            Err(no_fold())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::*;
+    use super::*;
+
+    fn mkfold(start: usize, end: usize, closed: bool) -> Fold {
+        Fold::new(start, end, closed)
+    }
+
+    #[test]
+    fn test_fold_create_lines() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("a\nb\nc\nd\ne\nf\n");
+        let next = MoveType::Line(MoveDir1D::Next);
+        let prev = MoveType::Line(MoveDir1D::Previous);
+
+        // Fold lines 1 through 3.
+        ebuf.set_leader(gid, Cursor::new(1, 0));
+        edit!(ebuf, EditAction::Fold, mv!(next, 2), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 3, true)]);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 0));
+
+        // Moving through the buffer treats the closed fold as a single line.
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        edit!(ebuf, EditAction::Motion, mv!(next, 1), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 0));
+        edit!(ebuf, EditAction::Motion, mv!(next, 1), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(4, 0));
+        edit!(ebuf, EditAction::Motion, mv!(prev, 1), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 0));
+        edit!(ebuf, EditAction::Motion, mv!(prev, 1), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 0));
+        edit!(ebuf, EditAction::Motion, mv!(next, 3), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(5, 0));
+
+        // Deleting lines across the fold treats it as a single line too.
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        edit!(ebuf, EditAction::Delete, mv!(next, 1), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "e\nf\n");
+        assert_eq!(ebuf.get_folds(gid), vec![]);
+
+        // Other cursor groups don't see the fold.
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("a\nb\nc\nd\ne\nf\n");
+        let other = ebuf.create_group();
+        edit!(ebuf, EditAction::Fold, mv!(next, 2), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(0, 2, true)]);
+        assert_eq!(ebuf.get_folds(other), vec![]);
+
+        edit!(ebuf, EditAction::Motion, mv!(next, 1), ctx!(other, vwctx, vctx), store);
+        assert_eq!(ebuf.get_leader(other), Cursor::new(1, 0));
+    }
+
+    #[test]
+    fn test_fold_open_close() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("a\nb\nc\nd\ne\nf\n");
+        let next = MoveType::Line(MoveDir1D::Next);
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        // Create nested folds over lines 1-4 and 2-3.
+        ebuf.set_leader(gid, Cursor::new(2, 0));
+        edit!(ebuf, EditAction::Fold, mv!(next, 1), ctx, store);
+        ebuf.set_leader(gid, Cursor::new(1, 0));
+        edit!(ebuf, EditAction::Fold, mv!(next, 2), ctx, store);
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, true), mkfold(2, 3, true)]);
+        assert_eq!(ebuf.fold_intervals(gid).query_point(2).count(), 1);
+
+        // Open the outer fold, leaving the inner one closed.
+        ebuf.set_leader(gid, Cursor::new(2, 0));
+        ebuf.fold_command(&FoldAction::Open(Count::Exact(1)), ctx, &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, false), mkfold(2, 3, true)]);
+
+        let closed = ebuf.fold_intervals(gid).into_iter().map(|f| f.value).collect::<Vec<_>>();
+        assert_eq!(closed, vec![(2, 3)]);
+
+        // Toggling opens the inner one.
+        ebuf.fold_command(&FoldAction::Toggle(Count::Exact(1)), ctx, &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, false), mkfold(2, 3, false)]);
+
+        // Closing goes from the innermost fold outwards.
+        ebuf.fold_command(&FoldAction::Close(Count::Exact(1)), ctx, &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, false), mkfold(2, 3, true)]);
+        ebuf.fold_command(&FoldAction::Toggle(Count::Exact(1)), ctx, &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, false), mkfold(2, 3, false)]);
+        ebuf.fold_command(&FoldAction::Close(Count::Exact(2)), ctx, &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, true), mkfold(2, 3, true)]);
+
+        // A count opens several levels at once.
+        ebuf.fold_command(&FoldAction::Open(Count::Exact(2)), ctx, &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, false), mkfold(2, 3, false)]);
+
+        ebuf.fold_command(&FoldAction::CloseAll, ctx, &mut store).unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, true), mkfold(2, 3, true)]);
+        ebuf.fold_command(&FoldAction::OpenAll, ctx, &mut store).unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, false), mkfold(2, 3, false)]);
+
+        // Deleting removes the innermost fold around the cursor.
+        ebuf.fold_command(&FoldAction::Delete, ctx, &mut store).unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, false)]);
+        ebuf.fold_command(&FoldAction::DeleteAll, ctx, &mut store).unwrap();
+        assert_eq!(ebuf.get_folds(gid), vec![]);
+
+        // There's nothing left to open or close.
+        let res = ebuf.fold_command(&FoldAction::Open(Count::Exact(1)), ctx, &mut store);
+        assert!(res.is_err());
+        let res = ebuf.fold_command(&FoldAction::Close(Count::Exact(1)), ctx, &mut store);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_fold_adjust() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("a\nb\nc\nd\ne\nf\n");
+        let next = MoveType::Line(MoveDir1D::Next);
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        ebuf.set_leader(gid, Cursor::new(2, 0));
+        edit!(ebuf, EditAction::Fold, mv!(next, 2), ctx, store);
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(2, 4, true)]);
+
+        // Deleting a line before the fold moves it up.
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        edit!(ebuf, EditAction::Delete, range!(RangeType::Line), ctx, store);
+        assert_eq!(ebuf.get_text(), "b\nc\nd\ne\nf\n");
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 3, true)]);
+
+        // Opening a line inside of the fold grows it.
+        ebuf.set_leader(gid, Cursor::new(2, 0));
+        ebuf.open_line(TargetShape::LineWise, MoveDir1D::Next, &Count::Exact(1), ctx, &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_text(), "b\nc\nd\n\ne\nf\n");
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 4, true)]);
+
+        // Deleting the first line of the fold shrinks it.
+        ebuf.set_leader(gid, Cursor::new(1, 0));
+        edit!(ebuf, EditAction::Delete, range!(RangeType::Line), ctx, store);
+        assert_eq!(ebuf.get_text(), "b\nd\n\ne\nf\n");
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 3, true)]);
+
+        // Deleting all of its lines removes it.
+        edit!(ebuf, EditAction::Delete, range!(RangeType::Line, 3), ctx, store);
+        assert_eq!(ebuf.get_text(), "b\nf\n");
+        assert_eq!(ebuf.get_folds(gid), vec![]);
+    }
+
+    #[test]
+    fn test_fold_indent() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) =
+            mkfivestr("a:\n  b\n    c\n    d\n\n  e\nf\n  g\n");
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        ebuf.set_fold_method(gid, FoldMethod::Indent);
+        assert_eq!(ebuf.get_fold_method(gid), FoldMethod::Indent);
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 5, true), mkfold(2, 3, true)]);
+
+        // Folds can't be created by hand.
+        let res = ebuf.edit(&EditAction::Fold, &range!(RangeType::Line), ctx, &mut store);
+        assert!(res.is_err());
+
+        // Opened folds stay open after editing.
+        ebuf.set_leader(gid, Cursor::new(1, 0));
+        ebuf.fold_command(&FoldAction::Open(Count::Exact(1)), ctx, &mut store)
+            .unwrap();
+        ebuf.set_leader(gid, Cursor::new(6, 0));
+        edit!(ebuf, EditAction::Delete, range!(RangeType::Line), ctx, store);
+        assert_eq!(ebuf.get_text(), "a:\n  b\n    c\n    d\n\n  e\n  g\n");
+        assert_eq!(ebuf.get_folds(gid), vec![mkfold(1, 6, false), mkfold(2, 3, true)]);
+    }
+
+    #[test]
+    fn test_fold_update_cached() {
+        let mut folds = FoldList { method: FoldMethod::Indent, ..Default::default() };
+        let mut text = EditRope::from("a\n  b\n  c\nd\n");
+
+        folds.update(&text, false);
+        assert_eq!(folds.folds, vec![mkfold(1, 2, false)]);
+
+        // Folds aren't recalculated while the text stays the same.
+        folds.folds.clear();
+        folds.update(&text.clone(), false);
+        assert_eq!(folds.folds, vec![]);
+
+        // Changing the text causes them to be recalculated.
+        text += EditRope::from("  e\n  f\n");
+        folds.update(&text, false);
+        assert_eq!(folds.folds, vec![mkfold(1, 2, false), mkfold(4, 5, false)]);
+    }
+
+    #[test]
+    fn test_fold_marker() {
+        let (mut ebuf, gid, _, _, _) = mkfivestr("a {{{\nb\nc {{{\nd\n}}}\ne\n}}}\nf\n{{{\ng\n");
+
+        ebuf.set_fold_method(gid, FoldMethod::marker());
+        assert_eq!(ebuf.get_folds(gid), vec![
+            mkfold(0, 6, true),
+            mkfold(2, 4, true),
+            mkfold(8, 9, true),
+        ]);
+
+        let closed = ebuf.fold_intervals(gid).into_iter().map(|f| f.value).collect::<Vec<_>>();
+        assert_eq!(closed, vec![(0, 6), (8, 9)]);
+
+        ebuf.set_fold_method(gid, FoldMethod::Marker("<".into(), ">".into()));
+        assert_eq!(ebuf.get_folds(gid), vec![]);
+    }
+
+    #[test]
+    fn test_fold_motion() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) =
+            mkfivestr("a\n  b\n  c\nd\n  e\n    f\n    g\nh\n");
+        let next = MoveType::Fold(MoveDir1D::Next);
+        let prev = MoveType::Fold(MoveDir1D::Previous);
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        // There are no folds to move to yet.
+        edit!(ebuf, EditAction::Motion, mv!(next), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 0));
+
+        ebuf.set_fold_method(gid, FoldMethod::Indent);
+        ebuf.fold_command(&FoldAction::OpenAll, ctx, &mut store).unwrap();
+
+        // Move forwards to the start of each fold.
+        edit!(ebuf, EditAction::Motion, mv!(next), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 2));
+        edit!(ebuf, EditAction::Motion, mv!(next, 2), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(5, 4));
+        edit!(ebuf, EditAction::Motion, mv!(next), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(5, 4));
+
+        // Move backwards to the end of each fold.
+        ebuf.set_leader(gid, Cursor::new(7, 0));
+        edit!(ebuf, EditAction::Motion, mv!(prev), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(6, 4));
+        edit!(ebuf, EditAction::Motion, mv!(prev), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(2, 2));
+
+        // Closed folds count as a single fold.
+        ebuf.fold_command(&FoldAction::CloseAll, ctx, &mut store).unwrap();
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        edit!(ebuf, EditAction::Motion, mv!(next, 2), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(4, 2));
+        edit!(ebuf, EditAction::Motion, mv!(next), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(4, 2));
+    }
+}
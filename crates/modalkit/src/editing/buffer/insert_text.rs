@@ -1,14 +1,109 @@
+use unicode_width::UnicodeWidthChar;
+
 use crate::editing::{
     application::ApplicationInfo,
-    buffer::{CursorGroupIdContext, EditBuffer},
+    buffer::{CursorGroupId, CursorGroupIdContext, EditBuffer},
     context::Resolve,
-    cursor::{Adjustable, CursorChoice, CursorState},
+    cursor::{Adjustable, Cursor, CursorChoice, CursorState},
     rope::EditRope,
     store::Store,
 };
 use crate::errors::EditResult;
 use crate::prelude::*;
 
+impl<I> EditBuffer<I>
+where
+    I: ApplicationInfo,
+{
+    /// Record the bounds of freshly pasted text in [Mark::LastYankedBegin] and
+    /// [Mark::LastYankedEnd], so that [PasteStyle::Cycle] can find it again.
+    fn _set_paste_marks(&self, start: &Cursor, len: usize, store: &mut Store<I>) {
+        if len == 0 {
+            return;
+        }
+
+        let soff = self.text.cursor_to_offset(start);
+        let end = self.text.offset_to_cursor(soff + (len - 1).into());
+
+        store
+            .cursors
+            .set_mark(self.id.clone(), Mark::LastYankedBegin, start.clone());
+        store.cursors.set_mark(self.id.clone(), Mark::LastYankedEnd, end);
+    }
+
+    /// Break the lines that were just typed on by each cursor if they go past the fill column.
+    fn _auto_fill(&mut self, gid: CursorGroupId, store: &mut Store<I>) {
+        let mut group = self.get_group(gid);
+        let mut adjs = vec![];
+
+        // Fill later lines first, so that the earlier cursors stay valid.
+        let cursors = group.iter().map(|state| state.cursor().clone()).collect::<Vec<_>>();
+
+        for cursor in cursors.into_iter().rev() {
+            // Fill the line up to the space or newline that was just typed.
+            let end = match (cursor.x, cursor.y) {
+                (0, 0) => continue,
+                (0, y) => Cursor::new(y - 1, self.text.get_columns(y - 1)),
+                (x, y) => Cursor::new(y, x - 1),
+            };
+
+            let start = self.text.offset_of_line(end.y);
+            let end = self.text.cursor_to_offset(&end);
+            let text = self.text.slice(start..end).to_string();
+            let width = text.chars().map(|c| c.width().unwrap_or(1)).sum::<usize>();
+
+            if width <= self.fill_column {
+                continue;
+            }
+
+            adjs.append(&mut self._fill(start, end, store));
+        }
+
+        for state in group.iter_mut() {
+            state.adjust(&adjs);
+        }
+
+        self.set_group(gid, group);
+    }
+
+    fn _paste_cycle(
+        &mut self,
+        dir: MoveDir1D,
+        count: usize,
+        ctx: &CursorGroupIdContext<'_>,
+        store: &mut Store<I>,
+    ) -> EditResult<EditInfo, I> {
+        let start = store.cursors.get_mark(self.id.clone(), Mark::LastYankedBegin)?;
+        let end = store.cursors.get_mark(self.id.clone(), Mark::LastYankedEnd)?;
+
+        store.registers.rotate_kill_ring(dir, count);
+
+        let cell = store.registers.get(&Register::KillRing)?;
+        let soff = self.text.cursor_to_offset(&start);
+        let eoff = self.text.cursor_to_offset(&end);
+
+        let gid = ctx.0;
+        let mut group = self.get_group(gid);
+
+        self.push_change(&group);
+
+        let len = cell.value.len();
+        let (_, adjs) = self.text.replace(soff..=eoff, cell.value);
+
+        self._adjust_all(adjs, store);
+        self._set_paste_marks(&start, len, store);
+
+        // Like a regular yank, leave the cursor after the inserted text.
+        let cursor = self.text.offset_to_cursor(soff + len.into());
+        group.leader.set(cursor.into());
+        self.clamp_state(&mut group.leader, ctx);
+
+        self.set_group(gid, group);
+
+        Ok(None)
+    }
+}
+
 pub trait InsertTextActions<C, I>
 where
     I: ApplicationInfo,
@@ -65,11 +160,21 @@ where
         store: &mut Store<I>,
     ) -> EditResult<EditInfo, I> {
         let count = ctx.2.resolve(count);
+
+        if let PasteStyle::Cycle(dir) = style {
+            return self._paste_cycle(*dir, count, ctx, store);
+        }
+
         let insty = ctx.2.get_insert_style();
         let cell = store.registers.get(&ctx.2.get_register().unwrap_or(Register::Unnamed))?;
         let text = cell.value.repeat(cell.shape, count);
         let end = ctx.2.get_cursor_end();
 
+        // Blockwise text doesn't get pasted as one contiguous range.
+        let contiguous = insty.is_some() ||
+            cell.shape != TargetShape::BlockWise ||
+            *style == PasteStyle::Replace;
+
         let gid = ctx.0;
         let mut group = self.get_group(gid);
 
@@ -105,10 +210,15 @@ where
 
                     self.text.replace(start..=end, text.clone())
                 },
+                PasteStyle::Cycle(_) => unreachable!(),
             };
 
             self._adjust_all(adjs, store);
 
+            if let (CursorChoice::Range(start, _, _), true) = (&choice, contiguous) {
+                self._set_paste_marks(start, text.len(), store);
+            }
+
             if let Some(cursor) = choice.resolve(end) {
                 state.set(cursor);
                 self.clamp_state(state, ctx);
@@ -243,6 +353,10 @@ where
         self._adjust_all(adjs, store);
         self.set_group(gid, group);
 
+        if self.auto_fill && matches!(ch, Char::Single(' ' | '\n')) {
+            self._auto_fill(gid, store);
+        }
+
         Ok(None)
     }
 }
@@ -608,4 +722,99 @@ mod tests {
         assert_eq!(ebuf.get_text(), "1aaaa 2 3\n1bbbb 2 3\nhccccellohellohellohellohello\n");
         assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 1));
     }
+
+    #[test]
+    fn test_paste_cycle() {
+        let (mut ebuf, gid, vwctx, mut vctx, mut store) = mkfivestr("hello world\n");
+
+        set_reg!(store, Register::KillRing, TargetShape::CharWise, "one");
+        set_reg!(store, Register::KillRing, TargetShape::CharWise, "two");
+        set_reg!(store, Register::KillRing, TargetShape::CharWise, "three");
+
+        vctx.insert_style = Some(InsertStyle::Insert);
+        vctx.last_column = true;
+        vctx.register = Some(Register::KillRing);
+
+        // Start out at (0, 6).
+        ebuf.set_leader(gid, Cursor::new(0, 6));
+
+        // Yank the most recent kill.
+        paste!(ebuf, PasteStyle::Cursor, Count::Contextual, ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello threeworld\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 11));
+
+        // Replace it with older entries.
+        let cycle = PasteStyle::Cycle(MoveDir1D::Previous);
+        paste!(ebuf, cycle, Count::Contextual, ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello twoworld\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 9));
+
+        paste!(ebuf, cycle, Count::Contextual, ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello oneworld\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 9));
+
+        // Cycling wraps around to the newest entry.
+        paste!(ebuf, cycle, Count::Contextual, ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello threeworld\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 11));
+
+        // Move forward two entries at once, wrapping around the other way.
+        let cycle = PasteStyle::Cycle(MoveDir1D::Next);
+        paste!(ebuf, cycle, Count::Exact(2), ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello twoworld\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 9));
+
+        // The yank pointer is left where it is for the next yank.
+        paste!(ebuf, PasteStyle::Cursor, Count::Contextual, ctx!(gid, vwctx, vctx), store);
+        assert_eq!(ebuf.get_text(), "hello twotwoworld\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 12));
+    }
+
+    #[test]
+    fn test_auto_fill() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfive();
+
+        ebuf.set_fill_column(10);
+
+        // Nothing happens while auto-fill is disabled.
+        for c in "aaaa bbbb cccc ".chars() {
+            type_char!(ebuf, c, gid, vwctx, vctx, store);
+        }
+
+        assert_eq!(ebuf.get_text(), "aaaa bbbb cccc \n");
+
+        ebuf.set_text("");
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        ebuf.set_auto_fill(true);
+
+        // Typing a space after the fill column breaks the line, and keeps the fill prefix.
+        for c in "# aaaa bbbb".chars() {
+            type_char!(ebuf, c, gid, vwctx, vctx, store);
+        }
+
+        assert_eq!(ebuf.get_text(), "# aaaa bbbb\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 11));
+
+        type_char!(ebuf, ' ', gid, vwctx, vctx, store);
+        assert_eq!(ebuf.get_text(), "# aaaa\n# bbbb \n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 7));
+
+        // Typing a newline after the fill column breaks the line too.
+        for c in "cc ddddd\n".chars() {
+            type_char!(ebuf, c, gid, vwctx, vctx, store);
+        }
+
+        assert_eq!(ebuf.get_text(), "# aaaa\n# bbbb cc\n# ddddd\n\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(3, 0));
+
+        // Wide characters count as two columns.
+        ebuf.set_text("");
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+
+        for c in "日本 日本 日本 ".chars() {
+            type_char!(ebuf, c, gid, vwctx, vctx, store);
+        }
+
+        assert_eq!(ebuf.get_text(), "日本 日本\n日本 \n");
+    }
 }
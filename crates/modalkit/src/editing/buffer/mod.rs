@@ -65,14 +65,22 @@ mod macros_test;
 mod complete;
 mod cursor;
 mod edit;
+mod fold;
 mod insert_text;
+mod replace;
 mod selection;
+mod spell;
 
 use self::complete::*;
 use self::cursor::*;
 use self::edit::*;
+use self::fold::*;
 use self::insert_text::*;
+use self::replace::*;
 use self::selection::*;
+use self::spell::*;
+
+pub use self::fold::{Fold, FoldInfo, FoldMethod};
 
 use intervaltree::IntervalTree;
 
@@ -106,14 +114,26 @@ pub struct EditBuffer<I: ApplicationInfo> {
     /// Tracks the jumplist for cursor groups within given buffers.
     jumped: AdjustStore<CursorGroupId, HistoryList<CursorGroup>>,
 
+    /// Tracks the folds for cursor groups within given buffers.
+    folds: AdjustStore<CursorGroupId, FoldList>,
+
     completions: HashMap<CursorGroupId, CompletionList>,
     lines: LineCompleter,
 
+    /// Tracks interactive replacements waiting on a response from the user.
+    replacing: HashMap<CursorGroupId, QueryReplace>,
+
     history: HistoryList<EditRope>,
     lineinfo: LineInfoStore<usize>,
 
     push_next_change: bool,
 
+    /// The column that [EditAction::Format] and auto-filling wrap lines at.
+    fill_column: usize,
+
+    /// Whether to automatically break lines that go past the fill column while typing.
+    auto_fill: bool,
+
     _p: PhantomData<I>,
 }
 
@@ -158,11 +178,15 @@ where
             changed,
             changed_idx,
             jumped,
+            folds: AdjustStore::new(),
             history,
             lineinfo,
             completions: HashMap::new(),
             lines: LineCompleter::default(),
+            replacing: HashMap::new(),
             push_next_change: true,
+            fill_column: 70,
+            auto_fill: false,
             _p: PhantomData,
         }
     }
@@ -182,6 +206,22 @@ where
         self.id.clone()
     }
 
+    /// Set the column that [EditAction::Format] and auto-filling wrap lines at.
+    pub fn set_fill_column(&mut self, column: usize) {
+        self.fill_column = column;
+    }
+
+    /// Get the column that [EditAction::Format] and auto-filling wrap lines at.
+    pub fn get_fill_column(&self) -> usize {
+        self.fill_column
+    }
+
+    /// Control whether typing a space or newline after the fill column breaks the line being
+    /// typed on, like Emacs' `auto-fill-mode`.
+    pub fn set_auto_fill(&mut self, auto_fill: bool) {
+        self.auto_fill = auto_fill;
+    }
+
     fn _char(&self, c: Char, cursor: &Cursor, digraphs: &DigraphStore) -> EditResult<char, I> {
         match c {
             Char::Single(c) => {
@@ -397,7 +437,11 @@ where
     }
 
     fn _get_regex(&self, store: &Store<I>) -> EditResult<Regex, I> {
-        let lsearch = store.registers.get_last_search();
+        // Searching while a search is being typed uses what's been typed so far.
+        let lsearch = match store.registers.get_search_preview() {
+            Some((needle, _)) => needle.clone(),
+            None => store.registers.get_last_search(),
+        };
         let regex = Regex::new(lsearch.to_string().as_ref())?;
 
         return Ok(regex);
@@ -405,6 +449,7 @@ where
 
     fn _target(
         &mut self,
+        gid: CursorGroupId,
         state: &CursorState,
         target: &EditTarget,
         ctx: &CursorMovementsContext<'_, Cursor>,
@@ -471,7 +516,14 @@ where
                 return Ok(Some(range));
             },
             EditTarget::Motion(motion, count) => {
-                return Ok(self.text.range_of_movement(&cursor, motion, count, ctx));
+                let (motion, count) =
+                    self._spell_movement(&cursor, motion, count, ctx.context, store);
+                let (motion, count) =
+                    self._fold_movement(gid, &cursor, &motion, &count, ctx.context);
+
+                let range = self.text.range_of_movement(&cursor, &motion, &count, ctx);
+
+                return Ok(range.map(|r| self._fold_range(gid, r)));
             },
             EditTarget::Range(range, inclusive, count) => {
                 return Ok(self.text.range(&cursor, range, *inclusive, count, ctx));
@@ -561,6 +613,7 @@ where
         self.cursors.zero();
         self.changed.zero();
         self.jumped.zero();
+        self.folds.zero();
     }
 
     fn _zero(&mut self, store: &mut Store<I>) {
@@ -572,6 +625,7 @@ where
         self.cursors.adjust(adjs);
         self.changed.adjust(adjs);
         self.jumped.adjust(adjs);
+        self.folds.adjust(adjs);
 
         for completion in self.completions.values_mut() {
             completion.adjust(adjs);
@@ -610,6 +664,28 @@ where
         self._adjust(&[adj], store);
     }
 
+    /// Reflow the lines between two offsets to fit within the fill column, and return the
+    /// adjustments made to the text.
+    fn _fill(
+        &mut self,
+        start: CharOff,
+        end: CharOff,
+        store: &mut Store<I>,
+    ) -> Vec<CursorAdjustment> {
+        let edits = self.text.fill(start, end, self.fill_column);
+        let mut adjs = vec![];
+
+        // Replace the later ranges first, so that the earlier ones stay valid.
+        for (range, text) in edits.into_iter().rev() {
+            let (_, mut radjs) = self.text.replace(range, text.into());
+
+            self._adjust(&radjs, store);
+            adjs.append(&mut radjs);
+        }
+
+        return adjs;
+    }
+
     pub(crate) fn motion(
         &mut self,
         target: &EditTarget,
@@ -660,7 +736,10 @@ where
                     state.set_cursor(nc);
                 },
                 EditTarget::Motion(mv, count) => {
-                    if let Some(nc) = self.text.movement(cursor, mv, count, &ctx) {
+                    let (mv, count) = self._spell_movement(cursor, mv, count, ctx.context, store);
+                    let (mv, count) = self._fold_movement(gid, cursor, &mv, &count, ctx.context);
+
+                    if let Some(nc) = self.text.movement(cursor, &mv, &count, &ctx) {
                         state.set_cursor(nc);
                     }
                 },
@@ -712,8 +791,9 @@ where
         // XXX: Need to zero out global marks on rope change.
         self._zero_local();
 
-        // Any existing completions are now invalid.
+        // Any existing completions and replacements are now invalid.
         self.completions.clear();
+        self.replacing.clear();
 
         return rope;
     }
@@ -979,6 +1059,64 @@ where
             .map(|c| ((c.y, c.x)..(c.y, c.x + 1), c))
             .collect()
     }
+
+    fn _match_intervals<T>(&self, matches: T) -> HighlightInfo
+    where
+        T: IntoIterator<Item = EditRange<Cursor>>,
+    {
+        matches
+            .into_iter()
+            .filter(|m| m.inclusive || m.start < m.end)
+            .map(|m| {
+                let end = if m.inclusive {
+                    m.end
+                } else {
+                    let end = self.text.cursor_to_offset(&m.end);
+                    self.text.offset_to_cursor(end - CharOff::from(1))
+                };
+
+                (m.start.y..end.y.saturating_add(1), (m.start, end, TargetShape::CharWise))
+            })
+            .collect()
+    }
+
+    /// Returns an [IntervalTree] containing every match of `needle` that starts within the lines
+    /// `start` through `end`.
+    pub fn search_intervals(&self, needle: &Regex, start: usize, end: usize) -> HighlightInfo {
+        if self.text.is_empty() || start > end || start > self.text.get_lines() {
+            return self._match_intervals(None);
+        }
+
+        let so = self.text.offset_of_line(start);
+        let eo = match self.text.line_after(end) {
+            Some(off) => off - CharOff::from(1),
+            None => self.text.last_offset(),
+        };
+
+        if so > eo {
+            return self._match_intervals(None);
+        }
+
+        let sc = self.text.offset_to_cursor(so);
+        let ec = self.text.offset_to_cursor(eo);
+
+        self._match_intervals(self.text.find_matches(&sc, &ec, needle))
+    }
+
+    /// Returns an [IntervalTree] containing the next match of `needle` in direction `dir` from
+    /// the leader of a cursor group.
+    ///
+    /// This is useful for previewing where an in-progress search will move the cursor to.
+    pub fn search_preview_intervals(
+        &mut self,
+        gid: CursorGroupId,
+        needle: &Regex,
+        dir: MoveDir1D,
+    ) -> HighlightInfo {
+        let cursor = self.get_leader(gid);
+
+        self._match_intervals(self.text.find_regex(&cursor, dir, needle, 1))
+    }
 }
 
 impl<'a, I> HistoryActions<CursorGroupIdContext<'a>, I> for EditBuffer<I>
@@ -1075,7 +1213,7 @@ where
         let mut group = self.get_group(gid);
 
         for state in group.iter_mut() {
-            let choice = match (self._target(state, target, ctx, store)?, action) {
+            let choice = match (self._target(gid, state, target, ctx, store)?, action) {
                 (Some(range), EditAction::Delete) => self.delete(&range, ctx, store)?,
                 (Some(range), EditAction::Yank) => self.yank(&range, ctx, store)?,
                 (Some(range), EditAction::Replace(v)) => {
@@ -1093,7 +1231,11 @@ where
                         },
                     }
                 },
-                (Some(range), EditAction::Format) => self.format(&range, ctx, store)?,
+                (Some(range), EditAction::Format) => {
+                    let cursor = state.cursor().clone();
+
+                    self.format(&cursor, &range, ctx, store)?
+                },
                 (Some(range), EditAction::ChangeCase(case)) => {
                     self.changecase(case, &range, ctx, store)?
                 },
@@ -1106,6 +1248,38 @@ where
                 (Some(range), EditAction::Indent(change)) => {
                     self.indent(change, &range, ctx, store)?
                 },
+                (Some(range), EditAction::Fold) => self.fold_create(&range, ictx, store)?,
+                (Some(range), EditAction::Surround(change)) => {
+                    let c = match (change, ctx.context.get_replace_char()) {
+                        (SurroundChange::Delete, _) => None,
+                        (_, Some(c)) => Some(self._char(c, state.cursor(), &store.digraphs)?),
+                        (_, None) => {
+                            let msg = "No surrounding character".to_string();
+                            let err = EditError::Failure(msg);
+
+                            return Err(err);
+                        },
+                    };
+
+                    self.surround(change, c, &range, ctx, store)?
+                },
+                (Some(range), EditAction::Rectangle(change)) => {
+                    self.rectangle(change, &range, ctx, store)?
+                },
+                (Some(range), EditAction::Transpose(style, dir)) => {
+                    let anchor = state.anchor().clone();
+
+                    self.transpose(style, *dir, &anchor, &range, ctx, store)?
+                },
+                (Some(range), EditAction::SortLines) => self.sort_lines(&range, ctx, store)?,
+                (Some(range), EditAction::TrimTrailingWhitespace) => {
+                    let cursor = state.cursor().clone();
+
+                    self.trim_whitespace(&cursor, &range, ctx, store)?
+                },
+                (Some(range), EditAction::Application(name)) => {
+                    self.application(name, &range, ctx, store)?
+                },
                 (Some(_), EditAction::Motion) => panic!("Unexpected EditAction::Motion!"),
                 (None, _) => CursorChoice::Empty,
             };
@@ -1222,6 +1396,8 @@ where
         match act {
             CursorAction::Close(target) => self.cursor_close(target, ctx, store),
             CursorAction::Split(count) => self.cursor_split(count, ctx, store),
+            CursorAction::PopMark(ring) => self.cursor_pop_mark(*ring, ctx, store),
+            CursorAction::PushMark => self.cursor_push_mark(ctx, store),
             CursorAction::Restore(style) => self.cursor_restore(style, ctx, store),
             CursorAction::Rotate(dir, count) => self.cursor_rotate(*dir, count, ctx, store),
             CursorAction::Save(style) => self.cursor_save(style, ctx, store),
@@ -1262,10 +1438,13 @@ where
             },
 
             EditorAction::Cursor(act) => self.cursor_command(act, ctx, store),
+            EditorAction::Fold(act) => self.fold_command(act, ctx, store),
             EditorAction::History(act) => self.history_command(act, ctx, store),
             EditorAction::InsertText(act) => self.insert_text(act, ctx, store),
             EditorAction::Mark(name) => self.mark(ctx.2.resolve(name), ctx, store),
+            EditorAction::Replace(act) => self.replace_command(act, ctx, store),
             EditorAction::Selection(act) => self.selection_command(act, ctx, store),
+            EditorAction::Spell(act) => self.spell_command(act, ctx, store),
 
             EditorAction::Complete(ct, sel, disp) => self.complete(ct, sel, disp, ctx, store),
         }
@@ -2162,6 +2341,66 @@ mod tests {
         assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 9));
     }
 
+    #[test]
+    fn test_search_intervals() {
+        let (mut ebuf, gid, _, _, _) =
+            mkfivestr("hello world\nhellfire hello brimstone\nhello hell\n");
+
+        let matches = |hinfo: HighlightInfo| {
+            let mut ms = hinfo
+                .iter()
+                .map(|e| (e.value.0.clone(), e.value.1.clone()))
+                .collect::<Vec<_>>();
+            ms.sort();
+            ms
+        };
+
+        // Find all of the matches in the buffer.
+        let needle = Regex::new("hell").unwrap();
+        let res = matches(ebuf.search_intervals(&needle, 0, 2));
+        assert_eq!(res, vec![
+            (Cursor::new(0, 0), Cursor::new(0, 3)),
+            (Cursor::new(1, 0), Cursor::new(1, 3)),
+            (Cursor::new(1, 9), Cursor::new(1, 12)),
+            (Cursor::new(2, 0), Cursor::new(2, 3)),
+            (Cursor::new(2, 6), Cursor::new(2, 9)),
+        ]);
+
+        // Only look at the middle line.
+        let res = matches(ebuf.search_intervals(&needle, 1, 1));
+        assert_eq!(res, vec![
+            (Cursor::new(1, 0), Cursor::new(1, 3)),
+            (Cursor::new(1, 9), Cursor::new(1, 12)),
+        ]);
+
+        // Lines past the end of the buffer have no matches.
+        let res = matches(ebuf.search_intervals(&needle, 5, 10));
+        assert_eq!(res, vec![]);
+
+        // Matches can span multiple lines.
+        let needle = Regex::new("world\nhell").unwrap();
+        let hinfo = ebuf.search_intervals(&needle, 0, 2);
+        assert_eq!(hinfo.query_point(0).count(), 1);
+        assert_eq!(hinfo.query_point(1).count(), 1);
+        assert_eq!(hinfo.query_point(2).count(), 0);
+        assert_eq!(matches(hinfo), vec![(Cursor::new(0, 6), Cursor::new(1, 3))]);
+
+        // Empty matches are skipped.
+        let needle = Regex::new("x*").unwrap();
+        let res = matches(ebuf.search_intervals(&needle, 0, 2));
+        assert_eq!(res, vec![]);
+
+        // Preview the next match after the cursor.
+        let needle = Regex::new("hell").unwrap();
+        ebuf.set_leader(gid, Cursor::new(1, 2));
+
+        let res = matches(ebuf.search_preview_intervals(gid, &needle, MoveDir1D::Next));
+        assert_eq!(res, vec![(Cursor::new(1, 9), Cursor::new(1, 12))]);
+
+        let res = matches(ebuf.search_preview_intervals(gid, &needle, MoveDir1D::Previous));
+        assert_eq!(res, vec![(Cursor::new(1, 0), Cursor::new(1, 3))]);
+    }
+
     #[test]
     fn test_history() {
         let (mut ebuf, gid, vwctx, mut vctx, mut store) = mkfive();
@@ -0,0 +1,601 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use regex::Regex;
+
+use crate::actions::{Action, EditorAction, ReplaceAction};
+use crate::editing::{
+    application::ApplicationInfo,
+    rope::{CharOff, EditRope},
+    store::Store,
+};
+use crate::errors::{EditError, EditResult};
+use crate::keybindings::dialog::Dialog;
+use crate::prelude::*;
+
+use super::{CursorGroupIdContext, EditBuffer, HistoryActions};
+
+/// The keys that can be used to respond to a [ReplacePrompt].
+const REPLACE_KEYS: &str = "(y, n, !, q, ., ^, u)";
+
+/// A match visited while replacing.
+#[derive(Clone, Debug)]
+struct Visit {
+    /// The character offsets of the match, or of its replacement.
+    range: Range<usize>,
+
+    /// The original text of the match, if it has been replaced.
+    original: Option<EditRope>,
+}
+
+impl From<Range<usize>> for Visit {
+    fn from(range: Range<usize>) -> Self {
+        Visit { range, original: None }
+    }
+}
+
+/// The state of a [ReplaceAction::Start] that's waiting on the user.
+#[derive(Clone, Debug)]
+pub(super) struct QueryReplace {
+    pattern: String,
+    needle: Regex,
+    replacement: String,
+
+    /// Whether the replacement can refer to the needle's capture groups.
+    expand: bool,
+
+    /// The character offset where the region being replaced in ends.
+    end: usize,
+
+    current: Visit,
+    visited: Vec<Visit>,
+    replaced: usize,
+}
+
+/// A [Dialog] asking whether to replace the current match.
+#[derive(Debug)]
+struct ReplacePrompt {
+    msg: String,
+}
+
+impl ReplacePrompt {
+    fn new(qr: &QueryReplace) -> Self {
+        let msg =
+            format!("Query replacing {:?} with {:?}: {REPLACE_KEYS}", qr.pattern, qr.replacement);
+
+        ReplacePrompt { msg }
+    }
+}
+
+impl<I> Dialog<Action<I>> for ReplacePrompt
+where
+    I: ApplicationInfo,
+{
+    fn render(&mut self, max_rows: usize, _: usize) -> Vec<Cow<'_, str>> {
+        if max_rows == 0 {
+            return vec![];
+        }
+
+        vec![Cow::from(self.msg.as_str())]
+    }
+
+    fn input(&mut self, c: char) -> Option<Vec<Action<I>>> {
+        let act = match c {
+            'y' | ' ' => ReplaceAction::Replace,
+            'n' => ReplaceAction::Skip,
+            '!' => ReplaceAction::ReplaceAll,
+            '.' => ReplaceAction::ReplaceAndQuit,
+            '^' => ReplaceAction::Back,
+            'u' => ReplaceAction::Undo,
+            'q' | '\n' | '\r' => ReplaceAction::Quit,
+            _ => return None,
+        };
+
+        Some(vec![EditorAction::Replace(act).into()])
+    }
+}
+
+fn offsets(range: &Range<usize>) -> Range<CharOff> {
+    CharOff::from(range.start)..CharOff::from(range.end)
+}
+
+fn replaced_msg(n: usize) -> InfoMessage {
+    if n == 1 {
+        InfoMessage::from("Replaced 1 occurrence")
+    } else {
+        InfoMessage::from(format!("Replaced {n} occurrences"))
+    }
+}
+
+impl<I> EditBuffer<I>
+where
+    I: ApplicationInfo,
+{
+    /// Get the part of the text to search for matches between `start` and `end`, along with the
+    /// character offset where it begins.
+    ///
+    /// This keeps a character on either side of the range, so that anchors and word boundaries
+    /// still see their neighbours, without copying the whole buffer for every match.
+    fn _replace_haystack(&self, start: usize, end: usize) -> (usize, EditRope) {
+        let base = start.saturating_sub(1);
+        let end = end.saturating_add(1);
+        let hay = self.text.slice(CharOff::from(base)..CharOff::from(end));
+
+        (base, hay)
+    }
+
+    /// Find the first match of `needle` that starts at or after `start` and ends before `end`.
+    fn _replace_find(&self, needle: &Regex, start: usize, end: usize) -> Option<Range<usize>> {
+        if start > end {
+            return None;
+        }
+
+        let (base, hay) = self._replace_haystack(start, end);
+        let text = Cow::from(&hay);
+        let m = needle.find_at(text.as_ref(), hay.offset_to_byte((start - base).into()))?;
+        let ms = base + usize::from(hay.byte_to_offset(m.start()));
+        let me = base + usize::from(hay.byte_to_offset(m.end()));
+
+        if me > end {
+            return None;
+        }
+
+        return Some(ms..me);
+    }
+
+    /// Get the text to replace the match at `range` with.
+    fn _replace_text(&self, qr: &QueryReplace, range: &Range<usize>) -> EditRope {
+        if !qr.expand {
+            return EditRope::from(qr.replacement.as_str());
+        }
+
+        let (base, hay) = self._replace_haystack(range.start, range.end);
+        let text = Cow::from(&hay);
+        let start = hay.offset_to_byte((range.start - base).into());
+        let mut s = String::new();
+
+        if let Some(caps) = qr.needle.captures_at(text.as_ref(), start) {
+            caps.expand(&qr.replacement, &mut s);
+        }
+
+        return EditRope::from(s);
+    }
+
+    /// Replace the current match, unless it's already been replaced.
+    fn _replace_current(&mut self, qr: &mut QueryReplace, store: &mut Store<I>) {
+        if qr.current.original.is_some() {
+            return;
+        }
+
+        let range = qr.current.range.clone();
+        let text = self._replace_text(qr, &range);
+        let len = text.len();
+        let original = self.text.slice(offsets(&range));
+
+        let (_, adjs) = self.text.replace(offsets(&range), text);
+        self._adjust_all(adjs, store);
+
+        qr.end = qr.end + len - range.len();
+        qr.current = Visit {
+            range: range.start..range.start + len,
+            original: Some(original),
+        };
+        qr.replaced += 1;
+    }
+
+    /// Move on to the next match, returning `false` if there are none left.
+    fn _replace_advance(&self, qr: &mut QueryReplace) -> bool {
+        let range = &qr.current.range;
+
+        // Skip ahead a character after empty matches so that we don't find them again.
+        let start = if range.is_empty() {
+            range.end + 1
+        } else {
+            range.end
+        };
+        let next = self._replace_find(&qr.needle, start, qr.end);
+
+        match next {
+            Some(range) => {
+                let prev = std::mem::replace(&mut qr.current, range.into());
+                qr.visited.push(prev);
+
+                return true;
+            },
+            None => {
+                return false;
+            },
+        }
+    }
+
+    /// Restore the text of the most recently replaced match, and make it the current one again.
+    fn _replace_undo(&mut self, qr: &mut QueryReplace, store: &mut Store<I>) {
+        if qr.current.original.is_none() {
+            let Some(idx) = qr.visited.iter().rposition(|v| v.original.is_some()) else {
+                return;
+            };
+
+            qr.current = qr.visited.swap_remove(idx);
+            qr.visited.truncate(idx);
+        }
+
+        let Some(original) = qr.current.original.take() else {
+            return;
+        };
+
+        let range = qr.current.range.clone();
+        let len = original.len();
+
+        let (_, adjs) = self.text.replace(offsets(&range), original);
+        self._adjust_all(adjs, store);
+
+        qr.end = qr.end + len - range.len();
+        qr.current = Visit::from(range.start..range.start + len);
+        qr.replaced -= 1;
+    }
+
+    /// Move the cursor to the current match, and ask the user what to do with it.
+    fn _replace_ask(&mut self, qr: QueryReplace, ctx: &CursorGroupIdContext<'_>) -> EditError<I> {
+        let cursor = self.text.offset_to_cursor(qr.current.range.start.into());
+        self.set_leader(ctx.0, cursor);
+
+        let dialog = ReplacePrompt::new(&qr);
+        self.replacing.insert(ctx.0, qr);
+
+        return EditError::NeedConfirm(Box::new(dialog));
+    }
+
+    fn _replace_start(
+        &mut self,
+        regex: bool,
+        pattern: String,
+        replacement: String,
+        ctx: &CursorGroupIdContext<'_>,
+        store: &mut Store<I>,
+    ) -> EditResult<EditInfo, I> {
+        if pattern.is_empty() {
+            return Err(EditError::NoSearch);
+        }
+
+        let needle = if regex {
+            Regex::new(&pattern)?
+        } else {
+            Regex::new(&regex::escape(&pattern))?
+        };
+
+        // Replace within the selection if there is one, and otherwise until the end of the buffer.
+        let leader = self.get_group(ctx.0).leader;
+        let (start, end) = if leader.is_selection() {
+            let (start, end) = leader.sorted();
+            self.get_group_mut(ctx.0).leader.unselect();
+
+            let start = usize::from(self.text.cursor_to_offset(&start));
+            let end = usize::from(self.text.cursor_to_offset(&end)) + 1;
+
+            (start, end.min(self.text.len()))
+        } else {
+            let start = usize::from(self.text.cursor_to_offset(leader.cursor()));
+
+            (start, self.text.len())
+        };
+
+        let Some(range) = self._replace_find(&needle, start, end) else {
+            return Ok(Some(replaced_msg(0)));
+        };
+
+        // Everything done while replacing gets undone together.
+        self.checkpoint(ctx, store)?;
+
+        let qr = QueryReplace {
+            pattern,
+            needle,
+            replacement,
+            expand: regex,
+            end,
+            current: range.into(),
+            visited: vec![],
+            replaced: 0,
+        };
+
+        return Err(self._replace_ask(qr, ctx));
+    }
+}
+
+pub trait ReplaceActions<C, S, I>
+where
+    I: ApplicationInfo,
+{
+    fn replace_command(
+        &mut self,
+        act: &ReplaceAction,
+        ctx: &C,
+        store: &mut S,
+    ) -> EditResult<EditInfo, I>;
+}
+
+impl<'a, I> ReplaceActions<CursorGroupIdContext<'a>, Store<I>, I> for EditBuffer<I>
+where
+    I: ApplicationInfo,
+{
+    fn replace_command(
+        &mut self,
+        act: &ReplaceAction,
+        ctx: &CursorGroupIdContext<'a>,
+        store: &mut Store<I>,
+    ) -> EditResult<EditInfo, I> {
+        let mut qr = match act {
+            ReplaceAction::Start(regex) => {
+                let pattern = store.registers.get_last_search().to_string();
+                let replacement = store.registers._get_last_cmd(CommandType::Prompt).to_string();
+                self.replacing.remove(&ctx.0);
+
+                return self._replace_start(*regex, pattern, replacement, ctx, store);
+            },
+            ReplaceAction::StartWith(regex, pattern, replacement) => {
+                let (pattern, replacement) = (pattern.clone(), replacement.clone());
+                self.replacing.remove(&ctx.0);
+
+                return self._replace_start(*regex, pattern, replacement, ctx, store);
+            },
+            _ => {
+                let msg = "No replacement in progress";
+
+                self.replacing.remove(&ctx.0).ok_or(EditError::Failure(msg.into()))?
+            },
+        };
+
+        let ask = match act {
+            ReplaceAction::Replace => {
+                self._replace_current(&mut qr, store);
+                self._replace_advance(&mut qr)
+            },
+            ReplaceAction::Skip => self._replace_advance(&mut qr),
+            ReplaceAction::ReplaceAll => {
+                self._replace_current(&mut qr, store);
+
+                while self._replace_advance(&mut qr) {
+                    self._replace_current(&mut qr, store);
+                }
+
+                false
+            },
+            ReplaceAction::ReplaceAndQuit => {
+                self._replace_current(&mut qr, store);
+
+                false
+            },
+            ReplaceAction::Back => {
+                if let Some(prev) = qr.visited.pop() {
+                    qr.current = prev;
+                }
+
+                true
+            },
+            ReplaceAction::Undo => {
+                self._replace_undo(&mut qr, store);
+
+                true
+            },
+            ReplaceAction::Start(_) | ReplaceAction::StartWith(..) | ReplaceAction::Quit => false,
+        };
+
+        if ask {
+            return Err(self._replace_ask(qr, ctx));
+        }
+
+        self.checkpoint(ctx, store)?;
+
+        return Ok(Some(replaced_msg(qr.replaced)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::*;
+    use super::*;
+    use crate::editing::cursor::Cursor;
+
+    fn respond(
+        ebuf: &mut EditBuffer<EmptyInfo>,
+        act: ReplaceAction,
+        ctx: &CursorGroupIdContext<'_>,
+        store: &mut Store<EmptyInfo>,
+    ) -> EditResult<EditInfo, EmptyInfo> {
+        ebuf.replace_command(&act, ctx, store)
+    }
+
+    fn start(
+        ebuf: &mut EditBuffer<EmptyInfo>,
+        regex: bool,
+        ctx: &CursorGroupIdContext<'_>,
+        store: &mut Store<EmptyInfo>,
+    ) {
+        let res = respond(ebuf, ReplaceAction::Start(regex), ctx, store);
+        assert!(matches!(res, Err(EditError::NeedConfirm(_))), "{:?}", res);
+    }
+
+    #[test]
+    fn test_replace_responses() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("a foo b foo c foo d foo\n");
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        store.registers.set_last_search("foo");
+        store.registers.set_last_command(CommandType::Prompt, "bar");
+
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        let res = respond(&mut ebuf, ReplaceAction::Start(false), ctx, &mut store);
+        let Err(EditError::NeedConfirm(mut dialog)) = res else {
+            panic!("expected a dialog, got {:?}", res);
+        };
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 2));
+        assert_eq!(dialog.render(1, 80), vec![
+            "Query replacing \"foo\" with \"bar\": (y, n, !, q, ., ^, u)"
+        ]);
+        assert_eq!(dialog.input('x'), None);
+        assert_eq!(dialog.input('y').unwrap(), vec![Action::from(EditorAction::Replace(
+            ReplaceAction::Replace
+        ))]);
+
+        // Replace the first match, and skip the second.
+        let res = respond(&mut ebuf, ReplaceAction::Replace, ctx, &mut store);
+        assert!(matches!(res, Err(EditError::NeedConfirm(_))));
+        assert_eq!(ebuf.get_text(), "a bar b foo c foo d foo\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 8));
+
+        let res = respond(&mut ebuf, ReplaceAction::Skip, ctx, &mut store);
+        assert!(matches!(res, Err(EditError::NeedConfirm(_))));
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 14));
+
+        // Going back to the skipped match lets us replace it after all.
+        let res = respond(&mut ebuf, ReplaceAction::Back, ctx, &mut store);
+        assert!(matches!(res, Err(EditError::NeedConfirm(_))));
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 8));
+
+        let res = respond(&mut ebuf, ReplaceAction::Replace, ctx, &mut store);
+        assert!(matches!(res, Err(EditError::NeedConfirm(_))));
+        assert_eq!(ebuf.get_text(), "a bar b bar c foo d foo\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 14));
+
+        // Undoing restores the last replacement and asks about it again.
+        let res = respond(&mut ebuf, ReplaceAction::Undo, ctx, &mut store);
+        assert!(matches!(res, Err(EditError::NeedConfirm(_))));
+        assert_eq!(ebuf.get_text(), "a bar b foo c foo d foo\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 8));
+
+        // Replace the rest.
+        let res = respond(&mut ebuf, ReplaceAction::ReplaceAll, ctx, &mut store).unwrap();
+        assert_eq!(res, Some(InfoMessage::from("Replaced 4 occurrences")));
+        assert_eq!(ebuf.get_text(), "a bar b bar c bar d bar\n");
+
+        // The whole operation gets undone at once.
+        ebuf.history_command(&HistoryAction::Undo(Count::Contextual), ctx, &mut store)
+            .unwrap();
+        assert_eq!(ebuf.get_text(), "a foo b foo c foo d foo\n");
+
+        // Once finished, there's nothing to respond to.
+        let res = respond(&mut ebuf, ReplaceAction::Replace, ctx, &mut store);
+        assert!(matches!(res, Err(EditError::Failure(_))));
+    }
+
+    #[test]
+    fn test_replace_quit() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("foo foo foo\n");
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        store.registers.set_last_search("foo");
+        store.registers.set_last_command(CommandType::Prompt, "x");
+
+        // Replacing starts at the cursor.
+        ebuf.set_leader(gid, Cursor::new(0, 1));
+        start(&mut ebuf, false, ctx, &mut store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 4));
+
+        let res = respond(&mut ebuf, ReplaceAction::ReplaceAndQuit, ctx, &mut store).unwrap();
+        assert_eq!(res, Some(InfoMessage::from("Replaced 1 occurrence")));
+        assert_eq!(ebuf.get_text(), "foo x foo\n");
+
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        start(&mut ebuf, false, ctx, &mut store);
+
+        let res = respond(&mut ebuf, ReplaceAction::Quit, ctx, &mut store).unwrap();
+        assert_eq!(res, Some(InfoMessage::from("Replaced 0 occurrences")));
+        assert_eq!(ebuf.get_text(), "foo x foo\n");
+
+        // Nothing to replace.
+        store.registers.set_last_search("bar");
+        let res = respond(&mut ebuf, ReplaceAction::Start(false), ctx, &mut store).unwrap();
+        assert_eq!(res, Some(InfoMessage::from("Replaced 0 occurrences")));
+    }
+
+    #[test]
+    fn test_replace_empty() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("a foo b foo c\n");
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        // An empty replacement deletes each match, even after a previous non-empty one.
+        store.registers.set_last_search("foo ");
+        store.registers.set_last_command(CommandType::Prompt, "bar");
+        store.registers.set_last_command(CommandType::Prompt, "");
+
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        start(&mut ebuf, false, ctx, &mut store);
+
+        let res = respond(&mut ebuf, ReplaceAction::ReplaceAll, ctx, &mut store).unwrap();
+        assert_eq!(res, Some(InfoMessage::from("Replaced 2 occurrences")));
+        assert_eq!(ebuf.get_text(), "a b c\n");
+    }
+
+    #[test]
+    fn test_replace_regex() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("a.b a-b\nc.d\n");
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        // Literal patterns don't treat "." specially.
+        store.registers.set_last_search("a.b");
+        store.registers.set_last_command(CommandType::Prompt, "$0!");
+        start(&mut ebuf, false, ctx, &mut store);
+        respond(&mut ebuf, ReplaceAction::ReplaceAll, ctx, &mut store).unwrap();
+        assert_eq!(ebuf.get_text(), "$0! a-b\nc.d\n");
+
+        // Regular expressions can refer to capture groups.
+        store.registers.set_last_search(r"(\w)[.-](\w)");
+        store.registers.set_last_command(CommandType::Prompt, "$2+$1");
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        start(&mut ebuf, true, ctx, &mut store);
+        respond(&mut ebuf, ReplaceAction::ReplaceAll, ctx, &mut store).unwrap();
+        assert_eq!(ebuf.get_text(), "$0! b+a\nd+c\n");
+
+        // Anchors and word boundaries still see the text around each match.
+        store.registers.set_last_search(r"^\$|\bd\b");
+        store.registers.set_last_command(CommandType::Prompt, "_");
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        start(&mut ebuf, true, ctx, &mut store);
+        respond(&mut ebuf, ReplaceAction::ReplaceAll, ctx, &mut store).unwrap();
+        assert_eq!(ebuf.get_text(), "_0! b+a\n_+c\n");
+    }
+
+    #[test]
+    fn test_replace_start_with() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("a.b a-b\nc.d\n");
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        store.registers.set_last_search("c");
+        store.registers.set_last_command(CommandType::Prompt, "x");
+
+        // The given pattern and replacement get used instead of the registers.
+        let act = ReplaceAction::StartWith(true, r"(\w)\.".into(), "$1,".into());
+        let res = respond(&mut ebuf, act, ctx, &mut store);
+        assert!(matches!(res, Err(EditError::NeedConfirm(_))), "{:?}", res);
+
+        respond(&mut ebuf, ReplaceAction::ReplaceAll, ctx, &mut store).unwrap();
+        assert_eq!(ebuf.get_text(), "a,b a-b\nc,d\n");
+        assert_eq!(store.registers.get_last_search().to_string(), "c");
+    }
+
+    #[test]
+    fn test_replace_selection() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkfivestr("ab ab\nab ab\nab ab\n");
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        store.registers.set_last_search("ab");
+        store.registers.set_last_command(CommandType::Prompt, "xyz");
+
+        // Select from the middle of the first line to the middle of the last.
+        ebuf.set_leader(gid, Cursor::new(0, 3));
+        let sel = EditTarget::Motion(MoveType::Line(MoveDir1D::Next), 2.into());
+        ebuf.selection_command(
+            &SelectionAction::Resize(SelectionResizeStyle::Extend, sel),
+            ctx,
+            &mut store,
+        )
+        .unwrap();
+        assert!(ebuf.get_leader_selection(gid).is_some());
+
+        start(&mut ebuf, false, ctx, &mut store);
+        assert!(ebuf.get_leader_selection(gid).is_none());
+
+        let res = respond(&mut ebuf, ReplaceAction::ReplaceAll, ctx, &mut store).unwrap();
+        assert_eq!(res, Some(InfoMessage::from("Replaced 4 occurrences")));
+        assert_eq!(ebuf.get_text(), "ab xyz\nxyz xyz\nxyz ab\n");
+    }
+}
@@ -463,7 +463,10 @@ where
                     TargetShape::LineWise
                 },
                 EditTarget::Motion(mv, count) => {
-                    if let Some(nc) = self.text.movement(&cursor, mv, count, ctx) {
+                    let (nmv, count) = self._spell_movement(&cursor, mv, count, ctx.context, store);
+                    let (nmv, count) = self._fold_movement(gid, &cursor, &nmv, &count, ctx.context);
+
+                    if let Some(nc) = self.text.movement(&cursor, &nmv, &count, ctx) {
                         state.set_cursor(nc);
                     }
 
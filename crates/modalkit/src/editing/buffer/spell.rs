@@ -0,0 +1,483 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::actions::{Action, EditorAction, SpellAction};
+use crate::editing::{
+    application::ApplicationInfo,
+    context::{EditContext, Resolve},
+    cursor::Cursor,
+    rope::EditRope,
+    store::{spell_words, SpellStore, Store},
+};
+use crate::errors::{EditError, EditResult};
+use crate::keybindings::dialog::{Dialog, MultiChoice, MultiChoiceItem};
+use crate::prelude::*;
+
+use super::{CursorGroupId, CursorGroupIdContext, EditBuffer, HighlightInfo};
+
+/// The keys used to pick from the suggestions shown for [SpellAction::Suggest].
+const SUGGESTION_KEYS: &str = "123456789abcdefghijklmnopqrstuvwxyz";
+
+fn spell_failure<I: ApplicationInfo>(e: std::io::Error) -> EditError<I> {
+    EditError::Failure(format!("Failed to update user dictionary: {e}"))
+}
+
+/// A [MultiChoice] dialog for picking which suggestion to replace a misspelled word with.
+#[derive(Debug)]
+struct SpellSuggestions {
+    choices: MultiChoice<String>,
+}
+
+impl SpellSuggestions {
+    fn new(suggestions: Vec<String>) -> Self {
+        let choices = suggestions
+            .into_iter()
+            .zip(SUGGESTION_KEYS.chars())
+            .map(|(suggestion, key)| {
+                MultiChoiceItem::new(key, format!("{suggestion:?}"), vec![suggestion])
+            })
+            .collect();
+
+        SpellSuggestions { choices: MultiChoice::new(choices) }
+    }
+}
+
+impl<I> Dialog<Action<I>> for SpellSuggestions
+where
+    I: ApplicationInfo,
+{
+    fn render(&mut self, max_rows: usize, max_cols: usize) -> Vec<Cow<'_, str>> {
+        self.choices.render(max_rows, max_cols)
+    }
+
+    fn input(&mut self, c: char) -> Option<Vec<Action<I>>> {
+        let words = self.choices.input(c)?;
+        let acts = words
+            .into_iter()
+            .map(|word| EditorAction::Spell(SpellAction::Replace(word)).into())
+            .collect();
+
+        Some(acts)
+    }
+}
+
+impl<I> EditBuffer<I>
+where
+    I: ApplicationInfo,
+{
+    fn _line_words(&self, line: usize) -> Vec<(Range<usize>, String)> {
+        match self.text.get_line(line) {
+            Some(text) => spell_words(Cow::from(&text).as_ref()),
+            None => vec![],
+        }
+    }
+
+    fn _misspellings(&self, spell: &SpellStore, line: usize) -> Vec<Range<usize>> {
+        match self.text.get_line(line) {
+            Some(text) => spell.misspellings(Cow::from(&text).as_ref()),
+            None => vec![],
+        }
+    }
+
+    /// Find the next misspelled word in `dir` direction, wrapping around the buffer.
+    fn _next_misspelling(
+        &self,
+        spell: &SpellStore,
+        cursor: &Cursor,
+        dir: MoveDir1D,
+    ) -> Option<Cursor> {
+        let lines = self.text.get_lines();
+
+        for i in 0..=lines {
+            let y = match dir {
+                MoveDir1D::Next => (cursor.y + i) % lines,
+                MoveDir1D::Previous => (cursor.y + lines - i % lines) % lines,
+            };
+
+            let words = self._misspellings(spell, y).into_iter().map(|r| r.start);
+
+            // The cursor's line gets checked first for words after it, and then again after
+            // wrapping around for words before it.
+            let x = match (dir, i) {
+                (MoveDir1D::Next, 0) => words.filter(|x| *x > cursor.x).min(),
+                (MoveDir1D::Previous, 0) => words.filter(|x| *x < cursor.x).max(),
+                (MoveDir1D::Next, _) => words.min(),
+                (MoveDir1D::Previous, _) => words.max(),
+            };
+
+            if let Some(x) = x {
+                return Some(Cursor::new(y, x));
+            }
+        }
+
+        return None;
+    }
+
+    /// Rewrite a movement to a misspelled word so that it moves to a buffer offset instead.
+    pub(super) fn _spell_movement(
+        &self,
+        cursor: &Cursor,
+        mv: &MoveType,
+        count: &Count,
+        ctx: &EditContext,
+        store: &Store<I>,
+    ) -> (MoveType, Count) {
+        let MoveType::Misspelling(dir) = mv else {
+            return (mv.clone(), count.clone());
+        };
+
+        if !store.spell.is_enabled() {
+            return (mv.clone(), count.clone());
+        }
+
+        let mut found = None;
+
+        for _ in 0..ctx.resolve(count) {
+            let from = found.as_ref().unwrap_or(cursor);
+
+            match self._next_misspelling(&store.spell, from, *dir) {
+                Some(nc) => found = Some(nc),
+                None => break,
+            }
+        }
+
+        match found {
+            Some(nc) => {
+                let off = self.text.offset_to_byte(self.text.cursor_to_offset(&nc));
+
+                (MoveType::BufferByteOffset, Count::Exact(off + 1))
+            },
+            None => (mv.clone(), count.clone()),
+        }
+    }
+
+    /// Returns the range of characters for the word underneath or after the cursor on the
+    /// current line.
+    fn _spell_word(&self, cursor: &Cursor) -> Option<(Range<usize>, String)> {
+        self._line_words(cursor.y).into_iter().find(|(r, _)| r.end > cursor.x)
+    }
+
+    /// Returns the selected text, or the word under the cursor.
+    fn _spell_target(&mut self, gid: CursorGroupId) -> EditResult<String, I> {
+        let leader = &self.get_group(gid).leader;
+
+        if leader.is_selection() {
+            let (start, end) = leader.sorted();
+            let so = self.text.cursor_to_offset(&start);
+            let eo = self.text.cursor_to_offset(&end);
+            let text = self.text.slice(so..=eo).to_string();
+            let text = text.trim();
+
+            if !text.is_empty() {
+                return Ok(text.to_string());
+            }
+        }
+
+        match self._spell_word(leader.cursor()) {
+            Some((_, word)) => Ok(word),
+            None => Err(EditError::NoCursorWord),
+        }
+    }
+
+    /// Returns an [IntervalTree] containing every misspelled word within the lines `start`
+    /// through `end`.
+    ///
+    /// [IntervalTree]: intervaltree::IntervalTree
+    pub fn spell_intervals(&self, spell: &SpellStore, start: usize, end: usize) -> HighlightInfo {
+        if !spell.is_enabled() {
+            return self._match_intervals(None);
+        }
+
+        let end = end.min(self.text.get_lines().saturating_sub(1));
+        let matches = (start..=end).flat_map(|y| {
+            self._misspellings(spell, y).into_iter().map(move |r| {
+                let start = Cursor::new(y, r.start);
+                let end = Cursor::new(y, r.end.saturating_sub(1));
+
+                EditRange::inclusive(start, end, TargetShape::CharWise)
+            })
+        });
+
+        self._match_intervals(matches)
+    }
+}
+
+pub trait SpellActions<C, S, I>
+where
+    I: ApplicationInfo,
+{
+    fn spell_replace(&mut self, word: &str, ctx: &C, store: &mut S) -> EditResult<EditInfo, I>;
+
+    fn spell_command(
+        &mut self,
+        act: &SpellAction,
+        ctx: &C,
+        store: &mut S,
+    ) -> EditResult<EditInfo, I>;
+}
+
+impl<'a, I> SpellActions<CursorGroupIdContext<'a>, Store<I>, I> for EditBuffer<I>
+where
+    I: ApplicationInfo,
+{
+    fn spell_replace(
+        &mut self,
+        word: &str,
+        ctx: &CursorGroupIdContext<'a>,
+        store: &mut Store<I>,
+    ) -> EditResult<EditInfo, I> {
+        let cursor = self.get_leader(ctx.0);
+        let (range, _) = self._spell_word(&cursor).ok_or(EditError::NoCursorWord)?;
+
+        let start = Cursor::new(cursor.y, range.start);
+        let end = Cursor::new(cursor.y, range.end);
+        let so = self.text.cursor_to_offset(&start);
+        let eo = self.text.cursor_to_offset(&end);
+
+        let (_, adjs) = self.text.replace(so..eo, EditRope::from(word));
+        self._adjust_all(adjs, store);
+        self.set_leader(ctx.0, start);
+
+        Ok(None)
+    }
+
+    fn spell_command(
+        &mut self,
+        act: &SpellAction,
+        ctx: &CursorGroupIdContext<'a>,
+        store: &mut Store<I>,
+    ) -> EditResult<EditInfo, I> {
+        match act {
+            SpellAction::Bad(session) => {
+                let word = self._spell_target(ctx.0)?;
+                store.spell.mark_bad(&word, *session).map_err(spell_failure)?;
+            },
+            SpellAction::Good(session) => {
+                let word = self._spell_target(ctx.0)?;
+                store.spell.mark_good(&word, *session).map_err(spell_failure)?;
+            },
+            SpellAction::Undo(session) => {
+                let word = self._spell_target(ctx.0)?;
+                store.spell.unmark(&word, *session).map_err(spell_failure)?;
+            },
+            SpellAction::Replace(word) => {
+                return self.spell_replace(word, ctx, store);
+            },
+            SpellAction::Suggest(count) => {
+                let cursor = self.get_leader(ctx.0);
+                let (_, word) = self._spell_word(&cursor).ok_or(EditError::NoCursorWord)?;
+
+                let choice = match count {
+                    Count::Contextual => ctx.2.count,
+                    count => Some(ctx.2.resolve(count)),
+                };
+
+                if let Some(n) = choice {
+                    // Suggestions are numbered from 1, so there's never a suggestion 0.
+                    let suggestion = n
+                        .checked_sub(1)
+                        .and_then(|idx| store.spell.suggest(&word, n).into_iter().nth(idx));
+                    let msg = format!("No suggestion {n} for {word:?}");
+                    let suggestion = suggestion.ok_or(EditError::Failure(msg))?;
+
+                    return self.spell_replace(&suggestion, ctx, store);
+                }
+
+                let suggestions = store.spell.suggest(&word, SUGGESTION_KEYS.len());
+
+                if suggestions.is_empty() {
+                    let msg = format!("No suggestions for {word:?}");
+
+                    return Err(EditError::Failure(msg));
+                }
+
+                let dialog = SpellSuggestions::new(suggestions);
+
+                return Err(EditError::NeedConfirm(Box::new(dialog)));
+            },
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::*;
+    use super::*;
+
+    fn mkspell(
+        s: &str,
+    ) -> (
+        EditBuffer<EmptyInfo>,
+        CursorGroupId,
+        ViewportContext<Cursor>,
+        EditContext,
+        Store<EmptyInfo>,
+    ) {
+        let (ebuf, gid, vwctx, vctx, mut store) = mkfivestr(s);
+
+        store.spell.add_words(["hello", "world", "help", "held", "the", "a"]);
+        store.spell.set_enabled(true);
+
+        (ebuf, gid, vwctx, vctx, store)
+    }
+
+    #[test]
+    fn test_spell_intervals() {
+        let (ebuf, _, _, _, mut store) = mkspell("hello wrold\nthe hepl\nworld\n");
+
+        let matches = |hinfo: HighlightInfo| {
+            let mut ms = hinfo
+                .iter()
+                .map(|e| (e.value.0.clone(), e.value.1.clone()))
+                .collect::<Vec<_>>();
+            ms.sort();
+            ms
+        };
+
+        let res = matches(ebuf.spell_intervals(&store.spell, 0, 10));
+        assert_eq!(res, vec![
+            (Cursor::new(0, 6), Cursor::new(0, 10)),
+            (Cursor::new(1, 4), Cursor::new(1, 7)),
+        ]);
+
+        let res = matches(ebuf.spell_intervals(&store.spell, 1, 2));
+        assert_eq!(res, vec![(Cursor::new(1, 4), Cursor::new(1, 7))]);
+
+        // Nothing gets highlighted when spell checking is disabled.
+        store.spell.set_enabled(false);
+        let res = matches(ebuf.spell_intervals(&store.spell, 0, 10));
+        assert_eq!(res, vec![]);
+    }
+
+    #[test]
+    fn test_spell_motion() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) =
+            mkspell("hello wrold\nthe hepl\nworld\na wrold\n");
+        let next = MoveType::Misspelling(MoveDir1D::Next);
+        let prev = MoveType::Misspelling(MoveDir1D::Previous);
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        edit!(ebuf, EditAction::Motion, mv!(next), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 6));
+
+        edit!(ebuf, EditAction::Motion, mv!(next), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 4));
+
+        edit!(ebuf, EditAction::Motion, mv!(next, 2), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 6));
+
+        edit!(ebuf, EditAction::Motion, mv!(prev), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(3, 2));
+
+        edit!(ebuf, EditAction::Motion, mv!(prev), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 4));
+
+        // Deleting up to the next misspelling.
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        edit!(ebuf, EditAction::Delete, mv!(next), ctx, store);
+        assert_eq!(ebuf.get_text(), "wrold\nthe hepl\nworld\na wrold\n");
+
+        // Nothing happens when spell checking is disabled.
+        store.spell.set_enabled(false);
+        ebuf.set_leader(gid, Cursor::new(1, 0));
+        edit!(ebuf, EditAction::Motion, mv!(next), ctx, store);
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(1, 0));
+    }
+
+    #[test]
+    fn test_spell_mark() {
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkspell("hello wrold\n");
+        let ctx = ctx!(gid, vwctx, vctx);
+
+        // Mark the word after the cursor as good.
+        ebuf.set_leader(gid, Cursor::new(0, 5));
+        ebuf.spell_command(&SpellAction::Good(true), ctx, &mut store).unwrap();
+        assert!(!store.spell.is_misspelled("wrold"));
+
+        ebuf.spell_command(&SpellAction::Undo(true), ctx, &mut store).unwrap();
+        assert!(store.spell.is_misspelled("wrold"));
+
+        // Mark the word under the cursor as bad.
+        ebuf.set_leader(gid, Cursor::new(0, 2));
+        ebuf.spell_command(&SpellAction::Bad(true), ctx, &mut store).unwrap();
+        assert!(store.spell.is_misspelled("hello"));
+
+        // Mark the selected text as good.
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        let sel = EditTarget::Motion(MoveType::Column(MoveDir1D::Next, false), 2.into());
+        ebuf.selection_command(
+            &SelectionAction::Resize(SelectionResizeStyle::Extend, sel),
+            ctx,
+            &mut store,
+        )
+        .unwrap();
+        ebuf.spell_command(&SpellAction::Good(true), ctx, &mut store).unwrap();
+        assert!(!store.spell.is_misspelled("hel"));
+
+        // There's no word after the end of the line.
+        let (mut ebuf, gid, vwctx, vctx, mut store) = mkspell("hello   \n");
+        let ctx = ctx!(gid, vwctx, vctx);
+        ebuf.set_leader(gid, Cursor::new(0, 6));
+        let res = ebuf.spell_command(&SpellAction::Good(true), ctx, &mut store);
+        assert!(matches!(res, Err(EditError::NoCursorWord)));
+    }
+
+    #[test]
+    fn test_spell_suggest() {
+        let (mut ebuf, gid, vwctx, mut vctx, mut store) = mkspell("the hepl wrold\n");
+
+        // Pick a suggestion with a count.
+        ebuf.set_leader(gid, Cursor::new(0, 5));
+        vctx.count = Some(1);
+        let act = SpellAction::Suggest(Count::Contextual);
+        ebuf.spell_command(&act, ctx!(gid, vwctx, vctx), &mut store).unwrap();
+        assert_eq!(ebuf.get_text(), "the help wrold\n");
+        assert_eq!(ebuf.get_leader(gid), Cursor::new(0, 4));
+
+        // Without a count, the suggestions are shown in a dialog.
+        vctx.count = None;
+        ebuf.set_leader(gid, Cursor::new(0, 9));
+
+        let res = ebuf.spell_command(&act, ctx!(gid, vwctx, vctx), &mut store);
+        let Err(EditError::NeedConfirm(mut dialog)) = res else {
+            panic!("expected a dialog, got {:?}", res);
+        };
+
+        let lines = dialog.render(10, 80);
+        assert_eq!(lines[0], "(1) \"world\"");
+
+        let acts = dialog.input('1').unwrap();
+        assert_eq!(acts, vec![Action::from(EditorAction::Spell(SpellAction::Replace(
+            "world".into()
+        )))]);
+
+        ebuf.spell_command(
+            &SpellAction::Replace("world".into()),
+            ctx!(gid, vwctx, vctx),
+            &mut store,
+        )
+        .unwrap();
+        assert_eq!(ebuf.get_text(), "the help world\n");
+
+        // Words with no suggestions fail.
+        let res = ebuf.spell_command(&act, ctx!(gid, vwctx, vctx), &mut store);
+        assert!(matches!(res, Err(EditError::Failure(_))));
+
+        // Asking for a suggestion that doesn't exist fails.
+        vctx.count = Some(5);
+        ebuf.set_leader(gid, Cursor::new(0, 0));
+        let res = ebuf.spell_command(&act, ctx!(gid, vwctx, vctx), &mut store);
+        assert!(matches!(res, Err(EditError::Failure(_))));
+        assert_eq!(ebuf.get_text(), "the help world\n");
+
+        // There's no suggestion 0.
+        ebuf.set_leader(gid, Cursor::new(0, 4));
+        ebuf.set_text("the hepl world\n");
+        let act = SpellAction::Suggest(Count::Exact(0));
+        let res = ebuf.spell_command(&act, ctx!(gid, vwctx, vctx), &mut store);
+        assert!(matches!(res, Err(EditError::Failure(_))));
+        assert_eq!(ebuf.get_text(), "the hepl world\n");
+    }
+}
@@ -58,6 +58,7 @@ pub struct EditContext {
     pub(crate) last_column: bool,
     pub(crate) register: Option<Register>,
     pub(crate) register_append: bool,
+    pub(crate) register_prepend: bool,
     pub(crate) search_regex_dir: MoveDir1D,
     pub(crate) search_char: Option<(MoveDir1D, bool, Char)>,
     pub(crate) replace_char: Option<Char>,
@@ -79,6 +80,7 @@ impl Default for EditContext {
             last_column: true,
             register: None,
             register_append: false,
+            register_prepend: false,
             search_regex_dir: MoveDir1D::Next,
             search_char: None,
             replace_char: None,
@@ -119,6 +121,12 @@ impl EditContext {
         self.register_append
     }
 
+    /// Indicates whether contents should be prepended to the target register when yanking or
+    /// deleting text.
+    pub fn get_register_prepend(&self) -> bool {
+        self.register_prepend
+    }
+
     /// Get the direction in which to search.
     pub fn get_search_regex_dir(&self) -> MoveDir1D {
         self.search_regex_dir
@@ -242,6 +250,15 @@ impl EditContextBuilder {
         self
     }
 
+    /// Set whether this operation should prepend contents to the register instead of replacing
+    /// the existing ones.
+    ///
+    /// Defaults to [false].
+    pub fn register_prepend(mut self, v: bool) -> Self {
+        self.0.register_prepend = v;
+        self
+    }
+
     /// Set the direction the regular expression should search in.
     ///
     /// Defaults to [MoveDir1D::Next].
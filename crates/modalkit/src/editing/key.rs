@@ -30,7 +30,7 @@ use std::collections::VecDeque;
 use crate::actions::MacroAction;
 use crate::errors::EditResult;
 use crate::key::MacroError;
-use crate::keybindings::{dialog::Dialog, BindingMachine, InputKey};
+use crate::keybindings::{dialog::Dialog, BindingMachine, InputKey, KeyHint, MappingScope};
 use crate::prelude::*;
 
 use super::{
@@ -55,6 +55,12 @@ where
     commit_on_input: bool,
     committed: EditRope,
     pending: EditRope,
+
+    /// The most recently typed key.
+    last_key: Option<K>,
+
+    /// A key that will execute a macro again if it's typed next.
+    repeat: Option<(K, String)>,
 }
 
 impl<K, A, S> KeyManager<K, A, S>
@@ -76,6 +82,9 @@ where
             commit_on_input: false,
             committed: EditRope::from(""),
             pending: EditRope::from(""),
+
+            last_key: None,
+            repeat: None,
         }
     }
 
@@ -96,11 +105,26 @@ where
 
                 (rope.to_string(), ctx.resolve(count))
             },
+            MacroAction::ExecuteRepeatable(count) => {
+                let reg = ctx.get_register().unwrap_or(Register::UnnamedMacro);
+                let mstr = store.registers.get_macro(reg)?.to_string();
+
+                if let Some(key) = self.last_key.clone() {
+                    self.repeat = Some((key, mstr.clone()));
+                }
+
+                (mstr, ctx.resolve(count))
+            },
             MacroAction::Run(s, count) => (s.clone(), ctx.resolve(count)),
             MacroAction::Repeat(count) => {
                 let rope = store.registers.get_last_macro()?;
                 (rope.to_string(), ctx.resolve(count))
             },
+            MacroAction::Rotate(dir, count) => {
+                store.registers.rotate_macro_ring(*dir, ctx.resolve(count));
+
+                return Ok(None);
+            },
             MacroAction::ToggleRecording => {
                 if let Some((reg, append)) = &self.recording {
                     // Save macro to register.
@@ -166,6 +190,20 @@ where
         }
 
         self.keystack.clear();
+
+        if let Some((rkey, mstr)) = self.repeat.take() {
+            if rkey == key {
+                if let Ok(keys) = K::from_macro_str(mstr.as_ref()) {
+                    self.keystack = VecDeque::from(keys);
+                    self.repeat = Some((rkey, mstr));
+
+                    return;
+                }
+            }
+        }
+
+        self.last_key = Some(key.clone());
+        self.bindings.set_macro_executing(false);
         self.bindings.input_key(key);
     }
 
@@ -178,7 +216,10 @@ where
             }
 
             match self.keystack.pop_front() {
-                Some(key) => self.bindings.input_key(key),
+                Some(key) => {
+                    self.bindings.set_macro_executing(true);
+                    self.bindings.input_key(key);
+                },
                 None => return None,
             }
         }
@@ -207,6 +248,18 @@ where
     fn run_dialog(&mut self, dialog: Box<dyn Dialog<A> + Send + Sync>) {
         self.bindings.run_dialog(dialog)
     }
+
+    fn pending_hints(&self) -> Vec<KeyHint> {
+        self.bindings.pending_hints()
+    }
+
+    fn set_focus(&mut self, focus: &[MappingScope]) {
+        self.bindings.set_focus(focus)
+    }
+
+    fn set_macro_executing(&mut self, executing: bool) {
+        self.bindings.set_macro_executing(executing)
+    }
 }
 
 #[cfg(test)]
@@ -382,6 +435,11 @@ mod tests {
             &vec![(Once, Key("Q".parse().unwrap()))],
             &TestAction::Macro(MacroAction::Execute(Count::Contextual)).into(),
         );
+        bindings.add_mapping(
+            TestMode::Normal,
+            &[(Once, Key("E".parse().unwrap()))],
+            &TestAction::Macro(MacroAction::ExecuteRepeatable(Count::Contextual)).into(),
+        );
         bindings.add_mapping(
             TestMode::Normal,
             &vec![(Once, Key("f".parse().unwrap()))],
@@ -456,6 +514,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_execute_repeatable() {
+        let (mut bindings, mut store) = setup_bindings(true);
+        let mut s = String::new();
+        let mut flag = false;
+        let mut err = None;
+        let mut noops = 0;
+
+        macro_rules! input {
+            ($key: expr) => {
+                input($key, &mut bindings, &mut store, &mut s, &mut flag, &mut noops, &mut err)
+            };
+        }
+
+        // Record an unnamed macro.
+        input!(key!('q'));
+        input!(key!('q'));
+        input!(key!('q'));
+        input!(key!('i'));
+        input!(key!('a'));
+        input!("<Esc>".parse().unwrap());
+        input!(key!('q'));
+        input!(key!('q'));
+        input!(key!('q'));
+        assert_eq!(s, "a");
+
+        // Execute the macro, and then keep pressing "E" to repeat it.
+        input!(key!('E'));
+        assert_eq!(s, "aa");
+        input!(key!('E'));
+        assert_eq!(s, "aaa");
+        input!(key!('E'));
+        assert_eq!(s, "aaaa");
+
+        // Typing another key stops repeating, and "i" enters Insert mode.
+        input!(key!('i'));
+        input!(key!('E'));
+        assert_eq!(s, "aaaaE");
+        input!("<Esc>".parse().unwrap());
+
+        // The count applies to the first execution.
+        input!(key!('2'));
+        input!(key!('E'));
+        assert_eq!(s, "aaaaEaa");
+        input!(key!('E'));
+        assert_eq!(s, "aaaaEaaa");
+        assert!(err.is_none(), "{:?}", err);
+    }
+
     #[test]
     fn test_macro_run() {
         let (mut bindings, mut store) = setup_bindings(true);
@@ -484,6 +591,37 @@ mod tests {
         assert_pop2!(bindings, TestAction::NoOp, ctx);
     }
 
+    #[test]
+    fn test_set_focus() {
+        use crate::keybindings::EdgeRepeat::Once;
+
+        let mut bindings = TestMachine::empty();
+        let scope = MappingScope::content(&"help");
+        let ctx = EditContext::from(VimState::<EmptyInfo>::default());
+
+        bindings.add_scoped_mapping(
+            scope.clone(),
+            TestMode::Normal,
+            &[(Once, Key("q".parse().unwrap()))],
+            &TestAction::SetFlag(true).into(),
+        );
+
+        let mut bindings = TestKeyManager::new(bindings);
+
+        // Unfocused, "q" is unmapped.
+        bindings.input_key("q".parse().unwrap());
+        assert_pop2!(bindings, TestAction::NoOp, ctx);
+
+        // Focusing the scope makes its mappings available.
+        bindings.set_focus(&[scope]);
+        bindings.input_key("q".parse().unwrap());
+        assert_pop2!(bindings, TestAction::SetFlag(true), ctx);
+
+        bindings.set_focus(&[]);
+        bindings.input_key("q".parse().unwrap());
+        assert_pop2!(bindings, TestAction::NoOp, ctx);
+    }
+
     #[test]
     fn test_record_and_execute() {
         let (mut bindings, mut store) = setup_bindings(true);
@@ -15,6 +15,7 @@ use std::ops::{Add, AddAssign, Bound, Range, RangeBounds};
 
 use regex::{Match, Regex};
 use ropey::{Rope, RopeSlice};
+use unicode_width::UnicodeWidthChar;
 
 use crate::actions::EditAction;
 use crate::editing::{
@@ -22,6 +23,7 @@ use crate::editing::{
     cursor::{Cursor, CursorAdjustment, CursorChoice, CursorState},
 };
 use crate::prelude::*;
+use crate::util::is_space_char;
 
 mod diff;
 
@@ -666,6 +668,47 @@ fn titlecase(s: String) -> String {
         .collect()
 }
 
+/// Get the length of the fill prefix at the start of a line: its indentation, along with any
+/// comment leaders or quotation markers that follow it.
+///
+/// A lone `*` only counts as a comment leader when `stars` is true, since it's otherwise more
+/// likely to be a bullet.
+fn fill_prefix_len(line: &[char], stars: bool) -> usize {
+    let mut i = 0;
+
+    loop {
+        while matches!(line.get(i), Some(' ' | '\t')) {
+            i += 1;
+        }
+
+        let start = i;
+
+        match line.get(i) {
+            Some('/') if line.get(i + 1) == Some(&'/') => {
+                i += 2;
+
+                while matches!(line.get(i), Some('/' | '!')) {
+                    i += 1;
+                }
+            },
+            Some(&c @ ('#' | ';' | '%' | '>')) => {
+                while line.get(i) == Some(&c) {
+                    i += 1;
+                }
+            },
+            Some('*') if stars && !line.get(i + 1).is_some_and(|c| !c.is_whitespace()) => {
+                i += 1;
+            },
+            _ => return start,
+        }
+    }
+}
+
+/// Get the number of columns that some text takes up when displayed.
+fn fill_width(chars: impl Iterator<Item = char>) -> usize {
+    chars.map(|c| c.width().unwrap_or(1)).sum()
+}
+
 fn get_last_column(ctx: &CursorMovementsContext<'_, Cursor>) -> bool {
     let shaped = ctx.context.get_target_shape().is_some();
 
@@ -694,6 +737,13 @@ impl EditRope {
         EditRope { rope }
     }
 
+    /// Whether this rope is an unmodified clone of `other`, sharing the same underlying data.
+    ///
+    /// Unlike comparing contents, this runs in constant time.
+    pub(crate) fn is_instance(&self, other: &EditRope) -> bool {
+        self.rope.is_instance(&other.rope)
+    }
+
     /// Write the contents of the [EditRope] to a writer.
     pub fn write_to<T: Write>(&self, writer: T) -> Result<(), std::io::Error> {
         self.rope.write_to(writer)
@@ -1516,6 +1566,16 @@ impl EditRope {
         CharOff(self.rope.line_to_char(line))
     }
 
+    /// Convert a character offset to a byte offset.
+    pub(crate) fn offset_to_byte(&self, off: CharOff) -> usize {
+        self.rope.char_to_byte(off.0)
+    }
+
+    /// Convert a byte offset to a character offset.
+    pub(crate) fn byte_to_offset(&self, byte: usize) -> CharOff {
+        CharOff(self.rope.byte_to_char(byte))
+    }
+
     /// Convert a character offset to a [Cursor].
     pub fn offset_to_cursor(&self, off: CharOff) -> Cursor {
         let off = off.min(self.last_offset());
@@ -1998,6 +2058,417 @@ impl EditRope {
         Some(range)
     }
 
+    /// Find the whitespace between words on the lines from *start* to *end* that needs to be
+    /// replaced so that each line fits within *column* characters.
+    ///
+    /// Paragraphs are separated by lines that are blank after their fill prefix, and get filled
+    /// separately. Lines after the first in each paragraph are given the fill prefix of its second
+    /// line, or of its first line when it only has one. The returned ranges are in ascending order.
+    pub(crate) fn fill(
+        &self,
+        start: CharOff,
+        end: CharOff,
+        column: usize,
+    ) -> Vec<(Range<CharOff>, String)> {
+        let mut edits = vec![];
+        let mut para = vec![];
+
+        let first = self.line_of_offset(start);
+        let last = self.line_of_offset(end);
+
+        for line in first..=last {
+            let lstart = self.offset_of_line(line);
+
+            if line > first && lstart >= end {
+                break;
+            }
+
+            let text = self.get_line(line).map(|l| l.to_string()).unwrap_or_default();
+            let chars = text.trim_end_matches('\n').chars().collect::<Vec<_>>();
+            let plen = fill_prefix_len(&chars, true);
+
+            if chars[plen..].iter().all(|c| c.is_whitespace()) {
+                self.fill_paragraph(&para, start, end, column, &mut edits);
+                para.clear();
+            } else {
+                para.push((lstart, chars));
+            }
+        }
+
+        self.fill_paragraph(&para, start, end, column, &mut edits);
+
+        return edits;
+    }
+
+    fn fill_paragraph(
+        &self,
+        para: &[(CharOff, Vec<char>)],
+        start: CharOff,
+        end: CharOff,
+        column: usize,
+        edits: &mut Vec<(Range<CharOff>, String)>,
+    ) {
+        // A "*" is only a comment leader if the paragraph's second line starts with one too, like
+        // in a block comment. Otherwise, it's a bullet that continuation lines get indented past.
+        let stars = para.get(1).is_some_and(|(_, chars)| {
+            chars[..fill_prefix_len(chars, true)].contains(&'*')
+        });
+
+        let prefix = match (para.first(), para.get(1)) {
+            (_, Some((_, chars))) => chars[..fill_prefix_len(chars, stars)].iter().collect(),
+            (Some((_, chars)), None) => {
+                chars[..fill_prefix_len(chars, true)]
+                    .iter()
+                    .map(|&c| if c == '*' { ' ' } else { c })
+                    .collect::<String>()
+            },
+            (None, None) => return,
+        };
+        let pwidth = fill_width(prefix.chars());
+
+        // Find where each word starts and ends, and the column the first one starts at.
+        let mut words = vec![];
+        let mut col = 0;
+
+        for (lstart, chars) in para.iter() {
+            let mut i = fill_prefix_len(chars, stars);
+
+            while i < chars.len() {
+                let wstart = *lstart + CharOff(i);
+
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+
+                let wend = *lstart + CharOff(i);
+
+                if wstart < wend && wstart >= start && wend <= end {
+                    let wlen = (wend - wstart).0;
+
+                    if words.is_empty() {
+                        col = fill_width(chars[..i - wlen].iter().copied());
+                    }
+
+                    words.push((wstart..wend, fill_width(chars[i - wlen..i].iter().copied())));
+                }
+
+                while i < chars.len() && chars[i].is_whitespace() {
+                    i += 1;
+                }
+            }
+        }
+
+        let mut words = words.into_iter();
+        let mut prev = match words.next() {
+            Some((word, width)) => {
+                col += width;
+                word
+            },
+            None => return,
+        };
+
+        for (word, width) in words {
+            let gap = if col + 1 + width <= column {
+                col += 1 + width;
+                " ".to_string()
+            } else {
+                col = pwidth + width;
+                format!("\n{prefix}")
+            };
+
+            let range = prev.end..word.start;
+
+            if self.slice(range.clone()).to_string() != gap {
+                edits.push((range, gap));
+            }
+
+            prev = word;
+        }
+    }
+
+    /// Find the text object described by *style* that starts at or after *off*
+    /// ([MoveDir1D::Next]), or that ends at or before it ([MoveDir1D::Previous]).
+    ///
+    /// If *off* is in the middle of a word or sexp, then that object is returned in either
+    /// direction. The returned range excludes the trailing newline of lines.
+    pub(crate) fn find_transposable(
+        &self,
+        style: &TransposeStyle,
+        off: CharOff,
+        dir: MoveDir1D,
+    ) -> Option<Range<CharOff>> {
+        // The buffer's trailing newline can't be swapped with anything.
+        let len = self.len();
+        let len = match self.rope.get_char(len.saturating_sub(1)) {
+            Some('\n') => len - 1,
+            _ => len,
+        };
+        let at = |off: usize| {
+            if off < len {
+                self.rope.get_char(off)
+            } else {
+                None
+            }
+        };
+        let off = off.0.min(len);
+
+        let run = |pred: &dyn Fn(char) -> bool| -> Option<Range<CharOff>> {
+            let mut start = off;
+
+            match dir {
+                MoveDir1D::Next => {
+                    while let Some(c) = at(start) {
+                        if pred(c) {
+                            break;
+                        }
+
+                        start += 1;
+                    }
+
+                    at(start)?;
+                },
+                MoveDir1D::Previous => {
+                    while start > 0 && !pred(at(start - 1)?) {
+                        start -= 1;
+                    }
+
+                    if start == 0 {
+                        return None;
+                    }
+                },
+            }
+
+            while start > 0 && at(start - 1).is_some_and(pred) {
+                start -= 1;
+            }
+
+            let mut end = start;
+
+            while at(end).is_some_and(pred) {
+                end += 1;
+            }
+
+            if start == end {
+                return None;
+            }
+
+            return Some(CharOff(start)..CharOff(end));
+        };
+
+        match style {
+            TransposeStyle::Char => {
+                return match dir {
+                    MoveDir1D::Next if off < len => Some(CharOff(off)..CharOff(off + 1)),
+                    MoveDir1D::Previous if off > 0 => Some(CharOff(off - 1)..CharOff(off)),
+                    _ => None,
+                };
+            },
+            TransposeStyle::Line => {
+                let line = self.line_of_offset(CharOff(off));
+                let line = match dir {
+                    MoveDir1D::Next if self.offset_of_line(line).0 == off => line,
+                    MoveDir1D::Next => line + 1,
+                    MoveDir1D::Previous => line.checked_sub(1)?,
+                };
+
+                if line >= self.get_lines() {
+                    return None;
+                }
+
+                let start = self.offset_of_line(line);
+                let end = start + CharOff(self.get_columns(line));
+
+                return Some(start..end);
+            },
+            TransposeStyle::Word(style) => {
+                return run(&|c| style.contains(c));
+            },
+            TransposeStyle::Sexp => {
+                return self.find_sexp(CharOff(off), dir);
+            },
+        }
+    }
+
+    /// Returns `true` if the character at *off* is preceded by an odd number of backslashes.
+    fn is_escaped(&self, off: usize) -> bool {
+        let mut n = 0;
+
+        while n < off && self.rope.get_char(off - n - 1) == Some('\\') {
+            n += 1;
+        }
+
+        return n % 2 == 1;
+    }
+
+    /// Find the offset of the quote that ends the string starting at *off* ([MoveDir1D::Next]),
+    /// or that starts the string ending at *off* ([MoveDir1D::Previous]).
+    fn sexp_string_end(&self, off: usize, dir: MoveDir1D) -> Option<usize> {
+        let mut i = off;
+
+        loop {
+            match dir {
+                MoveDir1D::Next => i += 1,
+                MoveDir1D::Previous => i = i.checked_sub(1)?,
+            }
+
+            if self.rope.get_char(i)? == '"' && !self.is_escaped(i) {
+                return Some(i);
+            }
+        }
+    }
+
+    /// Scan from *off* in *dir*, skipping over strings and balanced groups, until reaching a
+    /// bracket that closes ([MoveDir1D::Next]) or opens ([MoveDir1D::Previous]) the enclosing
+    /// list, and return its offset.
+    fn sexp_scan(&self, off: usize, dir: MoveDir1D) -> Option<usize> {
+        let (enter, leave) = match dir {
+            MoveDir1D::Next => ("([{", ")]}"),
+            MoveDir1D::Previous => (")]}", "([{"),
+        };
+        let mut depth = 0usize;
+        let mut i = off;
+
+        loop {
+            if let MoveDir1D::Previous = dir {
+                i = i.checked_sub(1)?;
+            }
+
+            let c = self.rope.get_char(i)?;
+
+            if self.is_escaped(i) {
+                // Escaped characters aren't delimiters.
+            } else if c == '"' {
+                i = self.sexp_string_end(i, dir)?;
+            } else if enter.contains(c) {
+                depth += 1;
+            } else if leave.contains(c) {
+                if depth == 0 {
+                    return Some(i);
+                }
+
+                depth -= 1;
+            }
+
+            if let MoveDir1D::Next = dir {
+                i += 1;
+            }
+        }
+    }
+
+    /// Find the balanced expression that starts at or after *off* ([MoveDir1D::Next]), or that
+    /// ends at or before it ([MoveDir1D::Previous]).
+    ///
+    /// Bracketed groups and strings are treated as single expressions, and other runs of
+    /// characters that aren't whitespace, brackets, quotes or commas are treated as atoms. If
+    /// *off* is in the middle of an atom, then it is returned in either direction.
+    pub(crate) fn find_sexp(&self, off: CharOff, dir: MoveDir1D) -> Option<Range<CharOff>> {
+        let at = |off: usize| self.rope.get_char(off);
+        let sep = |i: usize| at(i).is_some_and(|c| is_space_char(c) || c == ',');
+        let atom = |i: usize| {
+            at(i).is_some_and(|c| self.is_escaped(i) || !(sep(i) || "()[]{}\"".contains(c)))
+        };
+        let off = off.0.min(self.len());
+
+        let (start, end) = match dir {
+            MoveDir1D::Next => {
+                let mut start = off;
+
+                while sep(start) {
+                    start += 1;
+                }
+
+                match at(start)? {
+                    _ if atom(start) => {
+                        let mut end = start;
+
+                        while start > 0 && atom(start - 1) {
+                            start -= 1;
+                        }
+
+                        while atom(end) {
+                            end += 1;
+                        }
+
+                        (start, end)
+                    },
+                    '"' => (start, self.sexp_string_end(start, dir)? + 1),
+                    '(' | '[' | '{' => (start, self.sexp_scan(start + 1, dir)? + 1),
+                    _ => return None,
+                }
+            },
+            MoveDir1D::Previous => {
+                let mut end = off;
+
+                while end > 0 && sep(end - 1) {
+                    end -= 1;
+                }
+
+                let last = end.checked_sub(1)?;
+
+                match at(last)? {
+                    _ if atom(last) => {
+                        let mut start = last;
+
+                        while start > 0 && atom(start - 1) {
+                            start -= 1;
+                        }
+
+                        while atom(end) {
+                            end += 1;
+                        }
+
+                        (start, end)
+                    },
+                    '"' => (self.sexp_string_end(last, dir)?, end),
+                    ')' | ']' | '}' => (self.sexp_scan(last, dir)?, end),
+                    _ => return None,
+                }
+            },
+        };
+
+        return Some(CharOff(start)..CharOff(end));
+    }
+
+    /// Find where the cursor should move to when leaving the enclosing list ([MoveType::ListUp])
+    /// or entering the next one ([MoveType::ListDown]).
+    fn find_list(&self, off: usize, mt: &MoveType) -> Option<usize> {
+        match mt {
+            MoveType::ListUp(MoveDir1D::Previous) => {
+                return self.sexp_scan(off, MoveDir1D::Previous);
+            },
+            MoveType::ListUp(MoveDir1D::Next) => {
+                return self.sexp_scan(off, MoveDir1D::Next).map(|i| i + 1);
+            },
+            MoveType::ListDown(MoveDir1D::Next) => {
+                let mut off = off;
+
+                loop {
+                    let r = self.find_sexp(CharOff(off), MoveDir1D::Next)?;
+
+                    if self.rope.get_char(r.start.0).is_some_and(|c| "([{".contains(c)) {
+                        return Some(r.start.0 + 1);
+                    }
+
+                    off = r.end.0;
+                }
+            },
+            MoveType::ListDown(MoveDir1D::Previous) => {
+                let mut off = off;
+
+                loop {
+                    let r = self.find_sexp(CharOff(off), MoveDir1D::Previous)?;
+
+                    if self.rope.get_char(r.end.0 - 1).is_some_and(|c| ")]}".contains(c)) {
+                        return Some(r.end.0 - 1);
+                    }
+
+                    off = r.start.0;
+                }
+            },
+            _ => return None,
+        }
+    }
+
     fn find_item(&self, nc: &Cursor) -> Option<Cursor> {
         let off = self.cursor_to_offset(nc);
         let mut rc = self.offset_to_rc(off);
@@ -2407,6 +2878,18 @@ impl CursorMovements<Cursor> for EditRope {
                 return None;
             },
 
+            // fold-wise movement
+            (MoveType::Fold(_), _) => {
+                // Folds are tracked by the buffer, which handles these movements.
+                return None;
+            },
+
+            // spelling movement
+            (MoveType::Misspelling(_), _) => {
+                // Spelling is checked by the buffer, which handles these movements.
+                return None;
+            },
+
             // wordwise movement
             (MoveType::FinalNonBlank(dir), count) => {
                 nc.line(*dir, count, cctx);
@@ -2447,6 +2930,35 @@ impl CursorMovements<Cursor> for EditRope {
             (MoveType::ItemMatch, _) => {
                 return self.find_item(&nc);
             },
+
+            // balanced expression movements
+            (MoveType::Sexp(dir), count) => {
+                let mut off = self.cursor_to_offset(&nc);
+
+                for n in 0..count {
+                    match (self.find_sexp(off, *dir), dir) {
+                        (Some(r), MoveDir1D::Next) => off = r.end,
+                        (Some(r), MoveDir1D::Previous) => off = r.start,
+                        (None, _) if n > 0 => break,
+                        (None, _) => return None,
+                    }
+                }
+
+                return self.offset_to_cursor(off).into();
+            },
+            (mt @ (MoveType::ListUp(_) | MoveType::ListDown(_)), count) => {
+                let mut off = self.cursor_to_offset(&nc).0;
+
+                for n in 0..count {
+                    match self.find_list(off, mt) {
+                        Some(next) => off = next,
+                        None if n > 0 => break,
+                        None => return None,
+                    }
+                }
+
+                return self.offset_to_cursor(CharOff(off)).into();
+            },
             (MoveType::LineColumnOffset, count) => {
                 nc.set_column(count.saturating_sub(1), cctx);
             },
@@ -4120,6 +4632,86 @@ mod tests {
         assert_eq!(rope.find_item(&Cursor::new(0, 7)), None);
     }
 
+    #[test]
+    fn test_find_sexp() {
+        let rope = EditRope::from("(foo \"b)\" [c d]) e\n");
+        let sexp = |off: usize, dir| {
+            rope.find_sexp(off.into(), dir)
+                .map(|r| (usize::from(r.start), usize::from(r.end)))
+        };
+
+        // Bracketed groups, strings and atoms are each a single expression.
+        assert_eq!(sexp(0, MoveDir1D::Next), Some((0, 16)));
+        assert_eq!(sexp(1, MoveDir1D::Next), Some((1, 4)));
+        assert_eq!(sexp(4, MoveDir1D::Next), Some((5, 9)));
+        assert_eq!(sexp(9, MoveDir1D::Next), Some((10, 15)));
+        assert_eq!(sexp(16, MoveDir1D::Next), Some((17, 18)));
+
+        assert_eq!(sexp(16, MoveDir1D::Previous), Some((0, 16)));
+        assert_eq!(sexp(15, MoveDir1D::Previous), Some((10, 15)));
+        assert_eq!(sexp(10, MoveDir1D::Previous), Some((5, 9)));
+
+        // Atoms are found in either direction from their middle.
+        assert_eq!(sexp(2, MoveDir1D::Next), Some((1, 4)));
+        assert_eq!(sexp(2, MoveDir1D::Previous), Some((1, 4)));
+
+        // Nothing can be found past the edges of a list.
+        assert_eq!(sexp(15, MoveDir1D::Next), None);
+        assert_eq!(sexp(1, MoveDir1D::Previous), None);
+
+        // Escaped quotes don't end strings.
+        let rope = EditRope::from("\"x\\\"y\", z\n");
+        let sexp = |off: usize, dir| {
+            rope.find_sexp(off.into(), dir)
+                .map(|r| (usize::from(r.start), usize::from(r.end)))
+        };
+
+        assert_eq!(sexp(0, MoveDir1D::Next), Some((0, 6)));
+        assert_eq!(sexp(6, MoveDir1D::Previous), Some((0, 6)));
+        assert_eq!(sexp(6, MoveDir1D::Next), Some((8, 9)));
+    }
+
+    #[test]
+    fn test_motion_sexp_list() {
+        let rope = EditRope::from("(foo \"b)\" [c d]) e\n");
+        let vwctx = ViewportContext::<Cursor>::default();
+        let vctx = EditContext::default();
+        let mv = |cursor: Cursor, mov: &MoveType, count: usize| {
+            rope.movement(&cursor, mov, &count.into(), cmctx!(vwctx, vctx))
+        };
+
+        // Move over balanced expressions.
+        let mov = MoveType::Sexp(MoveDir1D::Next);
+        assert_eq!(mv(Cursor::new(0, 0), &mov, 1), Some(Cursor::new(0, 16)));
+        assert_eq!(mv(Cursor::new(0, 1), &mov, 2), Some(Cursor::new(0, 9)));
+        assert_eq!(mv(Cursor::new(0, 1), &mov, 5), Some(Cursor::new(0, 15)));
+        assert_eq!(mv(Cursor::new(0, 15), &mov, 1), None);
+
+        let mov = MoveType::Sexp(MoveDir1D::Previous);
+        assert_eq!(mv(Cursor::new(0, 18), &mov, 2), Some(Cursor::new(0, 0)));
+        assert_eq!(mv(Cursor::new(0, 15), &mov, 1), Some(Cursor::new(0, 10)));
+
+        // Move out of lists.
+        let mov = MoveType::ListUp(MoveDir1D::Previous);
+        assert_eq!(mv(Cursor::new(0, 12), &mov, 1), Some(Cursor::new(0, 10)));
+        assert_eq!(mv(Cursor::new(0, 12), &mov, 2), Some(Cursor::new(0, 0)));
+        assert_eq!(mv(Cursor::new(0, 0), &mov, 1), None);
+
+        let mov = MoveType::ListUp(MoveDir1D::Next);
+        assert_eq!(mv(Cursor::new(0, 12), &mov, 1), Some(Cursor::new(0, 15)));
+        assert_eq!(mv(Cursor::new(0, 12), &mov, 2), Some(Cursor::new(0, 16)));
+
+        // Move into lists.
+        let mov = MoveType::ListDown(MoveDir1D::Next);
+        assert_eq!(mv(Cursor::new(0, 0), &mov, 1), Some(Cursor::new(0, 1)));
+        assert_eq!(mv(Cursor::new(0, 4), &mov, 1), Some(Cursor::new(0, 11)));
+        assert_eq!(mv(Cursor::new(0, 16), &mov, 1), None);
+
+        let mov = MoveType::ListDown(MoveDir1D::Previous);
+        assert_eq!(mv(Cursor::new(0, 16), &mov, 1), Some(Cursor::new(0, 15)));
+        assert_eq!(mv(Cursor::new(0, 16), &mov, 2), Some(Cursor::new(0, 14)));
+    }
+
     #[test]
     fn test_motion_char_line() {
         let rope = EditRope::from("hello\nworld\na b c d e\n");
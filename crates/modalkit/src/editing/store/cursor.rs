@@ -1,5 +1,5 @@
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::hash::Hash;
 
 use crate::editing::{
@@ -7,7 +7,7 @@ use crate::editing::{
     cursor::{Adjustable, Cursor, CursorAdjustment, CursorGroup, CursorGroupCombineError},
 };
 use crate::errors::{EditError, EditResult};
-use crate::prelude::{Mark, Register};
+use crate::prelude::{Mark, MarkRing, Register};
 
 /// Trait for objects that store cursors from multiple buffers.
 pub trait GlobalAdjustable<ID: ApplicationContentId> {
@@ -132,9 +132,11 @@ where
 ///
 /// - Saved cursors (see [EditorAction::Mark])
 /// - Saved cursor groups (see [CursorAction::Save])
+/// - Mark rings (see [CursorAction::PushMark])
 ///
 /// [EditorAction::Mark]: crate::actions::EditorAction::Mark
 /// [CursorAction::Save]: crate::actions::CursorAction::Save
+/// [CursorAction::PushMark]: crate::actions::CursorAction::PushMark
 pub struct CursorStore<I>
 where
     I: ApplicationInfo,
@@ -147,6 +149,14 @@ where
 
     /// Tracks saved cursor groups.
     groups: HashMap<Register, (I::ContentId, CursorGroup)>,
+
+    /// Tracks buffer-local mark rings.
+    ring: HashMap<I::ContentId, VecDeque<Cursor>>,
+    ring_max: usize,
+
+    /// Tracks the global mark ring.
+    global_ring: VecDeque<(I::ContentId, Cursor)>,
+    global_ring_max: usize,
 }
 
 impl<I> CursorStore<I>
@@ -159,6 +169,12 @@ where
             global: HashMap::new(),
             buffer: HashMap::new(),
             groups: HashMap::new(),
+
+            ring: HashMap::new(),
+            ring_max: 16,
+
+            global_ring: VecDeque::new(),
+            global_ring_max: 16,
         }
     }
 
@@ -190,6 +206,70 @@ where
         }
     }
 
+    /// Set how many positions each buffer's [MarkRing::Buffer] can hold before dropping the
+    /// oldest ones.
+    pub fn set_mark_ring_size(&mut self, size: usize) {
+        self.ring_max = size;
+
+        for ring in self.ring.values_mut() {
+            ring.truncate(size);
+        }
+    }
+
+    /// Set how many positions [MarkRing::Global] can hold before dropping the oldest ones.
+    pub fn set_global_mark_ring_size(&mut self, size: usize) {
+        self.global_ring_max = size;
+        self.global_ring.truncate(size);
+    }
+
+    /// Push a position onto the buffer's [MarkRing::Buffer], and onto [MarkRing::Global] if its
+    /// most recent entry is for a different buffer.
+    pub fn push_mark(&mut self, id: I::ContentId, cursor: Cursor) {
+        if !matches!(self.global_ring.front(), Some((owner, _)) if owner == &id) {
+            self.global_ring.push_front((id.clone(), cursor.clone()));
+            self.global_ring.truncate(self.global_ring_max);
+        }
+
+        let ring = self.ring.entry(id).or_default();
+        ring.push_front(cursor);
+        ring.truncate(self.ring_max);
+    }
+
+    /// Get the most recent position on a [MarkRing], and rotate it to the back of the ring.
+    ///
+    /// If the position belongs to another buffer, then the ring is left as is, so that the pop
+    /// can be retried after switching to that buffer.
+    pub fn pop_mark(&mut self, id: I::ContentId, ring: MarkRing) -> EditResult<Cursor, I> {
+        match ring {
+            MarkRing::Buffer => {
+                let msg = "No mark set in this buffer";
+                let ring = self.ring.get_mut(&id);
+                let cursor = ring.and_then(|r| r.pop_front());
+                let cursor = cursor.ok_or_else(|| EditError::Failure(msg.into()))?;
+
+                self.ring.entry(id).or_default().push_back(cursor.clone());
+
+                return Ok(cursor);
+            },
+            MarkRing::Global => {
+                let msg = "No global mark set";
+                let (owner, cursor) = self
+                    .global_ring
+                    .front()
+                    .cloned()
+                    .ok_or_else(|| EditError::Failure(msg.into()))?;
+
+                if id != owner {
+                    return Err(EditError::WrongBuffer(owner));
+                }
+
+                self.global_ring.rotate_left(1);
+
+                return Ok(cursor);
+            },
+        }
+    }
+
     /// Restore a cursor group from a given [Register].
     pub fn get_group(&self, id: I::ContentId, reg: &Register) -> EditResult<CursorGroup, I> {
         let (owner, group) = self
@@ -239,9 +319,17 @@ where
             val.zero_id(id);
         }
 
+        for val in self.global_ring.iter_mut() {
+            val.zero_id(id);
+        }
+
         if let Some(bmarks) = self.buffer.get_mut(id) {
             bmarks.zero();
         }
+
+        if let Some(ring) = self.ring.get_mut(id) {
+            ring.zero();
+        }
     }
 
     /// Adjust all marks associated with a buffer as described.
@@ -254,9 +342,17 @@ where
             val.adjust_id(id, adjs);
         }
 
+        for val in self.global_ring.iter_mut() {
+            val.adjust_id(id, adjs);
+        }
+
         if let Some(bmarks) = self.buffer.get_mut(id) {
             bmarks.adjust(adjs);
         }
+
+        if let Some(ring) = self.ring.get_mut(id) {
+            ring.adjust(adjs);
+        }
     }
 }
 
@@ -28,12 +28,16 @@ mod complete;
 mod cursor;
 mod digraph;
 mod register;
+mod spell;
 
 pub use self::buffer::{BufferStore, SharedBuffer};
 pub use self::complete::CompletionStore;
 pub use self::cursor::{AdjustStore, CursorStore, GlobalAdjustable};
 pub use self::digraph::DigraphStore;
 pub use self::register::{RegisterCell, RegisterError, RegisterPutFlags, RegisterStore};
+pub use self::spell::SpellStore;
+
+pub(crate) use self::spell::spell_words;
 
 /// Global editing context
 pub struct Store<I: ApplicationInfo> {
@@ -49,6 +53,9 @@ pub struct Store<I: ApplicationInfo> {
     /// Tracks the current value of each [Register](crate::prelude::Register).
     pub registers: RegisterStore,
 
+    /// Tracks the words used for spell checking.
+    pub spell: SpellStore,
+
     /// Tracks globally-relevant cursors and cursor groups.
     pub cursors: CursorStore<I>,
 
@@ -70,6 +77,7 @@ where
             completions: CompletionStore::default(),
             digraphs: DigraphStore::default(),
             registers: RegisterStore::default(),
+            spell: SpellStore::default(),
             cursors: CursorStore::default(),
 
             application,
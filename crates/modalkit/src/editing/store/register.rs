@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use bitflags::bitflags;
 
@@ -12,7 +12,7 @@ use std::sync::{RwLock, RwLockWriteGuard};
 use crate::editing::history::HistoryList;
 use crate::editing::rope::EditRope;
 use crate::prelude::TargetShape::{self, BlockWise, CharWise, LineWise};
-use crate::prelude::{CommandType, Register};
+use crate::prelude::{CommandType, MoveDir1D, Register};
 
 #[cfg(all(feature = "clipboard", target_os = "linux"))]
 mod clipboard {
@@ -76,6 +76,9 @@ bitflags! {
         /// This will skip setting [Register::Unnamed] to have the same value as the updated
         /// register.
         const NOTEXT = 0b00000100;
+
+        /// Prepend contents to register.
+        const PREPEND = 0b00001000;
     }
 }
 
@@ -131,12 +134,23 @@ pub struct RegisterStore {
     last_deleted: Vec<RegisterCell>,
     last_macro: Option<Register>,
 
+    kill_ring: VecDeque<RegisterCell>,
+    kill_ring_max: usize,
+    kill_ring_yank: usize,
+    killed_rectangle: RegisterCell,
+
+    macro_ring: VecDeque<RegisterCell>,
+    macro_ring_max: usize,
+
     small_delete: RegisterCell,
 
     unnamed: RegisterCell,
     unnamed_macro: RegisterCell,
     named: HashMap<char, RegisterCell>,
 
+    hlsearch: bool,
+    search_preview: Option<(EditRope, MoveDir1D)>,
+
     #[cfg(feature = "clipboard")]
     clipboard: Option<RwLock<Clipboard>>,
 }
@@ -236,12 +250,23 @@ impl RegisterStore {
             last_deleted: vec![RegisterCell::default(); 9],
             last_macro: None,
 
+            kill_ring: VecDeque::new(),
+            kill_ring_max: 120,
+            kill_ring_yank: 0,
+            killed_rectangle: RegisterCell::default(),
+
+            macro_ring: VecDeque::new(),
+            macro_ring_max: 8,
+
             small_delete: RegisterCell::default(),
 
             unnamed: RegisterCell::default(),
             unnamed_macro: RegisterCell::default(),
             named: HashMap::new(),
 
+            hlsearch: true,
+            search_preview: None,
+
             #[cfg(feature = "clipboard")]
             clipboard: Clipboard::new().ok().map(RwLock::new),
         }
@@ -261,6 +286,67 @@ impl RegisterStore {
         }
     }
 
+    fn _push_killed(&mut self, cell: RegisterCell, merged: bool) {
+        if let (true, Some(front)) = (merged, self.kill_ring.front_mut()) {
+            *front = cell;
+        } else {
+            self.kill_ring.push_front(cell);
+            self.kill_ring.truncate(self.kill_ring_max);
+        }
+    }
+
+    /// Set the maximum number of entries to keep in [Register::KillRing].
+    ///
+    /// This defaults to 120.
+    pub fn set_kill_ring_size(&mut self, size: usize) {
+        self.kill_ring_max = size;
+        self.kill_ring.truncate(size);
+
+        if self.kill_ring_yank >= self.kill_ring.len() {
+            self.kill_ring_yank = 0;
+        }
+    }
+
+    /// Move the yank pointer of [Register::KillRing] `count` entries towards older
+    /// ([MoveDir1D::Previous]) or newer ([MoveDir1D::Next]) text, wrapping around at either end.
+    pub fn rotate_kill_ring(&mut self, dir: MoveDir1D, count: usize) {
+        let len = self.kill_ring.len();
+
+        if len == 0 {
+            return;
+        }
+
+        let count = count % len;
+
+        self.kill_ring_yank = match dir {
+            MoveDir1D::Previous => (self.kill_ring_yank + count) % len,
+            MoveDir1D::Next => (self.kill_ring_yank + len - count) % len,
+        };
+    }
+
+    /// Set the maximum number of entries to keep in [Register::MacroRing].
+    ///
+    /// This defaults to 8.
+    pub fn set_macro_ring_size(&mut self, size: usize) {
+        self.macro_ring_max = size;
+        self.macro_ring.truncate(size);
+    }
+
+    /// Rotate [Register::MacroRing] `count` times so that an older ([MoveDir1D::Previous]) or
+    /// newer ([MoveDir1D::Next]) macro is at its front.
+    pub fn rotate_macro_ring(&mut self, dir: MoveDir1D, count: usize) {
+        let len = self.macro_ring.len();
+
+        if len == 0 {
+            return;
+        }
+
+        match dir {
+            MoveDir1D::Previous => self.macro_ring.rotate_left(count % len),
+            MoveDir1D::Next => self.macro_ring.rotate_right(count % len),
+        }
+    }
+
     /// Get the current value of a [Register].
     ///
     /// If none is specified, this returns the value of [Register::Unnamed].
@@ -276,6 +362,11 @@ impl RegisterStore {
             Register::Named(name) => self.named.get(name).cloned().unwrap_or_default(),
             Register::AltBufName => self.altbufname.clone(),
             Register::LastYanked => self.last_yanked.clone(),
+            Register::KillRing => {
+                self.kill_ring.get(self.kill_ring_yank).cloned().unwrap_or_default()
+            },
+            Register::KilledRectangle => self.killed_rectangle.clone(),
+            Register::MacroRing => self.macro_ring.front().cloned().unwrap_or_default(),
 
             /*
              * Operating system clipboards.
@@ -339,7 +430,7 @@ impl RegisterStore {
     /// the value of [Register::Unnamed].
     ///
     /// The `append` flag controls whether this should wholly replace or append to the current
-    /// value.
+    /// value, and the `prepend` flag whether it should be placed before the current value.
     ///
     /// The `del` flag indicates whether this register update is being done as part of a text
     /// deletion in a document.
@@ -349,8 +440,17 @@ impl RegisterStore {
         mut cell: RegisterCell,
         flags: RegisterPutFlags,
     ) -> Result<(), RegisterError> {
+        let merged = flags.intersects(RegisterPutFlags::APPEND | RegisterPutFlags::PREPEND);
+
+        if let Register::KillRing = reg {
+            // New kills always merge with, or get placed in front of, the most recent one.
+            self.kill_ring_yank = 0;
+        }
+
         if flags.contains(RegisterPutFlags::APPEND) {
             cell = self.get(reg)?.merge(&cell)
+        } else if flags.contains(RegisterPutFlags::PREPEND) {
+            cell = cell.merge(&self.get(reg)?)
         }
 
         /*
@@ -399,6 +499,24 @@ impl RegisterStore {
                 self.last_yanked = cell.clone();
                 cell
             },
+            Register::KillRing => {
+                self._push_killed(cell.clone(), merged);
+                cell
+            },
+            Register::KilledRectangle => {
+                self.killed_rectangle = cell.clone();
+                cell
+            },
+            Register::MacroRing => {
+                if let (true, Some(front)) = (merged, self.macro_ring.front_mut()) {
+                    *front = cell.clone();
+                } else {
+                    self.macro_ring.push_front(cell.clone());
+                    self.macro_ring.truncate(self.macro_ring_max);
+                }
+
+                cell
+            },
 
             /*
              * Operating system clipboards.
@@ -474,10 +592,20 @@ impl RegisterStore {
         let rope = rope.into();
 
         if rope.is_empty() {
+            if ct == CommandType::Prompt {
+                // An empty answer to a prompt is meaningful, but isn't kept in the history.
+                self.last_commands.entry(ct).or_default().last_used = rope;
+            }
+
             // Disallow updating with an empty value.
             return;
         }
 
+        if ct == CommandType::Search {
+            // A new search turns highlighting back on after it's been cleared.
+            self.hlsearch = true;
+        }
+
         let hist = self.last_commands.entry(ct).or_default();
         hist.history.select(rope.clone());
         hist.last_used = rope;
@@ -516,6 +644,46 @@ impl RegisterStore {
     pub fn get_last_search(&self) -> EditRope {
         self._get_last_cmd(CommandType::Search)
     }
+
+    /// Get the search pattern whose matches should currently be highlighted.
+    ///
+    /// This returns `None` if there hasn't been a search yet, or if highlighting has been
+    /// disabled with [RegisterStore::set_search_highlight] since the last search.
+    pub fn get_search_highlight(&self) -> Option<EditRope> {
+        if !self.hlsearch {
+            return None;
+        }
+
+        let search = self.get_last_search();
+
+        if search.is_empty() {
+            return None;
+        }
+
+        return Some(search);
+    }
+
+    /// Control whether matches of the last search should be highlighted.
+    ///
+    /// Highlighting is turned back on whenever `Register::LastCommand(CommandType::Search)` gets
+    /// updated.
+    pub fn set_search_highlight(&mut self, hlsearch: bool) {
+        self.hlsearch = hlsearch;
+    }
+
+    /// Get the in-progress search pattern, and the direction it's being searched in.
+    pub fn get_search_preview(&self) -> Option<&(EditRope, MoveDir1D)> {
+        self.search_preview.as_ref()
+    }
+
+    /// Update the search pattern that is currently being typed, so that its next match can be
+    /// previewed before the search gets submitted.
+    ///
+    /// While a pattern is set here, regex searches use it instead of
+    /// `Register::LastCommand(CommandType::Search)`.
+    pub fn set_search_preview(&mut self, preview: Option<(EditRope, MoveDir1D)>) {
+        self.search_preview = preview;
+    }
 }
 
 impl Default for RegisterStore {
@@ -527,6 +695,7 @@ impl Default for RegisterStore {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::editing::history::ScrollbackState;
 
     #[test]
     fn test_cell_merge() {
@@ -549,4 +718,142 @@ mod tests {
         assert_eq!(c.merge(&b), RegisterCell::new(LineWise, EditRope::from("q\nr\ns\n1\n2\n")));
         assert_eq!(c.merge(&c), RegisterCell::new(LineWise, EditRope::from("q\nr\ns\nq\nr\ns\n")));
     }
+
+    #[test]
+    fn test_search_highlight() {
+        let mut store = RegisterStore::default();
+
+        // Nothing to highlight before the first search.
+        assert_eq!(store.get_search_highlight(), None);
+
+        store.set_last_search("foo");
+        assert_eq!(store.get_search_highlight(), Some(EditRope::from("foo")));
+
+        // Clearing the highlight keeps the search around.
+        store.set_search_highlight(false);
+        assert_eq!(store.get_search_highlight(), None);
+        assert_eq!(store.get_last_search(), EditRope::from("foo"));
+
+        // Running a command doesn't turn highlighting back on.
+        store.set_last_cmd("write");
+        assert_eq!(store.get_search_highlight(), None);
+
+        // But performing a new search does.
+        store.set_last_search("bar");
+        assert_eq!(store.get_search_highlight(), Some(EditRope::from("bar")));
+    }
+
+    #[test]
+    fn test_last_prompt() {
+        let mut store = RegisterStore::default();
+        let reg = Register::LastCommand(CommandType::Prompt);
+
+        store.set_last_command(CommandType::Prompt, "foo");
+        assert_eq!(store.get(&reg).unwrap().value, EditRope::from("foo"));
+
+        // Prompt answers don't touch the command history.
+        assert_eq!(store.get_last_cmd(), EditRope::empty());
+
+        // An empty answer replaces the last one, but doesn't get added to the history.
+        store.set_last_command(CommandType::Prompt, "");
+        assert_eq!(store.get(&reg).unwrap().value, EditRope::empty());
+
+        let hist = store.get_command_history(CommandType::Prompt);
+        let mut scrollback = ScrollbackState::Pending;
+        let prev = MoveDir1D::Previous;
+        let empty = EditRope::empty();
+        assert_eq!(hist.recall(&empty, &mut scrollback, prev, false, 1), Some("foo".into()));
+
+        // Other command types still ignore empty values.
+        store.set_last_cmd("write");
+        store.set_last_cmd("");
+        assert_eq!(store.get_last_cmd(), EditRope::from("write"));
+    }
+
+    #[test]
+    fn test_kill_ring() {
+        let mut store = RegisterStore::default();
+        let reg = Register::KillRing;
+
+        // Nothing has been killed yet.
+        assert_eq!(store.get(&reg).unwrap(), RegisterCell::default());
+
+        store.put(&reg, "a".into(), RegisterPutFlags::DELETE).unwrap();
+        store.put(&reg, "b".into(), RegisterPutFlags::DELETE).unwrap();
+        assert_eq!(store.get(&reg).unwrap(), "b".into());
+        assert_eq!(store.get(&Register::Unnamed).unwrap(), "b".into());
+
+        // Consecutive kills can be merged in either direction.
+        let flags = RegisterPutFlags::DELETE | RegisterPutFlags::APPEND;
+        store.put(&reg, "c".into(), flags).unwrap();
+        assert_eq!(store.get(&reg).unwrap(), "bc".into());
+
+        let flags = RegisterPutFlags::DELETE | RegisterPutFlags::PREPEND;
+        store.put(&reg, "d".into(), flags).unwrap();
+        assert_eq!(store.get(&reg).unwrap(), "dbc".into());
+
+        // Rotating wraps around at both ends.
+        store.rotate_kill_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get(&reg).unwrap(), "a".into());
+
+        store.rotate_kill_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get(&reg).unwrap(), "dbc".into());
+
+        store.rotate_kill_ring(MoveDir1D::Next, 3);
+        assert_eq!(store.get(&reg).unwrap(), "a".into());
+
+        // A new kill resets the yank pointer.
+        store.put(&reg, "e".into(), RegisterPutFlags::DELETE).unwrap();
+        assert_eq!(store.get(&reg).unwrap(), "e".into());
+
+        // Shrinking the ring drops the oldest entries.
+        store.set_kill_ring_size(2);
+        store.rotate_kill_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get(&reg).unwrap(), "dbc".into());
+        store.rotate_kill_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get(&reg).unwrap(), "e".into());
+
+        store.put(&reg, "f".into(), RegisterPutFlags::DELETE).unwrap();
+        store.rotate_kill_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get(&reg).unwrap(), "e".into());
+        store.rotate_kill_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get(&reg).unwrap(), "f".into());
+    }
+
+    #[test]
+    fn test_macro_ring() {
+        let mut store = RegisterStore::default();
+        let reg = Register::MacroRing;
+
+        // Nothing has been recorded yet.
+        assert!(store.get_macro(reg.clone()).unwrap().is_empty());
+
+        store.put(&reg, "a".into(), RegisterPutFlags::NOTEXT).unwrap();
+        store.put(&reg, "b".into(), RegisterPutFlags::NOTEXT).unwrap();
+        store.put(&reg, "c".into(), RegisterPutFlags::NOTEXT).unwrap();
+        assert_eq!(store.get_macro(reg.clone()).unwrap(), EditRope::from("c"));
+        assert_eq!(store.get(&Register::Unnamed).unwrap(), RegisterCell::default());
+
+        // Appending extends the most recent macro.
+        let flags = RegisterPutFlags::NOTEXT | RegisterPutFlags::APPEND;
+        store.put(&reg, "d".into(), flags).unwrap();
+        assert_eq!(store.get_macro(reg.clone()).unwrap(), EditRope::from("cd"));
+
+        // Rotating moves older or newer macros to the front, and wraps around.
+        store.rotate_macro_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get_last_macro().unwrap(), EditRope::from("b"));
+
+        store.rotate_macro_ring(MoveDir1D::Previous, 2);
+        assert_eq!(store.get_last_macro().unwrap(), EditRope::from("cd"));
+
+        store.rotate_macro_ring(MoveDir1D::Next, 1);
+        assert_eq!(store.get_last_macro().unwrap(), EditRope::from("a"));
+
+        // Shrinking the ring drops entries from the back.
+        store.set_macro_ring_size(2);
+        store.rotate_macro_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get_last_macro().unwrap(), EditRope::from("cd"));
+        store.rotate_macro_ring(MoveDir1D::Previous, 1);
+        assert_eq!(store.get_last_macro().unwrap(), EditRope::from("a"));
+    }
 }
@@ -0,0 +1,812 @@
+//! # Spell checking
+//!
+//! ## Overview
+//!
+//! The [SpellStore] tracks which words are considered correctly spelled. Words can be loaded from
+//! [Hunspell](https://github.com/hunspell/hunspell) `.dic` and `.aff` files, from plain word
+//! lists, and from a user dictionary that gets updated as words are marked good or bad.
+//!
+//! ## Example
+//!
+//! ```
+//! use modalkit::editing::store::SpellStore;
+//!
+//! let mut spell = SpellStore::default();
+//! spell.add_words(["hello", "world", "Paris"]);
+//!
+//! assert!(!spell.is_misspelled("Hello"));
+//! assert!(!spell.is_misspelled("PARIS"));
+//! assert!(spell.is_misspelled("paris"));
+//! assert!(spell.is_misspelled("wrold"));
+//!
+//! assert_eq!(spell.suggest("wrold", 5), vec!["world".to_string()]);
+//! ```
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+/// The maximum number of edits allowed between a word and one of its suggestions.
+const MAX_SUGGEST_DISTANCE: usize = 2;
+
+/// Returns true if `c` can be part of a word that gets spell checked.
+fn is_spell_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '\''
+}
+
+fn is_capitalized(word: &str) -> bool {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(c) if c.is_uppercase() => chars.all(|c| !c.is_uppercase()),
+        _ => false,
+    }
+}
+
+fn is_all_caps(word: &str) -> bool {
+    word.chars().any(char::is_uppercase) && !word.chars().any(char::is_lowercase)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Match the capitalization of a suggestion to that of the misspelled word.
+fn match_case(word: &str, suggestion: &str) -> String {
+    if is_all_caps(word) && word.chars().count() > 1 {
+        suggestion.to_uppercase()
+    } else if is_capitalized(word) {
+        capitalize(suggestion)
+    } else {
+        suggestion.to_string()
+    }
+}
+
+/// Calculate the optimal string alignment distance between two words, giving up once it's
+/// clear that it will be greater than `max`.
+fn edit_distance(a: &[char], b: &[char], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let width = b.len() + 1;
+    let mut prev2 = vec![0; width];
+    let mut prev: Vec<usize> = (0..width).collect();
+    let mut curr = vec![0; width];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+
+        let mut lowest = curr[0];
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut dist = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist = dist.min(prev2[j - 2] + 1);
+            }
+
+            curr[j] = dist;
+            lowest = lowest.min(dist);
+        }
+
+        if lowest > max {
+            return None;
+        }
+
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    Some(prev[b.len()]).filter(|dist| *dist <= max)
+}
+
+/// Find the words to check within a line of text, returned as ranges of character offsets.
+///
+/// Words containing digits are skipped, and apostrophes are only treated as part of a word when
+/// they're surrounded by other word characters.
+pub(crate) fn spell_words(text: &str) -> Vec<(Range<usize>, String)> {
+    let mut words = vec![];
+    let mut chars = text.chars().enumerate().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if !is_spell_char(c) {
+            continue;
+        }
+
+        let mut word = String::from(c);
+        let mut end = start + 1;
+
+        while let Some((i, c)) = chars.next_if(|(_, c)| is_spell_char(*c)) {
+            word.push(c);
+            end = i + 1;
+        }
+
+        let lead = word.chars().take_while(|c| *c == '\'').count();
+        let trail = word.chars().rev().take_while(|c| *c == '\'').count();
+
+        if lead == word.chars().count() || word.chars().any(|c| c.is_numeric()) {
+            continue;
+        }
+
+        let trimmed = word.trim_matches('\'').to_string();
+        words.push((start + lead..end - trail, trimmed));
+    }
+
+    return words;
+}
+
+/// How flags are written in a Hunspell `.aff` and `.dic` file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum FlagFormat {
+    /// Each flag is a single character.
+    #[default]
+    Char,
+
+    /// Each flag is two characters.
+    Long,
+
+    /// Flags are comma-separated decimal numbers.
+    Num,
+}
+
+impl FlagFormat {
+    fn parse(&self, flags: &str) -> Vec<String> {
+        match self {
+            FlagFormat::Char => flags.chars().map(String::from).collect(),
+            FlagFormat::Long => {
+                let chars = flags.chars().collect::<Vec<_>>();
+
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            },
+            FlagFormat::Num => {
+                flags
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|f| !f.is_empty())
+                    .map(String::from)
+                    .collect()
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Option<Regex>,
+}
+
+#[derive(Clone, Debug)]
+struct Affix {
+    prefix: bool,
+    cross: bool,
+    rules: Vec<AffixRule>,
+}
+
+impl Affix {
+    fn apply(&self, word: &str) -> Vec<String> {
+        let mut words = vec![];
+
+        for rule in self.rules.iter() {
+            if let Some(cond) = &rule.condition {
+                if !cond.is_match(word) {
+                    continue;
+                }
+            }
+
+            if self.prefix {
+                if let Some(stem) = word.strip_prefix(rule.strip.as_str()) {
+                    words.push(format!("{}{}", rule.add, stem));
+                }
+            } else if let Some(stem) = word.strip_suffix(rule.strip.as_str()) {
+                words.push(format!("{}{}", stem, rule.add));
+            }
+        }
+
+        return words;
+    }
+}
+
+/// The affix rules from a Hunspell `.aff` file.
+#[derive(Debug, Default)]
+struct AffixFile {
+    format: FlagFormat,
+    affixes: HashMap<String, Affix>,
+}
+
+impl AffixFile {
+    fn parse(text: &str) -> Self {
+        let mut aff = AffixFile::default();
+
+        for line in text.lines() {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+
+            match fields.as_slice() {
+                ["FLAG", "long", ..] => aff.format = FlagFormat::Long,
+                ["FLAG", "num", ..] => aff.format = FlagFormat::Num,
+                [kind @ ("PFX" | "SFX"), flag, cross, count] if count.parse::<usize>().is_ok() => {
+                    let affix = Affix {
+                        prefix: *kind == "PFX",
+                        cross: *cross == "Y",
+                        rules: vec![],
+                    };
+
+                    aff.affixes.insert(flag.to_string(), affix);
+                },
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let Some(affix) = aff.affixes.get_mut(*flag) else {
+                        continue;
+                    };
+
+                    let strip = if *strip == "0" { "" } else { strip };
+                    let add = add.split('/').next().unwrap_or_default();
+                    let add = if add == "0" { "" } else { add };
+
+                    let condition = match rest.first() {
+                        None | Some(&".") => None,
+                        Some(cond) if *kind == "PFX" => Regex::new(&format!("^{cond}")).ok(),
+                        Some(cond) => Regex::new(&format!("{cond}$")).ok(),
+                    };
+
+                    affix.rules.push(AffixRule {
+                        strip: strip.to_string(),
+                        add: add.to_string(),
+                        condition,
+                    });
+                },
+                _ => continue,
+            }
+        }
+
+        return aff;
+    }
+
+    /// Generate every form of a word given its flags.
+    fn expand(&self, word: &str, flags: &str) -> Vec<String> {
+        let affixes = self
+            .format
+            .parse(flags)
+            .into_iter()
+            .filter_map(|flag| self.affixes.get(&flag))
+            .collect::<Vec<_>>();
+
+        let mut words = vec![word.to_string()];
+
+        for sfx in affixes.iter().filter(|a| !a.prefix) {
+            for sword in sfx.apply(word) {
+                if sfx.cross {
+                    for pfx in affixes.iter().filter(|a| a.prefix && a.cross) {
+                        words.extend(pfx.apply(&sword));
+                    }
+                }
+
+                words.push(sword);
+            }
+        }
+
+        for pfx in affixes.iter().filter(|a| a.prefix) {
+            words.extend(pfx.apply(word));
+        }
+
+        return words;
+    }
+}
+
+/// Split an entry from a word list or Hunspell dictionary into the word and its flags.
+///
+/// Words that contain a slash need to be written with a trailing slash, like `and/or/`.
+fn split_entry(entry: &str) -> (&str, &str) {
+    match entry.rsplit_once('/') {
+        Some((word, flags)) => (word, flags),
+        None => (entry, ""),
+    }
+}
+
+fn format_entry(word: &str, bad: bool) -> String {
+    let slash = if word.contains('/') { "/" } else { "" };
+    let flags = if bad { "/!" } else { "" };
+
+    format!("{word}{slash}{flags}")
+}
+
+/// Tracks the words used for spell checking.
+///
+/// Words that are entirely lowercase in a dictionary will also match their capitalized and
+/// uppercase forms, while words that contain capital letters must always have them.
+#[derive(Debug, Default)]
+pub struct SpellStore {
+    enabled: bool,
+
+    /// Words loaded from dictionaries and word lists.
+    words: HashSet<String>,
+
+    /// Words marked as good or bad in the user dictionary.
+    user_good: HashSet<String>,
+    user_bad: HashSet<String>,
+    user_file: Option<PathBuf>,
+
+    /// Words marked as good or bad until the application exits.
+    session_good: HashSet<String>,
+    session_bad: HashSet<String>,
+}
+
+impl SpellStore {
+    /// Indicates whether misspelled words should be highlighted and searched for.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Control whether misspelled words should be highlighted and searched for.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Add words to the set of correctly spelled words.
+    pub fn add_words<T, S>(&mut self, words: T)
+    where
+        T: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.words.extend(words.into_iter().map(Into::into));
+    }
+
+    /// Load the words in a Hunspell dictionary, expanding them using the prefix and suffix rules
+    /// from its affix file.
+    pub fn load_hunspell<P: AsRef<Path>, Q: AsRef<Path>>(
+        &mut self,
+        dic: P,
+        aff: Q,
+    ) -> io::Result<()> {
+        let aff = AffixFile::parse(&String::from_utf8_lossy(&fs::read(aff)?));
+        let dic = String::from_utf8_lossy(&fs::read(dic)?).into_owned();
+        let mut lines = dic.lines().peekable();
+
+        // The first line is an approximate word count.
+        let _ = lines.next_if(|line| line.trim().parse::<usize>().is_ok());
+
+        for line in lines {
+            let entry = line.split_whitespace().next().unwrap_or_default();
+
+            if entry.is_empty() {
+                continue;
+            }
+
+            let (word, flags) = split_entry(entry);
+
+            self.words.extend(aff.expand(word, flags));
+        }
+
+        return Ok(());
+    }
+
+    /// Load a plain list of words, with one word per line.
+    ///
+    /// Lines starting with `#` are treated as comments, and words followed by `/!` are treated
+    /// as misspelled.
+    pub fn load_word_list<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let text = fs::read_to_string(path)?;
+        let (good, bad) = parse_word_list(&text);
+
+        self.words.extend(good);
+        self.session_bad.extend(bad);
+
+        return Ok(());
+    }
+
+    /// Set the file that words marked with [SpellStore::mark_good] and [SpellStore::mark_bad]
+    /// are saved to, and load any words already in it.
+    ///
+    /// If a user dictionary isn't set, then those words are only remembered until the
+    /// application exits.
+    pub fn set_user_dictionary<P: Into<PathBuf>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.into();
+
+        let (good, bad) = match fs::read_to_string(&path) {
+            Ok(text) => parse_word_list(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (vec![], vec![]),
+            Err(e) => return Err(e),
+        };
+
+        self.user_good = good.into_iter().collect();
+        self.user_bad = bad.into_iter().collect();
+        self.user_file = Some(path);
+
+        return Ok(());
+    }
+
+    fn save_user_dictionary(&self) -> io::Result<()> {
+        let Some(path) = &self.user_file else {
+            return Ok(());
+        };
+
+        let mut entries = self
+            .user_good
+            .iter()
+            .map(|word| format_entry(word, false))
+            .chain(self.user_bad.iter().map(|word| format_entry(word, true)))
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        let mut text = entries.join("\n");
+        text.push('\n');
+
+        fs::write(path, text)
+    }
+
+    /// Mark a word as correctly spelled.
+    ///
+    /// If `session` is true, then the word is only remembered until the application exits, and
+    /// otherwise it's saved to the user dictionary.
+    pub fn mark_good(&mut self, word: &str, session: bool) -> io::Result<()> {
+        if session {
+            self.session_bad.remove(word);
+            self.session_good.insert(word.to_string());
+
+            return Ok(());
+        }
+
+        self.user_bad.remove(word);
+        self.user_good.insert(word.to_string());
+        self.save_user_dictionary()
+    }
+
+    /// Mark a word as misspelled.
+    ///
+    /// If `session` is true, then the word is only remembered until the application exits, and
+    /// otherwise it's saved to the user dictionary.
+    pub fn mark_bad(&mut self, word: &str, session: bool) -> io::Result<()> {
+        if session {
+            self.session_good.remove(word);
+            self.session_bad.insert(word.to_string());
+
+            return Ok(());
+        }
+
+        self.user_good.remove(word);
+        self.user_bad.insert(word.to_string());
+        self.save_user_dictionary()
+    }
+
+    /// Forget that a word was marked as good or bad.
+    ///
+    /// If `session` is true, then this only looks at words marked until the application exits,
+    /// and otherwise it removes the word from the user dictionary.
+    pub fn unmark(&mut self, word: &str, session: bool) -> io::Result<()> {
+        if session {
+            self.session_good.remove(word);
+            self.session_bad.remove(word);
+
+            return Ok(());
+        }
+
+        self.user_good.remove(word);
+        self.user_bad.remove(word);
+        self.save_user_dictionary()
+    }
+
+    fn is_bad(&self, word: &str) -> bool {
+        self.session_bad.contains(word) || self.user_bad.contains(word)
+    }
+
+    fn is_good(&self, word: &str) -> bool {
+        self.words.contains(word) ||
+            self.user_good.contains(word) ||
+            self.session_good.contains(word)
+    }
+
+    fn check(&self, word: &str, test: impl Fn(&str) -> bool) -> bool {
+        if test(word) {
+            return true;
+        }
+
+        let lower = word.to_lowercase();
+
+        if is_capitalized(word) {
+            return test(&lower);
+        }
+
+        if is_all_caps(word) {
+            return test(&lower) || test(&capitalize(&lower));
+        }
+
+        return false;
+    }
+
+    /// Returns true if `word` is misspelled.
+    ///
+    /// Words that haven't been explicitly marked as bad are only considered misspelled once some
+    /// good words have been loaded.
+    pub fn is_misspelled(&self, word: &str) -> bool {
+        if self.check(word, |w| self.is_bad(w)) {
+            return true;
+        }
+
+        if self.words.is_empty() && self.user_good.is_empty() && self.session_good.is_empty() {
+            return false;
+        }
+
+        !self.check(word, |w| self.is_good(w))
+    }
+
+    /// Find the misspelled words within a line of text, returned as ranges of character offsets.
+    pub fn misspellings(&self, text: &str) -> Vec<Range<usize>> {
+        spell_words(text)
+            .into_iter()
+            .filter(|(_, word)| self.is_misspelled(word))
+            .map(|(range, _)| range)
+            .collect()
+    }
+
+    /// Suggest up to `max` correctly spelled words to replace `word` with, ordered from the most
+    /// to the least similar.
+    pub fn suggest(&self, word: &str, max: usize) -> Vec<String> {
+        let lower = word.to_lowercase().chars().collect::<Vec<_>>();
+        let candidates = self
+            .words
+            .iter()
+            .chain(self.user_good.iter())
+            .chain(self.session_good.iter())
+            .collect::<HashSet<_>>();
+
+        let mut found = candidates
+            .into_iter()
+            .filter(|candidate| !self.is_bad(candidate))
+            .filter_map(|candidate| {
+                let chars = candidate.to_lowercase().chars().collect::<Vec<_>>();
+                let dist = edit_distance(&lower, &chars, MAX_SUGGEST_DISTANCE)?;
+
+                Some((dist, match_case(word, candidate)))
+            })
+            .filter(|(_, candidate)| candidate != word)
+            .collect::<Vec<_>>();
+
+        found.sort();
+        found.dedup_by(|a, b| a.1 == b.1);
+        found.into_iter().take(max).map(|(_, candidate)| candidate).collect()
+    }
+}
+
+fn parse_word_list(text: &str) -> (Vec<String>, Vec<String>) {
+    let mut good = vec![];
+    let mut bad = vec![];
+
+    for line in text.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (word, flags) = split_entry(line);
+
+        if word.is_empty() {
+            continue;
+        } else if flags.contains('!') {
+            bad.push(word.to_string());
+        } else {
+            good.push(word.to_string());
+        }
+    }
+
+    return (good, bad);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use temp_dir::TempDir;
+
+    fn mkstore() -> SpellStore {
+        let mut spell = SpellStore::default();
+        spell.add_words(["hello", "world", "help", "held", "Paris", "don't"]);
+        spell
+    }
+
+    #[test]
+    fn test_spell_words() {
+        let words = spell_words("'Hello,' she said, don't x86 o'clock ''");
+        let words = words.into_iter().map(|(r, w)| (r.start, r.end, w)).collect::<Vec<_>>();
+
+        assert_eq!(words, vec![
+            (1, 6, "Hello".to_string()),
+            (9, 12, "she".to_string()),
+            (13, 17, "said".to_string()),
+            (19, 24, "don't".to_string()),
+            (29, 36, "o'clock".to_string()),
+        ]);
+
+        let words = spell_words("über café");
+        let words = words.into_iter().map(|(r, w)| (r.start, r.end, w)).collect::<Vec<_>>();
+        assert_eq!(words, vec![(0, 4, "über".to_string()), (5, 9, "café".to_string())]);
+    }
+
+    #[test]
+    fn test_is_misspelled() {
+        let mut spell = SpellStore::default();
+
+        // Nothing is misspelled without any words loaded.
+        assert!(!spell.is_misspelled("wrold"));
+
+        spell.add_words(["hello", "Paris"]);
+
+        assert!(!spell.is_misspelled("hello"));
+        assert!(!spell.is_misspelled("Hello"));
+        assert!(!spell.is_misspelled("HELLO"));
+        assert!(spell.is_misspelled("hELLO"));
+        assert!(spell.is_misspelled("wrold"));
+
+        // Capitals in the dictionary are required.
+        assert!(!spell.is_misspelled("Paris"));
+        assert!(!spell.is_misspelled("PARIS"));
+        assert!(spell.is_misspelled("paris"));
+
+        assert_eq!(spell.misspellings("Hello wrold, hello Paris"), vec![6..11]);
+    }
+
+    #[test]
+    fn test_mark_session() {
+        let mut spell = mkstore();
+
+        spell.mark_good("wrold", true).unwrap();
+        assert!(!spell.is_misspelled("wrold"));
+        assert!(!spell.is_misspelled("Wrold"));
+
+        spell.mark_bad("hello", true).unwrap();
+        assert!(spell.is_misspelled("hello"));
+        assert!(spell.is_misspelled("Hello"));
+
+        spell.unmark("hello", true).unwrap();
+        spell.unmark("wrold", true).unwrap();
+        assert!(!spell.is_misspelled("hello"));
+        assert!(spell.is_misspelled("wrold"));
+    }
+
+    #[test]
+    fn test_user_dictionary() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.child("user.add");
+
+        let mut spell = mkstore();
+        spell.set_user_dictionary(&path).unwrap();
+        spell.mark_good("wrold", false).unwrap();
+        spell.mark_good("and/or", false).unwrap();
+        spell.mark_bad("held", false).unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(text, "and/or/\nheld/!\nwrold\n");
+
+        // The words get loaded back in by another store.
+        let mut spell = mkstore();
+        spell.set_user_dictionary(&path).unwrap();
+        assert!(!spell.is_misspelled("wrold"));
+        assert!(!spell.is_misspelled("and/or"));
+        assert!(spell.is_misspelled("held"));
+
+        // Words can be removed.
+        spell.unmark("held", false).unwrap();
+        spell.unmark("wrold", false).unwrap();
+        assert!(!spell.is_misspelled("held"));
+        assert!(spell.is_misspelled("wrold"));
+
+        let text = fs::read_to_string(&path).unwrap();
+        assert_eq!(text, "and/or/\n");
+    }
+
+    #[test]
+    fn test_load_word_list() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.child("words.txt");
+        fs::write(&path, "# Comment\nfoo\n\nbar\nbaz/!\n").unwrap();
+
+        let mut spell = SpellStore::default();
+        spell.load_word_list(&path).unwrap();
+
+        assert!(!spell.is_misspelled("foo"));
+        assert!(!spell.is_misspelled("bar"));
+        assert!(spell.is_misspelled("baz"));
+        assert!(spell.is_misspelled("Comment"));
+    }
+
+    #[test]
+    fn test_load_hunspell() {
+        let dir = TempDir::new().unwrap();
+        let dic = dir.child("en.dic");
+        let aff = dir.child("en.aff");
+
+        fs::write(
+            &aff,
+            concat!(
+                "SET UTF-8\n",
+                "PFX A Y 1\n",
+                "PFX A 0 re .\n",
+                "SFX D Y 3\n",
+                "SFX D 0 d e\n",
+                "SFX D y ied [^aeiou]y\n",
+                "SFX D 0 ed [^ey]\n",
+                "SFX S N 1\n",
+                "SFX S 0 s .\n",
+            ),
+        )
+        .unwrap();
+        fs::write(&dic, "5\ncreate/AD\ncarry/DS\nwalk/D\ncat/S\nand/or/\n").unwrap();
+
+        let mut spell = SpellStore::default();
+        spell.load_hunspell(&dic, &aff).unwrap();
+
+        for word in [
+            "create",
+            "created",
+            "recreate",
+            "recreated",
+            "carry",
+            "carried",
+            "carrys",
+            "walk",
+            "walked",
+            "cat",
+            "cats",
+            "and/or",
+        ] {
+            assert!(!spell.is_misspelled(word), "{} should be correct", word);
+        }
+
+        for word in ["creates", "carryed", "walkd", "recat", "recarried"] {
+            assert!(spell.is_misspelled(word), "{} should be misspelled", word);
+        }
+    }
+
+    #[test]
+    fn test_load_hunspell_long_flags() {
+        let dir = TempDir::new().unwrap();
+        let dic = dir.child("en.dic");
+        let aff = dir.child("en.aff");
+
+        fs::write(&aff, "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\nSFX Bb Y 1\nSFX Bb 0 ed .\n")
+            .unwrap();
+        fs::write(&dic, "1\nwalk/AaBb\n").unwrap();
+
+        let mut spell = SpellStore::default();
+        spell.load_hunspell(&dic, &aff).unwrap();
+
+        assert!(!spell.is_misspelled("walk"));
+        assert!(!spell.is_misspelled("walks"));
+        assert!(!spell.is_misspelled("walked"));
+        assert!(spell.is_misspelled("walkeds"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let spell = mkstore();
+
+        assert_eq!(spell.suggest("wrold", 5), vec!["world".to_string()]);
+        assert_eq!(spell.suggest("hel", 5), vec![
+            "held".to_string(),
+            "help".to_string(),
+            "hello".to_string()
+        ]);
+        assert_eq!(spell.suggest("hel", 1), vec!["held".to_string()]);
+
+        // Suggestions match the capitalization of the word.
+        assert_eq!(spell.suggest("Wrold", 5), vec!["World".to_string()]);
+        assert_eq!(spell.suggest("WROLD", 5), vec!["WORLD".to_string()]);
+
+        // Words marked bad aren't suggested.
+        let mut spell = mkstore();
+        spell.mark_bad("held", true).unwrap();
+        assert_eq!(spell.suggest("hel", 5), vec!["help".to_string(), "hello".to_string()]);
+
+        // Nothing close enough.
+        assert_eq!(spell.suggest("xyzzy", 5), Vec::<String>::new());
+    }
+}
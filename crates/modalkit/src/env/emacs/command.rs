@@ -0,0 +1,765 @@
+//! # Emacs Commands
+//!
+//! ## Overview
+//!
+//! These components allow parsing the commands typed after `M-x` and turning them into
+//! [actions][Action].
+//!
+//! ## Divergences
+//!
+//! The commands here diverge from their Emacs counterparts in the following ways:
+//!
+//! - Instead of prompting, commands take their arguments from the same line as their name,
+//!   separated by whitespace (e.g., `M-x goto-line 42`)
+//! - `replace-string` and `replace-regexp` ask about each match like `M-%` and `C-M-%`, and
+//!   reuse the last pattern and replacement when no arguments are given
+//! - `sort-lines` sorts the whole buffer when there's no region
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::actions::{Action, EditAction, EditorAction, ReplaceAction, WindowAction};
+use crate::commands::{Command, CommandError, CommandMachine, CommandStep, ParsedCommand};
+use crate::prelude::*;
+
+use crate::editing::{
+    application::{ApplicationInfo, EmptyInfo},
+    completion::complete_path,
+    context::EditContext,
+    cursor::Cursor,
+    rope::EditRope,
+};
+
+/// Result type for a processed command.
+pub type CommandResult<I> = Result<CommandStep<EmacsCommand<I>>, CommandError>;
+
+/// Handler for a mapped command.
+pub type CommandFunc<I> = fn(CommandDescription, &mut EditContext) -> CommandResult<I>;
+
+/// A parsed command line, containing the command's name and its arguments.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CommandDescription {
+    /// The name of the command to run.
+    pub command: String,
+
+    /// The whitespace-separated arguments that followed the command name.
+    pub args: Vec<String>,
+}
+
+impl FromStr for CommandDescription {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace().map(String::from);
+        let command = words.next().unwrap_or_default();
+        let args = words.collect();
+
+        Ok(CommandDescription { command, args })
+    }
+}
+
+impl ParsedCommand for CommandDescription {
+    fn name(&self) -> String {
+        self.command.clone()
+    }
+}
+
+/// Description of a mapped Emacs command.
+pub struct EmacsCommand<I: ApplicationInfo = EmptyInfo> {
+    /// Primary name of this command.
+    pub name: String,
+
+    /// Additional names for this command.
+    pub aliases: Vec<String>,
+
+    /// Function that handles command.
+    pub f: CommandFunc<I>,
+}
+
+impl<I> Clone for EmacsCommand<I>
+where
+    I: ApplicationInfo,
+{
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            aliases: self.aliases.clone(),
+            f: self.f,
+        }
+    }
+}
+
+impl<I> fmt::Debug for EmacsCommand<I>
+where
+    I: ApplicationInfo,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmacsCommand")
+            .field("name", &self.name)
+            .field("aliases", &self.aliases)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I> Command for EmacsCommand<I>
+where
+    I: ApplicationInfo,
+{
+    type Parsed = CommandDescription;
+    type Action = Action<I>;
+    type Context = EditContext;
+    type CommandContext = EditContext;
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn aliases(&self) -> Vec<String> {
+        self.aliases.clone()
+    }
+
+    fn exec(&self, cmd: Self::Parsed, ctx: &mut Self::CommandContext) -> CommandResult<I> {
+        (self.f)(cmd, ctx)
+    }
+}
+
+fn optional_arg(desc: CommandDescription) -> Result<Option<String>, CommandError> {
+    let mut args = desc.args;
+
+    if args.len() > 1 {
+        return Err(CommandError::InvalidArgument);
+    }
+
+    Ok(args.pop())
+}
+
+fn required_arg(desc: CommandDescription) -> Result<String, CommandError> {
+    optional_arg(desc)?.ok_or(CommandError::InvalidArgument)
+}
+
+fn no_args(desc: &CommandDescription) -> Result<(), CommandError> {
+    if desc.args.is_empty() {
+        Ok(())
+    } else {
+        Err(CommandError::InvalidArgument)
+    }
+}
+
+fn replace<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+    regex: bool,
+) -> CommandResult<I> {
+    let action = match desc.args.as_slice() {
+        [] => ReplaceAction::Start(regex),
+        [from, to] => ReplaceAction::StartWith(regex, from.clone(), to.clone()),
+        _ => return Err(CommandError::InvalidArgument),
+    };
+    let action = EditorAction::Replace(action);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+fn change_case<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+    case: Case,
+) -> CommandResult<I> {
+    no_args(&desc)?;
+
+    let action = EditorAction::Edit(EditAction::ChangeCase(case).into(), EditTarget::Selection);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+fn region_lines<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+    action: EditAction,
+) -> CommandResult<I> {
+    no_args(&desc)?;
+
+    let target = if ctx.get_target_shape().is_some() {
+        EditTarget::Selection
+    } else {
+        EditTarget::Range(RangeType::Buffer, true, Count::Contextual)
+    };
+    let action = EditorAction::Edit(action.into(), target);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+/// The `save-buffer` command.
+///
+/// Write the contents of the current window.
+pub fn emacs_cmd_save_buffer<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    no_args(&desc)?;
+
+    let target = WindowTarget::Single(FocusChange::Current);
+    let action = WindowAction::Write(target, None, WriteFlags::NONE);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+/// The `write-file` command.
+///
+/// Write the contents of the current window to the given file.
+pub fn emacs_cmd_write_file<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    let filename = required_arg(desc)?;
+    let target = WindowTarget::Single(FocusChange::Current);
+    let action = WindowAction::Write(target, Some(filename), WriteFlags::NONE);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+/// The `save-some-buffers` command.
+///
+/// Write the contents of all windows in the current tab.
+pub fn emacs_cmd_save_some_buffers<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    no_args(&desc)?;
+
+    let action = WindowAction::Write(WindowTarget::All, None, WriteFlags::NONE);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+/// The `kill-buffer` command.
+///
+/// Close the current window.
+pub fn emacs_cmd_kill_buffer<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    no_args(&desc)?;
+
+    let target = WindowTarget::Single(FocusChange::Current);
+    let action = WindowAction::Close(target, CloseFlags::NONE);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+/// The `switch-to-buffer` command.
+///
+/// Switch the current window to the named buffer, or to the alternate buffer if no name is
+/// given.
+pub fn emacs_cmd_switch_to_buffer<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    let target = optional_arg(desc)?.map(OpenTarget::Name).unwrap_or(OpenTarget::Alternate);
+    let action = WindowAction::Switch(target);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+/// The `find-file` command.
+///
+/// Switch the current window to the given file.
+pub fn emacs_cmd_find_file<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    let target = OpenTarget::Name(required_arg(desc)?);
+    let action = WindowAction::Switch(target);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+/// The `goto-line` command.
+///
+/// Move the cursor to the given line, or to the line given by the prefix argument.
+pub fn emacs_cmd_goto_line<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    let count = match optional_arg(desc)? {
+        Some(line) => {
+            let line = line.parse::<usize>().map_err(|_| CommandError::InvalidArgument)?;
+
+            Count::Exact(line)
+        },
+        None => Count::Contextual,
+    };
+    let target = EditTarget::Motion(MoveType::BufferLineOffset, count);
+    let action = EditorAction::Edit(EditAction::Motion.into(), target);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+/// The `replace-string` command.
+///
+/// Replace occurrences of the first argument with the second.
+pub fn emacs_cmd_replace_string<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    replace(desc, ctx, false)
+}
+
+/// The `replace-regexp` command.
+///
+/// Replace matches of the regular expression in the first argument with the second.
+pub fn emacs_cmd_replace_regexp<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    replace(desc, ctx, true)
+}
+
+/// The `upcase-region` command.
+///
+/// Convert the region to upper case.
+pub fn emacs_cmd_upcase_region<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    change_case(desc, ctx, Case::Upper)
+}
+
+/// The `downcase-region` command.
+///
+/// Convert the region to lower case.
+pub fn emacs_cmd_downcase_region<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    change_case(desc, ctx, Case::Lower)
+}
+
+/// The `capitalize-region` command.
+///
+/// Capitalize each word in the region.
+pub fn emacs_cmd_capitalize_region<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    change_case(desc, ctx, Case::Title)
+}
+
+/// The `delete-trailing-whitespace` command.
+///
+/// Delete whitespace at the end of each line in the region, or in the whole buffer.
+pub fn emacs_cmd_delete_trailing_whitespace<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    region_lines(desc, ctx, EditAction::TrimTrailingWhitespace)
+}
+
+/// The `sort-lines` command.
+///
+/// Sort the lines in the region, or in the whole buffer.
+pub fn emacs_cmd_sort_lines<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    region_lines(desc, ctx, EditAction::SortLines)
+}
+
+/// The `save-buffers-kill-terminal` command.
+///
+/// *Aliases:* `kill-emacs`
+///
+/// Quit all windows.
+pub fn emacs_cmd_save_buffers_kill_terminal<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut EditContext,
+) -> CommandResult<I> {
+    no_args(&desc)?;
+
+    let action = WindowAction::Close(WindowTarget::All, CloseFlags::QUIT);
+
+    Ok(CommandStep::Continue(action.into(), ctx.clone()))
+}
+
+fn default_cmds<I: ApplicationInfo>() -> Vec<EmacsCommand<I>> {
+    vec![
+        EmacsCommand {
+            name: "save-buffer".into(),
+            aliases: strs![],
+            f: emacs_cmd_save_buffer,
+        },
+        EmacsCommand {
+            name: "write-file".into(),
+            aliases: strs![],
+            f: emacs_cmd_write_file,
+        },
+        EmacsCommand {
+            name: "save-some-buffers".into(),
+            aliases: strs![],
+            f: emacs_cmd_save_some_buffers,
+        },
+        EmacsCommand {
+            name: "kill-buffer".into(),
+            aliases: strs![],
+            f: emacs_cmd_kill_buffer,
+        },
+        EmacsCommand {
+            name: "switch-to-buffer".into(),
+            aliases: strs![],
+            f: emacs_cmd_switch_to_buffer,
+        },
+        EmacsCommand {
+            name: "find-file".into(),
+            aliases: strs![],
+            f: emacs_cmd_find_file,
+        },
+        EmacsCommand {
+            name: "goto-line".into(),
+            aliases: strs![],
+            f: emacs_cmd_goto_line,
+        },
+        EmacsCommand {
+            name: "replace-string".into(),
+            aliases: strs![],
+            f: emacs_cmd_replace_string,
+        },
+        EmacsCommand {
+            name: "replace-regexp".into(),
+            aliases: strs![],
+            f: emacs_cmd_replace_regexp,
+        },
+        EmacsCommand {
+            name: "upcase-region".into(),
+            aliases: strs![],
+            f: emacs_cmd_upcase_region,
+        },
+        EmacsCommand {
+            name: "downcase-region".into(),
+            aliases: strs![],
+            f: emacs_cmd_downcase_region,
+        },
+        EmacsCommand {
+            name: "capitalize-region".into(),
+            aliases: strs![],
+            f: emacs_cmd_capitalize_region,
+        },
+        EmacsCommand {
+            name: "delete-trailing-whitespace".into(),
+            aliases: strs![],
+            f: emacs_cmd_delete_trailing_whitespace,
+        },
+        EmacsCommand {
+            name: "sort-lines".into(),
+            aliases: strs![],
+            f: emacs_cmd_sort_lines,
+        },
+        EmacsCommand {
+            name: "save-buffers-kill-terminal".into(),
+            aliases: strs!["kill-emacs"],
+            f: emacs_cmd_save_buffers_kill_terminal,
+        },
+    ]
+}
+
+/// Manages parsing and mapping Emacs commands.
+pub type EmacsCommandMachine<I = EmptyInfo> = CommandMachine<EmacsCommand<I>>;
+
+impl<I> Default for EmacsCommandMachine<I>
+where
+    I: ApplicationInfo,
+{
+    fn default() -> Self {
+        let mut m = Self::new();
+
+        for cmd in default_cmds().into_iter() {
+            m.add_command(cmd);
+        }
+
+        return m;
+    }
+}
+
+/// Check whether each `-`-separated component of `input` is a prefix of the corresponding
+/// component in `name` (e.g., `s-t-b` matches `switch-to-buffer`).
+fn partial_match(input: &str, name: &str) -> bool {
+    let mut names = name.split('-');
+
+    input
+        .split('-')
+        .all(|component| names.next().is_some_and(|n| n.starts_with(component)))
+}
+
+/// Check whether the characters of `input` appear in order within `name`.
+fn flex_match(input: &str, name: &str) -> bool {
+    let mut chars = name.chars();
+
+    input.chars().all(|c| chars.any(|n| n == c))
+}
+
+/// Complete command names using Emacs-style fuzzy matching.
+///
+/// Names that start with `input` come first, followed by names whose `-`-separated components
+/// each start with the corresponding component of `input`, and then names that contain the
+/// characters of `input` in order.
+pub fn complete_name<I>(input: &str, cmds: &EmacsCommandMachine<I>) -> Vec<String>
+where
+    I: ApplicationInfo,
+{
+    let mut names = cmds.complete_name("");
+    names.extend(cmds.complete_aliases(""));
+    names.sort();
+    names.dedup();
+
+    let mut prefix = vec![];
+    let mut partial = vec![];
+    let mut flex = vec![];
+
+    for name in names.into_iter() {
+        if name.starts_with(input) {
+            prefix.push(name);
+        } else if partial_match(input, &name) {
+            partial.push(name);
+        } else if flex_match(input, &name) {
+            flex.push(name);
+        }
+    }
+
+    prefix.append(&mut partial);
+    prefix.append(&mut flex);
+
+    return prefix;
+}
+
+/// Complete text in the command-bar.
+pub fn complete_cmdbar<I>(
+    input: &EditRope,
+    cursor: &mut Cursor,
+    cmds: &EmacsCommandMachine<I>,
+) -> Vec<String>
+where
+    I: ApplicationInfo,
+{
+    let eo = input.cursor_to_offset(cursor);
+    let slice = input.slice(0.into()..eo);
+    let cow = Cow::from(&slice);
+    let text = cow.as_ref().trim_start();
+
+    match text.split_once(char::is_whitespace) {
+        None => {
+            // Complete command name and set cursor position.
+            let _ = input.get_prefix_word_mut(cursor, &WordStyle::Big);
+            complete_name(text, cmds)
+        },
+        Some(("find-file" | "write-file", _)) => {
+            // Complete filename argument.
+            complete_path(input, cursor)
+        },
+        Some(_) => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editing::context::EditContextBuilder;
+
+    fn mkcmd() -> (EmacsCommandMachine<EmptyInfo>, EditContext) {
+        let cmds = EmacsCommandMachine::default();
+        let ctx = EditContext::default();
+
+        return (cmds, ctx);
+    }
+
+    #[test]
+    fn test_empty() {
+        let (mut cmds, ctx) = mkcmd();
+
+        let res = cmds.input_cmd("", ctx.clone());
+        assert_eq!(res.unwrap().len(), 0);
+
+        let res = cmds.input_cmd("      ", ctx.clone());
+        assert_eq!(res.unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_unmapped() {
+        let (mut cmds, ctx) = mkcmd();
+
+        let res = cmds.input_cmd("unmapped-command", ctx);
+        assert!(matches!(res, Err(CommandError::InvalidCommand(c)) if c == "unmapped-command"));
+    }
+
+    #[test]
+    fn test_buffers() {
+        let (mut cmds, ctx) = mkcmd();
+
+        let target = WindowTarget::Single(FocusChange::Current);
+        let act: Action = WindowAction::Write(target.clone(), None, WriteFlags::NONE).into();
+        let res = cmds.input_cmd("save-buffer", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let res = cmds.input_cmd("save-buffer foo", ctx.clone());
+        assert!(matches!(res, Err(CommandError::InvalidArgument)));
+
+        let file = Some("foo.txt".to_string());
+        let act: Action = WindowAction::Write(target.clone(), file, WriteFlags::NONE).into();
+        let res = cmds.input_cmd("write-file foo.txt", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let act: Action = WindowAction::Close(target, CloseFlags::NONE).into();
+        let res = cmds.input_cmd("kill-buffer", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let act: Action = WindowAction::Switch(OpenTarget::Name("notes".into())).into();
+        let res = cmds.input_cmd("switch-to-buffer notes", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let act: Action = WindowAction::Switch(OpenTarget::Alternate).into();
+        let res = cmds.input_cmd("switch-to-buffer", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let act: Action = WindowAction::Switch(OpenTarget::Name("/tmp/a.rs".into())).into();
+        let res = cmds.input_cmd("  find-file   /tmp/a.rs ", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let res = cmds.input_cmd("find-file", ctx.clone());
+        assert!(matches!(res, Err(CommandError::InvalidArgument)));
+
+        // kill-emacs is an alias for save-buffers-kill-terminal.
+        let act: Action = WindowAction::Close(WindowTarget::All, CloseFlags::QUIT).into();
+        let res = cmds.input_cmd("save-buffers-kill-terminal", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act.clone(), ctx.clone())]);
+
+        let res = cmds.input_cmd("kill-emacs", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+    }
+
+    #[test]
+    fn test_editing() {
+        let (mut cmds, ctx) = mkcmd();
+
+        let target = EditTarget::Motion(MoveType::BufferLineOffset, Count::Exact(42));
+        let act: Action = EditorAction::Edit(EditAction::Motion.into(), target).into();
+        let res = cmds.input_cmd("goto-line 42", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let target = EditTarget::Motion(MoveType::BufferLineOffset, Count::Contextual);
+        let act: Action = EditorAction::Edit(EditAction::Motion.into(), target).into();
+        let res = cmds.input_cmd("goto-line", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let res = cmds.input_cmd("goto-line forty-two", ctx.clone());
+        assert!(matches!(res, Err(CommandError::InvalidArgument)));
+
+        let op = EditAction::ChangeCase(Case::Upper).into();
+        let act: Action = EditorAction::Edit(op, EditTarget::Selection).into();
+        let res = cmds.input_cmd("upcase-region", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let op = EditAction::ChangeCase(Case::Title).into();
+        let act: Action = EditorAction::Edit(op, EditTarget::Selection).into();
+        let res = cmds.input_cmd("capitalize-region", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        // Lines in the region get sorted and trimmed, or in the whole buffer without one.
+        let target = EditTarget::Range(RangeType::Buffer, true, Count::Contextual);
+        let act: Action = EditorAction::Edit(EditAction::SortLines.into(), target.clone()).into();
+        let res = cmds.input_cmd("sort-lines", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let op = EditAction::TrimTrailingWhitespace.into();
+        let act: Action = EditorAction::Edit(op, target).into();
+        let res = cmds.input_cmd("delete-trailing-whitespace", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let region = EditContextBuilder::default().target_shape(Some(TargetShape::CharWise)).build();
+
+        let act: Action =
+            EditorAction::Edit(EditAction::SortLines.into(), EditTarget::Selection).into();
+        let res = cmds.input_cmd("sort-lines", region.clone());
+        assert_eq!(res.unwrap(), vec![(act, region.clone())]);
+
+        let op = EditAction::TrimTrailingWhitespace.into();
+        let act: Action = EditorAction::Edit(op, EditTarget::Selection).into();
+        let res = cmds.input_cmd("delete-trailing-whitespace", region.clone());
+        assert_eq!(res.unwrap(), vec![(act, region.clone())]);
+
+        let res = cmds.input_cmd("sort-lines foo", ctx.clone());
+        assert!(matches!(res, Err(CommandError::InvalidArgument)));
+    }
+
+    #[test]
+    fn test_replace() {
+        let (mut cmds, ctx) = mkcmd();
+
+        // Without arguments, the last pattern and replacement get used.
+        let act: Action = EditorAction::Replace(ReplaceAction::Start(false)).into();
+        let res = cmds.input_cmd("replace-string", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let act = ReplaceAction::StartWith(false, "a<b".into(), "c".into());
+        let act: Action = EditorAction::Replace(act).into();
+        let res = cmds.input_cmd("replace-string a<b c", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let act = ReplaceAction::StartWith(true, "a+".into(), "b".into());
+        let act: Action = EditorAction::Replace(act).into();
+        let res = cmds.input_cmd("replace-regexp a+ b", ctx.clone());
+        assert_eq!(res.unwrap(), vec![(act, ctx.clone())]);
+
+        let res = cmds.input_cmd("replace-string a", ctx.clone());
+        assert!(matches!(res, Err(CommandError::InvalidArgument)));
+    }
+
+    #[test]
+    fn test_complete_name() {
+        let (cmds, _) = mkcmd();
+
+        // Prefix matches come before partial and flex matches.
+        let res = complete_name("s", &cmds);
+        assert_eq!(res[..4], strs![
+            "save-buffer",
+            "save-buffers-kill-terminal",
+            "save-some-buffers",
+            "sort-lines"
+        ]);
+
+        // Partial completion matches each component.
+        let res = complete_name("s-t-b", &cmds);
+        assert_eq!(res, strs!["switch-to-buffer"]);
+
+        let res = complete_name("del-tr", &cmds);
+        assert_eq!(res, strs!["delete-trailing-whitespace"]);
+
+        // Flex matches characters in order.
+        let res = complete_name("upreg", &cmds);
+        assert_eq!(res, strs!["upcase-region"]);
+
+        let res = complete_name("gl", &cmds);
+        assert_eq!(res, strs!["goto-line"]);
+
+        // Aliases can be completed too.
+        let res = complete_name("kill-", &cmds);
+        assert_eq!(res, strs!["kill-buffer", "kill-emacs", "save-buffers-kill-terminal"]);
+
+        let res = complete_name("zzz", &cmds);
+        assert_eq!(res.len(), 0);
+    }
+
+    #[test]
+    fn test_complete_cmdbar() {
+        let (cmds, _) = mkcmd();
+
+        let input = EditRope::from("fi-f");
+        let mut cursor = Cursor::new(0, 4);
+        let res = complete_cmdbar(&input, &mut cursor, &cmds);
+        assert_eq!(res, strs!["find-file"]);
+        assert_eq!(cursor, Cursor::new(0, 0));
+
+        let input = EditRope::from("goto-line 4");
+        let mut cursor = Cursor::new(0, 11);
+        let res = complete_cmdbar(&input, &mut cursor, &cmds);
+        assert_eq!(res.len(), 0);
+        assert_eq!(cursor, Cursor::new(0, 11));
+    }
+}
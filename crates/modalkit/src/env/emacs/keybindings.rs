@@ -10,6 +10,20 @@
 //! The keybindings here diverge from the defaults in Emacs in the following ways:
 //!
 //! - `C-_` and `C-x u` behave like `M-x undo-only`
+//! - Negative arguments (`M--` and `C-u -`) are only accepted by the transpose and keyboard
+//!   macro counter commands, and other commands show a message instead of running
+//! - Rectangle commands don't pad lines that end before the rectangle's left column
+//! - `C-M-%` refers to capture groups in the replacement using `$1` instead of `\1`
+//! - `C-x C-k C-c` and `C-x C-k C-a` take their value from the prefix argument instead of
+//!   prompting for it
+//! - `C-w` during isearch inserts the whole word under the cursor instead of the text up to the
+//!   end of the next word, and `C-g` always returns to where the search started
+//! - Typing during isearch looks for the first match from where the search started, even after
+//!   `C-s` or `C-r` has moved to a later match
+//! - `M-q` ignores the prefix argument instead of justifying, and joins sentences with a single
+//!   space
+//! - Balanced expression commands like `C-M-f` treat commas as whitespace in every buffer, and
+//!   repeating `C-M-SPC` marks the next expression instead of extending the region
 //!
 use bitflags::bitflags;
 
@@ -18,11 +32,14 @@ use crate::{
         Action,
         CommandAction,
         CommandBarAction,
+        CursorAction,
         EditAction,
         EditorAction,
         HistoryAction,
         InsertTextAction,
+        MacroAction,
         PromptAction,
+        ReplaceAction,
         SelectionAction,
         WindowAction,
     },
@@ -31,11 +48,15 @@ use crate::{
 };
 
 use super::{
-    super::{keyparse::parse, CommonKeyClass, ShellBindings},
+    super::{keyparse::parse, CommonEdgePath, CommonKeyClass, ShellBindings},
     EmacsMode,
     EmacsState,
 };
 
+#[cfg(feature = "serde")]
+use super::super::keymap::{Keymap, KeymapError};
+use super::super::keyparse::Leaders;
+
 use crate::key::TerminalKey;
 use crate::keybindings::{InputBindings, ModalMachine, Step};
 
@@ -79,9 +100,12 @@ impl MappedModes {
 
 #[derive(Clone, Debug)]
 enum InternalAction {
+    ChainPrompts(usize),
     ClearTargetShape(bool),
     SaveCounting(Option<usize>),
+    SaveNegativeCounting,
     SetInsertStyle(InsertStyle),
+    SetRectangle,
     SetRegister(Register),
     SetSearchRegexParams(MoveDir1D, bool),
     SetTargetShape(TargetShape, bool),
@@ -90,6 +114,9 @@ enum InternalAction {
 impl InternalAction {
     pub fn run<I: ApplicationInfo>(&self, ctx: &mut EmacsState<I>) {
         match self {
+            InternalAction::ChainPrompts(n) => {
+                ctx.persist.prompts = *n;
+            },
             InternalAction::ClearTargetShape(shiftreq) => {
                 if *shiftreq {
                     if ctx.persist.shift {
@@ -115,6 +142,17 @@ impl InternalAction {
 
                 ctx.action.counting = None;
             },
+            InternalAction::SaveNegativeCounting => {
+                // A negative argument without any digits means -1.
+                let counting = ctx.action.counting.take().unwrap_or(1);
+
+                ctx.action.count = Some(match ctx.action.count {
+                    None => counting,
+                    Some(prev) => prev.saturating_mul(counting),
+                });
+
+                ctx.action.negative = !ctx.action.negative;
+            },
             InternalAction::SetInsertStyle(style) => {
                 if style == &ctx.persist.insert {
                     ctx.persist.insert = !*style;
@@ -122,6 +160,9 @@ impl InternalAction {
                     ctx.persist.insert = *style;
                 }
             },
+            InternalAction::SetRectangle => {
+                ctx.action.rectangle = true;
+            },
             InternalAction::SetRegister(reg) => {
                 ctx.action.register = Some(reg.clone());
             },
@@ -137,21 +178,111 @@ impl InternalAction {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+enum CounterChange {
+    Insert,
+    Set,
+    Add,
+}
+
 #[derive(Debug)]
 enum ExternalAction<I: ApplicationInfo> {
     Something(Action<I>),
     Repeat(bool),
+    Kill(Action<I>, MoveDir1D),
+    Yank(Action<I>),
+    YankPop(Action<I>),
+    Transpose(TransposeStyle),
+    MacroStart(bool),
+    MacroEnd(bool),
+    MacroCall,
+    MacroCounter(CounterChange),
+}
+
+/// Get the prefix argument as a signed number, if one was given.
+fn signed_count<I: ApplicationInfo>(ctx: &EmacsState<I>) -> Option<isize> {
+    let n = ctx.action.count? as isize;
+
+    if ctx.action.negative {
+        Some(-n)
+    } else {
+        Some(n)
+    }
 }
 
 impl<I: ApplicationInfo> ExternalAction<I> {
+    /// Whether this action does something with a negative prefix argument.
+    fn accepts_negative(&self) -> bool {
+        matches!(
+            self,
+            ExternalAction::Transpose(_) |
+                ExternalAction::MacroStart(_) |
+                ExternalAction::MacroCounter(_)
+        )
+    }
+
     fn resolve(&self, ctx: &mut EmacsState<I>) -> Vec<Action<I>> {
         match self {
             ExternalAction::Something(act) => {
                 ctx.persist.repeating = false;
+                ctx.persist.killing = false;
+                ctx.persist.yanking = false;
+
+                vec![act.clone()]
+            },
+            ExternalAction::Kill(act, dir) => {
+                if ctx.persist.killing {
+                    // Consecutive kills get merged into a single kill ring entry.
+                    ctx.action.register_append = *dir == MoveDir1D::Next;
+                    ctx.action.register_prepend = *dir == MoveDir1D::Previous;
+                }
+
+                ctx.action.register.get_or_insert(Register::KillRing);
+                ctx.persist.repeating = false;
+                ctx.persist.killing = true;
+                ctx.persist.yanking = false;
 
                 vec![act.clone()]
             },
+            ExternalAction::Yank(act) => {
+                ctx.action.register.get_or_insert(Register::KillRing);
+                ctx.persist.repeating = false;
+                ctx.persist.killing = false;
+                ctx.persist.yanking = true;
+
+                vec![act.clone()]
+            },
+            ExternalAction::YankPop(act) => {
+                ctx.persist.repeating = false;
+                ctx.persist.killing = false;
+
+                if !ctx.persist.yanking {
+                    let msg = InfoMessage::from("Previous command was not a yank");
+
+                    return vec![Action::ShowInfoMessage(msg)];
+                }
+
+                vec![act.clone()]
+            },
+            ExternalAction::Transpose(style) => {
+                ctx.persist.repeating = false;
+                ctx.persist.killing = false;
+                ctx.persist.yanking = false;
+
+                // Negative arguments drag the text object backwards.
+                let dir = if ctx.action.negative {
+                    MoveDir1D::Previous
+                } else {
+                    MoveDir1D::Next
+                };
+                let act = EditAction::Transpose(style.clone(), dir);
+
+                vec![EditorAction::Edit(act.into(), EditTarget::CurrentPosition).into()]
+            },
             ExternalAction::Repeat(reqrep) => {
+                ctx.persist.killing = false;
+                ctx.persist.yanking = false;
+
                 if *reqrep && !ctx.persist.repeating {
                     let ch = Char::Single('z').into();
                     let it = InsertTextAction::Type(ch, MoveDir1D::Previous, Count::Contextual);
@@ -163,6 +294,96 @@ impl<I: ApplicationInfo> ExternalAction<I> {
 
                 return vec![Action::Repeat(RepeatType::LastAction)];
             },
+            ExternalAction::MacroStart(insert) => {
+                ctx.persist.repeating = false;
+                ctx.persist.killing = false;
+                ctx.persist.yanking = false;
+
+                if ctx.persist.recording || ctx.persist.executing {
+                    if *insert {
+                        return ExternalAction::MacroCounter(CounterChange::Insert).resolve(ctx);
+                    }
+
+                    let msg = InfoMessage::from("Already defining keyboard macro");
+
+                    return vec![Action::ShowInfoMessage(msg)];
+                }
+
+                ctx.persist.recording = true;
+                ctx.persist.macro_counter = signed_count(ctx).unwrap_or(0);
+                ctx.action.count = None;
+                ctx.action.register = Some(Register::MacroRing);
+
+                vec![MacroAction::ToggleRecording.into()]
+            },
+            ExternalAction::MacroEnd(call) => {
+                ctx.persist.repeating = false;
+                ctx.persist.killing = false;
+                ctx.persist.yanking = false;
+
+                if ctx.persist.recording {
+                    ctx.persist.recording = false;
+
+                    return vec![MacroAction::ToggleRecording.into()];
+                }
+
+                if *call {
+                    ctx.action.register = Some(Register::MacroRing);
+
+                    return vec![MacroAction::Execute(Count::Contextual).into()];
+                }
+
+                let msg = InfoMessage::from("Not defining keyboard macro");
+
+                vec![Action::ShowInfoMessage(msg)]
+            },
+            ExternalAction::MacroCall => {
+                ctx.persist.repeating = false;
+                ctx.persist.killing = false;
+                ctx.persist.yanking = false;
+                ctx.action.register = Some(Register::MacroRing);
+
+                let call = MacroAction::ExecuteRepeatable(Count::Contextual).into();
+
+                if ctx.persist.recording {
+                    // Finish the macro being defined before calling it.
+                    ctx.persist.recording = false;
+
+                    return vec![MacroAction::ToggleRecording.into(), call];
+                }
+
+                vec![call]
+            },
+            ExternalAction::MacroCounter(change) => {
+                ctx.persist.repeating = false;
+                ctx.persist.killing = false;
+                ctx.persist.yanking = false;
+
+                let count = signed_count(ctx);
+                ctx.action.count = None;
+                ctx.action.negative = false;
+
+                match change {
+                    CounterChange::Insert => {
+                        let text = ctx.persist.macro_counter.to_string();
+                        let it = InsertTextAction::Transcribe(text, MoveDir1D::Previous, 1.into());
+
+                        ctx.persist.macro_counter += count.unwrap_or(1);
+
+                        vec![it.into()]
+                    },
+                    CounterChange::Set => {
+                        ctx.persist.macro_counter = count.unwrap_or(0);
+
+                        vec![Action::NoOp]
+                    },
+                    CounterChange::Add => {
+                        ctx.persist.macro_counter += count.unwrap_or(1);
+
+                        vec![Action::NoOp]
+                    },
+                }
+            },
         }
     }
 }
@@ -172,6 +393,14 @@ impl<I: ApplicationInfo> Clone for ExternalAction<I> {
         match self {
             ExternalAction::Something(act) => ExternalAction::Something(act.clone()),
             ExternalAction::Repeat(reqrep) => ExternalAction::Repeat(*reqrep),
+            ExternalAction::Kill(act, dir) => ExternalAction::Kill(act.clone(), *dir),
+            ExternalAction::Yank(act) => ExternalAction::Yank(act.clone()),
+            ExternalAction::YankPop(act) => ExternalAction::YankPop(act.clone()),
+            ExternalAction::Transpose(style) => ExternalAction::Transpose(style.clone()),
+            ExternalAction::MacroStart(insert) => ExternalAction::MacroStart(*insert),
+            ExternalAction::MacroEnd(call) => ExternalAction::MacroEnd(*call),
+            ExternalAction::MacroCall => ExternalAction::MacroCall,
+            ExternalAction::MacroCounter(change) => ExternalAction::MacroCounter(*change),
         }
     }
 }
@@ -188,12 +417,18 @@ pub struct InputStep<I: ApplicationInfo> {
     internal: Vec<InternalAction>,
     external: Vec<ExternalAction<I>>,
     nextm: Option<EmacsMode>,
+    desc: Option<String>,
 }
 
 impl<I: ApplicationInfo> InputStep<I> {
     /// Create a new step that input keys can map to.
     pub fn new() -> Self {
-        InputStep { internal: vec![], external: vec![], nextm: None }
+        InputStep {
+            internal: vec![],
+            external: vec![],
+            nextm: None,
+            desc: None,
+        }
     }
 
     /// Set the [EmacsMode] to switch to after this step.
@@ -207,6 +442,12 @@ impl<I: ApplicationInfo> InputStep<I> {
         self.external = acts.into_iter().map(ExternalAction::Something).collect();
         self
     }
+
+    /// Set a short description of what this step does, to show in pending-key hints.
+    pub fn description(mut self, desc: impl Into<String>) -> Self {
+        self.desc = Some(desc.into());
+        self
+    }
 }
 
 impl<I: ApplicationInfo> Clone for InputStep<I> {
@@ -215,6 +456,7 @@ impl<I: ApplicationInfo> Clone for InputStep<I> {
             internal: self.internal.clone(),
             external: self.external.clone(),
             nextm: self.nextm,
+            desc: self.desc.clone(),
         }
     }
 }
@@ -228,7 +470,7 @@ impl<I: ApplicationInfo> Step<TerminalKey> for InputStep<I> {
 
     fn is_unmapped(&self) -> bool {
         match self {
-            InputStep { internal, external, nextm: None } => {
+            InputStep { internal, external, nextm: None, .. } => {
                 internal.is_empty() && external.is_empty()
             },
             _ => false,
@@ -239,14 +481,40 @@ impl<I: ApplicationInfo> Step<TerminalKey> for InputStep<I> {
         None
     }
 
+    fn describe(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+
     fn step(&self, ctx: &mut EmacsState<I>) -> (Vec<Action<I>>, Option<Self::M>) {
         for iact in self.internal.iter() {
             iact.run(ctx);
         }
 
+        if ctx.action.negative &&
+            !self.external.is_empty() &&
+            !self.external.iter().any(ExternalAction::accepts_negative)
+        {
+            let msg = InfoMessage::from("Negative arguments aren't supported by this command");
+            ctx.action.count = None;
+            ctx.action.negative = false;
+
+            return (vec![Action::ShowInfoMessage(msg)], None);
+        }
+
         let external: Vec<Action<I>> =
             self.external.iter().flat_map(|act| act.resolve(ctx)).collect();
 
+        // Submitting a prompt that another one follows keeps us in the command bar.
+        let submit = external
+            .iter()
+            .any(|act| matches!(act, Action::Prompt(PromptAction::Submit)));
+
+        if submit && ctx.persist.prompts > 0 {
+            ctx.persist.prompts -= 1;
+
+            return (external, None);
+        }
+
         return (external, self.nextm);
     }
 }
@@ -271,13 +539,28 @@ macro_rules! iact {
 
 macro_rules! isv {
     () => {
-        InputStep { internal: vec![], external: vec![], nextm: None }
+        InputStep {
+            internal: vec![],
+            external: vec![],
+            nextm: None,
+            desc: None,
+        }
     };
     ($ints: expr, $exts: expr) => {
-        InputStep { internal: $ints, external: $exts, nextm: None }
+        InputStep {
+            internal: $ints,
+            external: $exts,
+            nextm: None,
+            desc: None,
+        }
     };
     ($ints: expr, $exts: expr, $ns: expr) => {
-        InputStep { internal: $ints, external: $exts, nextm: Some($ns) }
+        InputStep {
+            internal: $ints,
+            external: $exts,
+            nextm: Some($ns),
+            desc: None,
+        }
     };
 }
 
@@ -328,28 +611,122 @@ macro_rules! motion {
 
 macro_rules! yank_target {
     ($target: expr) => {
-        edit_target!(EditAction::Yank, $target)
-    };
-    ($target: expr, $c: expr) => {
-        edit_target!(EditAction::Yank, $target, $c)
+        is!(
+            InternalAction::SetRegister(Register::KillRing),
+            EditorAction::Edit(Specifier::Exact(EditAction::Yank), $target)
+        )
     };
 }
 
 macro_rules! kill_target {
-    ($target: expr) => {
-        edit_target!(EditAction::Delete, $target)
-    };
-    ($target: expr, $c: expr) => {
-        edit_target!(EditAction::Delete, $target, $c)
+    ($target: expr, $dir: expr) => {
+        isv!(vec![], vec![ExternalAction::Kill(
+            EditorAction::Edit(Specifier::Exact(EditAction::Delete), $target).into(),
+            $dir
+        )])
     };
 }
 
 macro_rules! kill {
-    ($mt: expr) => {
-        edit!(EditAction::Delete, $mt)
+    ($mt: expr, $dir: expr) => {
+        kill_target!(EditTarget::Motion($mt, Count::Contextual), $dir)
     };
-    ($mt: expr, $c: expr) => {
-        edit!(EditAction::Delete, $mt, $c)
+    ($mt: expr, $c: expr, $dir: expr) => {
+        kill_target!(EditTarget::Motion($mt, $c), $dir)
+    };
+}
+
+macro_rules! yank {
+    () => {
+        isv!(vec![], vec![ExternalAction::Yank(
+            InsertTextAction::Paste(PasteStyle::Cursor, Count::Contextual).into()
+        )])
+    };
+}
+
+macro_rules! yank_pop {
+    ($dir: expr) => {
+        isv!(vec![], vec![ExternalAction::YankPop(
+            InsertTextAction::Paste(PasteStyle::Cycle($dir), Count::Contextual).into()
+        )])
+    };
+}
+
+macro_rules! rectangle {
+    ($act: expr) => {
+        isv!(
+            vec![
+                InternalAction::SetRectangle,
+                InternalAction::ClearTargetShape(false)
+            ],
+            vec![ExternalAction::Something($act.into())]
+        )
+    };
+    ($act: expr, $reg: expr) => {
+        isv!(
+            vec![
+                InternalAction::SetRectangle,
+                InternalAction::SetRegister($reg),
+                InternalAction::ClearTargetShape(false)
+            ],
+            vec![ExternalAction::Something($act.into())]
+        )
+    };
+    ($act: expr, $reg: expr, $ns: expr) => {
+        isv!(
+            vec![
+                InternalAction::SetRectangle,
+                InternalAction::SetRegister($reg),
+                InternalAction::ClearTargetShape(false)
+            ],
+            vec![ExternalAction::Something($act.into())],
+            $ns
+        )
+    };
+}
+
+macro_rules! rectangle_edit {
+    ($ea: expr) => {
+        rectangle!(EditorAction::Edit(Specifier::Exact($ea), EditTarget::Selection))
+    };
+    ($ea: expr, $reg: expr) => {
+        rectangle!(EditorAction::Edit(Specifier::Exact($ea), EditTarget::Selection), $reg)
+    };
+}
+
+macro_rules! transpose {
+    ($style: expr) => {
+        isv!(vec![InternalAction::ClearTargetShape(false)], vec![ExternalAction::Transpose($style)])
+    };
+}
+
+macro_rules! query_replace {
+    ($regex: expr) => {
+        isv!(
+            vec![InternalAction::ChainPrompts(1)],
+            vec![ExternalAction::Something(Action::CommandBar(
+                CommandBarAction::Focus(
+                    if $regex {
+                        "Query replace regexp: ".into()
+                    } else {
+                        "Query replace: ".into()
+                    },
+                    CommandType::Search,
+                    Box::new(Action::CommandBar(CommandBarAction::Focus(
+                        "Query replace with: ".into(),
+                        CommandType::Prompt,
+                        Box::new(EditorAction::Replace(ReplaceAction::Start($regex)).into())
+                    )))
+                )
+            ))],
+            EmacsMode::Command
+        )
+    };
+}
+
+macro_rules! kmacro {
+    ($ext: expr) => {
+        isv!(vec![], vec![$ext])
     };
 }
 
@@ -377,25 +754,55 @@ macro_rules! cmdbar_focus {
     };
 }
 
-macro_rules! cmdbar_search {
+macro_rules! isearch {
     ($dir: expr) => {
-        is!(
-            InternalAction::SetSearchRegexParams($dir, true),
-            Action::CommandBar(CommandBarAction::Focus(
-                match $dir {
-                    MoveDir1D::Next => "/".into(),
-                    MoveDir1D::Previous => "?".into(),
-                },
-                CommandType::Search,
-                Box::new(
+        isv!(
+            vec![InternalAction::SetSearchRegexParams($dir, true)],
+            vec![
+                // Remember where we started, so that typing can search from there, and C-g
+                // can return there.
+                ExternalAction::Something(
+                    EditorAction::Mark(Specifier::Exact(Mark::SearchStart)).into()
+                ),
+                ExternalAction::Something(Action::CommandBar(CommandBarAction::Focus(
+                    match $dir {
+                        MoveDir1D::Next => "I-search: ".into(),
+                        MoveDir1D::Previous => "I-search backward: ".into(),
+                    },
+                    CommandType::Search,
+                    Box::new(
+                        EditorAction::Edit(
+                            Specifier::Contextual,
+                            EditTarget::Search(
+                                SearchType::Regex,
+                                MoveDirMod::Same,
+                                Count::Contextual
+                            )
+                        )
+                        .into()
+                    )
+                )))
+            ],
+            EmacsMode::Search
+        )
+    };
+}
+
+macro_rules! isearch_abort {
+    () => {
+        isv!(
+            vec![],
+            vec![
+                ExternalAction::Something(PromptAction::Abort(false).into()),
+                ExternalAction::Something(
                     EditorAction::Edit(
-                        Specifier::Contextual,
-                        EditTarget::Search(SearchType::Regex, MoveDirMod::Same, Count::Contextual)
+                        Specifier::Exact(EditAction::Motion),
+                        EditTarget::CharJump(Specifier::Exact(Mark::SearchStart))
                     )
                     .into()
                 )
-            )),
-            EmacsMode::Search
+            ],
+            EmacsMode::Insert
         )
     };
 }
@@ -459,6 +866,42 @@ macro_rules! search_word {
     };
 }
 
+macro_rules! set_mark {
+    () => {
+        isv!(vec![InternalAction::SetTargetShape(TargetShape::CharWise, false)], vec![
+            ExternalAction::Something(CursorAction::PushMark.into()),
+            ExternalAction::Something(
+                SelectionAction::Resize(SelectionResizeStyle::Restart, EditTarget::CurrentPosition)
+                    .into()
+            ),
+        ])
+    };
+}
+
+macro_rules! mark_sexp {
+    () => {
+        isv!(vec![InternalAction::SetTargetShape(TargetShape::CharWise, false)], vec![
+            ExternalAction::Something(CursorAction::PushMark.into()),
+            ExternalAction::Something(
+                SelectionAction::Resize(
+                    SelectionResizeStyle::Restart,
+                    EditTarget::Motion(MoveType::Sexp(MoveDir1D::Next), Count::Contextual)
+                )
+                .into()
+            ),
+            ExternalAction::Something(
+                SelectionAction::CursorSet(SelectionCursorChange::SwapAnchor(false)).into()
+            ),
+        ])
+    };
+}
+
+macro_rules! pop_mark {
+    ($ring: expr) => {
+        act!(CursorAction::PopMark($ring).into())
+    };
+}
+
 macro_rules! start_selection {
     ($shape: expr) => {
         is!(
@@ -493,9 +936,10 @@ macro_rules! start_shift_selection {
 fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputStep<I>)> {
     [
         // Insert, Command and Search mode keybindings.
-        ( MAP, "<C-Y>", paste!(PasteStyle::Cursor) ),
-        ( MAP, "<M-BS>", kill!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
-        ( MAP, "<M-Del>", kill!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
+        ( MAP, "<C-Y>", yank!() ),
+        ( MAP, "<M-y>", yank_pop!(MoveDir1D::Previous) ),
+        ( MAP, "<M-BS>", kill!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous), MoveDir1D::Previous) ),
+        ( MAP, "<M-Del>", kill!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous), MoveDir1D::Previous) ),
         ( MAP, "<BS>", erase!(MoveType::Column(MoveDir1D::Previous, true)) ),
 
         // Insert and Command mode keybindings.
@@ -504,9 +948,9 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( ICMAP, "<C-D>", erase!(MoveType::Column(MoveDir1D::Next, true)) ),
         ( ICMAP, "<C-E>", motion!(MoveType::LinePos(MovePosition::End), Count::MinusOne) ),
         ( ICMAP, "<C-F>", motion!(MoveType::Column(MoveDir1D::Next, true)) ),
-        ( ICMAP, "<C-K>", kill!(MoveType::LinePos(MovePosition::End), Count::MinusOne) ),
-        ( ICMAP, "<C-X>z", isv!(vec![], vec![ExternalAction::Repeat(false)]) ),
-        ( ICMAP, "<C-W>", kill_target!(EditTarget::Selection) ),
+        ( ICMAP, "<C-K>", kill!(MoveType::LinePos(MovePosition::End), Count::MinusOne, MoveDir1D::Next) ),
+        ( ICMAP, "<C-X>z", isv!(vec![], vec![ExternalAction::Repeat(false)]).description("repeat") ),
+        ( ICMAP, "<C-W>", kill_target!(EditTarget::Selection, MoveDir1D::Next) ),
         ( ICMAP, "<C-Left>", motion!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
         ( ICMAP, "<C-Right>", motion!(MoveType::WordBegin(WordStyle::NonAlphaNum, MoveDir1D::Next)) ),
         ( ICMAP, "<C-S-Left>", start_shift_selection!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous).into()) ),
@@ -514,7 +958,7 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( ICMAP, "<M-b>", motion!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
         ( ICMAP, "<M-B>", start_shift_selection!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous).into()) ),
         ( ICMAP, "<M-c>", edit!(EditAction::ChangeCase(Case::Title), MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next)) ),
-        ( ICMAP, "<M-d>", kill!(MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next)) ),
+        ( ICMAP, "<M-d>", kill!(MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next), MoveDir1D::Next) ),
         ( ICMAP, "<M-f>", motion!(MoveType::WordBegin(WordStyle::NonAlphaNum, MoveDir1D::Next)) ),
         ( ICMAP, "<M-F>", start_shift_selection!(MoveType::WordBegin(WordStyle::NonAlphaNum, MoveDir1D::Next).into()) ),
         ( ICMAP, "<M-l>", edit!(EditAction::ChangeCase(Case::Lower), MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next)) ),
@@ -545,54 +989,79 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( IMAP, "<C-P>", motion!(MoveType::Line(MoveDir1D::Previous)) ),
         ( IMAP, "<C-Q>{oct<=3}", chartype!() ),
         ( IMAP, "<C-Q>{any}", chartype!() ),
-        ( IMAP, "<C-R>", cmdbar_search!(MoveDir1D::Previous) ),
-        ( IMAP, "<C-S>", cmdbar_search!(MoveDir1D::Next) ),
-        ( IMAP, "<C-T>", unmapped!() ),
+        ( IMAP, "<C-R>", isearch!(MoveDir1D::Previous) ),
+        ( IMAP, "<C-S>", isearch!(MoveDir1D::Next) ),
+        ( IMAP, "<C-T>", transpose!(TransposeStyle::Char) ),
         ( IMAP, "<C-U><C-X>s", unmapped!() ),
+        ( IMAP, "<C-U><C-@>", pop_mark!(MarkRing::Buffer) ),
+        ( IMAP, "<C-U><C-Space>", pop_mark!(MarkRing::Buffer) ),
         ( IMAP, "<C-V>", scroll2d!(MoveDir2D::Down, ScrollSize::Page) ),
-        ( IMAP, "<C-X><C-C>", window!(WindowAction::Close(WindowTarget::All, CloseFlags::QUIT)) ),
+        ( IMAP, "<C-X><C-K><C-A>", kmacro!(ExternalAction::MacroCounter(CounterChange::Add)).description("kmacro-add-counter") ),
+        ( IMAP, "<C-X><C-K><C-C>", kmacro!(ExternalAction::MacroCounter(CounterChange::Set)).description("kmacro-set-counter") ),
+        ( IMAP, "<C-X><C-K><C-I>", kmacro!(ExternalAction::MacroCounter(CounterChange::Insert)).description("kmacro-insert-counter") ),
+        ( IMAP, "<C-X><C-K><C-K>", kmacro!(ExternalAction::MacroCall).description("kmacro-end-or-call-macro-repeat") ),
+        ( IMAP, "<C-X><C-K><C-N>", act!(MacroAction::Rotate(MoveDir1D::Previous, Count::Contextual).into()).description("kmacro-cycle-ring-next") ),
+        ( IMAP, "<C-X><C-K><C-P>", act!(MacroAction::Rotate(MoveDir1D::Next, Count::Contextual).into()).description("kmacro-cycle-ring-previous") ),
+        ( IMAP, "<C-X><C-C>", window!(WindowAction::Close(WindowTarget::All, CloseFlags::QUIT)).description("save-buffers-kill-terminal") ),
         ( IMAP, "<C-X><C-O>", unmapped!() ),
         ( IMAP, "<C-X><C-Q>", unmapped!() ),
         ( IMAP, "<C-X><C-S>", unmapped!() ),
-        ( IMAP, "<C-X><C-T>", unmapped!() ),
+        ( IMAP, "<C-X><C-T>", transpose!(TransposeStyle::Line).description("transpose-lines") ),
         ( IMAP, "<C-X><C-W>", unmapped!() ),
-        ( IMAP, "<C-X><C-X>", selection!(SelectionAction::CursorSet(SelectionCursorChange::SwapAnchor(false))) ),
-        ( IMAP, "<C-X><C-Z>", act!(Action::Suspend) ),
+        ( IMAP, "<C-X><C-X>", selection!(SelectionAction::CursorSet(SelectionCursorChange::SwapAnchor(false))).description("exchange-point-and-mark") ),
+        ( IMAP, "<C-X><C-Z>", act!(Action::Suspend).description("suspend-frame") ),
+        ( IMAP, "<C-X><C-@>", pop_mark!(MarkRing::Global).description("pop-global-mark") ),
+        ( IMAP, "<C-X><C-Space>", pop_mark!(MarkRing::Global).description("pop-global-mark") ),
         ( IMAP, "<C-X><Space>", unmapped!() ),
-        ( IMAP, "<C-X><Left>", window_switch!(OpenTarget::Offset(MoveDir1D::Previous, Count::Contextual)) ),
-        ( IMAP, "<C-X><Right>", window_switch!(OpenTarget::Offset(MoveDir1D::Next, Count::Contextual)) ),
+        ( IMAP, "<C-X><Left>", window_switch!(OpenTarget::Offset(MoveDir1D::Previous, Count::Contextual)).description("previous-buffer") ),
+        ( IMAP, "<C-X><Right>", window_switch!(OpenTarget::Offset(MoveDir1D::Next, Count::Contextual)).description("next-buffer") ),
+        ( IMAP, "<C-X>(", kmacro!(ExternalAction::MacroStart(false)).description("kmacro-start-macro") ),
+        ( IMAP, "<C-X>)", kmacro!(ExternalAction::MacroEnd(false)).description("kmacro-end-macro") ),
         ( IMAP, "<C-X>b", unmapped!() ),
-        ( IMAP, "<C-X>h", start_selection!(TargetShape::CharWise, RangeType::Buffer.into()) ),
+        ( IMAP, "<C-X>e", kmacro!(ExternalAction::MacroCall).description("kmacro-end-and-call-macro") ),
+        ( IMAP, "<C-X>h", start_selection!(TargetShape::CharWise, RangeType::Buffer.into()).description("mark-whole-buffer") ),
         ( IMAP, "<C-X>k", unmapped!() ),
-        ( IMAP, "<C-X>o", window_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), true)) ),
-        ( IMAP, "<C-X>r<M-w>", unmapped!() ),
+        ( IMAP, "<C-X>o", window_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), true)).description("other-window") ),
+        ( IMAP, "<C-X>r<M-w>", rectangle_edit!(EditAction::Yank, Register::KilledRectangle).description("copy-rectangle-as-kill") ),
         ( IMAP, "<C-X>rb", unmapped!() ),
-        ( IMAP, "<C-X>rc", unmapped!() ),
-        ( IMAP, "<C-X>rd", unmapped!() ),
-        ( IMAP, "<C-X>rk", unmapped!() ),
+        ( IMAP, "<C-X>rc", rectangle_edit!(EditAction::Rectangle(RectangleChange::Clear)).description("clear-rectangle") ),
+        ( IMAP, "<C-X>rd", rectangle_edit!(EditAction::Delete, Register::Blackhole).description("delete-rectangle") ),
+        ( IMAP, "<C-X>rk", rectangle_edit!(EditAction::Delete, Register::KilledRectangle).description("kill-rectangle") ),
         ( IMAP, "<C-X>rl", unmapped!() ),
         ( IMAP, "<C-X>rm", unmapped!() ),
-        ( IMAP, "<C-X>ro", unmapped!() ),
-        ( IMAP, "<C-X>ry", unmapped!() ),
-        ( IMAP, "<C-X>rN", unmapped!() ),
+        ( IMAP, "<C-X>ro", rectangle_edit!(EditAction::Rectangle(RectangleChange::Open)).description("open-rectangle") ),
+        ( IMAP, "<C-X>rt", rectangle!(Action::CommandBar(CommandBarAction::Focus("String rectangle: ".into(), CommandType::Prompt, Box::new(EditorAction::Edit(Specifier::Exact(EditAction::Rectangle(RectangleChange::Fill)), EditTarget::Selection).into()))), Register::LastCommand(CommandType::Prompt), EmacsMode::Command).description("string-rectangle") ),
+        ( IMAP, "<C-X>ry", rectangle!(InsertTextAction::Paste(PasteStyle::Cursor, Count::Contextual), Register::KilledRectangle).description("yank-rectangle") ),
+        ( IMAP, "<C-X>rN", rectangle_edit!(EditAction::Rectangle(RectangleChange::Number)).description("rectangle-number-lines") ),
         ( IMAP, "<C-X>s", unmapped!() ),
-        ( IMAP, "<C-X>u", history!(HistoryAction::Undo(Count::Contextual)) ),
-        ( IMAP, "<C-X>0", window_quit!(WindowTarget::Single, FocusChange::Current) ),
-        ( IMAP, "<C-X>1", window_quit!(WindowTarget::AllBut, FocusChange::Current) ),
-        ( IMAP, "<C-X>2", window_split!(Axis::Horizontal) ),
-        ( IMAP, "<C-X>3", window_split!(Axis::Vertical) ),
-        ( IMAP, "<C-X><", scroll2d!(MoveDir2D::Left, ScrollSize::Page) ),
-        ( IMAP, "<C-X>>", scroll2d!(MoveDir2D::Right, ScrollSize::Page) ),
+        ( IMAP, "<C-X>u", history!(HistoryAction::Undo(Count::Contextual)).description("undo") ),
+        ( IMAP, "<C-X>0", window_quit!(WindowTarget::Single, FocusChange::Current).description("delete-window") ),
+        ( IMAP, "<C-X>1", window_quit!(WindowTarget::AllBut, FocusChange::Current).description("delete-other-windows") ),
+        ( IMAP, "<C-X>2", window_split!(Axis::Horizontal).description("split-window-below") ),
+        ( IMAP, "<C-X>3", window_split!(Axis::Vertical).description("split-window-right") ),
+        ( IMAP, "<C-X><", scroll2d!(MoveDir2D::Left, ScrollSize::Page).description("scroll-left") ),
+        ( IMAP, "<C-X>>", scroll2d!(MoveDir2D::Right, ScrollSize::Page).description("scroll-right") ),
         ( IMAP, "<C-Z>", act!(Action::Suspend) ),
-        ( IMAP, "<C-@>", start_selection!(TargetShape::CharWise) ),
+        ( IMAP, "<C-@>", set_mark!() ),
         ( IMAP, "<C-_>", history!(HistoryAction::Undo(Count::Contextual)) ),
-        ( IMAP, "<C-Del>", kill!(MoveType::LinePos(MovePosition::End), Count::MinusOne) ),
-        ( IMAP, "<C-Space>", start_selection!(TargetShape::CharWise) ),
-        ( IMAP, "<M-s>.", search_word!(WordStyle::Big) ),
-        ( IMAP, "<M-t>", unmapped!() ),
+        ( IMAP, "<C-Del>", kill!(MoveType::LinePos(MovePosition::End), Count::MinusOne, MoveDir1D::Next) ),
+        ( IMAP, "<C-Space>", set_mark!() ),
+        ( IMAP, "<C-M-b>", motion!(MoveType::Sexp(MoveDir1D::Previous)) ),
+        ( IMAP, "<C-M-d>", motion!(MoveType::ListDown(MoveDir1D::Next)) ),
+        ( IMAP, "<C-M-f>", motion!(MoveType::Sexp(MoveDir1D::Next)) ),
+        ( IMAP, "<C-M-k>", kill!(MoveType::Sexp(MoveDir1D::Next), MoveDir1D::Next) ),
+        ( IMAP, "<C-M-t>", transpose!(TransposeStyle::Sexp) ),
+        ( IMAP, "<C-M-u>", motion!(MoveType::ListUp(MoveDir1D::Previous)) ),
+        ( IMAP, "<C-M-@>", mark_sexp!() ),
+        ( IMAP, "<C-M-Space>", mark_sexp!() ),
+        ( IMAP, "<C-M-%>", query_replace!(true) ),
+        ( IMAP, "<M-s>.", search_word!(WordStyle::Big).description("isearch-forward-symbol-at-point") ),
+        ( IMAP, "<M-%>", query_replace!(false) ),
+        ( IMAP, "<M-q>", edit_target!(EditAction::Format, EditTarget::Range(RangeType::Paragraph, false, Count::Exact(1))) ),
+        ( IMAP, "<M-t>", transpose!(TransposeStyle::Word(WordStyle::AlphaNum)) ),
         ( IMAP, "<M-v>", scroll2d!(MoveDir2D::Up, ScrollSize::Page) ),
         ( IMAP, "<M-x>", cmdbar_focus!(CommandType::Command, EmacsMode::Command, CommandAction::Execute(1.into())) ),
-        ( IMAP, "<M-z>{char}", kill_target!(EditTarget::Search(SearchType::Char(true), MoveDirMod::Same, Count::Contextual)) ),
+        ( IMAP, "<M-z>{char}", kill_target!(EditTarget::Search(SearchType::Char(true), MoveDirMod::Same, Count::Contextual), MoveDir1D::Next) ),
         ( IMAP, "<M-S>.", search_word!(WordStyle::Big) ),
         ( IMAP, "<M-\\>", erase_range!(RangeType::Word(WordStyle::Whitespace(false))) ),
         ( IMAP, "<M-^>", edit!(EditAction::Join(JoinStyle::OneSpace), MoveType::Line(MoveDir1D::Previous)) ),
@@ -601,6 +1070,8 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( IMAP, "<M-Space>", just_one_space!() ),
         ( IMAP, "<S-Up>", start_shift_selection!(MoveType::Line(MoveDir1D::Previous).into()) ),
         ( IMAP, "<S-Down>", start_shift_selection!(MoveType::Line(MoveDir1D::Next).into()) ),
+        ( IMAP, "<F3>", kmacro!(ExternalAction::MacroStart(true)) ),
+        ( IMAP, "<F4>", kmacro!(ExternalAction::MacroEnd(true)) ),
         ( IMAP, "<Insert>", iact!(InternalAction::SetInsertStyle(InsertStyle::Replace)) ),
         ( IMAP, "<Up>", motion!(MoveType::Line(MoveDir1D::Previous)) ),
         ( IMAP, "<Down>", motion!(MoveType::Line(MoveDir1D::Next)) ),
@@ -616,9 +1087,11 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( CMAP, "<Tab>", editor!(EditorAction::Complete(CompletionType::Auto, CompletionSelection::Single, CompletionDisplay::Bar)) ),
 
         // Search mode keybindings.
-        ( SMAP, "<C-G>", prompt!(PromptAction::Abort(false), EmacsMode::Insert) ),
+        ( SMAP, "<C-G>", isearch_abort!() ),
         ( SMAP, "<C-R>", search!(MoveDir1D::Previous) ),
         ( SMAP, "<C-S>", search!(MoveDir1D::Next) ),
+        ( SMAP, "<C-W>", prompt!(PromptAction::InsertCursorWord(WordStyle::AlphaNum)) ),
+        ( SMAP, "<Enter>", prompt!(PromptAction::Submit, EmacsMode::Insert) ),
     ].to_vec()
 }
 
@@ -637,6 +1110,8 @@ fn default_pfxs<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, Option<
         ( ICMAP, "<M-8>{count*}", Some(iact!(InternalAction::SaveCounting(8.into()))) ),
         ( ICMAP, "<M-9>{count*}", Some(iact!(InternalAction::SaveCounting(9.into()))) ),
         ( ICMAP, "<M-0>{count*}", Some(iact!(InternalAction::SaveCounting(0.into()))) ),
+        ( ICMAP, "<M-->{count*}", Some(iact!(InternalAction::SaveNegativeCounting)) ),
+        ( ICMAP, "<C-U>-{count*}", Some(iact!(InternalAction::SaveNegativeCounting)) ),
     ].to_vec()
 }
 
@@ -697,6 +1172,8 @@ pub struct EmacsBindings<I: ApplicationInfo> {
     prefixes: Vec<(MappedModes, &'static str, Option<InputStep<I>>)>,
     mappings: Vec<(MappedModes, &'static str, InputStep<I>)>,
     enter: Vec<(MappedModes, &'static str, InputStep<I>)>,
+    keymap: Vec<(MappedModes, CommonEdgePath, InputStep<I>)>,
+    leaders: Leaders,
 }
 
 impl<I: ApplicationInfo> EmacsBindings<I> {
@@ -705,6 +1182,60 @@ impl<I: ApplicationInfo> EmacsBindings<I> {
         self.enter = submit_on_enter();
         self
     }
+
+    /// Add the mappings in a [Keymap] on top of these bindings.
+    ///
+    /// Modes are named `insert`, `command` (for the command bar) and `search` (for incremental
+    /// search).
+    ///
+    /// Any `<Leader>` or `<LocalLeader>` keys are replaced with the ones currently set with
+    /// [leader](Self::leader) and [local_leader](Self::local_leader).
+    #[cfg(feature = "serde")]
+    pub fn keymap(mut self, keymap: &Keymap<I>) -> Result<Self, KeymapError> {
+        for (modes, evs, act) in keymap.resolve(keymap_mode, &self.leaders)? {
+            let modes = modes.into_iter().fold(MappedModes::empty(), |acc, m| acc | m);
+
+            self.keymap.push((modes, evs, act!(act)));
+        }
+
+        Ok(self)
+    }
+
+    /// Change the key that `<Leader>` stands for in keymaps added after this.
+    ///
+    /// This defaults to `\`.
+    pub fn leader(mut self, key: TerminalKey) -> Self {
+        self.leaders.leader = key;
+        self
+    }
+
+    /// Change the key that `<LocalLeader>` stands for in keymaps added after this.
+    ///
+    /// This defaults to `\`.
+    pub fn local_leader(mut self, key: TerminalKey) -> Self {
+        self.leaders.local_leader = key;
+        self
+    }
+
+    /// Get the keys that `<Leader>` and `<LocalLeader>` currently stand for, for parsing other
+    /// mappings with [parse_with_leaders].
+    ///
+    /// [parse_with_leaders]: crate::env::keyparse::parse_with_leaders
+    pub fn get_leaders(&self) -> &Leaders {
+        &self.leaders
+    }
+}
+
+#[cfg(feature = "serde")]
+fn keymap_mode(name: &str) -> Option<MappedModes> {
+    let modes = match name {
+        "insert" => IMAP,
+        "command" => CMAP,
+        "search" => SMAP,
+        _ => return None,
+    };
+
+    Some(modes)
 }
 
 impl<I: ApplicationInfo> ShellBindings for EmacsBindings<I> {
@@ -719,6 +1250,8 @@ impl<I: ApplicationInfo> Default for EmacsBindings<I> {
             prefixes: default_pfxs(),
             mappings: default_keys(),
             enter: default_enter(),
+            keymap: vec![],
+            leaders: Leaders::default(),
         }
     }
 }
@@ -736,6 +1269,12 @@ impl<I: ApplicationInfo> InputBindings<TerminalKey, InputStep<I>> for EmacsBindi
         for (modes, keys, action) in self.enter.iter() {
             add_mapping(machine, modes, keys, action);
         }
+
+        for (modes, evs, action) in self.keymap.iter() {
+            for mode in modes.split() {
+                machine.add_mapping(mode, evs, action);
+            }
+        }
     }
 }
 
@@ -751,8 +1290,11 @@ pub fn default_emacs_keys<I: ApplicationInfo>() -> EmacsMachine<TerminalKey, I>
 mod tests {
     use super::*;
     use crate::editing::context::{EditContext, EditContextBuilder};
-    use crate::keybindings::BindingMachine;
+    use crate::editing::key::KeyManager;
+    use crate::editing::store::Store;
+    use crate::keybindings::{BindingMachine, EdgeEvent};
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::collections::HashMap;
 
     macro_rules! mv {
         ($mt: expr) => {
@@ -783,6 +1325,8 @@ mod tests {
     }
 
     const CMDBAR_ABORT: Action = Action::Prompt(PromptAction::Abort(false));
+    const ISEARCH_MARK: Action =
+        Action::Editor(EditorAction::Mark(Specifier::Exact(Mark::SearchStart)));
 
     fn cmdbar_focus(s: &str, ct: CommandType, act: Action) -> Action {
         Action::CommandBar(CommandBarAction::Focus(s.into(), ct, act.into()))
@@ -839,10 +1383,11 @@ mod tests {
         // Start out in Insert mode.
         assert_eq!(vm.mode(), EmacsMode::Insert);
 
-        // <C-Space> begins selection.
+        // <C-Space> pushes the mark and begins selection.
         let act =
             SelectionAction::Resize(SelectionResizeStyle::Restart, EditTarget::CurrentPosition);
         vm.input_key(ctl!(' '));
+        assert_pop1!(vm, Action::from(CursorAction::PushMark), ctx);
         assert_pop2!(vm, Action::from(act), ctx);
 
         // <Left> continues to select.
@@ -860,10 +1405,11 @@ mod tests {
         // Start out in Insert mode.
         assert_eq!(vm.mode(), EmacsMode::Insert);
 
-        // ^R moves to Search mode.
+        // ^R marks where we started, and moves to Search mode.
         ctx.search_regex_dir = MoveDir1D::Previous;
         vm.input_key(ctl!('r'));
-        assert_pop2!(vm, cmdbar_search("?"), ctx);
+        assert_pop1!(vm, ISEARCH_MARK, ctx);
+        assert_pop2!(vm, cmdbar_search("I-search backward: "), ctx);
         assert_eq!(vm.mode(), EmacsMode::Search);
 
         // Unmapped, typable character types a character.
@@ -893,12 +1439,32 @@ mod tests {
 
         // ^S moves to Search mode.
         vm.input_key(ctl!('s'));
-        assert_pop2!(vm, cmdbar_search("/"), ctx);
+        assert_pop1!(vm, ISEARCH_MARK, ctx);
+        assert_pop2!(vm, cmdbar_search("I-search: "), ctx);
         assert_eq!(vm.mode(), EmacsMode::Search);
 
-        // ^G leaves Search mode.
+        // ^W inserts the word under the cursor.
+        let act = PromptAction::InsertCursorWord(WordStyle::AlphaNum);
+        vm.input_key(ctl!('w'));
+        assert_pop2!(vm, Action::Prompt(act), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Search);
+
+        // ^G leaves Search mode, and returns to where we started.
+        let jump = EditTarget::CharJump(Specifier::Exact(Mark::SearchStart));
+        let jump = EditorAction::Edit(EditAction::Motion.into(), jump);
         vm.input_key(ctl!('g'));
-        assert_pop2!(vm, CMDBAR_ABORT, ctx);
+        assert_pop1!(vm, CMDBAR_ABORT, ctx);
+        assert_pop2!(vm, Action::from(jump), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Insert);
+
+        // <Enter> accepts the search.
+        vm.input_key(ctl!('s'));
+        assert_pop1!(vm, ISEARCH_MARK, ctx);
+        assert_pop2!(vm, cmdbar_search("I-search: "), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Search);
+
+        vm.input_key(key!(KeyCode::Enter));
+        assert_pop2!(vm, Action::Prompt(PromptAction::Submit), ctx);
         assert_eq!(vm.mode(), EmacsMode::Insert);
     }
 
@@ -1027,4 +1593,635 @@ mod tests {
         assert_pop2!(vm, typechar!('z'), ctx);
         assert_eq!(vm.state().persist.repeating, false);
     }
+
+    #[test]
+    fn test_kill_ring() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let ctx = mkctx();
+
+        let kill_line = Action::from(EditorAction::Edit(
+            EditAction::Delete.into(),
+            EditTarget::Motion(MoveType::LinePos(MovePosition::End), Count::MinusOne),
+        ));
+        let kill_word_back = Action::from(EditorAction::Edit(
+            EditAction::Delete.into(),
+            EditTarget::Motion(
+                MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous),
+                Count::Contextual,
+            ),
+        ));
+        let yank = Action::from(EditorAction::InsertText(InsertTextAction::Paste(
+            PasteStyle::Cursor,
+            Count::Contextual,
+        )));
+        let yank_pop = Action::from(EditorAction::InsertText(InsertTextAction::Paste(
+            PasteStyle::Cycle(MoveDir1D::Previous),
+            Count::Contextual,
+        )));
+
+        let mut killctx = ctx.clone();
+        killctx.register = Some(Register::KillRing);
+
+        let mut appendctx = killctx.clone();
+        appendctx.register_append = true;
+
+        let mut prependctx = killctx.clone();
+        prependctx.register_prepend = true;
+
+        // The first kill starts a new kill ring entry.
+        vm.input_key(ctl!('k'));
+        assert_pop2!(vm, kill_line, killctx);
+        assert!(vm.state().persist.killing);
+
+        // Consecutive forward kills append.
+        vm.input_key(ctl!('k'));
+        assert_pop2!(vm, kill_line, appendctx);
+
+        // Consecutive backward kills prepend.
+        vm.input_key(key!(KeyCode::Backspace, KeyModifiers::ALT));
+        assert_pop2!(vm, kill_word_back, prependctx);
+
+        // Typing a character interrupts the sequence.
+        vm.input_key(key!('a'));
+        assert_pop2!(vm, typechar!('a'), ctx);
+        assert!(!vm.state().persist.killing);
+
+        vm.input_key(ctl!('k'));
+        assert_pop2!(vm, kill_line, killctx);
+
+        // M-y after C-y cycles through the kill ring.
+        vm.input_key(ctl!('y'));
+        assert_pop2!(vm, yank, killctx);
+        assert!(!vm.state().persist.killing);
+        assert!(vm.state().persist.yanking);
+
+        vm.input_key(key!('y', KeyModifiers::ALT));
+        assert_pop2!(vm, yank_pop, ctx);
+
+        vm.input_key(key!('y', KeyModifiers::ALT));
+        assert_pop2!(vm, yank_pop, ctx);
+
+        // M-y without a preceding yank doesn't do anything.
+        vm.input_key(key!('b'));
+        assert_pop2!(vm, typechar!('b'), ctx);
+
+        vm.input_key(key!('y', KeyModifiers::ALT));
+        let msg = InfoMessage::from("Previous command was not a yank");
+        assert_pop2!(vm, Action::ShowInfoMessage(msg), ctx);
+    }
+
+    #[test]
+    fn test_rectangles() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let ctx = mkctx();
+
+        let mut rectctx = ctx.clone();
+        rectctx.target_shape = Some(TargetShape::BlockWise);
+        rectctx.insert_style = None;
+
+        let mut killctx = rectctx.clone();
+        killctx.register = Some(Register::KilledRectangle);
+
+        let mut delctx = rectctx.clone();
+        delctx.register = Some(Register::Blackhole);
+
+        let mut strctx = rectctx.clone();
+        strctx.register = Some(Register::LastCommand(CommandType::Prompt));
+
+        let rect = |ea: EditAction| -> Action {
+            EditorAction::Edit(Specifier::Exact(ea), EditTarget::Selection).into()
+        };
+
+        // C-x r k kills the rectangle between point and mark, and deactivates the mark.
+        vm.input_key(key!(' ', KeyModifiers::CONTROL));
+        let _ = vm.pop();
+        let _ = vm.pop();
+        assert_eq!(vm.state().persist.shape, Some(TargetShape::CharWise));
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('k'));
+        assert_pop2!(vm, rect(EditAction::Delete), killctx);
+        assert_eq!(vm.state().persist.shape, None);
+
+        // C-x r M-w copies the rectangle.
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('w', KeyModifiers::ALT));
+        assert_pop2!(vm, rect(EditAction::Yank), killctx);
+
+        // C-x r d deletes the rectangle without saving it.
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('d'));
+        assert_pop2!(vm, rect(EditAction::Delete), delctx);
+
+        // C-x r y pastes the last killed rectangle blockwise.
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('y'));
+        let paste = InsertTextAction::Paste(PasteStyle::Cursor, Count::Contextual);
+        assert_pop2!(vm, Action::from(paste), killctx);
+
+        // C-x r o, C-x r c and C-x r N change the rectangle in place.
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('o'));
+        assert_pop2!(vm, rect(EditAction::Rectangle(RectangleChange::Open)), rectctx);
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('c'));
+        assert_pop2!(vm, rect(EditAction::Rectangle(RectangleChange::Clear)), rectctx);
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('N'));
+        assert_pop2!(vm, rect(EditAction::Rectangle(RectangleChange::Number)), rectctx);
+
+        // C-x r t prompts for the string to fill the rectangle with.
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('t'));
+        let fill = rect(EditAction::Rectangle(RectangleChange::Fill));
+        let act = cmdbar_focus("String rectangle: ", CommandType::Prompt, fill);
+        assert_pop2!(vm, act, strctx);
+        assert_eq!(vm.mode(), EmacsMode::Command);
+
+        // Other actions go back to using the normal context.
+        vm.input_key(ctl!('g'));
+        assert_pop2!(vm, CMDBAR_ABORT, ctx);
+        vm.input_key(key!('a'));
+        assert_pop2!(vm, typechar!('a'), ctx);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let mut ctx = mkctx();
+
+        let transpose = |style: TransposeStyle, dir: MoveDir1D| -> Action {
+            let act = EditAction::Transpose(style, dir);
+
+            EditorAction::Edit(act.into(), EditTarget::CurrentPosition).into()
+        };
+        let word = TransposeStyle::Word(WordStyle::AlphaNum);
+
+        // C-t, M-t, C-x C-t and C-M-t transpose forwards.
+        vm.input_key(ctl!('t'));
+        assert_pop2!(vm, transpose(TransposeStyle::Char, MoveDir1D::Next), ctx);
+
+        vm.input_key(key!('t', KeyModifiers::ALT));
+        assert_pop2!(vm, transpose(word.clone(), MoveDir1D::Next), ctx);
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('t'));
+        assert_pop2!(vm, transpose(TransposeStyle::Line, MoveDir1D::Next), ctx);
+
+        vm.input_key(key!('t', KeyModifiers::ALT | KeyModifiers::CONTROL));
+        assert_pop2!(vm, transpose(TransposeStyle::Sexp, MoveDir1D::Next), ctx);
+
+        // M-- on its own is an argument of -1.
+        ctx.count = Some(1);
+        vm.input_key(key!('-', KeyModifiers::ALT));
+        vm.input_key(ctl!('t'));
+        assert_pop2!(vm, transpose(TransposeStyle::Char, MoveDir1D::Previous), ctx);
+
+        // C-u - 3 is an argument of -3.
+        ctx.count = Some(3);
+        vm.input_key(ctl!('u'));
+        vm.input_key(key!('-'));
+        vm.input_key(key!('3'));
+        vm.input_key(key!('t', KeyModifiers::ALT));
+        assert_pop2!(vm, transpose(word.clone(), MoveDir1D::Previous), ctx);
+
+        // M-0 is an argument of zero.
+        ctx.count = Some(0);
+        vm.input_key(key!('0', KeyModifiers::ALT));
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('t'));
+        assert_pop2!(vm, transpose(TransposeStyle::Line, MoveDir1D::Next), ctx);
+
+        // The sign doesn't carry over to the next command.
+        ctx.count = None;
+        vm.input_key(ctl!('t'));
+        assert_pop2!(vm, transpose(TransposeStyle::Char, MoveDir1D::Next), ctx);
+    }
+
+    #[test]
+    fn test_negative_unsupported() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let ctx = mkctx();
+
+        // Commands that don't take a negative argument refuse to run.
+        vm.input_key(key!('-', KeyModifiers::ALT));
+        vm.input_key(ctl!('k'));
+        let msg = InfoMessage::from("Negative arguments aren't supported by this command");
+        assert_pop2!(vm, Action::ShowInfoMessage(msg.clone()), ctx);
+
+        vm.input_key(ctl!('u'));
+        vm.input_key(key!('-'));
+        vm.input_key(key!('2'));
+        vm.input_key(ctl!('f'));
+        assert_pop2!(vm, Action::ShowInfoMessage(msg), ctx);
+
+        // The argument doesn't carry over to the next command.
+        let mov = mv!(MoveType::Column(MoveDir1D::Next, true));
+        vm.input_key(ctl!('f'));
+        assert_pop2!(vm, mov, ctx);
+    }
+
+    #[test]
+    fn test_query_replace() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let ctx = mkctx();
+
+        let submit = Action::Prompt(PromptAction::Submit);
+        let replace = |prompt: &str, regex: bool| -> Action {
+            let start = EditorAction::Replace(ReplaceAction::Start(regex));
+            let with = cmdbar_focus("Query replace with: ", CommandType::Prompt, start.into());
+
+            cmdbar_focus(prompt, CommandType::Search, with)
+        };
+
+        // M-% prompts for the pattern, and then stays in Command mode for the replacement.
+        vm.input_key(key!('%', KeyModifiers::ALT));
+        assert_pop2!(vm, replace("Query replace: ", false), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Command);
+
+        vm.input_key(key!('a'));
+        assert_pop2!(vm, typechar!('a'), ctx);
+        vm.input_key(key!(KeyCode::Enter));
+        assert_pop2!(vm, submit.clone(), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Command);
+
+        vm.input_key(key!('b'));
+        assert_pop2!(vm, typechar!('b'), ctx);
+        vm.input_key(key!(KeyCode::Enter));
+        assert_pop2!(vm, submit.clone(), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Insert);
+
+        // Aborting the first prompt of C-M-% means the next prompt submits normally.
+        vm.input_key(key!('%', KeyModifiers::ALT | KeyModifiers::CONTROL));
+        assert_pop2!(vm, replace("Query replace regexp: ", true), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Command);
+
+        vm.input_key(ctl!('g'));
+        assert_pop2!(vm, CMDBAR_ABORT, ctx);
+        assert_eq!(vm.mode(), EmacsMode::Insert);
+
+        vm.input_key(key!('x', KeyModifiers::ALT));
+        assert_pop2!(
+            vm,
+            cmdbar_focus(":", CommandType::Command, CommandAction::Execute(1.into()).into()),
+            ctx
+        );
+        vm.input_key(key!(KeyCode::Enter));
+        assert_pop2!(vm, submit, ctx);
+        assert_eq!(vm.mode(), EmacsMode::Insert);
+    }
+
+    #[test]
+    fn test_keyboard_macros() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let mut ctx = mkctx();
+
+        let counter = |n: isize| -> Action {
+            InsertTextAction::Transcribe(n.to_string(), MoveDir1D::Previous, 1.into()).into()
+        };
+
+        // C-x ) when not recording does nothing.
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!(')'));
+        let msg = InfoMessage::from("Not defining keyboard macro");
+        assert_pop2!(vm, Action::ShowInfoMessage(msg), ctx);
+
+        // C-u 5 C-x ( starts recording into the macro ring with the counter at 5.
+        ctx.register = Some(Register::MacroRing);
+        vm.input_key(ctl!('u'));
+        vm.input_key(key!('5'));
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('('));
+        assert_pop2!(vm, Action::from(MacroAction::ToggleRecording), ctx);
+        assert_eq!(vm.show_mode(), Some("Defining kbd macro...".into()));
+
+        // C-x ( again shows a message.
+        ctx.register = None;
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('('));
+        let msg = InfoMessage::from("Already defining keyboard macro");
+        assert_pop2!(vm, Action::ShowInfoMessage(msg), ctx);
+
+        // F3 and C-x C-k C-i insert and increment the counter.
+        vm.input_key(key!(KeyCode::F(3)));
+        assert_pop2!(vm, counter(5), ctx);
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('k'));
+        vm.input_key(ctl!('i'));
+        assert_pop2!(vm, counter(6), ctx);
+
+        // C-u -2 C-x C-k C-a adds -2, and the increment can be given as an argument.
+        vm.input_key(ctl!('u'));
+        vm.input_key(key!('-'));
+        vm.input_key(key!('2'));
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('k'));
+        vm.input_key(ctl!('a'));
+        assert_pop2!(vm, Action::NoOp, ctx);
+
+        vm.input_key(ctl!('u'));
+        vm.input_key(key!('3'));
+        vm.input_key(key!(KeyCode::F(3)));
+        assert_pop2!(vm, counter(5), ctx);
+
+        vm.input_key(key!(KeyCode::F(3)));
+        assert_pop2!(vm, counter(8), ctx);
+
+        // C-u 1 0 C-x C-k C-c sets the counter.
+        vm.input_key(ctl!('u'));
+        vm.input_key(key!('1'));
+        vm.input_key(key!('0'));
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('k'));
+        vm.input_key(ctl!('c'));
+        assert_pop2!(vm, Action::NoOp, ctx);
+
+        vm.input_key(key!(KeyCode::F(3)));
+        assert_pop2!(vm, counter(10), ctx);
+
+        // F4 stops recording.
+        ctx.register = None;
+        vm.input_key(key!(KeyCode::F(4)));
+        assert_pop2!(vm, Action::from(MacroAction::ToggleRecording), ctx);
+        assert_eq!(vm.show_mode(), None);
+
+        // F4 now executes the macro at the front of the ring.
+        ctx.register = Some(Register::MacroRing);
+        vm.input_key(key!(KeyCode::F(4)));
+        assert_pop2!(vm, Action::from(MacroAction::Execute(Count::Contextual)), ctx);
+
+        // C-x e and C-x C-k C-k execute it so that it can be repeated.
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('e'));
+        assert_pop2!(vm, Action::from(MacroAction::ExecuteRepeatable(Count::Contextual)), ctx);
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('k'));
+        vm.input_key(ctl!('k'));
+        assert_pop2!(vm, Action::from(MacroAction::ExecuteRepeatable(Count::Contextual)), ctx);
+
+        // C-x e while recording finishes the macro first.
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('('));
+        assert_pop2!(vm, Action::from(MacroAction::ToggleRecording), ctx);
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(key!('e'));
+        assert_pop1!(vm, Action::from(MacroAction::ToggleRecording), ctx);
+        assert_pop2!(vm, Action::from(MacroAction::ExecuteRepeatable(Count::Contextual)), ctx);
+
+        // C-x C-k C-n and C-x C-k C-p rotate the macro ring.
+        ctx.register = None;
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('k'));
+        vm.input_key(ctl!('n'));
+        let rotate = MacroAction::Rotate(MoveDir1D::Previous, Count::Contextual);
+        assert_pop2!(vm, Action::from(rotate), ctx);
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('k'));
+        vm.input_key(ctl!('p'));
+        let rotate = MacroAction::Rotate(MoveDir1D::Next, Count::Contextual);
+        assert_pop2!(vm, Action::from(rotate), ctx);
+    }
+
+    #[test]
+    fn test_macro_counter_replay() {
+        let mut bindings = KeyManager::new(default_emacs_keys::<EmptyInfo>());
+        let mut store = Store::<EmptyInfo>::default();
+        let mut s = String::new();
+
+        macro_rules! input {
+            ($key: expr) => {
+                bindings.input_key($key);
+
+                while let Some((act, ctx)) = bindings.pop() {
+                    match act {
+                        Action::Macro(act) => {
+                            bindings.macro_command(&act, &ctx, &mut store).unwrap();
+                        },
+                        Action::Editor(EditorAction::InsertText(InsertTextAction::Type(
+                            Specifier::Exact(Char::Single(c)),
+                            _,
+                            _,
+                        ))) => s.push(c),
+                        Action::Editor(EditorAction::InsertText(InsertTextAction::Transcribe(
+                            text,
+                            _,
+                            _,
+                        ))) => s.push_str(&text),
+                        act => panic!("unexpected action: {:?}", act),
+                    }
+                }
+            };
+        }
+
+        // Record a macro that types "a" and then inserts the counter.
+        input!(key!(KeyCode::F(3)));
+        input!(key!('a'));
+        input!(key!(KeyCode::F(3)));
+        input!(key!(KeyCode::F(4)));
+        assert_eq!(s, "a0");
+
+        // Replaying F3 inserts the counter instead of starting a new recording.
+        input!(key!(KeyCode::F(4)));
+        assert_eq!(s, "a0a1");
+        assert_eq!(bindings.show_mode(), None);
+
+        input!(key!(KeyCode::F(4)));
+        assert_eq!(s, "a0a1a2");
+        assert_eq!(bindings.show_mode(), None);
+
+        // Once the replay is done, F3 starts recording again.
+        input!(key!(KeyCode::F(3)));
+        assert_eq!(bindings.show_mode(), Some("Defining kbd macro...".into()));
+    }
+
+    #[test]
+    fn test_mark_ring() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let ctx = mkctx();
+
+        // C-u C-SPC pops the buffer's mark ring.
+        vm.input_key(ctl!('u'));
+        vm.input_key(ctl!(' '));
+        assert_pop2!(vm, Action::from(CursorAction::PopMark(MarkRing::Buffer)), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Insert);
+
+        // C-x C-SPC pops the global mark ring.
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!(' '));
+        assert_pop2!(vm, Action::from(CursorAction::PopMark(MarkRing::Global)), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Insert);
+
+        // C-@ is the same as C-SPC.
+        vm.input_key(ctl!('u'));
+        vm.input_key(ctl!('@'));
+        assert_pop2!(vm, Action::from(CursorAction::PopMark(MarkRing::Buffer)), ctx);
+
+        vm.input_key(ctl!('x'));
+        vm.input_key(ctl!('@'));
+        assert_pop2!(vm, Action::from(CursorAction::PopMark(MarkRing::Global)), ctx);
+    }
+
+    #[test]
+    fn test_fill_paragraph() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let ctx = mkctx();
+
+        // M-q fills the paragraph around point.
+        let target = EditTarget::Range(RangeType::Paragraph, false, Count::Exact(1));
+        let act = EditorAction::Edit(Specifier::Exact(EditAction::Format), target);
+        vm.input_key(key!('q', KeyModifiers::ALT));
+        assert_pop2!(vm, Action::from(act), ctx);
+        assert_eq!(vm.mode(), EmacsMode::Insert);
+    }
+
+    #[test]
+    fn test_sexp() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let ctx = mkctx();
+        let cm = KeyModifiers::ALT | KeyModifiers::CONTROL;
+
+        // C-M-f and C-M-b move over balanced expressions.
+        vm.input_key(key!('f', cm));
+        assert_pop2!(vm, mv!(MoveType::Sexp(MoveDir1D::Next)), ctx);
+
+        vm.input_key(key!('b', cm));
+        assert_pop2!(vm, mv!(MoveType::Sexp(MoveDir1D::Previous)), ctx);
+
+        // C-M-u and C-M-d move up and down lists, and take a count.
+        let mut countctx = ctx.clone();
+        countctx.count = Some(2);
+
+        vm.input_key(ctl!('u'));
+        vm.input_key(key!('2'));
+        vm.input_key(key!('u', cm));
+        assert_pop2!(vm, mv!(MoveType::ListUp(MoveDir1D::Previous)), countctx);
+
+        vm.input_key(key!('d', cm));
+        assert_pop2!(vm, mv!(MoveType::ListDown(MoveDir1D::Next)), ctx);
+
+        // C-M-k kills the following expression.
+        let mut killctx = ctx.clone();
+        killctx.register = Some(Register::KillRing);
+
+        let act = EditorAction::Edit(
+            EditAction::Delete.into(),
+            EditTarget::Motion(MoveType::Sexp(MoveDir1D::Next), Count::Contextual),
+        );
+        vm.input_key(key!('k', cm));
+        assert_pop2!(vm, Action::from(act), killctx);
+
+        // C-M-SPC marks the following expression, and leaves point where it is.
+        let mut markctx = ctx.clone();
+        markctx.target_shape = Some(TargetShape::CharWise);
+
+        let target = EditTarget::Motion(MoveType::Sexp(MoveDir1D::Next), Count::Contextual);
+        let resize = SelectionAction::Resize(SelectionResizeStyle::Restart, target);
+        let swap = SelectionAction::CursorSet(SelectionCursorChange::SwapAnchor(false));
+        vm.input_key(key!(' ', cm));
+        assert_pop1!(vm, Action::from(CursorAction::PushMark), markctx);
+        assert_pop1!(vm, Action::from(resize), markctx);
+        assert_pop2!(vm, Action::from(swap), markctx);
+        assert_eq!(vm.mode(), EmacsMode::Insert);
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut vm: EmacsMachine<TerminalKey> = default_emacs_keys();
+        let next = |vm: &EmacsMachine<TerminalKey>| {
+            vm.continuations()
+                .into_iter()
+                .map(|(ev, step)| (ev, step.map(|s| s.describe().map(String::from))))
+                .collect::<HashMap<_, _>>()
+        };
+
+        // After C-x, "r" leads to more keys, while "o" runs a command.
+        vm.input_key(ctl!('x'));
+        assert!(vm.is_pending());
+
+        let res = next(&vm);
+        assert_eq!(res[&EdgeEvent::Key(key!('r'))], None);
+        assert_eq!(res[&EdgeEvent::Key(key!('o'))], Some(Some("other-window".into())));
+        assert_eq!(res[&EdgeEvent::Key(key!('2'))], Some(Some("split-window-below".into())));
+
+        // After C-x r, the rectangle commands are described.
+        vm.input_key(key!('r'));
+        assert!(vm.is_pending());
+
+        let res = next(&vm);
+        assert_eq!(res[&EdgeEvent::Key(key!('k'))], Some(Some("kill-rectangle".into())));
+        assert_eq!(res[&EdgeEvent::Key(key!('y'))], Some(Some("yank-rectangle".into())));
+
+        // Unmapped keys have no description.
+        assert_eq!(res[&EdgeEvent::Key(key!('b'))], Some(None));
+    }
+
+    #[test]
+    fn test_validate() {
+        use crate::keybindings::{diagnostics::MappingDiagnostic, EdgeRepeat};
+
+        let em: EmacsMachine<TerminalKey> = default_emacs_keys();
+
+        // The only problem is the zero-digit case of "<C-Q>{oct<=3}", which "<C-Q>{any}" always
+        // handles instead.
+        for diag in em.validate() {
+            match diag {
+                MappingDiagnostic::Shadowed { path, .. } => {
+                    assert_eq!(path, vec![(EdgeRepeat::Once, EdgeEvent::Key(ctl!('q')))]);
+                },
+                diag => panic!("unexpected diagnostic: {}", diag),
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_keymap() {
+        use crate::env::keymap::{KeymapAction, KeymapEntry, KeymapErrorKind};
+
+        let entry = |modes: &[&str], keys: &str, action| {
+            let modes = modes.iter().map(ToString::to_string).collect();
+
+            KeymapEntry { modes, keys: keys.into(), action }
+        };
+        let keymap = Keymap {
+            mappings: vec![entry(
+                &["insert"],
+                "<C-C>w",
+                KeymapAction::Command("write".into()),
+            )],
+        };
+
+        let mut em: EmacsMachine<TerminalKey> = ModalMachine::empty();
+        EmacsBindings::default().keymap(&keymap).unwrap().setup(&mut em);
+
+        em.input_key(ctl!('c'));
+        em.input_key(key!('w'));
+        let (act, _) = em.pop().unwrap();
+        assert_eq!(act, Action::from(CommandAction::Run("write".into())));
+
+        // Vim mode names aren't valid here.
+        let keymap = Keymap {
+            mappings: vec![
+                entry(&["insert"], "<C-C>w", KeymapAction::Command("write".into())),
+                entry(&["insert", "normal"], "<C-C>q", KeymapAction::Command("quit".into())),
+            ],
+        };
+        let err = EmacsBindings::<EmptyInfo>::default().keymap(&keymap).unwrap_err();
+        assert_eq!(err.to_string(), "map[1] (\"<C-C>q\"): unknown mode \"normal\"");
+        assert_eq!(err.kind, KeymapErrorKind::UnknownMode("normal".into()));
+    }
 }
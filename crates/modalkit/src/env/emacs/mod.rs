@@ -32,6 +32,7 @@ use crate::util::{keycode_to_num, option_muladd_u32, option_muladd_usize};
 
 use super::{CharacterContext, CommonKeyClass, KeyContext};
 
+pub mod command;
 pub mod keybindings;
 
 /// Emacs' modes
@@ -44,7 +45,7 @@ pub enum EmacsMode {
     /// Command bar keypresses.
     Command,
 
-    /// Search bar keypresses.
+    /// Incremental search (isearch) keypresses.
     Search,
 }
 
@@ -52,6 +53,9 @@ impl<I: ApplicationInfo> Mode<Action<I>, EmacsState<I>> for EmacsMode {
     fn enter(&self, _: Self, ctx: &mut EmacsState<I>) -> Vec<Action<I>> {
         match self {
             EmacsMode::Insert => {
+                // Leaving the command bar drops any prompts that were going to follow.
+                ctx.persist.prompts = 0;
+
                 return vec![];
             },
             EmacsMode::Command | EmacsMode::Search => {
@@ -65,13 +69,16 @@ impl<I: ApplicationInfo> Mode<Action<I>, EmacsState<I>> for EmacsMode {
     fn show(&self, ctx: &EmacsState<I>) -> Option<String> {
         match self {
             EmacsMode::Insert => {
-                match ctx.persist.insert {
-                    InsertStyle::Insert => {
-                        return None;
-                    },
-                    InsertStyle::Replace => {
-                        return Some("Overwrite mode enabled".into());
-                    },
+                let overwrite = match ctx.persist.insert {
+                    InsertStyle::Insert => None,
+                    InsertStyle::Replace => Some("Overwrite mode enabled"),
+                };
+
+                match (overwrite, ctx.persist.recording) {
+                    (Some(msg), true) => return Some(format!("{msg} (Defining kbd macro...)")),
+                    (Some(msg), false) => return Some(msg.into()),
+                    (None, true) => return Some("Defining kbd macro...".into()),
+                    (None, false) => return None,
                 }
             },
             EmacsMode::Command | EmacsMode::Search => {
@@ -110,6 +117,8 @@ impl<I: ApplicationInfo> ModeKeys<TerminalKey, Action<I>, EmacsState<I>> for Ema
         ctx: &mut EmacsState<I>,
     ) -> (Vec<Action<I>>, Option<Self>) {
         ctx.persist.repeating = false;
+        ctx.persist.killing = false;
+        ctx.persist.yanking = false;
 
         match self {
             EmacsMode::Insert => {
@@ -157,6 +166,10 @@ pub(crate) struct ActionContext {
     pub(crate) count: Option<usize>,
     pub(crate) counting: Option<usize>,
     pub(crate) register: Option<Register>,
+    pub(crate) register_append: bool,
+    pub(crate) register_prepend: bool,
+    pub(crate) rectangle: bool,
+    pub(crate) negative: bool,
 }
 
 /// This is the context preserved across actions, and changes either with the mode, or through
@@ -166,9 +179,15 @@ pub(crate) struct PersistentContext {
     regexsearch_dir: MoveDir1D,
     regexsearch_inc: bool,
     repeating: bool,
+    killing: bool,
+    yanking: bool,
     insert: InsertStyle,
     shape: Option<TargetShape>,
     shift: bool,
+    prompts: usize,
+    recording: bool,
+    executing: bool,
+    macro_counter: isize,
 }
 
 impl Default for PersistentContext {
@@ -177,9 +196,15 @@ impl Default for PersistentContext {
             regexsearch_dir: MoveDir1D::Next,
             regexsearch_inc: true,
             repeating: false,
+            killing: false,
+            yanking: false,
             insert: InsertStyle::Insert,
             shape: None,
             shift: false,
+            prompts: 0,
+            recording: false,
+            executing: false,
+            macro_counter: 0,
         }
     }
 }
@@ -224,6 +249,10 @@ impl<I: ApplicationInfo> InputState for EmacsState<I> {
         self.action = ActionContext::default();
     }
 
+    fn set_macro_executing(&mut self, executing: bool) {
+        self.persist.executing = executing;
+    }
+
     fn take(&mut self) -> Self::Output {
         let state = Self {
             persist: self.persist.clone(),
@@ -239,15 +268,23 @@ impl<I: ApplicationInfo> InputState for EmacsState<I> {
 
 impl<I: ApplicationInfo> From<EmacsState<I>> for EditContext {
     fn from(ctx: EmacsState<I>) -> Self {
+        // Rectangle commands always operate blockwise, and paste without inserting.
+        let (shape, insert) = if ctx.action.rectangle {
+            (Some(TargetShape::BlockWise), None)
+        } else {
+            (ctx.persist.shape, ctx.persist.insert.into())
+        };
+
         EditContextBuilder::default()
             .count(ctx.action.count)
             .typed_char(ctx.ch.get_typed())
             .search_regex_dir(ctx.persist.regexsearch_dir)
-            .target_shape(ctx.persist.shape)
-            .insert_style(ctx.persist.insert.into())
+            .target_shape(shape)
+            .insert_style(insert)
             .last_column(true)
             .register(ctx.action.register.clone())
-            .register_append(false)
+            .register_append(ctx.action.register_append)
+            .register_prepend(ctx.action.register_prepend)
             .search_incremental(ctx.persist.regexsearch_inc)
             .matched_keys(ctx.key.keys)
             .build()
@@ -354,5 +391,12 @@ mod tests {
 
         ctx.persist.insert = InsertStyle::Replace;
         assert_eq!(EmacsMode::Insert.show(&ctx), Some("Overwrite mode enabled".into()));
+
+        ctx.persist.recording = true;
+        let msg = "Overwrite mode enabled (Defining kbd macro...)";
+        assert_eq!(EmacsMode::Insert.show(&ctx), Some(msg.into()));
+
+        ctx.persist.insert = InsertStyle::Insert;
+        assert_eq!(EmacsMode::Insert.show(&ctx), Some("Defining kbd macro...".into()));
     }
 }
@@ -0,0 +1,1259 @@
+//! # Helix Keybindings
+//!
+//! ## Overview
+//!
+//! This module handles mapping the keybindings used in Helix onto the [Action] type.
+//!
+//! Normal mode operates on selections: keys like `w` or `f` start new selections, and editing
+//! keys like `d` or `c` act on whatever is currently selected. Select mode, entered with `v`,
+//! extends the current selections with each motion instead.
+//!
+//! The `g` and `m` prefixes provide Helix's goto and match modes. `<Space>` provides the window
+//! (`<Space>w`), clipboard (`<Space>y`, `<Space>p`, `<Space>P`, `<Space>R`), keyword lookup
+//! (`<Space>k`) and search (`<Space>/`) commands from its space menu, along with stand-ins for its
+//! pickers (`<Space>f`, `<Space>b`, `<Space>s`).
+//!
+//! ## Divergences
+//!
+//! The keybindings here diverge from the defaults in Helix in the following ways:
+//!
+//! - There are no pickers. Instead, `<Space>f` and `<Space>b` start typing an `:edit` or `:buffer`
+//!   command, and `<Space>s` starts a search for the word under the cursor. The other picker keys
+//!   aren't mapped
+//! - `w` and `b` select up to the start of the next or previous word, instead of selecting the
+//!   word and its trailing whitespace
+//! - `x` selects the full lines covered by each selection, but doesn't extend onto the next line
+//!   when pressed again
+//! - Prompts always return to Normal mode once they are submitted or aborted
+//! - Leaving Insert mode reduces each selection to its cursor
+//!
+use bitflags::bitflags;
+
+use crate::{
+    actions::{
+        Action,
+        CommandAction,
+        CommandBarAction,
+        CursorAction,
+        EditAction,
+        EditorAction,
+        HistoryAction,
+        InsertTextAction,
+        PromptAction,
+        SelectionAction,
+        WindowAction,
+    },
+    editing::application::{ApplicationInfo, EmptyInfo},
+    prelude::*,
+};
+
+use super::{
+    super::{keyparse::parse, CommonKeyClass, ShellBindings},
+    HelixMode,
+    HelixState,
+};
+
+use crate::key::TerminalKey;
+use crate::keybindings::{InputBindings, ModalMachine, Step};
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    struct MappedModes: u32 {
+        const N = 0b0000000000000001;
+        const S = 0b0000000000000010;
+        const I = 0b0000000000000100;
+        const C = 0b0000000000001000;
+
+        const NS = MappedModes::N.bits() | MappedModes::S.bits();
+        const NSI = MappedModes::NS.bits() | MappedModes::I.bits();
+        const IC = MappedModes::I.bits() | MappedModes::C.bits();
+    }
+}
+
+const NSIMAP: MappedModes = MappedModes::NSI;
+const NSMAP: MappedModes = MappedModes::NS;
+const ICMAP: MappedModes = MappedModes::IC;
+const NMAP: MappedModes = MappedModes::N;
+const SMAP: MappedModes = MappedModes::S;
+const IMAP: MappedModes = MappedModes::I;
+const CMAP: MappedModes = MappedModes::C;
+
+impl MappedModes {
+    pub fn split(&self) -> Vec<HelixMode> {
+        let mut modes = Vec::new();
+
+        if self.contains(MappedModes::N) {
+            modes.push(HelixMode::Normal);
+        }
+
+        if self.contains(MappedModes::S) {
+            modes.push(HelixMode::Select);
+        }
+
+        if self.contains(MappedModes::I) {
+            modes.push(HelixMode::Insert);
+        }
+
+        if self.contains(MappedModes::C) {
+            modes.push(HelixMode::Command);
+        }
+
+        return modes;
+    }
+}
+
+#[derive(Clone, Debug)]
+enum InternalAction {
+    SaveCounting,
+    SetCharSearch(MoveDir1D, bool),
+    SetInsertStyle(InsertStyle),
+    SetRegister(Register),
+    SetReplaceChar,
+    SetSearchRegexParams(MoveDir1D),
+}
+
+impl InternalAction {
+    pub fn run<I: ApplicationInfo>(&self, ctx: &mut HelixState<I>) {
+        match self {
+            InternalAction::SaveCounting => {
+                match ctx.action.count {
+                    None => {
+                        ctx.action.count = ctx.action.counting;
+                        ctx.action.counting = None;
+                    },
+                    Some(prev) => {
+                        ctx.action.count =
+                            Some(prev.saturating_mul(ctx.action.counting.unwrap_or(1)));
+                        ctx.action.counting = None;
+                    },
+                }
+            },
+            InternalAction::SetCharSearch(dir, inclusive) => {
+                ctx.action.charsearch = ctx.ch.get_typed().map(|c| (*dir, *inclusive, c));
+            },
+            InternalAction::SetInsertStyle(style) => {
+                ctx.persist.insert = Some(*style);
+            },
+            InternalAction::SetRegister(reg) => {
+                ctx.action.register = Some(reg.clone());
+            },
+            InternalAction::SetReplaceChar => {
+                ctx.action.replace = ctx.ch.get_typed();
+            },
+            InternalAction::SetSearchRegexParams(dir) => {
+                ctx.persist.regexsearch_dir = *dir;
+            },
+        }
+    }
+}
+
+/// Description of actions to take after an input sequence.
+#[derive(Debug, Default)]
+pub struct InputStep<I: ApplicationInfo> {
+    internal: Vec<InternalAction>,
+    external: Vec<Action<I>>,
+    nextm: Option<HelixMode>,
+    desc: Option<String>,
+}
+
+impl<I: ApplicationInfo> InputStep<I> {
+    /// Create a new step that input keys can map to.
+    pub fn new() -> Self {
+        InputStep { internal: vec![], external: vec![], nextm: None, desc: None }
+    }
+
+    /// Set the [HelixMode] to switch to after this step.
+    pub fn goto(mut self, mode: HelixMode) -> Self {
+        self.nextm = Some(mode);
+        self
+    }
+
+    /// Set the [actions](Action) that this step produces.
+    pub fn actions(mut self, acts: Vec<Action<I>>) -> Self {
+        self.external = acts;
+        self
+    }
+
+    /// Set a short description of what this step does, to show in pending-key hints.
+    pub fn description(mut self, desc: impl Into<String>) -> Self {
+        self.desc = Some(desc.into());
+        self
+    }
+}
+
+impl<I: ApplicationInfo> Clone for InputStep<I> {
+    fn clone(&self) -> Self {
+        Self {
+            internal: self.internal.clone(),
+            external: self.external.clone(),
+            nextm: self.nextm,
+            desc: self.desc.clone(),
+        }
+    }
+}
+
+impl<I: ApplicationInfo> Step<TerminalKey> for InputStep<I> {
+    type A = Action<I>;
+    type State = HelixState<I>;
+    type M = HelixMode;
+    type Class = CommonKeyClass;
+    type Sequence = RepeatType;
+
+    fn is_unmapped(&self) -> bool {
+        match self {
+            InputStep { internal, external, nextm: None, .. } => {
+                internal.is_empty() && external.is_empty()
+            },
+            _ => false,
+        }
+    }
+
+    fn fallthrough(&self) -> Option<Self::M> {
+        None
+    }
+
+    fn describe(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+
+    fn step(&self, ctx: &mut HelixState<I>) -> (Vec<Action<I>>, Option<Self::M>) {
+        for iact in self.internal.iter() {
+            iact.run(ctx);
+        }
+
+        return (self.external.clone(), self.nextm);
+    }
+}
+
+macro_rules! act {
+    ($ext: expr) => {
+        isv!(vec![], vec![$ext])
+    };
+    ($ext: expr, $ns: expr) => {
+        isv!(vec![], vec![$ext], $ns)
+    };
+}
+
+macro_rules! acts {
+    ( $( $ext: expr ),+ ) => {
+        isv!(vec![], vec![ $( Action::from($ext) ),+ ])
+    };
+}
+
+macro_rules! iact {
+    ($int: expr) => {
+        isv!(vec![$int], vec![])
+    };
+}
+
+macro_rules! isv {
+    () => {
+        InputStep { internal: vec![], external: vec![], nextm: None, desc: None }
+    };
+    ($ints: expr, $exts: expr) => {
+        InputStep { internal: $ints, external: $exts, nextm: None, desc: None }
+    };
+    ($ints: expr, $exts: expr, $ns: expr) => {
+        InputStep { internal: $ints, external: $exts, nextm: Some($ns), desc: None }
+    };
+}
+
+macro_rules! is {
+    ($int: expr, $ext: expr) => {
+        isv!(vec![$int], vec![$ext.into()])
+    };
+    ($int: expr, $ext: expr, $ns: expr) => {
+        isv!(vec![$int], vec![$ext.into()], $ns)
+    };
+}
+
+macro_rules! blackhole {
+    ($act: expr) => {
+        is!(InternalAction::SetRegister(Register::Blackhole), $act)
+    };
+}
+
+macro_rules! cursor {
+    ($ca: expr) => {
+        editor!(EditorAction::Cursor($ca))
+    };
+}
+
+macro_rules! reduce {
+    () => {
+        SelectionAction::Resize(SelectionResizeStyle::Restart, EditTarget::CurrentPosition)
+    };
+}
+
+macro_rules! move_target {
+    ($target: expr) => {
+        acts!(SelectionAction::Resize(SelectionResizeStyle::Extend, $target), reduce!())
+    };
+}
+
+macro_rules! move_to {
+    ($mt: expr) => {
+        move_target!(EditTarget::Motion($mt, Count::Contextual))
+    };
+    ($mt: expr, $c: expr) => {
+        move_target!(EditTarget::Motion($mt, $c))
+    };
+}
+
+macro_rules! select_to {
+    ($mt: expr) => {
+        selection!(SelectionAction::Resize(
+            SelectionResizeStyle::Restart,
+            EditTarget::Motion($mt, Count::Contextual)
+        ))
+    };
+}
+
+macro_rules! extend_target {
+    ($target: expr) => {
+        selection!(SelectionAction::Resize(SelectionResizeStyle::Extend, $target))
+    };
+}
+
+macro_rules! extend_to {
+    ($mt: expr) => {
+        extend_target!(EditTarget::Motion($mt, Count::Contextual))
+    };
+    ($mt: expr, $c: expr) => {
+        extend_target!(EditTarget::Motion($mt, $c))
+    };
+}
+
+macro_rules! select_object {
+    ($rt: expr, $inclusive: expr) => {
+        selection!(SelectionAction::Resize(
+            SelectionResizeStyle::Object,
+            EditTarget::Range($rt, $inclusive, Count::Contextual)
+        ))
+    };
+}
+
+macro_rules! charsearch {
+    ($style: expr, $dir: expr, $inclusive: expr) => {
+        is!(
+            InternalAction::SetCharSearch($dir, $inclusive),
+            Action::from(SelectionAction::Resize(
+                $style,
+                EditTarget::Search(SearchType::Char(true), MoveDirMod::Same, Count::Contextual)
+            ))
+        )
+    };
+}
+
+macro_rules! edit_sel {
+    ($ea: expr) => {
+        isv!(
+            vec![],
+            vec![EditorAction::Edit(Specifier::Exact($ea), EditTarget::Selection).into()],
+            HelixMode::Normal
+        )
+    };
+}
+
+macro_rules! edit_then_reduce {
+    ($ea: expr) => {
+        isv!(
+            vec![],
+            vec![
+                EditorAction::Edit(Specifier::Exact($ea), EditTarget::Selection).into(),
+                reduce!().into()
+            ],
+            HelixMode::Normal
+        )
+    };
+}
+
+macro_rules! change {
+    () => {
+        isv!(
+            vec![InternalAction::SetInsertStyle(InsertStyle::Insert)],
+            vec![EditorAction::Edit(EditAction::Delete.into(), EditTarget::Selection).into()],
+            HelixMode::Insert
+        )
+    };
+}
+
+macro_rules! insert {
+    ($cursor: expr) => {
+        isv!(
+            vec![InternalAction::SetInsertStyle(InsertStyle::Insert)],
+            vec![SelectionAction::CursorSet($cursor).into()],
+            HelixMode::Insert
+        )
+    };
+    ($cursor: expr, $mt: expr, $c: expr) => {
+        isv!(
+            vec![InternalAction::SetInsertStyle(InsertStyle::Insert)],
+            vec![
+                SelectionAction::CursorSet($cursor).into(),
+                EditorAction::Edit(EditAction::Motion.into(), EditTarget::Motion($mt, $c)).into()
+            ],
+            HelixMode::Insert
+        )
+    };
+}
+
+macro_rules! open_lines {
+    ($dir: expr) => {
+        isv!(
+            vec![InternalAction::SetInsertStyle(InsertStyle::Insert)],
+            vec![InsertTextAction::OpenLine(TargetShape::LineWise, $dir, 1.into()).into()],
+            HelixMode::Insert
+        )
+    };
+}
+
+macro_rules! paste_side {
+    ($cursor: expr, $dir: expr) => {
+        acts!(
+            SelectionAction::CursorSet($cursor),
+            InsertTextAction::Paste(PasteStyle::Side($dir), Count::Contextual)
+        )
+    };
+}
+
+macro_rules! clipboard {
+    ($step: expr) => {{
+        let mut step = $step;
+        step.internal
+            .insert(0, InternalAction::SetRegister(Register::SelectionClipboard));
+        step
+    }};
+}
+
+macro_rules! surround_add {
+    () => {
+        is!(
+            InternalAction::SetReplaceChar,
+            Action::from(EditorAction::Edit(
+                EditAction::Surround(SurroundChange::Add).into(),
+                EditTarget::Selection
+            )),
+            HelixMode::Normal
+        )
+    };
+}
+
+macro_rules! surround_change {
+    ($change: expr, $rt: expr) => {
+        edit_target!(
+            EditAction::Surround($change),
+            EditTarget::Range($rt, true, Count::Contextual),
+            HelixMode::Normal
+        )
+    };
+}
+
+macro_rules! surround_delete {
+    ($rt: expr) => {
+        surround_change!(SurroundChange::Delete, $rt)
+    };
+}
+
+macro_rules! surround_replace {
+    ($rt: expr) => {{
+        let mut step = surround_change!(SurroundChange::Replace, $rt);
+        step.internal.push(InternalAction::SetReplaceChar);
+        step
+    }};
+}
+
+macro_rules! window {
+    ($act: expr) => {
+        act!(Action::Window($act))
+    };
+}
+
+macro_rules! window_focus {
+    ($fc: expr) => {
+        window!(WindowAction::Focus($fc))
+    };
+}
+
+macro_rules! window_split {
+    ($axis: expr) => {
+        window!(WindowAction::Split(
+            OpenTarget::Current,
+            $axis,
+            MoveDir1D::Previous,
+            Count::Exact(1)
+        ))
+    };
+}
+
+macro_rules! window_close {
+    ($target: expr) => {
+        window!(WindowAction::Close($target(FocusChange::Current), CloseFlags::QUIT))
+    };
+}
+
+macro_rules! cmdbar_focus {
+    ($prompt: expr, $type: expr, $act: expr) => {
+        cmdbar!(
+            CommandBarAction::Focus($prompt.into(), $type, Box::new(Action::from($act))),
+            HelixMode::Command
+        )
+    };
+}
+
+macro_rules! cmdbar_select {
+    ($prompt: expr, $act: expr) => {
+        cmdbar_focus!($prompt, CommandType::Search, $act)
+    };
+}
+
+macro_rules! cmdbar_search {
+    ($style: expr, $dir: expr) => {
+        is!(
+            InternalAction::SetSearchRegexParams($dir),
+            Action::CommandBar(CommandBarAction::Focus(
+                match $dir {
+                    MoveDir1D::Next => "/".into(),
+                    MoveDir1D::Previous => "?".into(),
+                },
+                CommandType::Search,
+                Box::new(Action::from(SelectionAction::Resize(
+                    $style,
+                    EditTarget::Search(SearchType::Regex, MoveDirMod::Same, Count::Contextual)
+                )))
+            )),
+            HelixMode::Command
+        )
+    };
+}
+
+macro_rules! cmdbar_command {
+    ($cmd: expr) => {
+        isv!(
+            vec![],
+            vec![
+                Action::CommandBar(CommandBarAction::Focus(
+                    ":".into(),
+                    CommandType::Command,
+                    Box::new(Action::from(CommandAction::Execute(1.into())))
+                )),
+                Action::from(InsertTextAction::Transcribe($cmd.into(), MoveDir1D::Previous, 1.into())),
+            ],
+            HelixMode::Command
+        )
+    };
+}
+
+macro_rules! cmdbar_search_word {
+    ($style: expr, $dir: expr) => {{
+        let mut step = cmdbar_search!($style, $dir);
+        step.external.push(Action::from(PromptAction::InsertCursorWord(WordStyle::AlphaNum)));
+        step
+    }};
+}
+
+macro_rules! search {
+    ($style: expr, $flip: expr) => {
+        selection!(SelectionAction::Resize(
+            $style,
+            EditTarget::Search(SearchType::Regex, $flip, Count::Contextual)
+        ))
+    };
+}
+
+macro_rules! normal {
+    () => {
+        goto!(HelixMode::Normal)
+    };
+}
+
+#[rustfmt::skip]
+fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputStep<I>)> {
+    [
+        // Normal, Select and Insert mode keybindings.
+        ( NSIMAP, "<C-F>", scroll2d!(MoveDir2D::Down, ScrollSize::Page) ),
+        ( NSIMAP, "<C-B>", scroll2d!(MoveDir2D::Up, ScrollSize::Page) ),
+        ( NSIMAP, "<PageDown>", scroll2d!(MoveDir2D::Down, ScrollSize::Page) ),
+        ( NSIMAP, "<PageUp>", scroll2d!(MoveDir2D::Up, ScrollSize::Page) ),
+
+        // Normal mode keybindings for moving the cursors.
+        ( NMAP, "h", move_to!(MoveType::Column(MoveDir1D::Previous, false)) ),
+        ( NMAP, "j", move_to!(MoveType::Line(MoveDir1D::Next)) ),
+        ( NMAP, "k", move_to!(MoveType::Line(MoveDir1D::Previous)) ),
+        ( NMAP, "l", move_to!(MoveType::Column(MoveDir1D::Next, false)) ),
+        ( NMAP, "<Left>", move_to!(MoveType::Column(MoveDir1D::Previous, false)) ),
+        ( NMAP, "<Down>", move_to!(MoveType::Line(MoveDir1D::Next)) ),
+        ( NMAP, "<Up>", move_to!(MoveType::Line(MoveDir1D::Previous)) ),
+        ( NMAP, "<Right>", move_to!(MoveType::Column(MoveDir1D::Next, false)) ),
+        ( NMAP, "<Home>", move_to!(MoveType::LinePos(MovePosition::Beginning), 0.into()) ),
+        ( NMAP, "<End>", move_to!(MoveType::LinePos(MovePosition::End), 0.into()) ),
+        ( NMAP, "G", move_to!(MoveType::BufferLineOffset) ),
+
+        // Normal mode keybindings for starting new selections.
+        ( NMAP, "w", select_to!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next)) ),
+        ( NMAP, "e", select_to!(MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next)) ),
+        ( NMAP, "b", select_to!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
+        ( NMAP, "W", select_to!(MoveType::WordBegin(WordStyle::Big, MoveDir1D::Next)) ),
+        ( NMAP, "E", select_to!(MoveType::WordEnd(WordStyle::Big, MoveDir1D::Next)) ),
+        ( NMAP, "B", select_to!(MoveType::WordBegin(WordStyle::Big, MoveDir1D::Previous)) ),
+        ( NMAP, "f{any}", charsearch!(SelectionResizeStyle::Restart, MoveDir1D::Next, true) ),
+        ( NMAP, "t{any}", charsearch!(SelectionResizeStyle::Restart, MoveDir1D::Next, false) ),
+        ( NMAP, "F{any}", charsearch!(SelectionResizeStyle::Restart, MoveDir1D::Previous, true) ),
+        ( NMAP, "T{any}", charsearch!(SelectionResizeStyle::Restart, MoveDir1D::Previous, false) ),
+        ( NMAP, "n", search!(SelectionResizeStyle::Object, MoveDirMod::Same) ),
+        ( NMAP, "N", search!(SelectionResizeStyle::Object, MoveDirMod::Flip) ),
+        ( NMAP, "/", cmdbar_search!(SelectionResizeStyle::Object, MoveDir1D::Next) ),
+        ( NMAP, "?", cmdbar_search!(SelectionResizeStyle::Object, MoveDir1D::Previous) ),
+        ( NMAP, "v", goto!(HelixMode::Select) ),
+
+        // Normal mode keybindings for goto mode.
+        ( NMAP, "gg", move_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::Beginning, Count::Contextual)) ),
+        ( NMAP, "ge", move_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::End, Count::Contextual)) ),
+        ( NMAP, "gh", move_to!(MoveType::LinePos(MovePosition::Beginning), 0.into()) ),
+        ( NMAP, "gl", move_to!(MoveType::LinePos(MovePosition::End), 0.into()) ),
+        ( NMAP, "gs", move_to!(MoveType::FirstWord(MoveDir1D::Next), 0.into()) ),
+        ( NMAP, "gt", move_to!(MoveType::ViewportPos(MovePosition::Beginning)) ),
+        ( NMAP, "gc", move_to!(MoveType::ViewportPos(MovePosition::Middle)) ),
+        ( NMAP, "gb", move_to!(MoveType::ViewportPos(MovePosition::End)) ),
+        ( NMAP, "gj", move_to!(MoveType::ScreenLine(MoveDir1D::Next)) ),
+        ( NMAP, "gk", move_to!(MoveType::ScreenLine(MoveDir1D::Previous)) ),
+        ( NMAP, "mm", move_to!(MoveType::ItemMatch) ),
+
+        // Select mode keybindings for extending the current selections.
+        ( SMAP, "h", extend_to!(MoveType::Column(MoveDir1D::Previous, false)) ),
+        ( SMAP, "j", extend_to!(MoveType::Line(MoveDir1D::Next)) ),
+        ( SMAP, "k", extend_to!(MoveType::Line(MoveDir1D::Previous)) ),
+        ( SMAP, "l", extend_to!(MoveType::Column(MoveDir1D::Next, false)) ),
+        ( SMAP, "<Left>", extend_to!(MoveType::Column(MoveDir1D::Previous, false)) ),
+        ( SMAP, "<Down>", extend_to!(MoveType::Line(MoveDir1D::Next)) ),
+        ( SMAP, "<Up>", extend_to!(MoveType::Line(MoveDir1D::Previous)) ),
+        ( SMAP, "<Right>", extend_to!(MoveType::Column(MoveDir1D::Next, false)) ),
+        ( SMAP, "<Home>", extend_to!(MoveType::LinePos(MovePosition::Beginning), 0.into()) ),
+        ( SMAP, "<End>", extend_to!(MoveType::LinePos(MovePosition::End), 0.into()) ),
+        ( SMAP, "G", extend_to!(MoveType::BufferLineOffset) ),
+        ( SMAP, "w", extend_to!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next)) ),
+        ( SMAP, "e", extend_to!(MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next)) ),
+        ( SMAP, "b", extend_to!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
+        ( SMAP, "W", extend_to!(MoveType::WordBegin(WordStyle::Big, MoveDir1D::Next)) ),
+        ( SMAP, "E", extend_to!(MoveType::WordEnd(WordStyle::Big, MoveDir1D::Next)) ),
+        ( SMAP, "B", extend_to!(MoveType::WordBegin(WordStyle::Big, MoveDir1D::Previous)) ),
+        ( SMAP, "f{any}", charsearch!(SelectionResizeStyle::Extend, MoveDir1D::Next, true) ),
+        ( SMAP, "t{any}", charsearch!(SelectionResizeStyle::Extend, MoveDir1D::Next, false) ),
+        ( SMAP, "F{any}", charsearch!(SelectionResizeStyle::Extend, MoveDir1D::Previous, true) ),
+        ( SMAP, "T{any}", charsearch!(SelectionResizeStyle::Extend, MoveDir1D::Previous, false) ),
+        ( SMAP, "n", search!(SelectionResizeStyle::Extend, MoveDirMod::Same) ),
+        ( SMAP, "N", search!(SelectionResizeStyle::Extend, MoveDirMod::Flip) ),
+        ( SMAP, "/", cmdbar_search!(SelectionResizeStyle::Extend, MoveDir1D::Next) ),
+        ( SMAP, "?", cmdbar_search!(SelectionResizeStyle::Extend, MoveDir1D::Previous) ),
+        ( SMAP, "v", normal!() ),
+        ( SMAP, "<Esc>", normal!() ),
+
+        // Select mode keybindings for goto mode.
+        ( SMAP, "gg", extend_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::Beginning, Count::Contextual)) ),
+        ( SMAP, "ge", extend_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::End, Count::Contextual)) ),
+        ( SMAP, "gh", extend_to!(MoveType::LinePos(MovePosition::Beginning), 0.into()) ),
+        ( SMAP, "gl", extend_to!(MoveType::LinePos(MovePosition::End), 0.into()) ),
+        ( SMAP, "gs", extend_to!(MoveType::FirstWord(MoveDir1D::Next), 0.into()) ),
+        ( SMAP, "gt", extend_to!(MoveType::ViewportPos(MovePosition::Beginning)) ),
+        ( SMAP, "gc", extend_to!(MoveType::ViewportPos(MovePosition::Middle)) ),
+        ( SMAP, "gb", extend_to!(MoveType::ViewportPos(MovePosition::End)) ),
+        ( SMAP, "gj", extend_to!(MoveType::ScreenLine(MoveDir1D::Next)) ),
+        ( SMAP, "gk", extend_to!(MoveType::ScreenLine(MoveDir1D::Previous)) ),
+        ( SMAP, "mm", extend_to!(MoveType::ItemMatch) ),
+
+        // Normal and Select mode keybindings for goto mode.
+        ( NSMAP, "g.", jump!(PositionList::ChangeList, MoveDir1D::Previous) ),
+        ( NSMAP, "<C-O>", jump!(PositionList::JumpList, MoveDir1D::Previous) ),
+        ( NSMAP, "<C-I>", jump!(PositionList::JumpList, MoveDir1D::Next) ),
+        ( NSMAP, "<Tab>", jump!(PositionList::JumpList, MoveDir1D::Next) ),
+
+        // Normal and Select mode keybindings for match mode.
+        ( NSMAP, "ms{any}", surround_add!() ),
+        ( NSMAP, "mr({any}", surround_replace!(RangeType::Bracketed('(', ')')) ),
+        ( NSMAP, "mr){any}", surround_replace!(RangeType::Bracketed('(', ')')) ),
+        ( NSMAP, "mr[{any}", surround_replace!(RangeType::Bracketed('[', ']')) ),
+        ( NSMAP, "mr]{any}", surround_replace!(RangeType::Bracketed('[', ']')) ),
+        ( NSMAP, "mr{{any}", surround_replace!(RangeType::Bracketed('{', '}')) ),
+        ( NSMAP, "mr}{any}", surround_replace!(RangeType::Bracketed('{', '}')) ),
+        ( NSMAP, "mr<lt>{any}", surround_replace!(RangeType::Bracketed('<', '>')) ),
+        ( NSMAP, "mr>{any}", surround_replace!(RangeType::Bracketed('<', '>')) ),
+        ( NSMAP, "mr\"{any}", surround_replace!(RangeType::Quote('"')) ),
+        ( NSMAP, "mr'{any}", surround_replace!(RangeType::Quote('\'')) ),
+        ( NSMAP, "mr`{any}", surround_replace!(RangeType::Quote('`')) ),
+        ( NSMAP, "md(", surround_delete!(RangeType::Bracketed('(', ')')) ),
+        ( NSMAP, "md)", surround_delete!(RangeType::Bracketed('(', ')')) ),
+        ( NSMAP, "md[", surround_delete!(RangeType::Bracketed('[', ']')) ),
+        ( NSMAP, "md]", surround_delete!(RangeType::Bracketed('[', ']')) ),
+        ( NSMAP, "md{", surround_delete!(RangeType::Bracketed('{', '}')) ),
+        ( NSMAP, "md}", surround_delete!(RangeType::Bracketed('{', '}')) ),
+        ( NSMAP, "md<lt>", surround_delete!(RangeType::Bracketed('<', '>')) ),
+        ( NSMAP, "md>", surround_delete!(RangeType::Bracketed('<', '>')) ),
+        ( NSMAP, "md\"", surround_delete!(RangeType::Quote('"')) ),
+        ( NSMAP, "md'", surround_delete!(RangeType::Quote('\'')) ),
+        ( NSMAP, "md`", surround_delete!(RangeType::Quote('`')) ),
+        ( NSMAP, "miw", select_object!(RangeType::Word(WordStyle::Little), false) ),
+        ( NSMAP, "miW", select_object!(RangeType::Word(WordStyle::Big), false) ),
+        ( NSMAP, "mip", select_object!(RangeType::Paragraph, false) ),
+        ( NSMAP, "mi(", select_object!(RangeType::Bracketed('(', ')'), false) ),
+        ( NSMAP, "mi)", select_object!(RangeType::Bracketed('(', ')'), false) ),
+        ( NSMAP, "mi[", select_object!(RangeType::Bracketed('[', ']'), false) ),
+        ( NSMAP, "mi]", select_object!(RangeType::Bracketed('[', ']'), false) ),
+        ( NSMAP, "mi{", select_object!(RangeType::Bracketed('{', '}'), false) ),
+        ( NSMAP, "mi}", select_object!(RangeType::Bracketed('{', '}'), false) ),
+        ( NSMAP, "mi<lt>", select_object!(RangeType::Bracketed('<', '>'), false) ),
+        ( NSMAP, "mi>", select_object!(RangeType::Bracketed('<', '>'), false) ),
+        ( NSMAP, "mi\"", select_object!(RangeType::Quote('"'), false) ),
+        ( NSMAP, "mi'", select_object!(RangeType::Quote('\''), false) ),
+        ( NSMAP, "mi`", select_object!(RangeType::Quote('`'), false) ),
+        ( NSMAP, "maw", select_object!(RangeType::Word(WordStyle::Little), true) ),
+        ( NSMAP, "maW", select_object!(RangeType::Word(WordStyle::Big), true) ),
+        ( NSMAP, "map", select_object!(RangeType::Paragraph, true) ),
+        ( NSMAP, "ma(", select_object!(RangeType::Bracketed('(', ')'), true) ),
+        ( NSMAP, "ma)", select_object!(RangeType::Bracketed('(', ')'), true) ),
+        ( NSMAP, "ma[", select_object!(RangeType::Bracketed('[', ']'), true) ),
+        ( NSMAP, "ma]", select_object!(RangeType::Bracketed('[', ']'), true) ),
+        ( NSMAP, "ma{", select_object!(RangeType::Bracketed('{', '}'), true) ),
+        ( NSMAP, "ma}", select_object!(RangeType::Bracketed('{', '}'), true) ),
+        ( NSMAP, "ma<lt>", select_object!(RangeType::Bracketed('<', '>'), true) ),
+        ( NSMAP, "ma>", select_object!(RangeType::Bracketed('<', '>'), true) ),
+        ( NSMAP, "ma\"", select_object!(RangeType::Quote('"'), true) ),
+        ( NSMAP, "ma'", select_object!(RangeType::Quote('\''), true) ),
+        ( NSMAP, "ma`", select_object!(RangeType::Quote('`'), true) ),
+
+        // Normal and Select mode keybindings for manipulating selections.
+        ( NSMAP, "x", selection!(SelectionAction::Expand(SelectionBoundary::Line, TargetShapeFilter::ALL)) ),
+        ( NSMAP, "X", selection!(SelectionAction::Expand(SelectionBoundary::Line, TargetShapeFilter::ALL)) ),
+        ( NSMAP, "<A-x>", selection!(SelectionAction::Trim(SelectionBoundary::Line, TargetShapeFilter::ALL)) ),
+        ( NSMAP, "%", select_object!(RangeType::Buffer, true) ),
+        ( NSMAP, ";", selection!(reduce!()) ),
+        ( NSMAP, "<A-;>", selection!(SelectionAction::CursorSet(SelectionCursorChange::SwapAnchor(false))) ),
+        ( NSMAP, "<A-:>", selection!(SelectionAction::CursorSet(SelectionCursorChange::End)) ),
+        ( NSMAP, "s", cmdbar_select!("select:", SelectionAction::Split(SelectionSplitStyle::Regex(false), TargetShapeFilter::ALL)) ),
+        ( NSMAP, "S", cmdbar_select!("split:", SelectionAction::Split(SelectionSplitStyle::Regex(true), TargetShapeFilter::ALL)) ),
+        ( NSMAP, "<A-s>", selection!(SelectionAction::Split(SelectionSplitStyle::Lines, TargetShapeFilter::ALL)) ),
+        ( NSMAP, "K", cmdbar_select!("keep:", SelectionAction::Filter(false)) ),
+        ( NSMAP, "<A-K>", cmdbar_select!("remove:", SelectionAction::Filter(true)) ),
+        ( NSMAP, "_", selection!(SelectionAction::Trim(SelectionBoundary::NonWhitespace, TargetShapeFilter::ALL)) ),
+        ( NSMAP, "C", selection!(SelectionAction::Duplicate(MoveDir1D::Next, Count::Contextual)) ),
+        ( NSMAP, "<A-C>", selection!(SelectionAction::Duplicate(MoveDir1D::Previous, Count::Contextual)) ),
+        ( NSMAP, ",", cursor!(CursorAction::Close(CursorCloseTarget::Followers)) ),
+        ( NSMAP, "<A-,>", cursor!(CursorAction::Close(CursorCloseTarget::Leader)) ),
+        ( NSMAP, "(", cursor!(CursorAction::Rotate(MoveDir1D::Previous, Count::Contextual)) ),
+        ( NSMAP, ")", cursor!(CursorAction::Rotate(MoveDir1D::Next, Count::Contextual)) ),
+
+        // Normal and Select mode keybindings for editing the selected text.
+        ( NSMAP, "d", edit_then_reduce!(EditAction::Delete) ),
+        ( NSMAP, "<A-d>", isv!(
+            vec![InternalAction::SetRegister(Register::Blackhole)],
+            vec![
+                EditorAction::Edit(EditAction::Delete.into(), EditTarget::Selection).into(),
+                reduce!().into()
+            ],
+            HelixMode::Normal
+        ) ),
+        ( NSMAP, "c", change!() ),
+        ( NSMAP, "<A-c>", isv!(
+            vec![
+                InternalAction::SetRegister(Register::Blackhole),
+                InternalAction::SetInsertStyle(InsertStyle::Insert),
+            ],
+            vec![EditorAction::Edit(EditAction::Delete.into(), EditTarget::Selection).into()],
+            HelixMode::Insert
+        ) ),
+        ( NSMAP, "y", edit_sel!(EditAction::Yank) ),
+        ( NSMAP, "p", paste_side!(SelectionCursorChange::End, MoveDir1D::Next) ),
+        ( NSMAP, "P", paste_side!(SelectionCursorChange::Beginning, MoveDir1D::Previous) ),
+        ( NSMAP, "R", paste!(PasteStyle::Replace) ),
+        ( NSMAP, "r{any}", is!(InternalAction::SetReplaceChar, Action::from(EditorAction::Edit(EditAction::Replace(false).into(), EditTarget::Selection)), HelixMode::Normal) ),
+        ( NSMAP, "~", edit_sel!(EditAction::ChangeCase(Case::Toggle)) ),
+        ( NSMAP, "`", edit_sel!(EditAction::ChangeCase(Case::Lower)) ),
+        ( NSMAP, "<A-`>", edit_sel!(EditAction::ChangeCase(Case::Upper)) ),
+        ( NSMAP, ">", edit_sel!(EditAction::Indent(IndentChange::Increase(Count::Contextual))) ),
+        ( NSMAP, "<lt>", edit_sel!(EditAction::Indent(IndentChange::Decrease(Count::Contextual))) ),
+        ( NSMAP, "J", edit_sel!(EditAction::Join(JoinStyle::OneSpace)) ),
+        ( NSMAP, "<C-A>", edit_sel!(EditAction::ChangeNumber(NumberChange::Increase(Count::Contextual), false)) ),
+        ( NSMAP, "<C-X>", edit_sel!(EditAction::ChangeNumber(NumberChange::Decrease(Count::Contextual), false)) ),
+        ( NSMAP, "u", editor!(EditorAction::History(HistoryAction::Undo(Count::Contextual))) ),
+        ( NSMAP, "U", editor!(EditorAction::History(HistoryAction::Redo(Count::Contextual))) ),
+        ( NSMAP, ".", act!(Action::Repeat(RepeatType::EditSequence)) ),
+
+        // Normal and Select mode keybindings for entering Insert mode.
+        ( NSMAP, "i", insert!(SelectionCursorChange::Beginning) ),
+        ( NSMAP, "a", insert!(SelectionCursorChange::End, MoveType::Column(MoveDir1D::Next, false), 1.into()) ),
+        ( NSMAP, "I", insert!(SelectionCursorChange::Beginning, MoveType::FirstWord(MoveDir1D::Next), 0.into()) ),
+        ( NSMAP, "A", insert!(SelectionCursorChange::End, MoveType::LinePos(MovePosition::End), 0.into()) ),
+        ( NSMAP, "o", open_lines!(MoveDir1D::Next) ),
+        ( NSMAP, "O", open_lines!(MoveDir1D::Previous) ),
+
+        // Normal and Select mode keybindings for view mode.
+        ( NSMAP, "zz", scrollcp!(MovePosition::Middle, Axis::Vertical) ),
+        ( NSMAP, "zc", scrollcp!(MovePosition::Middle, Axis::Vertical) ),
+        ( NSMAP, "zt", scrollcp!(MovePosition::Beginning, Axis::Vertical) ),
+        ( NSMAP, "zb", scrollcp!(MovePosition::End, Axis::Vertical) ),
+        ( NSMAP, "zj", scroll2d!(MoveDir2D::Down, ScrollSize::Cell) ),
+        ( NSMAP, "zk", scroll2d!(MoveDir2D::Up, ScrollSize::Cell) ),
+        ( NSMAP, "<C-D>", scroll2d!(MoveDir2D::Down, ScrollSize::HalfPage) ),
+        ( NSMAP, "<C-U>", scroll2d!(MoveDir2D::Up, ScrollSize::HalfPage) ),
+
+        // Normal and Select mode keybindings for window mode.
+        ( NSMAP, "<C-W>h", window_focus!(FocusChange::Direction2D(MoveDir2D::Left, Count::Contextual)) ),
+        ( NSMAP, "<C-W>j", window_focus!(FocusChange::Direction2D(MoveDir2D::Down, Count::Contextual)) ),
+        ( NSMAP, "<C-W>k", window_focus!(FocusChange::Direction2D(MoveDir2D::Up, Count::Contextual)) ),
+        ( NSMAP, "<C-W>l", window_focus!(FocusChange::Direction2D(MoveDir2D::Right, Count::Contextual)) ),
+        ( NSMAP, "<C-W>w", window_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Contextual, true)) ),
+        ( NSMAP, "<C-W>s", window_split!(Axis::Horizontal) ),
+        ( NSMAP, "<C-W>v", window_split!(Axis::Vertical) ),
+        ( NSMAP, "<C-W>q", window_close!(WindowTarget::Single) ),
+        ( NSMAP, "<C-W>o", window_close!(WindowTarget::AllBut) ),
+
+        // Normal and Select mode keybindings for the space menu.
+        ( NSMAP, "<Space>f", cmdbar_command!("edit ").description("open file") ),
+        ( NSMAP, "<Space>b", cmdbar_command!("buffer ").description("open buffer") ),
+        ( NSMAP, "<Space>s", cmdbar_search_word!(SelectionResizeStyle::Object, MoveDir1D::Next).description("search for symbol under cursor") ),
+        ( NSMAP, "<Space>wh", window_focus!(FocusChange::Direction2D(MoveDir2D::Left, Count::Contextual)).description("focus window to the left") ),
+        ( NSMAP, "<Space>wj", window_focus!(FocusChange::Direction2D(MoveDir2D::Down, Count::Contextual)).description("focus window below") ),
+        ( NSMAP, "<Space>wk", window_focus!(FocusChange::Direction2D(MoveDir2D::Up, Count::Contextual)).description("focus window above") ),
+        ( NSMAP, "<Space>wl", window_focus!(FocusChange::Direction2D(MoveDir2D::Right, Count::Contextual)).description("focus window to the right") ),
+        ( NSMAP, "<Space>ww", window_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Contextual, true)).description("focus next window") ),
+        ( NSMAP, "<Space>ws", window_split!(Axis::Horizontal).description("split window horizontally") ),
+        ( NSMAP, "<Space>wv", window_split!(Axis::Vertical).description("split window vertically") ),
+        ( NSMAP, "<Space>wq", window_close!(WindowTarget::Single).description("close window") ),
+        ( NSMAP, "<Space>wo", window_close!(WindowTarget::AllBut).description("close other windows") ),
+        ( NSMAP, "<Space>y", clipboard!(edit_sel!(EditAction::Yank)).description("yank to clipboard") ),
+        ( NSMAP, "<Space>p", clipboard!(paste_side!(SelectionCursorChange::End, MoveDir1D::Next)).description("paste clipboard after") ),
+        ( NSMAP, "<Space>P", clipboard!(paste_side!(SelectionCursorChange::Beginning, MoveDir1D::Previous)).description("paste clipboard before") ),
+        ( NSMAP, "<Space>R", clipboard!(paste!(PasteStyle::Replace)).description("replace with clipboard") ),
+        ( NSMAP, "<Space>k", act!(Action::KeywordLookup).description("look up keyword") ),
+        ( NSMAP, "<Space>/", cmdbar_search!(SelectionResizeStyle::Object, MoveDir1D::Next).description("search") ),
+
+        // Normal and Select mode keybindings for prompts.
+        ( NSMAP, ":", cmdbar_focus!(":", CommandType::Command, CommandAction::Execute(1.into())) ),
+
+        // Insert mode keybindings.
+        ( IMAP, "<Esc>", normal!() ),
+        ( IMAP, "<C-N>", complete!(CompletionType::Auto, CompletionSelection::List(MoveDir1D::Next), CompletionDisplay::List) ),
+        ( IMAP, "<C-P>", complete!(CompletionType::Auto, CompletionSelection::List(MoveDir1D::Previous), CompletionDisplay::List) ),
+        ( IMAP, "<C-R>{register}", paste!(PasteStyle::Cursor, 1) ),
+        ( IMAP, "<C-S>", editor!(EditorAction::History(HistoryAction::Checkpoint)) ),
+        ( IMAP, "<C-U>", erase!(MoveType::LinePos(MovePosition::Beginning), 0) ),
+        ( IMAP, "<C-K>", erase!(MoveType::LinePos(MovePosition::End), 0) ),
+        ( IMAP, "<Tab>", chartype!(Char::Single('\t')) ),
+        ( IMAP, "<Up>", edit!(EditAction::Motion, MoveType::Line(MoveDir1D::Previous)) ),
+        ( IMAP, "<Down>", edit!(EditAction::Motion, MoveType::Line(MoveDir1D::Next)) ),
+
+        // Insert and Command mode keybindings.
+        ( ICMAP, "<BS>", erase!(MoveType::Column(MoveDir1D::Previous, true)) ),
+        ( ICMAP, "<C-H>", erase!(MoveType::Column(MoveDir1D::Previous, true)) ),
+        ( ICMAP, "<Del>", erase!(MoveType::Column(MoveDir1D::Next, true)) ),
+        ( ICMAP, "<C-D>", erase!(MoveType::Column(MoveDir1D::Next, true)) ),
+        ( ICMAP, "<C-W>", erase!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
+        ( ICMAP, "<Left>", edit!(EditAction::Motion, MoveType::Column(MoveDir1D::Previous, true)) ),
+        ( ICMAP, "<Right>", edit!(EditAction::Motion, MoveType::Column(MoveDir1D::Next, true)) ),
+        ( ICMAP, "<Home>", edit!(EditAction::Motion, MoveType::LinePos(MovePosition::Beginning), 0) ),
+        ( ICMAP, "<End>", edit!(EditAction::Motion, MoveType::LinePos(MovePosition::End), 0) ),
+
+        // Command mode keybindings.
+        ( CMAP, "<Esc>", prompt!(PromptAction::Abort(false), HelixMode::Normal) ),
+        ( CMAP, "<C-C>", prompt!(PromptAction::Abort(false), HelixMode::Normal) ),
+        ( CMAP, "<Up>", prompt!(PromptAction::Recall(MoveDir1D::Previous, Count::Contextual, false)) ),
+        ( CMAP, "<Down>", prompt!(PromptAction::Recall(MoveDir1D::Next, Count::Contextual, false)) ),
+        ( CMAP, "<Tab>", complete!(CompletionType::Auto, CompletionSelection::List(MoveDir1D::Next), CompletionDisplay::Bar) ),
+        ( CMAP, "<S-Tab>", complete!(CompletionType::Auto, CompletionSelection::List(MoveDir1D::Previous), CompletionDisplay::Bar) ),
+    ].to_vec()
+}
+
+#[rustfmt::skip]
+fn default_pfxs<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, Option<InputStep<I>>)> {
+    [
+        // Normal and Select mode commands can be prefixed w/ a count.
+        ( NSMAP, "{count}", Some(iact!(InternalAction::SaveCounting)) ),
+
+        // Normal and Select mode commands can be prefixed w/ a register.
+        ( NSMAP, "\"{register}", None ),
+    ].to_vec()
+}
+
+#[rustfmt::skip]
+fn default_enter<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputStep<I>)> {
+    [
+        // <Enter> in Insert mode types a newline character.
+        ( IMAP, "<Enter>", chartype!(Char::Single('\n')) ),
+
+        // <Enter> in Command mode submits the prompt.
+        ( CMAP, "<Enter>", prompt!(PromptAction::Submit, HelixMode::Normal) ),
+    ].to_vec()
+}
+
+#[rustfmt::skip]
+fn submit_on_enter<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputStep<I>)> {
+    [
+        // <Enter> in Normal, Select and Insert mode submits the text.
+        ( NSIMAP, "<Enter>", prompt!(PromptAction::Submit) ),
+
+        // <Enter> in Command mode submits the prompt.
+        ( CMAP, "<Enter>", prompt!(PromptAction::Submit, HelixMode::Normal) ),
+    ].to_vec()
+}
+
+#[inline]
+fn add_prefix<I: ApplicationInfo>(
+    machine: &mut HelixMachine<TerminalKey, I>,
+    modes: &MappedModes,
+    keys: &str,
+    action: &Option<InputStep<I>>,
+) {
+    if let Ok((_, evs)) = parse(keys) {
+        for mode in modes.split() {
+            machine.add_prefix(mode, &evs, action);
+        }
+    } else {
+        panic!("invalid Helix keybinding: {}", keys);
+    }
+}
+
+#[inline]
+fn add_mapping<I: ApplicationInfo>(
+    machine: &mut HelixMachine<TerminalKey, I>,
+    modes: &MappedModes,
+    keys: &str,
+    action: &InputStep<I>,
+) {
+    if let Ok((_, evs)) = parse(keys) {
+        for mode in modes.split() {
+            machine.add_mapping(mode, &evs, action);
+        }
+    } else {
+        panic!("invalid Helix keybinding: {}", keys);
+    }
+}
+
+/// A configurable collection of Helix bindings that can be added to a [ModalMachine].
+#[derive(Debug)]
+pub struct HelixBindings<I: ApplicationInfo> {
+    prefixes: Vec<(MappedModes, &'static str, Option<InputStep<I>>)>,
+    mappings: Vec<(MappedModes, &'static str, InputStep<I>)>,
+    enter: Vec<(MappedModes, &'static str, InputStep<I>)>,
+}
+
+impl<I: ApplicationInfo> HelixBindings<I> {
+    /// Remap the Enter key in Normal, Select and Insert mode to [submit](PromptAction::Submit)
+    /// instead.
+    pub fn submit_on_enter(mut self) -> Self {
+        self.enter = submit_on_enter();
+        self
+    }
+}
+
+impl<I: ApplicationInfo> ShellBindings for HelixBindings<I> {
+    fn shell(self) -> Self {
+        self.submit_on_enter()
+    }
+}
+
+impl<I: ApplicationInfo> Default for HelixBindings<I> {
+    fn default() -> Self {
+        HelixBindings {
+            prefixes: default_pfxs(),
+            mappings: default_keys(),
+            enter: default_enter(),
+        }
+    }
+}
+
+impl<I: ApplicationInfo> InputBindings<TerminalKey, InputStep<I>> for HelixBindings<I> {
+    fn setup(&self, machine: &mut HelixMachine<TerminalKey, I>) {
+        for (modes, keys, action) in self.prefixes.iter() {
+            add_prefix(machine, modes, keys, action);
+        }
+
+        for (modes, keys, action) in self.mappings.iter() {
+            add_mapping(machine, modes, keys, action);
+        }
+
+        for (modes, keys, action) in self.enter.iter() {
+            add_mapping(machine, modes, keys, action);
+        }
+    }
+}
+
+/// Manage Helix keybindings and modes.
+pub type HelixMachine<Key, T = EmptyInfo> = ModalMachine<Key, InputStep<T>>;
+
+/// Create a new [HelixMachine] populated with standard Helix keys.
+pub fn default_helix_keys<I: ApplicationInfo>() -> HelixMachine<TerminalKey, I> {
+    ModalMachine::from_bindings::<HelixBindings<I>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editing::context::{EditContext, EditContextBuilder, MatchedKey};
+    use crate::keybindings::{BindingMachine, KeyHint};
+    use crossterm::event::{KeyCode, KeyEvent};
+
+    const CHECKPOINT: Action = Action::Editor(EditorAction::History(HistoryAction::Checkpoint));
+    const REDUCE: Action = Action::Editor(EditorAction::Selection(SelectionAction::Resize(
+        SelectionResizeStyle::Restart,
+        EditTarget::CurrentPosition,
+    )));
+
+    macro_rules! assert_normal {
+        ($mm: expr) => {
+            assert_pop2!($mm, CHECKPOINT, mkctx());
+            assert_eq!($mm.mode(), HelixMode::Normal);
+        };
+    }
+
+    fn resize(style: SelectionResizeStyle, target: EditTarget) -> Action {
+        Action::from(SelectionAction::Resize(style, target))
+    }
+
+    fn edit(ea: EditAction, target: EditTarget) -> Action {
+        Action::from(EditorAction::Edit(Specifier::Exact(ea), target))
+    }
+
+    fn mkctx() -> EditContext {
+        EditContextBuilder::default()
+            .target_shape(Some(TargetShape::CharWise))
+            .last_column(true)
+            .search_incremental(true)
+            .build()
+    }
+
+    #[test]
+    fn test_select_mode() {
+        let mut vm: HelixMachine<TerminalKey> = default_helix_keys();
+        let ctx = mkctx();
+
+        // Start out in Normal mode.
+        assert_eq!(vm.mode(), HelixMode::Normal);
+
+        // "w" starts a new selection up to the next word.
+        let mt = MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next);
+        let select = resize(
+            SelectionResizeStyle::Restart,
+            EditTarget::Motion(mt.clone(), Count::Contextual),
+        );
+        vm.input_key(key!('w'));
+        assert_pop1!(vm, select, ctx);
+        assert_normal!(vm);
+
+        // "v" enters Select mode.
+        vm.input_key(key!('v'));
+        assert_pop2!(vm, Action::NoOp, ctx);
+        assert_eq!(vm.mode(), HelixMode::Select);
+
+        // "w" now extends the current selection.
+        let extend =
+            resize(SelectionResizeStyle::Extend, EditTarget::Motion(mt, Count::Contextual));
+        vm.input_key(key!('w'));
+        assert_pop2!(vm, extend, ctx);
+        assert_eq!(vm.mode(), HelixMode::Select);
+
+        // "l" also extends instead of moving.
+        let mt = MoveType::Column(MoveDir1D::Next, false);
+        let extend =
+            resize(SelectionResizeStyle::Extend, EditTarget::Motion(mt, Count::Contextual));
+        vm.input_key(key!('l'));
+        assert_pop2!(vm, extend, ctx);
+        assert_eq!(vm.mode(), HelixMode::Select);
+
+        // "d" deletes the selection, and returns to Normal mode.
+        vm.input_key(key!('d'));
+        assert_pop1!(vm, edit(EditAction::Delete, EditTarget::Selection), ctx);
+        assert_pop1!(vm, REDUCE, ctx);
+        assert_normal!(vm);
+
+        // "v" followed by "v" returns to Normal mode without touching the selections.
+        vm.input_key(key!('v'));
+        assert_pop2!(vm, Action::NoOp, ctx);
+        assert_eq!(vm.mode(), HelixMode::Select);
+
+        vm.input_key(key!('v'));
+        assert_pop1!(vm, Action::NoOp, ctx);
+        assert_normal!(vm);
+    }
+
+    #[test]
+    fn test_goto_mode() {
+        let mut vm: HelixMachine<TerminalKey> = default_helix_keys();
+        let ctx = mkctx();
+
+        // "gh" moves to the start of the line, and reduces the selections.
+        let mt = MoveType::LinePos(MovePosition::Beginning);
+        let extend = resize(SelectionResizeStyle::Extend, EditTarget::Motion(mt, 0.into()));
+        vm.input_key(key!('g'));
+        vm.input_key(key!('h'));
+        assert_pop1!(vm, extend, ctx);
+        assert_pop1!(vm, REDUCE, ctx);
+        assert_normal!(vm);
+
+        // "ge" moves to the end of the buffer.
+        let target =
+            EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::End, Count::Contextual);
+        vm.input_key(key!('g'));
+        vm.input_key(key!('e'));
+        assert_pop1!(vm, resize(SelectionResizeStyle::Extend, target), ctx);
+        assert_pop1!(vm, REDUCE, ctx);
+        assert_normal!(vm);
+    }
+
+    #[test]
+    fn test_match_mode() {
+        let mut vm: HelixMachine<TerminalKey> = default_helix_keys();
+        let mut ctx = mkctx();
+
+        // "mm" moves to the matching bracket.
+        let target = EditTarget::Motion(MoveType::ItemMatch, Count::Contextual);
+        vm.input_key(key!('m'));
+        vm.input_key(key!('m'));
+        assert_pop1!(vm, resize(SelectionResizeStyle::Extend, target), ctx);
+        assert_pop1!(vm, REDUCE, ctx);
+        assert_normal!(vm);
+
+        // "ms(" surrounds the selections with parentheses.
+        let act = edit(EditAction::Surround(SurroundChange::Add), EditTarget::Selection);
+        ctx.replace_char = Some('('.into());
+        ctx.typed = Some('('.into());
+        ctx.matched_keys = vec![MatchedKey::String("(".into())];
+        vm.input_key(key!('m'));
+        vm.input_key(key!('s'));
+        vm.input_key(key!('('));
+        assert_pop1!(vm, act, ctx);
+        assert_normal!(vm);
+
+        // "mr([" replaces the surrounding parentheses with brackets.
+        let target = EditTarget::Range(RangeType::Bracketed('(', ')'), true, Count::Contextual);
+        let act = edit(EditAction::Surround(SurroundChange::Replace), target);
+        ctx.replace_char = Some('['.into());
+        ctx.typed = Some('['.into());
+        ctx.matched_keys = vec![MatchedKey::String("[".into())];
+        vm.input_key(key!('m'));
+        vm.input_key(key!('r'));
+        vm.input_key(key!('('));
+        vm.input_key(key!('['));
+        assert_pop1!(vm, act, ctx);
+        assert_normal!(vm);
+
+        // "md[" deletes the surrounding brackets.
+        let ctx = mkctx();
+        let target = EditTarget::Range(RangeType::Bracketed('[', ']'), true, Count::Contextual);
+        let act = edit(EditAction::Surround(SurroundChange::Delete), target);
+        vm.input_key(key!('m'));
+        vm.input_key(key!('d'));
+        vm.input_key(key!('['));
+        assert_pop1!(vm, act, ctx);
+        assert_normal!(vm);
+
+        // "mi(" selects the text within the parentheses.
+        let target = EditTarget::Range(RangeType::Bracketed('(', ')'), false, Count::Contextual);
+        vm.input_key(key!('m'));
+        vm.input_key(key!('i'));
+        vm.input_key(key!('('));
+        assert_pop1!(vm, resize(SelectionResizeStyle::Object, target), ctx);
+        assert_normal!(vm);
+    }
+
+    #[test]
+    fn test_space_menu() {
+        let mut vm: HelixMachine<TerminalKey> = default_helix_keys();
+        let mut ctx = mkctx();
+
+        // "<Space>y" yanks the selections into the clipboard.
+        ctx.register = Some(Register::SelectionClipboard);
+        vm.input_key(key!(' '));
+        vm.input_key(key!('y'));
+        assert_pop1!(vm, edit(EditAction::Yank, EditTarget::Selection), ctx);
+        assert_normal!(vm);
+
+        // "<Space>wv" splits the window.
+        let ctx = mkctx();
+        let act = WindowAction::Split(
+            OpenTarget::Current,
+            Axis::Vertical,
+            MoveDir1D::Previous,
+            Count::Exact(1),
+        );
+        vm.input_key(key!(' '));
+        vm.input_key(key!('w'));
+        vm.input_key(key!('v'));
+        assert_pop1!(vm, Action::Window(act), ctx);
+        assert_normal!(vm);
+
+        // "<Space>k" looks up the keyword under the cursor.
+        vm.input_key(key!(' '));
+        vm.input_key(key!('k'));
+        assert_pop1!(vm, Action::KeywordLookup, ctx);
+        assert_normal!(vm);
+
+        // "<Space>f" starts typing an ":edit" command in place of the file picker.
+        let focus = CommandBarAction::Focus(
+            ":".into(),
+            CommandType::Command,
+            Box::new(CommandAction::Execute(1.into()).into()),
+        );
+        let edit = InsertTextAction::Transcribe("edit ".into(), MoveDir1D::Previous, 1.into());
+        vm.input_key(key!(' '));
+        vm.input_key(key!('f'));
+        assert_pop1!(vm, Action::from(focus), ctx);
+        assert_pop1!(vm, Action::from(edit), ctx);
+        assert_eq!(vm.mode(), HelixMode::Command);
+
+        // "<Space>s" searches for the word under the cursor in place of the symbol picker.
+        let mut vm: HelixMachine<TerminalKey> = default_helix_keys();
+        let target = EditTarget::Search(SearchType::Regex, MoveDirMod::Same, Count::Contextual);
+        let focus = CommandBarAction::Focus(
+            "/".into(),
+            CommandType::Search,
+            Box::new(resize(SelectionResizeStyle::Object, target)),
+        );
+        let word = PromptAction::InsertCursorWord(WordStyle::AlphaNum);
+        vm.input_key(key!(' '));
+        vm.input_key(key!('s'));
+        assert_pop1!(vm, Action::from(focus), ctx);
+        assert_pop1!(vm, Action::from(word), ctx);
+        assert_eq!(vm.mode(), HelixMode::Command);
+    }
+
+    #[test]
+    fn test_space_menu_hints() {
+        let mut vm: HelixMachine<TerminalKey> = default_helix_keys();
+        let hint = |key: &str, desc: &str| KeyHint { key: key.into(), desc: desc.into() };
+
+        vm.input_key(key!(' '));
+
+        let hints = vm.pending_hints();
+        assert!(hints.contains(&hint("f", "open file")));
+        assert!(hints.contains(&hint("b", "open buffer")));
+        assert!(hints.contains(&hint("s", "search for symbol under cursor")));
+        assert!(hints.contains(&hint("w", "+prefix")));
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut vm: HelixMachine<TerminalKey> = default_helix_keys();
+        let mut ctx = mkctx();
+
+        // "a" moves each cursor past the end of its selection, and enters Insert mode.
+        let end = SelectionAction::CursorSet(SelectionCursorChange::End);
+        let mt = MoveType::Column(MoveDir1D::Next, false);
+        ctx.insert_style = Some(InsertStyle::Insert);
+        vm.input_key(key!('a'));
+        assert_pop1!(vm, Action::from(end), ctx);
+        assert_pop1!(vm, edit(EditAction::Motion, EditTarget::Motion(mt, 1.into())), ctx);
+
+        // Entering Insert mode drops the selections.
+        ctx.target_shape = None;
+        assert_pop2!(vm, edit(EditAction::Motion, EditTarget::CurrentPosition), ctx);
+        assert_eq!(vm.mode(), HelixMode::Insert);
+
+        // <Esc> returns to Normal mode, leaving a single character selected.
+        vm.input_key(key!(KeyCode::Esc));
+        assert_pop1!(vm, Action::NoOp, ctx);
+
+        let ctx = mkctx();
+        assert_pop1!(vm, REDUCE, ctx);
+        assert_pop2!(vm, CHECKPOINT, ctx);
+        assert_eq!(vm.mode(), HelixMode::Normal);
+    }
+}
@@ -0,0 +1,381 @@
+//! # Helix-like User Interfaces
+//!
+//! ## Overview
+//!
+//! This module contains components to help with building applications that mimic Helix's user
+//! interfaces, where text is selected first and then acted upon.
+//!
+use std::marker::PhantomData;
+
+use crate::{
+    actions::{Action, EditAction, EditorAction, HistoryAction, InsertTextAction, SelectionAction},
+    key::TerminalKey,
+    keybindings::{
+        EdgeEvent,
+        InputKey,
+        InputKeyState,
+        InputState,
+        Mode,
+        ModeKeys,
+        ModeSequence,
+        SequenceStatus,
+    },
+    prelude::*,
+    util::{keycode_to_num, option_muladd_usize},
+};
+
+use crate::editing::{
+    application::{ApplicationInfo, EmptyInfo},
+    context::{EditContext, EditContextBuilder},
+};
+
+use super::{CharacterContext, CommonKeyClass, KeyContext};
+
+pub mod keybindings;
+
+/// Helix's modes
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum HelixMode {
+    /// Normal mode keypresses.
+    #[default]
+    Normal,
+
+    /// Select mode keypresses.
+    Select,
+
+    /// Insert mode keypresses.
+    Insert,
+
+    /// Prompt keypresses.
+    Command,
+}
+
+impl<I: ApplicationInfo> Mode<Action<I>, HelixState<I>> for HelixMode {
+    fn enter(&self, prev: Self, ctx: &mut HelixState<I>) -> Vec<Action<I>> {
+        match self {
+            HelixMode::Normal => {
+                // Every motion in Normal mode acts on the current selections.
+                ctx.persist.shape = Some(TargetShape::CharWise);
+                ctx.persist.insert = None;
+
+                match prev {
+                    HelixMode::Normal => {
+                        return vec![HistoryAction::Checkpoint.into()];
+                    },
+                    HelixMode::Select => {
+                        // Leaving Select mode keeps the current selections.
+                        return vec![HistoryAction::Checkpoint.into()];
+                    },
+                    HelixMode::Insert => {
+                        // Leave a single character selected under each cursor.
+                        let reduce = SelectionAction::Resize(
+                            SelectionResizeStyle::Restart,
+                            EditTarget::CurrentPosition,
+                        );
+
+                        return vec![reduce.into(), HistoryAction::Checkpoint.into()];
+                    },
+                    HelixMode::Command => {
+                        // Prompts act on the selections once submitted, so leave them alone.
+                        return vec![];
+                    },
+                }
+            },
+            HelixMode::Select => {
+                // Motions in Select mode extend the current selections.
+                ctx.persist.shape = Some(TargetShape::CharWise);
+                ctx.persist.insert = None;
+
+                return vec![];
+            },
+            HelixMode::Insert => {
+                ctx.persist.shape = None;
+
+                if prev == HelixMode::Insert {
+                    return vec![];
+                }
+
+                // Drop the selections, and start typing at each cursor.
+                let action = EditAction::Motion.into();
+                let target = EditTarget::CurrentPosition;
+
+                return vec![EditorAction::Edit(action, target).into()];
+            },
+            HelixMode::Command => {
+                ctx.persist.shape = None;
+                ctx.persist.insert = Some(InsertStyle::Insert);
+
+                return vec![];
+            },
+        }
+    }
+
+    fn show(&self, _: &HelixState<I>) -> Option<String> {
+        match self {
+            HelixMode::Normal => None,
+            HelixMode::Select => Some("select".into()),
+            HelixMode::Insert => Some("insert".into()),
+            HelixMode::Command => None,
+        }
+    }
+}
+
+impl<I: ApplicationInfo> ModeSequence<RepeatType, Action<I>, HelixState<I>> for HelixMode {
+    fn sequences(
+        &self,
+        action: &Action<I>,
+        ctx: &EditContext,
+    ) -> Vec<(RepeatType, SequenceStatus)> {
+        let motion = match self {
+            HelixMode::Command => {
+                // Don't track anything done at a prompt.
+                return vec![];
+            },
+            HelixMode::Normal | HelixMode::Select => {
+                if ctx.get_insert_style().is_some() {
+                    SequenceStatus::Restart
+                } else {
+                    SequenceStatus::Break
+                }
+            },
+            HelixMode::Insert => SequenceStatus::Break,
+        };
+
+        return vec![
+            (RepeatType::EditSequence, action.is_edit_sequence(motion, ctx)),
+            (RepeatType::LastAction, action.is_last_action(ctx)),
+            (RepeatType::LastSelection, action.is_last_selection(ctx)),
+        ];
+    }
+}
+
+impl<I: ApplicationInfo> ModeKeys<TerminalKey, Action<I>, HelixState<I>> for HelixMode {
+    fn unmapped(&self, ke: &TerminalKey, _: &mut HelixState<I>) -> (Vec<Action<I>>, Option<Self>) {
+        match self {
+            HelixMode::Normal | HelixMode::Select => {
+                return (vec![], None);
+            },
+            HelixMode::Insert | HelixMode::Command => {
+                if let Some(c) = ke.get_char() {
+                    let ch = Char::Single(c).into();
+                    let it = InsertTextAction::Type(ch, MoveDir1D::Previous, 1.into());
+
+                    (vec![it.into()], None)
+                } else {
+                    (vec![], None)
+                }
+            },
+        }
+    }
+}
+
+/// This is the context specific to an action, and gets reset every time a full sequence of
+/// keybindings is pressed.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct ActionContext {
+    // Prefix arguments to key sequences.
+    pub(crate) count: Option<usize>,
+    pub(crate) counting: Option<usize>,
+    pub(crate) register: Option<Register>,
+
+    // Characters typed after a key sequence.
+    pub(crate) charsearch: Option<(MoveDir1D, bool, Char)>,
+    pub(crate) replace: Option<Char>,
+}
+
+/// This is the context preserved across actions, and changes either with the mode, or through
+/// future keybinding sequences.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct PersistentContext {
+    pub(crate) regexsearch_dir: MoveDir1D,
+    pub(crate) shape: Option<TargetShape>,
+    pub(crate) insert: Option<InsertStyle>,
+}
+
+impl Default for PersistentContext {
+    fn default() -> Self {
+        Self {
+            regexsearch_dir: MoveDir1D::Next,
+            shape: Some(TargetShape::CharWise),
+            insert: None,
+        }
+    }
+}
+
+/// This wraps both action specific context, and persistent context.
+#[derive(Debug, Eq, PartialEq)]
+pub struct HelixState<I: ApplicationInfo = EmptyInfo> {
+    pub(crate) action: ActionContext,
+    pub(crate) persist: PersistentContext,
+    pub(self) ch: CharacterContext,
+    pub(self) key: KeyContext,
+    _p: PhantomData<I>,
+}
+
+impl<I: ApplicationInfo> Clone for HelixState<I> {
+    fn clone(&self) -> Self {
+        Self {
+            action: self.action.clone(),
+            persist: self.persist.clone(),
+            ch: self.ch.clone(),
+            key: self.key.clone(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<I: ApplicationInfo> InputState for HelixState<I> {
+    type Output = EditContext;
+
+    fn merge(original: EditContext, overrides: &EditContext) -> EditContext {
+        let mut builder = EditContextBuilder::from(original);
+
+        // Allow overriding the two fields that can prefix keybindings.
+        if let n @ Some(_) = overrides.count {
+            builder = builder.count(n);
+        }
+
+        if let reg @ Some(_) = overrides.get_register() {
+            builder = builder.register(reg);
+        }
+
+        builder.build()
+    }
+
+    fn reset(&mut self) {
+        self.action = ActionContext::default();
+    }
+
+    fn take(&mut self) -> Self::Output {
+        let state = Self {
+            persist: self.persist.clone(),
+            action: std::mem::take(&mut self.action),
+            ch: std::mem::take(&mut self.ch),
+            key: std::mem::take(&mut self.key),
+            _p: PhantomData,
+        };
+
+        EditContext::from(state)
+    }
+}
+
+impl<I: ApplicationInfo> From<HelixState<I>> for EditContext {
+    fn from(ctx: HelixState<I>) -> Self {
+        EditContextBuilder::default()
+            .count(ctx.action.count)
+            .typed_char(ctx.ch.get_typed())
+            .replace_char(ctx.action.replace)
+            .search_char(ctx.action.charsearch)
+            .search_regex_dir(ctx.persist.regexsearch_dir)
+            .target_shape(ctx.persist.shape)
+            .insert_style(ctx.persist.insert)
+            .last_column(true)
+            .register(ctx.action.register)
+            .register_append(false)
+            .search_incremental(true)
+            .matched_keys(ctx.key.keys)
+            .build()
+    }
+}
+
+impl<I: ApplicationInfo> Default for HelixState<I> {
+    fn default() -> Self {
+        Self {
+            action: ActionContext::default(),
+            persist: PersistentContext::default(),
+            ch: CharacterContext::default(),
+            key: KeyContext::default(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<I: ApplicationInfo> InputKeyState<TerminalKey, CommonKeyClass> for HelixState<I> {
+    fn event(
+        &mut self,
+        ev: &EdgeEvent<TerminalKey, CommonKeyClass>,
+        ke: &TerminalKey,
+        step_number: usize,
+    ) {
+        match ev {
+            EdgeEvent::Key(_) | EdgeEvent::Fallthrough => {
+                // Do nothing.
+            },
+            EdgeEvent::Class(CommonKeyClass::Count) => {
+                if let Some(n) = keycode_to_num(ke, 10) {
+                    let new = option_muladd_usize(&self.action.counting, 10, n as usize);
+
+                    self.action.counting = Some(new);
+                    self.key.update_numeric(n, step_number);
+                }
+            },
+            EdgeEvent::Class(CommonKeyClass::Register) => {
+                if let Some(reg) = ke.get_char().and_then(char_to_register) {
+                    self.action.register = Some(reg);
+                }
+            },
+
+            // Track the characters given to "f", "t", "r", etc.
+            EdgeEvent::Any => {
+                self.ch.any = Some(*ke);
+
+                if let Some(ch) = ke.get_literal_char() {
+                    self.key.update_string(ch, step_number);
+                }
+            },
+
+            // Other classes are currently unused.
+            EdgeEvent::Class(_) => {
+                // Do nothing
+            },
+        }
+    }
+}
+
+fn char_to_register(c: char) -> Option<Register> {
+    let r = match c {
+        // Letters
+        c @ 'a'..='z' => Register::Named(c),
+        c @ 'A'..='Z' => Register::Named(c.to_ascii_lowercase()),
+
+        // Special Characters
+        '"' => Register::Unnamed,
+        '_' => Register::Blackhole,
+        '%' => Register::CurBufName,
+        '.' => Register::LastInserted,
+        '/' => Register::LastCommand(CommandType::Search),
+        ':' => Register::LastCommand(CommandType::Command),
+        '*' => Register::SelectionPrimary,
+        '+' => Register::SelectionClipboard,
+
+        _ => return None,
+    };
+
+    return Some(r);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_show() {
+        let ctx: HelixState = HelixState::default();
+
+        assert_eq!(HelixMode::Normal.show(&ctx), None);
+        assert_eq!(HelixMode::Select.show(&ctx), Some("select".into()));
+        assert_eq!(HelixMode::Insert.show(&ctx), Some("insert".into()));
+        assert_eq!(HelixMode::Command.show(&ctx), None);
+    }
+
+    #[test]
+    fn test_char_to_register() {
+        assert_eq!(char_to_register('a'), Some(Register::Named('a')));
+        assert_eq!(char_to_register('A'), Some(Register::Named('a')));
+        assert_eq!(char_to_register('"'), Some(Register::Unnamed));
+        assert_eq!(char_to_register('_'), Some(Register::Blackhole));
+        assert_eq!(char_to_register('/'), Some(Register::LastCommand(CommandType::Search)));
+        assert_eq!(char_to_register('+'), Some(Register::SelectionClipboard));
+        assert_eq!(char_to_register('0'), None);
+    }
+}
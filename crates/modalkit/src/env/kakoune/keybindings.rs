@@ -0,0 +1,1007 @@
+//! # Kakoune Keybindings
+//!
+//! ## Overview
+//!
+//! This module handles mapping the keybindings used in Kakoune onto the [Action] type.
+//!
+//! Normal mode always operates on selections: movement keys like `w` or `f` start new selections,
+//! their shifted variants extend the existing ones, and editing keys like `d` or `c` act on
+//! whatever is currently selected.
+//!
+//! ## Divergences
+//!
+//! The keybindings here diverge from the defaults in Kakoune in the following ways:
+//!
+//! - `w` and `b` select up to the start of the next or previous word, instead of selecting the
+//!   word and its trailing whitespace
+//! - `x` selects the full lines covered by each selection, but doesn't extend onto the next line
+//!   when pressed again
+//! - `s`, `S`, `<A-k>` and `<A-K>` use the search prompt, and save their regular expression as
+//!   the last search
+//! - Leaving Insert mode reduces each selection to its cursor
+//!
+use bitflags::bitflags;
+
+use crate::{
+    actions::{
+        Action,
+        CommandAction,
+        CommandBarAction,
+        CursorAction,
+        EditAction,
+        EditorAction,
+        HistoryAction,
+        InsertTextAction,
+        PromptAction,
+        SelectionAction,
+    },
+    editing::application::{ApplicationInfo, EmptyInfo},
+    prelude::*,
+};
+
+use super::{
+    super::{keyparse::parse, CommonKeyClass, ShellBindings},
+    KakouneMode,
+    KakouneState,
+};
+
+use crate::key::TerminalKey;
+use crate::keybindings::{InputBindings, ModalMachine, Step};
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    struct MappedModes: u32 {
+        const N = 0b0000000000000001;
+        const I = 0b0000000000000010;
+        const C = 0b0000000000000100;
+
+        const NI = MappedModes::N.bits() | MappedModes::I.bits();
+        const IC = MappedModes::I.bits() | MappedModes::C.bits();
+    }
+}
+
+const NIMAP: MappedModes = MappedModes::NI;
+const ICMAP: MappedModes = MappedModes::IC;
+const NMAP: MappedModes = MappedModes::N;
+const IMAP: MappedModes = MappedModes::I;
+const CMAP: MappedModes = MappedModes::C;
+
+impl MappedModes {
+    pub fn split(&self) -> Vec<KakouneMode> {
+        let mut modes = Vec::new();
+
+        if self.contains(MappedModes::N) {
+            modes.push(KakouneMode::Normal);
+        }
+
+        if self.contains(MappedModes::I) {
+            modes.push(KakouneMode::Insert);
+        }
+
+        if self.contains(MappedModes::C) {
+            modes.push(KakouneMode::Command);
+        }
+
+        return modes;
+    }
+}
+
+#[derive(Clone, Debug)]
+enum InternalAction {
+    SaveCounting,
+    SetCharSearch(MoveDir1D, bool),
+    SetInsertStyle(InsertStyle),
+    SetRegister(Register),
+    SetReplaceChar,
+    SetSearchRegexParams(MoveDir1D),
+}
+
+impl InternalAction {
+    pub fn run<I: ApplicationInfo>(&self, ctx: &mut KakouneState<I>) {
+        match self {
+            InternalAction::SaveCounting => {
+                match ctx.action.count {
+                    None => {
+                        ctx.action.count = ctx.action.counting;
+                        ctx.action.counting = None;
+                    },
+                    Some(prev) => {
+                        ctx.action.count =
+                            Some(prev.saturating_mul(ctx.action.counting.unwrap_or(1)));
+                        ctx.action.counting = None;
+                    },
+                }
+            },
+            InternalAction::SetCharSearch(dir, inclusive) => {
+                ctx.action.charsearch = ctx.ch.get_typed().map(|c| (*dir, *inclusive, c));
+            },
+            InternalAction::SetInsertStyle(style) => {
+                ctx.persist.insert = Some(*style);
+            },
+            InternalAction::SetRegister(reg) => {
+                ctx.action.register = Some(reg.clone());
+            },
+            InternalAction::SetReplaceChar => {
+                ctx.action.replace = ctx.ch.get_typed();
+            },
+            InternalAction::SetSearchRegexParams(dir) => {
+                ctx.persist.regexsearch_dir = *dir;
+            },
+        }
+    }
+}
+
+/// Description of actions to take after an input sequence.
+#[derive(Debug, Default)]
+pub struct InputStep<I: ApplicationInfo> {
+    internal: Vec<InternalAction>,
+    external: Vec<Action<I>>,
+    nextm: Option<KakouneMode>,
+}
+
+impl<I: ApplicationInfo> InputStep<I> {
+    /// Create a new step that input keys can map to.
+    pub fn new() -> Self {
+        InputStep { internal: vec![], external: vec![], nextm: None }
+    }
+
+    /// Set the [KakouneMode] to switch to after this step.
+    pub fn goto(mut self, mode: KakouneMode) -> Self {
+        self.nextm = Some(mode);
+        self
+    }
+
+    /// Set the [actions](Action) that this step produces.
+    pub fn actions(mut self, acts: Vec<Action<I>>) -> Self {
+        self.external = acts;
+        self
+    }
+}
+
+impl<I: ApplicationInfo> Clone for InputStep<I> {
+    fn clone(&self) -> Self {
+        Self {
+            internal: self.internal.clone(),
+            external: self.external.clone(),
+            nextm: self.nextm,
+        }
+    }
+}
+
+impl<I: ApplicationInfo> Step<TerminalKey> for InputStep<I> {
+    type A = Action<I>;
+    type State = KakouneState<I>;
+    type M = KakouneMode;
+    type Class = CommonKeyClass;
+    type Sequence = RepeatType;
+
+    fn is_unmapped(&self) -> bool {
+        match self {
+            InputStep { internal, external, nextm: None } => {
+                internal.is_empty() && external.is_empty()
+            },
+            _ => false,
+        }
+    }
+
+    fn fallthrough(&self) -> Option<Self::M> {
+        None
+    }
+
+    fn step(&self, ctx: &mut KakouneState<I>) -> (Vec<Action<I>>, Option<Self::M>) {
+        for iact in self.internal.iter() {
+            iact.run(ctx);
+        }
+
+        return (self.external.clone(), self.nextm);
+    }
+}
+
+macro_rules! act {
+    ($ext: expr) => {
+        isv!(vec![], vec![$ext])
+    };
+    ($ext: expr, $ns: expr) => {
+        isv!(vec![], vec![$ext], $ns)
+    };
+}
+
+macro_rules! acts {
+    ( $( $ext: expr ),+ ) => {
+        isv!(vec![], vec![ $( Action::from($ext) ),+ ])
+    };
+}
+
+macro_rules! iact {
+    ($int: expr) => {
+        isv!(vec![$int], vec![])
+    };
+}
+
+macro_rules! isv {
+    () => {
+        InputStep { internal: vec![], external: vec![], nextm: None }
+    };
+    ($ints: expr, $exts: expr) => {
+        InputStep { internal: $ints, external: $exts, nextm: None }
+    };
+    ($ints: expr, $exts: expr, $ns: expr) => {
+        InputStep { internal: $ints, external: $exts, nextm: Some($ns) }
+    };
+}
+
+macro_rules! is {
+    ($int: expr, $ext: expr) => {
+        isv!(vec![$int], vec![$ext.into()])
+    };
+    ($int: expr, $ext: expr, $ns: expr) => {
+        isv!(vec![$int], vec![$ext.into()], $ns)
+    };
+}
+
+macro_rules! blackhole {
+    ($act: expr) => {
+        is!(InternalAction::SetRegister(Register::Blackhole), $act)
+    };
+}
+
+macro_rules! cursor {
+    ($ca: expr) => {
+        editor!(EditorAction::Cursor($ca))
+    };
+}
+
+macro_rules! reduce {
+    () => {
+        SelectionAction::Resize(SelectionResizeStyle::Restart, EditTarget::CurrentPosition)
+    };
+}
+
+macro_rules! move_target {
+    ($target: expr) => {
+        acts!(SelectionAction::Resize(SelectionResizeStyle::Extend, $target), reduce!())
+    };
+}
+
+macro_rules! move_to {
+    ($mt: expr) => {
+        move_target!(EditTarget::Motion($mt, Count::Contextual))
+    };
+    ($mt: expr, $c: expr) => {
+        move_target!(EditTarget::Motion($mt, $c))
+    };
+}
+
+macro_rules! select_to {
+    ($mt: expr) => {
+        selection!(SelectionAction::Resize(
+            SelectionResizeStyle::Restart,
+            EditTarget::Motion($mt, Count::Contextual)
+        ))
+    };
+}
+
+macro_rules! extend_target {
+    ($target: expr) => {
+        selection!(SelectionAction::Resize(SelectionResizeStyle::Extend, $target))
+    };
+}
+
+macro_rules! extend_to {
+    ($mt: expr) => {
+        extend_target!(EditTarget::Motion($mt, Count::Contextual))
+    };
+    ($mt: expr, $c: expr) => {
+        extend_target!(EditTarget::Motion($mt, $c))
+    };
+}
+
+macro_rules! select_object {
+    ($rt: expr, $inclusive: expr) => {
+        selection!(SelectionAction::Resize(
+            SelectionResizeStyle::Object,
+            EditTarget::Range($rt, $inclusive, Count::Contextual)
+        ))
+    };
+}
+
+macro_rules! charsearch {
+    ($style: expr, $dir: expr, $inclusive: expr) => {
+        is!(
+            InternalAction::SetCharSearch($dir, $inclusive),
+            Action::from(SelectionAction::Resize(
+                $style,
+                EditTarget::Search(SearchType::Char(true), MoveDirMod::Same, Count::Contextual)
+            ))
+        )
+    };
+}
+
+macro_rules! edit_then_reduce {
+    ($ea: expr) => {
+        acts!(EditorAction::Edit(Specifier::Exact($ea), EditTarget::Selection), reduce!())
+    };
+}
+
+macro_rules! change {
+    () => {
+        isv!(
+            vec![InternalAction::SetInsertStyle(InsertStyle::Insert)],
+            vec![EditorAction::Edit(EditAction::Delete.into(), EditTarget::Selection).into()],
+            KakouneMode::Insert
+        )
+    };
+}
+
+macro_rules! insert {
+    ($cursor: expr) => {
+        isv!(
+            vec![InternalAction::SetInsertStyle(InsertStyle::Insert)],
+            vec![SelectionAction::CursorSet($cursor).into()],
+            KakouneMode::Insert
+        )
+    };
+    ($cursor: expr, $mt: expr, $c: expr) => {
+        isv!(
+            vec![InternalAction::SetInsertStyle(InsertStyle::Insert)],
+            vec![
+                SelectionAction::CursorSet($cursor).into(),
+                EditorAction::Edit(EditAction::Motion.into(), EditTarget::Motion($mt, $c)).into()
+            ],
+            KakouneMode::Insert
+        )
+    };
+}
+
+macro_rules! open_lines {
+    ($dir: expr) => {
+        isv!(
+            vec![InternalAction::SetInsertStyle(InsertStyle::Insert)],
+            vec![InsertTextAction::OpenLine(TargetShape::LineWise, $dir, 1.into()).into()],
+            KakouneMode::Insert
+        )
+    };
+}
+
+macro_rules! paste_side {
+    ($cursor: expr, $dir: expr) => {
+        acts!(
+            SelectionAction::CursorSet($cursor),
+            InsertTextAction::Paste(PasteStyle::Side($dir), Count::Contextual)
+        )
+    };
+}
+
+macro_rules! cmdbar_focus {
+    ($prompt: expr, $type: expr, $act: expr) => {
+        cmdbar!(
+            CommandBarAction::Focus($prompt.into(), $type, Box::new(Action::from($act))),
+            KakouneMode::Command
+        )
+    };
+}
+
+macro_rules! cmdbar_select {
+    ($prompt: expr, $act: expr) => {
+        cmdbar_focus!($prompt, CommandType::Search, $act)
+    };
+}
+
+macro_rules! cmdbar_search {
+    ($style: expr, $dir: expr) => {
+        is!(
+            InternalAction::SetSearchRegexParams($dir),
+            Action::CommandBar(CommandBarAction::Focus(
+                match $dir {
+                    MoveDir1D::Next => "/".into(),
+                    MoveDir1D::Previous => "?".into(),
+                },
+                CommandType::Search,
+                Box::new(Action::from(SelectionAction::Resize(
+                    $style,
+                    EditTarget::Search(SearchType::Regex, MoveDirMod::Same, Count::Contextual)
+                )))
+            )),
+            KakouneMode::Command
+        )
+    };
+}
+
+macro_rules! search {
+    ($style: expr, $flip: expr) => {
+        selection!(SelectionAction::Resize(
+            $style,
+            EditTarget::Search(SearchType::Regex, $flip, Count::Contextual)
+        ))
+    };
+}
+
+macro_rules! normal {
+    () => {
+        goto!(KakouneMode::Normal)
+    };
+}
+
+#[rustfmt::skip]
+fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputStep<I>)> {
+    [
+        // Normal and Insert mode keybindings.
+        ( NIMAP, "<C-F>", scroll2d!(MoveDir2D::Down, ScrollSize::Page) ),
+        ( NIMAP, "<C-B>", scroll2d!(MoveDir2D::Up, ScrollSize::Page) ),
+        ( NIMAP, "<PageDown>", scroll2d!(MoveDir2D::Down, ScrollSize::Page) ),
+        ( NIMAP, "<PageUp>", scroll2d!(MoveDir2D::Up, ScrollSize::Page) ),
+
+        // Normal mode keybindings for moving the cursors.
+        ( NMAP, "h", move_to!(MoveType::Column(MoveDir1D::Previous, false)) ),
+        ( NMAP, "j", move_to!(MoveType::Line(MoveDir1D::Next)) ),
+        ( NMAP, "k", move_to!(MoveType::Line(MoveDir1D::Previous)) ),
+        ( NMAP, "l", move_to!(MoveType::Column(MoveDir1D::Next, false)) ),
+        ( NMAP, "<Left>", move_to!(MoveType::Column(MoveDir1D::Previous, false)) ),
+        ( NMAP, "<Down>", move_to!(MoveType::Line(MoveDir1D::Next)) ),
+        ( NMAP, "<Up>", move_to!(MoveType::Line(MoveDir1D::Previous)) ),
+        ( NMAP, "<Right>", move_to!(MoveType::Column(MoveDir1D::Next, false)) ),
+        ( NMAP, "gg", move_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::Beginning, Count::Contextual)) ),
+        ( NMAP, "gk", move_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::Beginning, Count::Contextual)) ),
+        ( NMAP, "gj", move_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::End, Count::Contextual)) ),
+        ( NMAP, "ge", move_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::End, Count::Contextual)) ),
+        ( NMAP, "gh", move_to!(MoveType::LinePos(MovePosition::Beginning), 0.into()) ),
+        ( NMAP, "gl", move_to!(MoveType::LinePos(MovePosition::End), 0.into()) ),
+        ( NMAP, "gi", move_to!(MoveType::FirstWord(MoveDir1D::Next), 0.into()) ),
+        ( NMAP, "<C-O>", jump!(PositionList::JumpList, MoveDir1D::Previous) ),
+        ( NMAP, "<C-I>", jump!(PositionList::JumpList, MoveDir1D::Next) ),
+        ( NMAP, "<Tab>", jump!(PositionList::JumpList, MoveDir1D::Next) ),
+
+        // Normal mode keybindings for starting new selections.
+        ( NMAP, "w", select_to!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next)) ),
+        ( NMAP, "e", select_to!(MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next)) ),
+        ( NMAP, "b", select_to!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
+        ( NMAP, "<A-w>", select_to!(MoveType::WordBegin(WordStyle::Big, MoveDir1D::Next)) ),
+        ( NMAP, "<A-e>", select_to!(MoveType::WordEnd(WordStyle::Big, MoveDir1D::Next)) ),
+        ( NMAP, "<A-b>", select_to!(MoveType::WordBegin(WordStyle::Big, MoveDir1D::Previous)) ),
+        ( NMAP, "f{any}", charsearch!(SelectionResizeStyle::Restart, MoveDir1D::Next, true) ),
+        ( NMAP, "t{any}", charsearch!(SelectionResizeStyle::Restart, MoveDir1D::Next, false) ),
+        ( NMAP, "<A-f>{any}", charsearch!(SelectionResizeStyle::Restart, MoveDir1D::Previous, true) ),
+        ( NMAP, "<A-t>{any}", charsearch!(SelectionResizeStyle::Restart, MoveDir1D::Previous, false) ),
+        ( NMAP, "x", selection!(SelectionAction::Expand(SelectionBoundary::Line, TargetShapeFilter::ALL)) ),
+        ( NMAP, "<A-x>", selection!(SelectionAction::Trim(SelectionBoundary::Line, TargetShapeFilter::ALL)) ),
+        ( NMAP, "%", select_object!(RangeType::Buffer, true) ),
+        ( NMAP, "<A-i>w", select_object!(RangeType::Word(WordStyle::Little), false) ),
+        ( NMAP, "<A-i>W", select_object!(RangeType::Word(WordStyle::Big), false) ),
+        ( NMAP, "<A-i>p", select_object!(RangeType::Paragraph, false) ),
+        ( NMAP, "<A-i>s", select_object!(RangeType::Sentence, false) ),
+        ( NMAP, "<A-i>b", select_object!(RangeType::Bracketed('(', ')'), false) ),
+        ( NMAP, "<A-i>(", select_object!(RangeType::Bracketed('(', ')'), false) ),
+        ( NMAP, "<A-i>)", select_object!(RangeType::Bracketed('(', ')'), false) ),
+        ( NMAP, "<A-i>B", select_object!(RangeType::Bracketed('{', '}'), false) ),
+        ( NMAP, "<A-i>{", select_object!(RangeType::Bracketed('{', '}'), false) ),
+        ( NMAP, "<A-i>}", select_object!(RangeType::Bracketed('{', '}'), false) ),
+        ( NMAP, "<A-i>r", select_object!(RangeType::Bracketed('[', ']'), false) ),
+        ( NMAP, "<A-i>[", select_object!(RangeType::Bracketed('[', ']'), false) ),
+        ( NMAP, "<A-i>]", select_object!(RangeType::Bracketed('[', ']'), false) ),
+        ( NMAP, "<A-i>a", select_object!(RangeType::Bracketed('<', '>'), false) ),
+        ( NMAP, "<A-i><", select_object!(RangeType::Bracketed('<', '>'), false) ),
+        ( NMAP, "<A-i>>", select_object!(RangeType::Bracketed('<', '>'), false) ),
+        ( NMAP, "<A-i>Q", select_object!(RangeType::Quote('"'), false) ),
+        ( NMAP, "<A-i>\"", select_object!(RangeType::Quote('"'), false) ),
+        ( NMAP, "<A-i>q", select_object!(RangeType::Quote('\''), false) ),
+        ( NMAP, "<A-i>'", select_object!(RangeType::Quote('\''), false) ),
+        ( NMAP, "<A-i>g", select_object!(RangeType::Quote('`'), false) ),
+        ( NMAP, "<A-i>`", select_object!(RangeType::Quote('`'), false) ),
+        ( NMAP, "<A-a>w", select_object!(RangeType::Word(WordStyle::Little), true) ),
+        ( NMAP, "<A-a>W", select_object!(RangeType::Word(WordStyle::Big), true) ),
+        ( NMAP, "<A-a>p", select_object!(RangeType::Paragraph, true) ),
+        ( NMAP, "<A-a>s", select_object!(RangeType::Sentence, true) ),
+        ( NMAP, "<A-a>b", select_object!(RangeType::Bracketed('(', ')'), true) ),
+        ( NMAP, "<A-a>(", select_object!(RangeType::Bracketed('(', ')'), true) ),
+        ( NMAP, "<A-a>)", select_object!(RangeType::Bracketed('(', ')'), true) ),
+        ( NMAP, "<A-a>B", select_object!(RangeType::Bracketed('{', '}'), true) ),
+        ( NMAP, "<A-a>{", select_object!(RangeType::Bracketed('{', '}'), true) ),
+        ( NMAP, "<A-a>}", select_object!(RangeType::Bracketed('{', '}'), true) ),
+        ( NMAP, "<A-a>r", select_object!(RangeType::Bracketed('[', ']'), true) ),
+        ( NMAP, "<A-a>[", select_object!(RangeType::Bracketed('[', ']'), true) ),
+        ( NMAP, "<A-a>]", select_object!(RangeType::Bracketed('[', ']'), true) ),
+        ( NMAP, "<A-a>a", select_object!(RangeType::Bracketed('<', '>'), true) ),
+        ( NMAP, "<A-a><", select_object!(RangeType::Bracketed('<', '>'), true) ),
+        ( NMAP, "<A-a>>", select_object!(RangeType::Bracketed('<', '>'), true) ),
+        ( NMAP, "<A-a>Q", select_object!(RangeType::Quote('"'), true) ),
+        ( NMAP, "<A-a>\"", select_object!(RangeType::Quote('"'), true) ),
+        ( NMAP, "<A-a>q", select_object!(RangeType::Quote('\''), true) ),
+        ( NMAP, "<A-a>'", select_object!(RangeType::Quote('\''), true) ),
+        ( NMAP, "<A-a>g", select_object!(RangeType::Quote('`'), true) ),
+        ( NMAP, "<A-a>`", select_object!(RangeType::Quote('`'), true) ),
+
+        // Normal mode keybindings for extending the current selections.
+        ( NMAP, "H", extend_to!(MoveType::Column(MoveDir1D::Previous, false)) ),
+        ( NMAP, "J", extend_to!(MoveType::Line(MoveDir1D::Next)) ),
+        ( NMAP, "K", extend_to!(MoveType::Line(MoveDir1D::Previous)) ),
+        ( NMAP, "L", extend_to!(MoveType::Column(MoveDir1D::Next, false)) ),
+        ( NMAP, "<S-Left>", extend_to!(MoveType::Column(MoveDir1D::Previous, false)) ),
+        ( NMAP, "<S-Down>", extend_to!(MoveType::Line(MoveDir1D::Next)) ),
+        ( NMAP, "<S-Up>", extend_to!(MoveType::Line(MoveDir1D::Previous)) ),
+        ( NMAP, "<S-Right>", extend_to!(MoveType::Column(MoveDir1D::Next, false)) ),
+        ( NMAP, "W", extend_to!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next)) ),
+        ( NMAP, "E", extend_to!(MoveType::WordEnd(WordStyle::Little, MoveDir1D::Next)) ),
+        ( NMAP, "B", extend_to!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
+        ( NMAP, "<A-W>", extend_to!(MoveType::WordBegin(WordStyle::Big, MoveDir1D::Next)) ),
+        ( NMAP, "<A-E>", extend_to!(MoveType::WordEnd(WordStyle::Big, MoveDir1D::Next)) ),
+        ( NMAP, "<A-B>", extend_to!(MoveType::WordBegin(WordStyle::Big, MoveDir1D::Previous)) ),
+        ( NMAP, "F{any}", charsearch!(SelectionResizeStyle::Extend, MoveDir1D::Next, true) ),
+        ( NMAP, "T{any}", charsearch!(SelectionResizeStyle::Extend, MoveDir1D::Next, false) ),
+        ( NMAP, "<A-F>{any}", charsearch!(SelectionResizeStyle::Extend, MoveDir1D::Previous, true) ),
+        ( NMAP, "<A-T>{any}", charsearch!(SelectionResizeStyle::Extend, MoveDir1D::Previous, false) ),
+        ( NMAP, "Gg", extend_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::Beginning, Count::Contextual)) ),
+        ( NMAP, "Gk", extend_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::Beginning, Count::Contextual)) ),
+        ( NMAP, "Gj", extend_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::End, Count::Contextual)) ),
+        ( NMAP, "Ge", extend_target!(EditTarget::Boundary(RangeType::Buffer, true, MoveTerminus::End, Count::Contextual)) ),
+        ( NMAP, "Gh", extend_to!(MoveType::LinePos(MovePosition::Beginning), 0.into()) ),
+        ( NMAP, "Gl", extend_to!(MoveType::LinePos(MovePosition::End), 0.into()) ),
+        ( NMAP, "Gi", extend_to!(MoveType::FirstWord(MoveDir1D::Next), 0.into()) ),
+
+        // Normal mode keybindings for manipulating selections.
+        ( NMAP, ";", selection!(reduce!()) ),
+        ( NMAP, "<A-;>", selection!(SelectionAction::CursorSet(SelectionCursorChange::SwapAnchor(false))) ),
+        ( NMAP, "<A-:>", selection!(SelectionAction::CursorSet(SelectionCursorChange::End)) ),
+        ( NMAP, "s", cmdbar_select!("select:", SelectionAction::Split(SelectionSplitStyle::Regex(false), TargetShapeFilter::ALL)) ),
+        ( NMAP, "S", cmdbar_select!("split:", SelectionAction::Split(SelectionSplitStyle::Regex(true), TargetShapeFilter::ALL)) ),
+        ( NMAP, "<A-s>", selection!(SelectionAction::Split(SelectionSplitStyle::Lines, TargetShapeFilter::ALL)) ),
+        ( NMAP, "<A-k>", cmdbar_select!("keep matching:", SelectionAction::Filter(false)) ),
+        ( NMAP, "<A-K>", cmdbar_select!("keep not matching:", SelectionAction::Filter(true)) ),
+        ( NMAP, "_", selection!(SelectionAction::Trim(SelectionBoundary::NonWhitespace, TargetShapeFilter::ALL)) ),
+        ( NMAP, "<A-_>", selection!(SelectionAction::Join) ),
+        ( NMAP, "C", selection!(SelectionAction::Duplicate(MoveDir1D::Next, Count::Contextual)) ),
+        ( NMAP, "<A-C>", selection!(SelectionAction::Duplicate(MoveDir1D::Previous, Count::Contextual)) ),
+        ( NMAP, ",", cursor!(CursorAction::Close(CursorCloseTarget::Followers)) ),
+        ( NMAP, "<A-,>", cursor!(CursorAction::Close(CursorCloseTarget::Leader)) ),
+        ( NMAP, "<Space>", cursor!(CursorAction::Close(CursorCloseTarget::Followers)) ),
+        ( NMAP, "<A-Space>", cursor!(CursorAction::Close(CursorCloseTarget::Leader)) ),
+        ( NMAP, "(", cursor!(CursorAction::Rotate(MoveDir1D::Previous, Count::Contextual)) ),
+        ( NMAP, ")", cursor!(CursorAction::Rotate(MoveDir1D::Next, Count::Contextual)) ),
+        ( NMAP, "Z", cursor!(CursorAction::Save(CursorGroupCombineStyle::Replace)) ),
+        ( NMAP, "z", cursor!(CursorAction::Restore(CursorGroupCombineStyle::Replace)) ),
+        ( NMAP, "<A-Z>a", cursor!(CursorAction::Save(CursorGroupCombineStyle::Append)) ),
+        ( NMAP, "<A-z>a", cursor!(CursorAction::Restore(CursorGroupCombineStyle::Append)) ),
+
+        // Normal mode keybindings for searching.
+        ( NMAP, "/", cmdbar_search!(SelectionResizeStyle::Object, MoveDir1D::Next) ),
+        ( NMAP, "<A-/>", cmdbar_search!(SelectionResizeStyle::Object, MoveDir1D::Previous) ),
+        ( NMAP, "?", cmdbar_search!(SelectionResizeStyle::Extend, MoveDir1D::Next) ),
+        ( NMAP, "<A-?>", cmdbar_search!(SelectionResizeStyle::Extend, MoveDir1D::Previous) ),
+        ( NMAP, "n", search!(SelectionResizeStyle::Object, MoveDirMod::Same) ),
+        ( NMAP, "<A-n>", search!(SelectionResizeStyle::Object, MoveDirMod::Flip) ),
+
+        // Normal mode keybindings for editing the selected text.
+        ( NMAP, "d", edit_then_reduce!(EditAction::Delete) ),
+        ( NMAP, "<A-d>", isv!(vec![InternalAction::SetRegister(Register::Blackhole)], vec![
+            EditorAction::Edit(EditAction::Delete.into(), EditTarget::Selection).into(),
+            reduce!().into()
+        ]) ),
+        ( NMAP, "c", change!() ),
+        ( NMAP, "<A-c>", isv!(
+            vec![
+                InternalAction::SetRegister(Register::Blackhole),
+                InternalAction::SetInsertStyle(InsertStyle::Insert),
+            ],
+            vec![EditorAction::Edit(EditAction::Delete.into(), EditTarget::Selection).into()],
+            KakouneMode::Insert
+        ) ),
+        ( NMAP, "y", edit_selection!(EditAction::Yank) ),
+        ( NMAP, "p", paste_side!(SelectionCursorChange::End, MoveDir1D::Next) ),
+        ( NMAP, "P", paste_side!(SelectionCursorChange::Beginning, MoveDir1D::Previous) ),
+        ( NMAP, "R", paste!(PasteStyle::Replace) ),
+        ( NMAP, "r{any}", is!(InternalAction::SetReplaceChar, Action::from(EditorAction::Edit(EditAction::Replace(false).into(), EditTarget::Selection))) ),
+        ( NMAP, "~", edit_selection!(EditAction::ChangeCase(Case::Upper)) ),
+        ( NMAP, "`", edit_selection!(EditAction::ChangeCase(Case::Lower)) ),
+        ( NMAP, "<A-`>", edit_selection!(EditAction::ChangeCase(Case::Toggle)) ),
+        ( NMAP, ">", edit_selection!(EditAction::Indent(IndentChange::Increase(Count::Contextual))) ),
+        ( NMAP, "<", edit_selection!(EditAction::Indent(IndentChange::Decrease(Count::Contextual))) ),
+        ( NMAP, "<A-j>", edit_selection!(EditAction::Join(JoinStyle::OneSpace)) ),
+        ( NMAP, "u", editor!(EditorAction::History(HistoryAction::Undo(Count::Contextual))) ),
+        ( NMAP, "U", editor!(EditorAction::History(HistoryAction::Redo(Count::Contextual))) ),
+        ( NMAP, ".", act!(Action::Repeat(RepeatType::EditSequence)) ),
+
+        // Normal mode keybindings for entering Insert mode.
+        ( NMAP, "i", insert!(SelectionCursorChange::Beginning) ),
+        ( NMAP, "a", insert!(SelectionCursorChange::End, MoveType::Column(MoveDir1D::Next, false), 1.into()) ),
+        ( NMAP, "I", insert!(SelectionCursorChange::Beginning, MoveType::FirstWord(MoveDir1D::Next), 0.into()) ),
+        ( NMAP, "A", insert!(SelectionCursorChange::End, MoveType::LinePos(MovePosition::End), 0.into()) ),
+        ( NMAP, "o", open_lines!(MoveDir1D::Next) ),
+        ( NMAP, "O", open_lines!(MoveDir1D::Previous) ),
+
+        // Normal mode keybindings for the view.
+        ( NMAP, "vv", scrollcp!(MovePosition::Middle, Axis::Vertical) ),
+        ( NMAP, "vc", scrollcp!(MovePosition::Middle, Axis::Vertical) ),
+        ( NMAP, "vt", scrollcp!(MovePosition::Beginning, Axis::Vertical) ),
+        ( NMAP, "vb", scrollcp!(MovePosition::End, Axis::Vertical) ),
+        ( NMAP, "vj", scroll2d!(MoveDir2D::Down, ScrollSize::Cell) ),
+        ( NMAP, "vk", scroll2d!(MoveDir2D::Up, ScrollSize::Cell) ),
+        ( NMAP, "<C-D>", scroll2d!(MoveDir2D::Down, ScrollSize::HalfPage) ),
+        ( NMAP, "<C-U>", scroll2d!(MoveDir2D::Up, ScrollSize::HalfPage) ),
+
+        // Normal mode keybindings for prompts.
+        ( NMAP, ":", cmdbar_focus!(":", CommandType::Command, CommandAction::Execute(1.into())) ),
+
+        // Insert mode keybindings.
+        ( IMAP, "<Esc>", normal!() ),
+        ( IMAP, "<C-N>", complete!(CompletionType::Auto, CompletionSelection::List(MoveDir1D::Next), CompletionDisplay::List) ),
+        ( IMAP, "<C-P>", complete!(CompletionType::Auto, CompletionSelection::List(MoveDir1D::Previous), CompletionDisplay::List) ),
+        ( IMAP, "<C-R>{register}", paste!(PasteStyle::Cursor, 1) ),
+        ( IMAP, "<C-U>", editor!(EditorAction::History(HistoryAction::Checkpoint)) ),
+        ( IMAP, "<C-V>{any}", chartype!() ),
+        ( IMAP, "<Tab>", chartype!(Char::Single('\t')) ),
+        ( IMAP, "<Up>", edit!(EditAction::Motion, MoveType::Line(MoveDir1D::Previous)) ),
+        ( IMAP, "<Down>", edit!(EditAction::Motion, MoveType::Line(MoveDir1D::Next)) ),
+
+        // Insert and Command mode keybindings.
+        ( ICMAP, "<BS>", erase!(MoveType::Column(MoveDir1D::Previous, true)) ),
+        ( ICMAP, "<Del>", erase!(MoveType::Column(MoveDir1D::Next, true)) ),
+        ( ICMAP, "<C-W>", erase!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous)) ),
+        ( ICMAP, "<Left>", edit!(EditAction::Motion, MoveType::Column(MoveDir1D::Previous, true)) ),
+        ( ICMAP, "<Right>", edit!(EditAction::Motion, MoveType::Column(MoveDir1D::Next, true)) ),
+        ( ICMAP, "<Home>", edit!(EditAction::Motion, MoveType::LinePos(MovePosition::Beginning), 0) ),
+        ( ICMAP, "<End>", edit!(EditAction::Motion, MoveType::LinePos(MovePosition::End), 0) ),
+
+        // Command mode keybindings.
+        ( CMAP, "<Esc>", prompt!(PromptAction::Abort(false), KakouneMode::Normal) ),
+        ( CMAP, "<C-C>", prompt!(PromptAction::Abort(false), KakouneMode::Normal) ),
+        ( CMAP, "<Up>", prompt!(PromptAction::Recall(MoveDir1D::Previous, Count::Contextual, false)) ),
+        ( CMAP, "<Down>", prompt!(PromptAction::Recall(MoveDir1D::Next, Count::Contextual, false)) ),
+        ( CMAP, "<Tab>", complete!(CompletionType::Auto, CompletionSelection::List(MoveDir1D::Next), CompletionDisplay::Bar) ),
+        ( CMAP, "<S-Tab>", complete!(CompletionType::Auto, CompletionSelection::List(MoveDir1D::Previous), CompletionDisplay::Bar) ),
+    ].to_vec()
+}
+
+#[rustfmt::skip]
+fn default_pfxs<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, Option<InputStep<I>>)> {
+    [
+        // Normal mode commands can be prefixed w/ a count.
+        ( NMAP, "{count}", Some(iact!(InternalAction::SaveCounting)) ),
+
+        // Normal mode commands can be prefixed w/ a register.
+        ( NMAP, "\"{register}", None ),
+    ].to_vec()
+}
+
+#[rustfmt::skip]
+fn default_enter<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputStep<I>)> {
+    [
+        // <Enter> in Insert mode types a newline character.
+        ( IMAP, "<Enter>", chartype!(Char::Single('\n')) ),
+
+        // <Enter> in Command mode submits the prompt.
+        ( CMAP, "<Enter>", prompt!(PromptAction::Submit, KakouneMode::Normal) ),
+    ].to_vec()
+}
+
+#[rustfmt::skip]
+fn submit_on_enter<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputStep<I>)> {
+    [
+        // <Enter> in Normal and Insert mode submits the text.
+        ( NIMAP, "<Enter>", prompt!(PromptAction::Submit) ),
+
+        // <Enter> in Command mode submits the prompt.
+        ( CMAP, "<Enter>", prompt!(PromptAction::Submit, KakouneMode::Normal) ),
+    ].to_vec()
+}
+
+#[inline]
+fn add_prefix<I: ApplicationInfo>(
+    machine: &mut KakouneMachine<TerminalKey, I>,
+    modes: &MappedModes,
+    keys: &str,
+    action: &Option<InputStep<I>>,
+) {
+    if let Ok((_, evs)) = parse(keys) {
+        for mode in modes.split() {
+            machine.add_prefix(mode, &evs, action);
+        }
+    } else {
+        panic!("invalid Kakoune keybinding: {}", keys);
+    }
+}
+
+#[inline]
+fn add_mapping<I: ApplicationInfo>(
+    machine: &mut KakouneMachine<TerminalKey, I>,
+    modes: &MappedModes,
+    keys: &str,
+    action: &InputStep<I>,
+) {
+    if let Ok((_, evs)) = parse(keys) {
+        for mode in modes.split() {
+            machine.add_mapping(mode, &evs, action);
+        }
+    } else {
+        panic!("invalid Kakoune keybinding: {}", keys);
+    }
+}
+
+/// A configurable collection of Kakoune bindings that can be added to a [ModalMachine].
+#[derive(Debug)]
+pub struct KakouneBindings<I: ApplicationInfo> {
+    prefixes: Vec<(MappedModes, &'static str, Option<InputStep<I>>)>,
+    mappings: Vec<(MappedModes, &'static str, InputStep<I>)>,
+    enter: Vec<(MappedModes, &'static str, InputStep<I>)>,
+}
+
+impl<I: ApplicationInfo> KakouneBindings<I> {
+    /// Remap the Enter key in Normal and Insert mode to [submit](PromptAction::Submit) instead.
+    pub fn submit_on_enter(mut self) -> Self {
+        self.enter = submit_on_enter();
+        self
+    }
+}
+
+impl<I: ApplicationInfo> ShellBindings for KakouneBindings<I> {
+    fn shell(self) -> Self {
+        self.submit_on_enter()
+    }
+}
+
+impl<I: ApplicationInfo> Default for KakouneBindings<I> {
+    fn default() -> Self {
+        KakouneBindings {
+            prefixes: default_pfxs(),
+            mappings: default_keys(),
+            enter: default_enter(),
+        }
+    }
+}
+
+impl<I: ApplicationInfo> InputBindings<TerminalKey, InputStep<I>> for KakouneBindings<I> {
+    fn setup(&self, machine: &mut KakouneMachine<TerminalKey, I>) {
+        for (modes, keys, action) in self.prefixes.iter() {
+            add_prefix(machine, modes, keys, action);
+        }
+
+        for (modes, keys, action) in self.mappings.iter() {
+            add_mapping(machine, modes, keys, action);
+        }
+
+        for (modes, keys, action) in self.enter.iter() {
+            add_mapping(machine, modes, keys, action);
+        }
+    }
+}
+
+/// Manage Kakoune keybindings and modes.
+pub type KakouneMachine<Key, T = EmptyInfo> = ModalMachine<Key, InputStep<T>>;
+
+/// Create a new [KakouneMachine] populated with standard Kakoune keys.
+pub fn default_kakoune_keys<I: ApplicationInfo>() -> KakouneMachine<TerminalKey, I> {
+    ModalMachine::from_bindings::<KakouneBindings<I>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editing::context::{EditContext, EditContextBuilder, MatchedKey};
+    use crate::keybindings::BindingMachine;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    const CHECKPOINT: Action = Action::Editor(EditorAction::History(HistoryAction::Checkpoint));
+    const REDUCE: Action = Action::Editor(EditorAction::Selection(SelectionAction::Resize(
+        SelectionResizeStyle::Restart,
+        EditTarget::CurrentPosition,
+    )));
+
+    macro_rules! assert_normal {
+        ($mm: expr) => {
+            assert_pop2!($mm, CHECKPOINT, mkctx());
+            assert_eq!($mm.mode(), KakouneMode::Normal);
+        };
+    }
+
+    fn resize(style: SelectionResizeStyle, target: EditTarget) -> Action {
+        Action::from(SelectionAction::Resize(style, target))
+    }
+
+    fn mkctx() -> EditContext {
+        EditContextBuilder::default()
+            .target_shape(Some(TargetShape::CharWise))
+            .last_column(true)
+            .search_incremental(true)
+            .build()
+    }
+
+    #[test]
+    fn test_select_word() {
+        let mut vm: KakouneMachine<TerminalKey> = default_kakoune_keys();
+        let mut ctx = mkctx();
+
+        // Start out in Normal mode.
+        assert_eq!(vm.mode(), KakouneMode::Normal);
+
+        // "w" starts a new selection up to the next word.
+        let mt = MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next);
+        let select = resize(
+            SelectionResizeStyle::Restart,
+            EditTarget::Motion(mt.clone(), Count::Contextual),
+        );
+        vm.input_key(key!('w'));
+        assert_pop1!(vm, select, ctx);
+        assert_normal!(vm);
+
+        // "W" extends the current selection instead.
+        let extend =
+            resize(SelectionResizeStyle::Extend, EditTarget::Motion(mt, Count::Contextual));
+        vm.input_key(key!('W'));
+        assert_pop1!(vm, extend, ctx);
+        assert_normal!(vm);
+
+        // Counts are passed along to the selection motion.
+        let mt = MoveType::WordBegin(WordStyle::Little, MoveDir1D::Previous);
+        let select =
+            resize(SelectionResizeStyle::Restart, EditTarget::Motion(mt, Count::Contextual));
+        ctx.count = Some(3);
+        ctx.matched_keys = vec![MatchedKey::Numeric(3)];
+        vm.input_key(key!('3'));
+        vm.input_key(key!('b'));
+        assert_pop1!(vm, select, ctx);
+        assert_normal!(vm);
+    }
+
+    #[test]
+    fn test_move_reduces() {
+        let mut vm: KakouneMachine<TerminalKey> = default_kakoune_keys();
+        let ctx = mkctx();
+
+        // "l" moves each selection, and then reduces it to its cursor.
+        let mt = MoveType::Column(MoveDir1D::Next, false);
+        let extend =
+            resize(SelectionResizeStyle::Extend, EditTarget::Motion(mt, Count::Contextual));
+        vm.input_key(key!('l'));
+        assert_pop1!(vm, extend, ctx);
+        assert_pop1!(vm, REDUCE, ctx);
+        assert_normal!(vm);
+
+        // ";" reduces each selection to its cursor.
+        vm.input_key(key!(';'));
+        assert_pop1!(vm, REDUCE, ctx);
+        assert_normal!(vm);
+
+        // "<A-;>" swaps the cursor and the anchor.
+        let swap = SelectionAction::CursorSet(SelectionCursorChange::SwapAnchor(false));
+        vm.input_key(key!(';', KeyModifiers::ALT));
+        assert_pop1!(vm, Action::from(swap), ctx);
+        assert_normal!(vm);
+    }
+
+    #[test]
+    fn test_select_lines() {
+        let mut vm: KakouneMachine<TerminalKey> = default_kakoune_keys();
+        let ctx = mkctx();
+
+        // "x" expands each selection to cover full lines.
+        let expand = SelectionAction::Expand(SelectionBoundary::Line, TargetShapeFilter::ALL);
+        vm.input_key(key!('x'));
+        assert_pop1!(vm, Action::from(expand), ctx);
+        assert_normal!(vm);
+
+        // "<A-x>" trims each selection to the full lines it contains.
+        let trim = SelectionAction::Trim(SelectionBoundary::Line, TargetShapeFilter::ALL);
+        vm.input_key(key!('x', KeyModifiers::ALT));
+        assert_pop1!(vm, Action::from(trim), ctx);
+        assert_normal!(vm);
+    }
+
+    #[test]
+    fn test_charsearch() {
+        let mut vm: KakouneMachine<TerminalKey> = default_kakoune_keys();
+        let mut ctx = mkctx();
+
+        let target =
+            EditTarget::Search(SearchType::Char(true), MoveDirMod::Same, Count::Contextual);
+
+        // "fa" selects up to and including the next "a".
+        ctx.search_char = Some((MoveDir1D::Next, true, 'a'.into()));
+        ctx.typed = Some('a'.into());
+        ctx.matched_keys = vec![MatchedKey::String("a".into())];
+        vm.input_key(key!('f'));
+        vm.input_key(key!('a'));
+        assert_pop1!(vm, resize(SelectionResizeStyle::Restart, target.clone()), ctx);
+        assert_normal!(vm);
+
+        // "<A-T>b" extends backwards up to the previous "b".
+        ctx.search_char = Some((MoveDir1D::Previous, false, 'b'.into()));
+        ctx.typed = Some('b'.into());
+        ctx.matched_keys = vec![MatchedKey::String("b".into())];
+        vm.input_key(key!('T', KeyModifiers::ALT | KeyModifiers::SHIFT));
+        vm.input_key(key!('b'));
+        assert_pop1!(vm, resize(SelectionResizeStyle::Extend, target), ctx);
+        assert_normal!(vm);
+    }
+
+    #[test]
+    fn test_split_and_filter() {
+        let mut vm: KakouneMachine<TerminalKey> = default_kakoune_keys();
+        let mut ctx = mkctx();
+
+        // "s" prompts for a regular expression to split the selections with.
+        let split =
+            SelectionAction::Split(SelectionSplitStyle::Regex(false), TargetShapeFilter::ALL);
+        let focus = CommandBarAction::Focus(
+            "select:".into(),
+            CommandType::Search,
+            Box::new(Action::from(split)),
+        );
+        vm.input_key(key!('s'));
+        assert_pop2!(vm, Action::from(focus), ctx);
+        assert_eq!(vm.mode(), KakouneMode::Command);
+
+        // Typed characters go into the prompt.
+        let it = InsertTextAction::Type(Char::from('a').into(), MoveDir1D::Previous, 1.into());
+        ctx.target_shape = None;
+        ctx.insert_style = Some(InsertStyle::Insert);
+        vm.input_key(key!('a'));
+        assert_pop2!(vm, Action::from(it), ctx);
+        assert_eq!(vm.mode(), KakouneMode::Command);
+
+        // <Enter> submits, and returns to Normal mode without modifying the selections.
+        vm.input_key(key!(KeyCode::Enter));
+        assert_pop2!(vm, Action::from(PromptAction::Submit), ctx);
+        assert_eq!(vm.mode(), KakouneMode::Normal);
+
+        // "<A-k>" prompts for a regular expression to filter the selections with.
+        let focus = CommandBarAction::Focus(
+            "keep matching:".into(),
+            CommandType::Search,
+            Box::new(Action::from(SelectionAction::Filter(false))),
+        );
+        let ctx = mkctx();
+        vm.input_key(key!('k', KeyModifiers::ALT));
+        assert_pop2!(vm, Action::from(focus), ctx);
+        assert_eq!(vm.mode(), KakouneMode::Command);
+
+        // <Esc> aborts the prompt.
+        let mut ctx = mkctx();
+        ctx.target_shape = None;
+        ctx.insert_style = Some(InsertStyle::Insert);
+        vm.input_key(key!(KeyCode::Esc));
+        assert_pop2!(vm, Action::from(PromptAction::Abort(false)), ctx);
+        assert_eq!(vm.mode(), KakouneMode::Normal);
+
+        // "<A-K>" filters out matching selections instead.
+        let focus = CommandBarAction::Focus(
+            "keep not matching:".into(),
+            CommandType::Search,
+            Box::new(Action::from(SelectionAction::Filter(true))),
+        );
+        let ctx = mkctx();
+        vm.input_key(key!('K', KeyModifiers::ALT | KeyModifiers::SHIFT));
+        assert_pop2!(vm, Action::from(focus), ctx);
+        assert_eq!(vm.mode(), KakouneMode::Command);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut vm: KakouneMachine<TerminalKey> = default_kakoune_keys();
+        let mut ctx = mkctx();
+
+        // "i" moves each cursor to the start of its selection, and enters Insert mode.
+        let start = SelectionAction::CursorSet(SelectionCursorChange::Beginning);
+        ctx.insert_style = Some(InsertStyle::Insert);
+        vm.input_key(key!('i'));
+        assert_pop1!(vm, Action::from(start), ctx);
+
+        // Entering Insert mode drops the selections.
+        let motion = EditorAction::Edit(EditAction::Motion.into(), EditTarget::CurrentPosition);
+        ctx.target_shape = None;
+        assert_pop2!(vm, Action::from(motion), ctx);
+        assert_eq!(vm.mode(), KakouneMode::Insert);
+
+        // Unmapped characters are typed.
+        let it = InsertTextAction::Type(Char::from('a').into(), MoveDir1D::Previous, 1.into());
+        vm.input_key(key!('a'));
+        assert_pop2!(vm, Action::from(it), ctx);
+        assert_eq!(vm.mode(), KakouneMode::Insert);
+
+        // <Esc> returns to Normal mode, leaving a single character selected.
+        vm.input_key(key!(KeyCode::Esc));
+        assert_pop1!(vm, Action::NoOp, ctx);
+
+        let ctx = mkctx();
+        assert_pop1!(vm, REDUCE, ctx);
+        assert_pop2!(vm, CHECKPOINT, ctx);
+        assert_eq!(vm.mode(), KakouneMode::Normal);
+    }
+}
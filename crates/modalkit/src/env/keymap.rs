@@ -0,0 +1,424 @@
+//! # Keymap files
+//!
+//! ## Overview
+//!
+//! This module contains a [serde] representation of user-defined key mappings, so that they can
+//! be loaded from configuration files written in formats like TOML or JSON, and then added on top
+//! of the [Vim](super::vim::keybindings::VimBindings::keymap) or
+//! [Emacs](super::emacs::keybindings::EmacsBindings::keymap) bindings.
+//!
+//! Each entry names the modes it applies to, a key sequence written the same way as in the
+//! default bindings (e.g., `"<C-W>z"`, `"{count}|"` or `"\"{register}p"`), and what to do once
+//! the keys have been typed:
+//!
+//! - `macro`: type another sequence of keys, as if they had been pressed instead
+//! - `command`: run a command, as if it had been entered at the command bar
+//! - `action`: perform an [Action], written in the format described in [actions](crate::actions)
+//!
+//! Key sequences can also use Vim's pseudo-keys:
+//!
+//! - `<Leader>` and `<LocalLeader>` are replaced with the keys configured on the bindings (see
+//!   [Leaders](super::keyparse::Leaders)) when the keymap is added
+//! - `<Plug>` is a key that is never typed in the terminal, so that mappings like
+//!   `<Plug>(wrap-toggle)` can only be reached from other mappings' macros
+//! - `<Nop>` is a macro that types nothing, for disabling keys
+//!
+//! ## Example
+//!
+//! ```toml
+//! [[map]]
+//! modes = ["normal", "visual"]
+//! keys = "<C-S>"
+//! command = "write"
+//!
+//! [[map]]
+//! modes = ["insert"]
+//! keys = "jk"
+//! macro = "<Esc>"
+//!
+//! [[map]]
+//! modes = ["normal"]
+//! keys = "<C-W>z"
+//! action = { Window = "ZoomToggle" }
+//!
+//! [[map]]
+//! modes = ["normal"]
+//! keys = "<Leader>z"
+//! macro = "<C-W>z"
+//!
+//! [[map]]
+//! modes = ["insert"]
+//! keys = "<F1>"
+//! macro = "<Nop>"
+//! ```
+//!
+//! This is only available when the `serde` feature is enabled.
+use nom::IResult;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::{Action, CommandAction, MacroAction};
+use crate::editing::application::{ApplicationInfo, EmptyInfo};
+use crate::prelude::Count;
+
+use super::keyparse::{parse_macro_with_leaders, parse_with_leaders, Leaders};
+use super::CommonEdgePath;
+
+/// A collection of user-defined key mappings.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "I::Action: Serialize, I::WindowId: Serialize",
+    deserialize = "I::Action: Deserialize<'de>, I::WindowId: Deserialize<'de>"
+))]
+pub struct Keymap<I: ApplicationInfo = EmptyInfo> {
+    /// The mappings to add, in the order that they should be applied.
+    #[serde(default, rename = "map")]
+    pub mappings: Vec<KeymapEntry<I>>,
+}
+
+impl<I: ApplicationInfo> Default for Keymap<I> {
+    fn default() -> Self {
+        Keymap { mappings: vec![] }
+    }
+}
+
+/// A single mapping in a [Keymap].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "I::Action: Serialize, I::WindowId: Serialize",
+    deserialize = "I::Action: Deserialize<'de>, I::WindowId: Deserialize<'de>"
+))]
+pub struct KeymapEntry<I: ApplicationInfo = EmptyInfo> {
+    /// The names of the modes that this mapping applies in.
+    pub modes: Vec<String>,
+
+    /// The key sequence to map.
+    pub keys: String,
+
+    /// What to do when the key sequence is typed.
+    #[serde(flatten)]
+    pub action: KeymapAction<I>,
+}
+
+/// What a [KeymapEntry] does when its keys are typed.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(bound(
+    serialize = "I::Action: Serialize, I::WindowId: Serialize",
+    deserialize = "I::Action: Deserialize<'de>, I::WindowId: Deserialize<'de>"
+))]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapAction<I: ApplicationInfo = EmptyInfo> {
+    /// Type another sequence of keys, using any count given before the mapping to repeat them.
+    ///
+    /// A macro of `<Nop>` does nothing.
+    Macro(String),
+
+    /// Run a command string.
+    Command(String),
+
+    /// Perform an action.
+    Action(Action<I>),
+}
+
+/// The ways that a [KeymapEntry] can be invalid.
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum KeymapErrorKind {
+    /// The entry doesn't list any modes.
+    #[error("no modes given")]
+    NoModes,
+
+    /// The entry names a mode that doesn't exist in the environment it's being applied to.
+    #[error("unknown mode {0:?}")]
+    UnknownMode(String),
+
+    /// The key sequence can't be parsed, starting at the given column.
+    #[error("invalid key sequence at column {0}")]
+    InvalidKeys(usize),
+
+    /// The keys to type for a [KeymapAction::Macro] can't be parsed, starting at the given
+    /// column.
+    #[error("invalid macro at column {0}")]
+    InvalidMacro(usize),
+
+    /// The [KeymapAction::Command] is empty.
+    #[error("empty command")]
+    EmptyCommand,
+}
+
+/// Error returned when a [Keymap] contains an invalid entry.
+#[derive(thiserror::Error, Clone, Debug, Eq, PartialEq)]
+#[error("map[{index}] ({keys:?}): {kind}")]
+pub struct KeymapError {
+    /// The position of the invalid entry in [Keymap::mappings].
+    pub index: usize,
+
+    /// The key sequence of the invalid entry.
+    pub keys: String,
+
+    /// What's wrong with the entry.
+    pub kind: KeymapErrorKind,
+}
+
+/// Convert a parser result into the column where parsing failed.
+fn column<T>(input: &str, res: IResult<&str, T>) -> Result<T, usize> {
+    let rest = match res {
+        Ok((_, v)) => return Ok(v),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => e.input,
+        Err(nom::Err::Incomplete(_)) => "",
+    };
+
+    let offset = input.len() - rest.len();
+
+    return Err(input[..offset].chars().count() + 1);
+}
+
+impl<I: ApplicationInfo> KeymapAction<I> {
+    fn to_action(&self, leaders: &Leaders) -> Result<Action<I>, KeymapErrorKind> {
+        match self {
+            KeymapAction::Macro(keys) => {
+                let keys = column(keys, parse_macro_with_leaders(keys, leaders))
+                    .map_err(KeymapErrorKind::InvalidMacro)?;
+
+                if keys.is_empty() {
+                    return Ok(Action::NoOp);
+                }
+
+                let keys = keys.iter().map(ToString::to_string).collect();
+                let act = MacroAction::Run(keys, Count::Contextual);
+
+                return Ok(act.into());
+            },
+            KeymapAction::Command(cmd) => {
+                if cmd.trim().is_empty() {
+                    return Err(KeymapErrorKind::EmptyCommand);
+                }
+
+                return Ok(CommandAction::Run(cmd.clone()).into());
+            },
+            KeymapAction::Action(act) => {
+                return Ok(act.clone());
+            },
+        }
+    }
+}
+
+impl<I: ApplicationInfo> KeymapEntry<I> {
+    fn resolve<M>(
+        &self,
+        mode: impl Fn(&str) -> Option<M>,
+        leaders: &Leaders,
+    ) -> Result<(Vec<M>, CommonEdgePath, Action<I>), KeymapErrorKind> {
+        if self.modes.is_empty() {
+            return Err(KeymapErrorKind::NoModes);
+        }
+
+        let modes = self
+            .modes
+            .iter()
+            .map(|name| mode(name).ok_or_else(|| KeymapErrorKind::UnknownMode(name.clone())))
+            .collect::<Result<Vec<_>, _>>()?;
+        let evs = column(&self.keys, parse_with_leaders(&self.keys, leaders))
+            .map_err(KeymapErrorKind::InvalidKeys)?;
+        let act = self.action.to_action(leaders)?;
+
+        Ok((modes, evs, act))
+    }
+}
+
+impl<I: ApplicationInfo> Keymap<I> {
+    /// Check each entry, using `mode` to look up mode names and `leaders` for any `<Leader>` or
+    /// `<LocalLeader>` keys, and return the modes, key sequence and action for each one.
+    pub(crate) fn resolve<M>(
+        &self,
+        mode: impl Fn(&str) -> Option<M>,
+        leaders: &Leaders,
+    ) -> Result<Vec<(Vec<M>, CommonEdgePath, Action<I>)>, KeymapError> {
+        self.mappings
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                entry
+                    .resolve(&mode, leaders)
+                    .map_err(|kind| KeymapError { index, keys: entry.keys.clone(), kind })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::WindowAction;
+    use crate::key::TerminalKey;
+    use crate::keybindings::{EdgeEvent, EdgeRepeat};
+    use std::str::FromStr;
+
+    fn mode(name: &str) -> Option<char> {
+        match name {
+            "insert" => Some('i'),
+            "normal" => Some('n'),
+            _ => None,
+        }
+    }
+
+    fn resolve(keymap: &Keymap) -> Result<Vec<(Vec<char>, CommonEdgePath, Action)>, KeymapError> {
+        keymap.resolve(mode, &Leaders::default())
+    }
+
+    fn entry(keys: &str, action: KeymapAction) -> KeymapEntry {
+        KeymapEntry {
+            modes: vec!["normal".into()],
+            keys: keys.into(),
+            action,
+        }
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "map": [
+                { "modes": ["normal", "insert"], "keys": "<C-S>", "command": "write" },
+                { "modes": ["insert"], "keys": "jk", "macro": "<Esc>" },
+                { "modes": ["normal"], "keys": "<C-W>z", "action": { "Window": "ZoomToggle" } }
+            ]
+        }"#;
+        let keymap: Keymap = serde_json::from_str(json).unwrap();
+
+        assert_eq!(keymap.mappings, vec![
+            KeymapEntry {
+                modes: vec!["normal".into(), "insert".into()],
+                keys: "<C-S>".into(),
+                action: KeymapAction::Command("write".into()),
+            },
+            KeymapEntry {
+                modes: vec!["insert".into()],
+                keys: "jk".into(),
+                action: KeymapAction::Macro("<Esc>".into()),
+            },
+            KeymapEntry {
+                modes: vec!["normal".into()],
+                keys: "<C-W>z".into(),
+                action: KeymapAction::Action(WindowAction::ZoomToggle.into()),
+            },
+        ]);
+
+        // Round trip back through JSON.
+        let json = serde_json::to_string(&keymap).unwrap();
+        assert_eq!(serde_json::from_str::<Keymap>(&json).unwrap(), keymap);
+
+        // An empty file has no mappings.
+        let keymap: Keymap = serde_json::from_str("{}").unwrap();
+        assert_eq!(keymap, Keymap::default());
+
+        // Entries need an action.
+        let json = r#"{ "map": [ { "modes": ["normal"], "keys": "Q" } ] }"#;
+        assert!(serde_json::from_str::<Keymap>(json).is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let keymap = Keymap {
+            mappings: vec![
+                entry("\"{register}Q", KeymapAction::Macro("<C-W>s".into())),
+                entry("<C-S>", KeymapAction::Command("write".into())),
+            ],
+        };
+
+        let res = resolve(&keymap).unwrap();
+        assert_eq!(res.len(), 2);
+
+        let (modes, evs, act) = &res[0];
+        let ctl_s: TerminalKey = "<C-S>".parse().unwrap();
+        assert_eq!(modes, &vec!['n']);
+        assert_eq!(evs.len(), 3);
+        assert_eq!(act, &Action::from(MacroAction::Run("<C-W>s".into(), Count::Contextual)));
+
+        let (_, evs, act) = &res[1];
+        assert_eq!(evs, &vec![(EdgeRepeat::Once, EdgeEvent::Key(ctl_s))]);
+        assert_eq!(act, &Action::from(CommandAction::Run("write".into())));
+    }
+
+    #[test]
+    fn test_pseudo_keys() {
+        let keymap = Keymap {
+            mappings: vec![
+                entry("<Leader>w", KeymapAction::Macro("<Plug>(save)".into())),
+                entry("<Plug>(save)", KeymapAction::Command("write".into())),
+                entry("<F1>", KeymapAction::Macro("<Nop>".into())),
+                entry("Q", KeymapAction::Macro("<LocalLeader><lt>".into())),
+            ],
+        };
+        let leaders = Leaders {
+            leader: TerminalKey::from_str(" ").unwrap(),
+            local_leader: TerminalKey::from_str("<C-L>").unwrap(),
+        };
+
+        let res = keymap.resolve(mode, &leaders).unwrap();
+        assert_eq!(res.len(), 4);
+
+        // The leader is substituted in the mapped keys.
+        let (_, evs, act) = &res[0];
+        let space = (EdgeRepeat::Once, EdgeEvent::Key(leaders.leader));
+        assert_eq!(evs[0], space);
+        assert_eq!(act, &Action::from(MacroAction::Run("<Plug>(save)".into(), Count::Contextual)));
+
+        // <Plug> is a key of its own.
+        let (_, evs, _) = &res[1];
+        let plug = TerminalKey::from_str("<Plug>").unwrap();
+        assert_eq!(evs.len(), 7);
+        assert_eq!(evs[0], (EdgeRepeat::Once, EdgeEvent::Key(plug)));
+
+        // <Nop> does nothing.
+        let (_, _, act) = &res[2];
+        assert_eq!(act, &Action::NoOp);
+
+        // Leaders in macros are substituted when the keymap is resolved.
+        let (_, _, act) = &res[3];
+        assert_eq!(act, &Action::from(MacroAction::Run("<C-L><lt>".into(), Count::Contextual)));
+
+        // <Nop> isn't a key that can be mapped.
+        let keymap = Keymap {
+            mappings: vec![entry("a<Nop>", KeymapAction::Command("quit".into()))],
+        };
+        let err = keymap.resolve(mode, &leaders).unwrap_err();
+        assert_eq!(err.kind, KeymapErrorKind::InvalidKeys(2));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = |bad: KeymapEntry| {
+            let keymap = Keymap {
+                mappings: vec![entry("n", KeymapAction::Macro("j".into())), bad],
+            };
+
+            resolve(&keymap).unwrap_err()
+        };
+
+        // Unknown modes are reported with the entry's position.
+        let mut e = entry("Q", KeymapAction::Command("quit".into()));
+        e.modes.push("nromal".into());
+        assert_eq!(err(e), KeymapError {
+            index: 1,
+            keys: "Q".into(),
+            kind: KeymapErrorKind::UnknownMode("nromal".into()),
+        });
+
+        // There needs to be at least one mode.
+        let mut e = entry("Q", KeymapAction::Command("quit".into()));
+        e.modes.clear();
+        assert_eq!(err(e).kind, KeymapErrorKind::NoModes);
+
+        // Bad key sequences point to where parsing stopped.
+        let e = entry("", KeymapAction::Command("quit".into()));
+        let e = err(e);
+        assert_eq!(e.kind, KeymapErrorKind::InvalidKeys(1));
+        assert_eq!(e.to_string(), "map[1] (\"\"): invalid key sequence at column 1");
+
+        let e = entry("Q", KeymapAction::Macro("".into()));
+        assert_eq!(err(e).kind, KeymapErrorKind::InvalidMacro(1));
+
+        // Commands can't be empty.
+        let e = entry("Q", KeymapAction::Command(" ".into()));
+        assert_eq!(err(e).kind, KeymapErrorKind::EmptyCommand);
+    }
+}
@@ -2,17 +2,48 @@
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, tag_no_case},
     character::complete::{char, digit1},
-    combinator::{eof, map_res, opt, value},
+    combinator::{eof, map, map_res, opt, value},
+    error::{Error, ErrorKind},
     multi::many1,
     IResult,
 };
 
 use super::{CommonEdgeEvent, CommonEdgePath, CommonEdgePathPart, CommonKeyClass};
 
+use crate::key::parse::{parse_nop, parse_simple};
+use crate::key::TerminalKey;
 use crate::keybindings::{EdgeEvent, EdgeRepeat};
 
+/// The keys that `<Leader>` and `<LocalLeader>` stand for when parsing key sequences.
+///
+/// Like in Vim, these get resolved when a mapping is added, so changing them afterwards doesn't
+/// affect existing mappings. Both default to `\`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Leaders {
+    /// The key to use for `<Leader>`.
+    pub leader: TerminalKey,
+
+    /// The key to use for `<LocalLeader>`.
+    pub local_leader: TerminalKey,
+}
+
+impl Default for Leaders {
+    fn default() -> Self {
+        let bslash = TerminalKey::from(crossterm::event::KeyCode::Char('\\'));
+
+        Leaders { leader: bslash, local_leader: bslash }
+    }
+}
+
+fn parse_leader<'a>(input: &'a str, leaders: &Leaders) -> IResult<&'a str, TerminalKey> {
+    alt((
+        value(leaders.leader, tag_no_case("<Leader>")),
+        value(leaders.local_leader, tag_no_case("<LocalLeader>")),
+    ))(input)
+}
+
 fn parse_base10_usize(input: &str) -> Result<usize, std::num::ParseIntError> {
     input.parse::<usize>()
 }
@@ -85,23 +116,65 @@ fn parse_key_simple(input: &str) -> IResult<&str, CommonEdgePathPart> {
     Ok((input, (rep, key)))
 }
 
-fn parse_key(input: &str) -> IResult<&str, (EdgeRepeat, CommonEdgeEvent)> {
-    alt((parse_special, parse_count, parse_edgename, parse_key_simple))(input)
+fn parse_mapped_nop(input: &str) -> IResult<&str, CommonEdgePathPart> {
+    let _ = parse_nop(input)?;
+
+    // <Nop> can be typed by a macro, but there's no way to map it.
+    Err(nom::Err::Failure(Error::new(input, ErrorKind::Verify)))
+}
+
+fn parse_key<'a>(
+    input: &'a str,
+    leaders: &Leaders,
+) -> IResult<&'a str, (EdgeRepeat, CommonEdgeEvent)> {
+    alt((
+        map(|i| parse_leader(i, leaders), |k| (EdgeRepeat::Once, EdgeEvent::Key(k))),
+        parse_mapped_nop,
+        parse_special,
+        parse_count,
+        parse_edgename,
+        parse_key_simple,
+    ))(input)
 }
 
 /// Parse
 pub fn parse(input: &str) -> IResult<&str, CommonEdgePath> {
-    let (input, res) = many1(parse_key)(input)?;
+    parse_with_leaders(input, &Leaders::default())
+}
+
+/// Parse a key sequence to map, using `leaders` for any `<Leader>` or `<LocalLeader>` keys.
+pub fn parse_with_leaders<'a>(
+    input: &'a str,
+    leaders: &Leaders,
+) -> IResult<&'a str, CommonEdgePath> {
+    let (input, res) = many1(|i| parse_key(i, leaders))(input)?;
     let (input, _) = eof(input)?;
 
     Ok((input, res))
 }
 
+/// Parse the keys typed by a macro, using `leaders` for any `<Leader>` or `<LocalLeader>` keys.
+pub fn parse_macro_with_leaders<'a>(
+    input: &'a str,
+    leaders: &Leaders,
+) -> IResult<&'a str, Vec<TerminalKey>> {
+    let (input, res) = many1(alt((
+        map(|i| parse_leader(i, leaders), Some),
+        value(None, parse_nop),
+        map(crate::key::parse::parse_special, Some),
+        map(parse_simple, Some),
+    )))(input)?;
+    let (input, _) = eof(input)?;
+
+    Ok((input, res.into_iter().flatten().collect()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::key::TerminalKey;
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::str::FromStr;
 
     macro_rules! once {
         ($ev: expr) => {
@@ -232,6 +305,43 @@ mod tests {
         assert_eq!(parse("<Bar>"), res![evkey!('|')]);
         assert_eq!(parse("<Bslash>"), res![evkey!('\\')]);
         assert_eq!(parse("<lt>"), res![evkey!('<')]);
+
+        // Like in Vim, the escape spellings ignore case.
+        assert_eq!(parse("<BAR>"), res![evkey!('|')]);
+        assert_eq!(parse("<bslash>"), res![evkey!('\\')]);
+        assert_eq!(parse("<LT>"), res![evkey!('<')]);
+    }
+
+    #[test]
+    fn test_pseudo_keys() {
+        let plug = TerminalKey::from_str("<Plug>").unwrap();
+        assert_eq!(parse("<Plug>(x)"), res![
+            once!(EdgeEvent::Key(plug)),
+            evkey!('('),
+            evkey!('x'),
+            evkey!(')')
+        ]);
+
+        // Leaders default to a backslash.
+        assert_eq!(parse("<Leader>w"), res![evkey!('\\'), evkey!('w')]);
+        assert_eq!(parse("<LocalLeader>w"), res![evkey!('\\'), evkey!('w')]);
+
+        let leaders = Leaders { leader: key!(' '), local_leader: key!(',') };
+        assert_eq!(parse_with_leaders("<leader>w<LocalLeader>", &leaders), res![
+            evkey!(' '),
+            evkey!('w'),
+            evkey!(',')
+        ]);
+
+        // <Nop> can't be mapped.
+        assert!(matches!(parse("a<Nop>"), Err(nom::Err::Failure(_))));
+
+        // But it can be typed, as nothing.
+        let (_, keys) = parse_macro_with_leaders("<Nop>", &leaders).unwrap();
+        assert_eq!(keys, vec![]);
+
+        let (_, keys) = parse_macro_with_leaders("<Leader><Nop><LT>", &leaders).unwrap();
+        assert_eq!(keys, vec![key!(' '), key!('<')]);
     }
 
     #[test]
@@ -311,6 +421,11 @@ mod tests {
     #[test]
     fn test_multiple_modifiers() {
         assert_eq!(parse("<C-M-X>"), res![evkey!('x', KeyModifiers::CONTROL | KeyModifiers::ALT)]);
+        assert_eq!(parse("<C-D-x>"), res![evkey!(
+            'x',
+            KeyModifiers::CONTROL | KeyModifiers::SUPER
+        )]);
+        assert_eq!(parse("<T-H-x>"), res![evkey!('x', KeyModifiers::META | KeyModifiers::HYPER)]);
     }
 
     #[test]
@@ -320,4 +435,24 @@ mod tests {
         assert_eq!(parse("<M->>"), res![evalt!('>')]);
         assert_eq!(parse("<M-<>"), res![evalt!('<')]);
     }
+
+    #[test]
+    fn test_class_display() {
+        let classes = [
+            CommonKeyClass::Count,
+            CommonKeyClass::Register,
+            CommonKeyClass::Mark,
+            CommonKeyClass::Octal,
+            CommonKeyClass::Decimal,
+            CommonKeyClass::Hexadecimal,
+            CommonKeyClass::Digraph1,
+            CommonKeyClass::Digraph2,
+        ];
+
+        for class in classes {
+            let (_, evs) = parse(&class.to_string()).unwrap();
+            assert_eq!(evs.len(), 1);
+            assert_eq!(evs[0].1, EdgeEvent::Class(class));
+        }
+    }
 }
@@ -11,7 +11,7 @@ use crate::{
     editing::application::{ApplicationInfo, EmptyInfo},
     editing::context::EditContext,
     key::TerminalKey,
-    keybindings::{dialog::Dialog, BindingMachine, InputKey, Step},
+    keybindings::{dialog::Dialog, BindingMachine, InputKey, KeyHint, MappingScope, Step},
     prelude::RepeatType,
 };
 
@@ -20,6 +20,14 @@ use super::{
         keybindings::{default_emacs_keys, EmacsMachine, InputStep as EmacsStep},
         EmacsState,
     },
+    helix::{
+        keybindings::{default_helix_keys, HelixMachine, InputStep as HelixStep},
+        HelixState,
+    },
+    kakoune::{
+        keybindings::{default_kakoune_keys, InputStep as KakouneStep, KakouneMachine},
+        KakouneState,
+    },
     vim::{
         keybindings::{default_vim_keys, InputStep as VimStep, VimMachine},
         VimState,
@@ -33,6 +41,12 @@ pub enum MixedChoice {
     /// Choose Emacs keybindings.
     Emacs,
 
+    /// Choose Helix keybindings.
+    Helix,
+
+    /// Choose Kakoune keybindings.
+    Kakoune,
+
     /// Choose Vim keybindings.
     Vim,
 }
@@ -41,18 +55,24 @@ macro_rules! delegate_bindings {
     ($s: expr, $invoke: expr) => {
         match $s {
             MixedBindings::Emacs(c) => $invoke(c),
+            MixedBindings::Helix(c) => $invoke(c),
+            MixedBindings::Kakoune(c) => $invoke(c),
             MixedBindings::Vim(c) => $invoke(c),
         }
     };
     ($s: expr, $invoke: expr, $arg: expr) => {
         match $s {
             MixedBindings::Emacs(c) => $invoke(c, $arg),
+            MixedBindings::Helix(c) => $invoke(c, $arg),
+            MixedBindings::Kakoune(c) => $invoke(c, $arg),
             MixedBindings::Vim(c) => $invoke(c, $arg),
         }
     };
     ($s: expr, $invoke: expr, $arg1: expr, $arg2: expr) => {
         match $s {
             MixedBindings::Emacs(c) => $invoke(c, $arg1, $arg2),
+            MixedBindings::Helix(c) => $invoke(c, $arg1, $arg2),
+            MixedBindings::Kakoune(c) => $invoke(c, $arg1, $arg2),
             MixedBindings::Vim(c) => $invoke(c, $arg1, $arg2),
         }
     };
@@ -65,11 +85,19 @@ where
     K: InputKey,
     I: ApplicationInfo,
     EmacsStep<I>: Step<K>,
+    HelixStep<I>: Step<K>,
+    KakouneStep<I>: Step<K>,
     VimStep<I>: Step<K>,
 {
     /// Wrap Emacs bindings.
     Emacs(EmacsMachine<K, I>),
 
+    /// Wrap Helix bindings.
+    Helix(HelixMachine<K, I>),
+
+    /// Wrap Kakoune bindings.
+    Kakoune(KakouneMachine<K, I>),
+
     /// Wrap Vim bindings.
     Vim(VimMachine<K, I>),
 }
@@ -81,6 +109,8 @@ where
     fn from(choice: MixedChoice) -> Self {
         match choice {
             MixedChoice::Emacs => MixedBindings::Emacs(default_emacs_keys()),
+            MixedChoice::Helix => MixedBindings::Helix(default_helix_keys()),
+            MixedChoice::Kakoune => MixedBindings::Kakoune(default_kakoune_keys()),
             MixedChoice::Vim => MixedBindings::Vim(default_vim_keys()),
         }
     }
@@ -91,6 +121,8 @@ where
     K: InputKey,
     I: ApplicationInfo,
     EmacsStep<I>: Step<K, A = Action<I>, Sequence = RepeatType, State = EmacsState<I>>,
+    HelixStep<I>: Step<K, A = Action<I>, Sequence = RepeatType, State = HelixState<I>>,
+    KakouneStep<I>: Step<K, A = Action<I>, Sequence = RepeatType, State = KakouneState<I>>,
     VimStep<I>: Step<K, A = Action<I>, Sequence = RepeatType, State = VimState<I>>,
 {
     fn input_key(&mut self, key: K) {
@@ -124,4 +156,16 @@ where
     fn run_dialog(&mut self, dialog: Box<dyn Dialog<Action<I>> + Send + Sync>) {
         delegate_bindings!(self, BindingMachine::run_dialog, dialog)
     }
+
+    fn pending_hints(&self) -> Vec<KeyHint> {
+        delegate_bindings!(self, BindingMachine::pending_hints)
+    }
+
+    fn set_focus(&mut self, focus: &[MappingScope]) {
+        delegate_bindings!(self, BindingMachine::set_focus, focus)
+    }
+
+    fn set_macro_executing(&mut self, executing: bool) {
+        delegate_bindings!(self, BindingMachine::set_macro_executing, executing)
+    }
 }
@@ -4,6 +4,8 @@
 //!
 //! This module contains components for recreating different flavors of editing environments.
 //!
+use std::fmt::{self, Display};
+
 use crossterm::event::KeyModifiers;
 
 use crate::{
@@ -19,7 +21,12 @@ mod macros;
 
 pub mod keyparse;
 
+#[cfg(feature = "serde")]
+pub mod keymap;
+
 pub mod emacs;
+pub mod helix;
+pub mod kakoune;
 pub mod mixed;
 pub mod vim;
 
@@ -92,6 +99,28 @@ impl InputKeyClass<TerminalKey> for CommonKeyClass {
 
         return classes;
     }
+
+    fn name(&self) -> Option<String> {
+        Some(self.to_string())
+    }
+}
+
+impl Display for CommonKeyClass {
+    /// Formats the class the way it's written in a key sequence, like `{register}`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CommonKeyClass::Count => "count",
+            CommonKeyClass::Register => "register",
+            CommonKeyClass::Mark => "mark",
+            CommonKeyClass::Octal => "oct",
+            CommonKeyClass::Decimal => "dec",
+            CommonKeyClass::Hexadecimal => "hex",
+            CommonKeyClass::Digraph1 => "digraph1",
+            CommonKeyClass::Digraph2 => "digraph2",
+        };
+
+        write!(f, "{{{name}}}")
+    }
 }
 
 #[inline]
@@ -848,6 +848,22 @@ pub fn vim_cmd_write_all<I: ApplicationInfo>(
     Ok(CommandStep::Continue(action.into(), ctx.context.clone()))
 }
 
+/// The `:nohlsearch` command.
+///
+/// *Aliases:* `noh`, `nohl`
+///
+/// Stop highlighting matches of the last search until the next search is performed.
+pub fn vim_cmd_nohlsearch<I: ApplicationInfo>(
+    desc: CommandDescription,
+    ctx: &mut CommandContext,
+) -> CommandResult<I> {
+    if !desc.arg.strings()?.is_empty() {
+        return Err(CommandError::InvalidArgument);
+    }
+
+    Ok(CommandStep::Continue(Action::ClearSearchHighlight, ctx.context.clone()))
+}
+
 fn vim_cmd_filter<I: ApplicationInfo>(
     _: CommandDescription,
     _: &mut CommandContext,
@@ -915,6 +931,11 @@ fn default_cmds<I: ApplicationInfo>() -> Vec<VimCommand<I>> {
             aliases: strs!["p"],
             f: vim_cmd_print,
         },
+        VimCommand {
+            name: "nohlsearch".into(),
+            aliases: strs!["noh", "nohl"],
+            f: vim_cmd_nohlsearch,
+        },
         VimCommand {
             name: "substitute".into(),
             aliases: strs!["s"],
@@ -1311,4 +1332,23 @@ mod tests {
         let res = cmds.input_cmd("4resize -2", ctx.clone());
         assert_eq!(res.unwrap(), expect);
     }
+
+    #[test]
+    fn test_nohlsearch() {
+        let (mut cmds, ctx) = mkcmd();
+        let expect = vec![(Action::ClearSearchHighlight, ctx.clone())];
+
+        let res = cmds.input_cmd("nohlsearch", ctx.clone());
+        assert_eq!(res.unwrap(), expect);
+
+        let res = cmds.input_cmd("nohl", ctx.clone());
+        assert_eq!(res.unwrap(), expect);
+
+        let res = cmds.input_cmd("noh", ctx.clone());
+        assert_eq!(res.unwrap(), expect);
+
+        // :nohlsearch doesn't take any arguments.
+        let res = cmds.input_cmd("noh foo", ctx.clone());
+        assert_eq!(res, Err(CommandError::InvalidArgument));
+    }
 }
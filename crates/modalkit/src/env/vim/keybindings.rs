@@ -57,11 +57,13 @@ use crate::actions::{
     CursorAction,
     EditAction,
     EditorAction,
+    FoldAction,
     HistoryAction,
     InsertTextAction,
     MacroAction,
     PromptAction,
     SelectionAction,
+    SpellAction,
     TabAction,
     WindowAction,
 };
@@ -69,11 +71,15 @@ use crate::editing::application::{ApplicationInfo, EmptyInfo};
 use crate::prelude::*;
 
 use super::{
-    super::{keyparse::parse, CommonKeyClass, ShellBindings},
+    super::{keyparse::parse, CommonEdgePath, CommonKeyClass, ShellBindings},
     VimMode,
     VimState,
 };
 
+#[cfg(feature = "serde")]
+use super::super::keymap::{Keymap, KeymapError};
+use super::super::keyparse::Leaders;
+
 use crate::key::TerminalKey;
 use crate::keybindings::{InputBindings, ModalMachine, Step};
 
@@ -347,6 +353,7 @@ pub struct InputStep<I: ApplicationInfo> {
     external: Vec<ExternalAction<I>>,
     fallthrough_mode: Option<VimMode>,
     nextm: Option<VimMode>,
+    desc: Option<String>,
 }
 
 impl<I: ApplicationInfo> InputStep<I> {
@@ -357,6 +364,7 @@ impl<I: ApplicationInfo> InputStep<I> {
             external: vec![],
             fallthrough_mode: None,
             nextm: None,
+            desc: None,
         }
     }
 
@@ -380,6 +388,23 @@ impl<I: ApplicationInfo> InputStep<I> {
         self
     }
 
+    /// Create an operator that acts on the current selection, for use in Visual and Select modes.
+    ///
+    /// `mode` specifies which mode to enter after the operator runs. This defaults to
+    /// [VimMode::Normal].
+    pub fn selection_operator(mut self, op: EditAction, mode: Option<VimMode>) -> Self {
+        let act = EditorAction::Edit(Specifier::Exact(op), EditTarget::Selection);
+
+        self.nextm = Some(mode.unwrap_or_default());
+        self.external = vec![ExternalAction::Something(act.into())];
+
+        if matches!(mode, Some(VimMode::Insert)) {
+            self.internal = vec![InternalAction::SetInsertStyle(InsertStyle::Insert)];
+        }
+
+        self
+    }
+
     /// Set the [VimMode] to switch to after this step.
     pub fn goto(mut self, mode: VimMode) -> Self {
         self.nextm = Some(mode);
@@ -391,6 +416,12 @@ impl<I: ApplicationInfo> InputStep<I> {
         self.external = acts.into_iter().map(ExternalAction::Something).collect();
         self
     }
+
+    /// Set a short description of what this step does, to show in pending-key hints.
+    pub fn description(mut self, desc: impl Into<String>) -> Self {
+        self.desc = Some(desc.into());
+        self
+    }
 }
 
 impl<I: ApplicationInfo> Clone for InputStep<I> {
@@ -400,6 +431,7 @@ impl<I: ApplicationInfo> Clone for InputStep<I> {
             external: self.external.clone(),
             fallthrough_mode: self.fallthrough_mode,
             nextm: self.nextm,
+            desc: self.desc.clone(),
         }
     }
 }
@@ -418,6 +450,7 @@ impl<I: ApplicationInfo> Step<TerminalKey> for InputStep<I> {
                 external,
                 fallthrough_mode: None,
                 nextm: None,
+                ..
             } => internal.is_empty() && external.is_empty(),
             _ => false,
         }
@@ -427,6 +460,20 @@ impl<I: ApplicationInfo> Step<TerminalKey> for InputStep<I> {
         self.fallthrough_mode
     }
 
+    fn can_wait(&self) -> bool {
+        // Steps that only update the context, or that toggle macro recording when it hasn't
+        // started yet, keep waiting for the rest of the sequence.
+        self.nextm.is_none() &&
+            self.fallthrough_mode.is_none() &&
+            self.external
+                .iter()
+                .all(|act| matches!(act, ExternalAction::MacroToggle(true)))
+    }
+
+    fn describe(&self) -> Option<&str> {
+        self.desc.as_deref()
+    }
+
     fn step(&self, ctx: &mut VimState<I>) -> (Vec<Action<I>>, Option<Self::M>) {
         match (self.nextm, self.internal.as_slice(), ctx.persist.shape) {
             (Some(VimMode::Visual), [InternalAction::SetTargetShape(f, s1)], Some(ref s2))
@@ -492,6 +539,7 @@ macro_rules! isv {
             fallthrough_mode: None,
 
             nextm: None,
+            desc: None,
         }
     };
     ($ints: expr, $exts: expr) => {
@@ -501,6 +549,7 @@ macro_rules! isv {
             fallthrough_mode: None,
 
             nextm: None,
+            desc: None,
         }
     };
     ($ints: expr, $exts: expr, $ns: expr) => {
@@ -510,6 +559,7 @@ macro_rules! isv {
             fallthrough_mode: None,
 
             nextm: Some($ns),
+            desc: None,
         }
     };
 }
@@ -531,6 +581,7 @@ macro_rules! fallthrough {
             fallthrough_mode: Some($mode),
 
             nextm: None,
+            desc: None,
         }
     };
     ($mode: expr, $iacts: expr) => {
@@ -540,6 +591,7 @@ macro_rules! fallthrough {
             fallthrough_mode: Some($mode),
 
             nextm: None,
+            desc: None,
         }
     };
 }
@@ -1320,26 +1372,26 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( NXOMAP, "E", edit_end!(MoveType::WordEnd(WordStyle::Big, MoveDir1D::Next)) ),
         ( NXOMAP, "f", charsearch!(MoveDir1D::Next, true) ),
         ( NXOMAP, "F", charsearch!(MoveDir1D::Previous, true) ),
-        ( NXOMAP, "g0", edit_end!(MoveType::ScreenLinePos(MovePosition::Beginning), 0) ),
-        ( NXOMAP, "ge", edit_end!(MoveType::WordEnd(WordStyle::Little, MoveDir1D::Previous)) ),
-        ( NXOMAP, "gE", edit_end!(MoveType::WordEnd(WordStyle::Big, MoveDir1D::Previous)) ),
-        ( NXOMAP, "gg", edit_end_ca!(MoveType::BufferPos(MovePosition::Beginning), MoveType::BufferLineOffset) ),
-        ( NXOMAP, "gj", edit_end!(MoveType::ScreenLine(MoveDir1D::Next)) ),
-        ( NXOMAP, "gk", edit_end!(MoveType::ScreenLine(MoveDir1D::Previous)) ),
-        ( NXOMAP, "gm", edit_end!(MoveType::ScreenLinePos(MovePosition::Middle), 0) ),
-        ( NXOMAP, "gM", edit_target_end_ca!(EditTarget::Motion(MoveType::LinePos(MovePosition::Middle), Count::MinusOne), EditTarget::Motion(MoveType::LinePercent, Count::Contextual)) ),
-        ( NXOMAP, "go", edit_end!(MoveType::BufferByteOffset) ),
-        ( NXOMAP, "g_", edit_end!(MoveType::FinalNonBlank(MoveDir1D::Next), Count::MinusOne) ),
-        ( NXOMAP, "g^", edit_end!(MoveType::ScreenFirstWord(MoveDir1D::Next), 0) ),
-        ( NXOMAP, "g$", edit_end!(MoveType::ScreenLinePos(MovePosition::End), Count::MinusOne) ),
-        ( NXOMAP, "g#", edit_word_search_end!(WordStyle::Little, false, MoveDir1D::Previous) ),
-        ( NXOMAP, "g*", edit_word_search_end!(WordStyle::Little, false, MoveDir1D::Next) ),
+        ( NXOMAP, "g0", edit_end!(MoveType::ScreenLinePos(MovePosition::Beginning), 0).description("start of screen line") ),
+        ( NXOMAP, "ge", edit_end!(MoveType::WordEnd(WordStyle::Little, MoveDir1D::Previous)).description("end of previous word") ),
+        ( NXOMAP, "gE", edit_end!(MoveType::WordEnd(WordStyle::Big, MoveDir1D::Previous)).description("end of previous WORD") ),
+        ( NXOMAP, "gg", edit_end_ca!(MoveType::BufferPos(MovePosition::Beginning), MoveType::BufferLineOffset).description("first line") ),
+        ( NXOMAP, "gj", edit_end!(MoveType::ScreenLine(MoveDir1D::Next)).description("screen line down") ),
+        ( NXOMAP, "gk", edit_end!(MoveType::ScreenLine(MoveDir1D::Previous)).description("screen line up") ),
+        ( NXOMAP, "gm", edit_end!(MoveType::ScreenLinePos(MovePosition::Middle), 0).description("middle of screen line") ),
+        ( NXOMAP, "gM", edit_target_end_ca!(EditTarget::Motion(MoveType::LinePos(MovePosition::Middle), Count::MinusOne), EditTarget::Motion(MoveType::LinePercent, Count::Contextual)).description("middle of text line") ),
+        ( NXOMAP, "go", edit_end!(MoveType::BufferByteOffset).description("byte offset") ),
+        ( NXOMAP, "g_", edit_end!(MoveType::FinalNonBlank(MoveDir1D::Next), Count::MinusOne).description("last non-blank character") ),
+        ( NXOMAP, "g^", edit_end!(MoveType::ScreenFirstWord(MoveDir1D::Next), 0).description("first non-blank of screen line") ),
+        ( NXOMAP, "g$", edit_end!(MoveType::ScreenLinePos(MovePosition::End), Count::MinusOne).description("end of screen line") ),
+        ( NXOMAP, "g#", edit_word_search_end!(WordStyle::Little, false, MoveDir1D::Previous).description("search word backward, partial") ),
+        ( NXOMAP, "g*", edit_word_search_end!(WordStyle::Little, false, MoveDir1D::Next).description("search word forward, partial") ),
         ( NXOMAP, "g'{mark}", unmapped!() ),
         ( NXOMAP, "g`{mark}", unmapped!() ),
-        ( NXOMAP, "g<Down>", edit_end!(MoveType::ScreenLine(MoveDir1D::Next)) ),
-        ( NXOMAP, "g<Up>", edit_end!(MoveType::ScreenLine(MoveDir1D::Previous)) ),
-        ( NXOMAP, "g<End>", edit_end!(MoveType::ScreenLinePos(MovePosition::End), Count::MinusOne) ),
-        ( NXOMAP, "g<Home>", edit_end!(MoveType::ScreenLinePos(MovePosition::Beginning), 1) ),
+        ( NXOMAP, "g<Down>", edit_end!(MoveType::ScreenLine(MoveDir1D::Next)).description("screen line down") ),
+        ( NXOMAP, "g<Up>", edit_end!(MoveType::ScreenLine(MoveDir1D::Previous)).description("screen line up") ),
+        ( NXOMAP, "g<End>", edit_end!(MoveType::ScreenLinePos(MovePosition::End), Count::MinusOne).description("end of screen line") ),
+        ( NXOMAP, "g<Home>", edit_end!(MoveType::ScreenLinePos(MovePosition::Beginning), 1).description("start of screen line") ),
         ( NXOMAP, "G", edit_end_ca!(MoveType::BufferPos(MovePosition::End), MoveType::BufferLineOffset) ),
         ( NXOMAP, "h", edit_end!(MoveType::Column(MoveDir1D::Previous, false)) ),
         ( NXOMAP, "H", edit_end!(MoveType::ViewportPos(MovePosition::Beginning)) ),
@@ -1370,10 +1422,14 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( NXOMAP, ")", edit_end!(MoveType::SentenceBegin(MoveDir1D::Next)) ),
         ( NXOMAP, "{", edit_end!(MoveType::ParagraphBegin(MoveDir1D::Previous)) ),
         ( NXOMAP, "}", edit_end!(MoveType::ParagraphBegin(MoveDir1D::Next)) ),
-        ( NXOMAP, "[[", edit_end!(MoveType::SectionBegin(MoveDir1D::Previous)) ),
-        ( NXOMAP, "[]", edit_end!(MoveType::SectionEnd(MoveDir1D::Previous)) ),
-        ( NXOMAP, "][", edit_end!(MoveType::SectionEnd(MoveDir1D::Next)) ),
-        ( NXOMAP, "]]", edit_end!(MoveType::SectionBegin(MoveDir1D::Next)) ),
+        ( NXOMAP, "[[", edit_end!(MoveType::SectionBegin(MoveDir1D::Previous)).description("previous section start") ),
+        ( NXOMAP, "[]", edit_end!(MoveType::SectionEnd(MoveDir1D::Previous)).description("previous section end") ),
+        ( NXOMAP, "][", edit_end!(MoveType::SectionEnd(MoveDir1D::Next)).description("next section end") ),
+        ( NXOMAP, "]]", edit_end!(MoveType::SectionBegin(MoveDir1D::Next)).description("next section start") ),
+        ( NXOMAP, "[s", edit_end!(MoveType::Misspelling(MoveDir1D::Previous)).description("previous misspelling") ),
+        ( NXOMAP, "[S", edit_end!(MoveType::Misspelling(MoveDir1D::Previous)).description("previous misspelling") ),
+        ( NXOMAP, "]s", edit_end!(MoveType::Misspelling(MoveDir1D::Next)).description("next misspelling") ),
+        ( NXOMAP, "]S", edit_end!(MoveType::Misspelling(MoveDir1D::Next)).description("next misspelling") ),
         ( NXOMAP, "[(", unmapped!() ),
         ( NXOMAP, "[{", unmapped!() ),
         ( NXOMAP, "])", unmapped!() ),
@@ -1389,63 +1445,63 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( NVMAP, "<C-F>", scroll2d!(MoveDir2D::Down, ScrollSize::Page) ),
         ( NVMAP, "<C-U>", scroll2d!(MoveDir2D::Up, ScrollSize::HalfPage) ),
         ( NVMAP, "<C-V>", visual!(TargetShape::BlockWise) ),
-        ( NVMAP, "<C-W>b", window_focus!(FocusChange::Position(MovePosition::End)) ),
-        ( NVMAP, "<C-W>c", window_close!(WindowTarget::Single, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)) ),
+        ( NVMAP, "<C-W>b", window_focus!(FocusChange::Position(MovePosition::End)).description("bottom window") ),
+        ( NVMAP, "<C-W>c", window_close!(WindowTarget::Single, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)).description("close window") ),
         ( NVMAP, "<C-W>d", unmapped!() ),
-        ( NVMAP, "<C-W>g<Tab>", tab_focus!(FocusChange::PreviouslyFocused) ),
-        ( NVMAP, "<C-W>h", window_focus!(FocusChange::Direction2D(MoveDir2D::Left, Count::Contextual)) ),
-        ( NVMAP, "<C-W>H", window!(WindowAction::MoveSide(MoveDir2D::Left)) ),
+        ( NVMAP, "<C-W>g<Tab>", tab_focus!(FocusChange::PreviouslyFocused).description("last accessed tab") ),
+        ( NVMAP, "<C-W>h", window_focus!(FocusChange::Direction2D(MoveDir2D::Left, Count::Contextual)).description("window to the left") ),
+        ( NVMAP, "<C-W>H", window!(WindowAction::MoveSide(MoveDir2D::Left)).description("move window to far left") ),
         ( NVMAP, "<C-W>i", unmapped!() ),
-        ( NVMAP, "<C-W>j", window_focus!(FocusChange::Direction2D(MoveDir2D::Down, Count::Contextual)) ),
-        ( NVMAP, "<C-W>J", window!(WindowAction::MoveSide(MoveDir2D::Down)) ),
-        ( NVMAP, "<C-W>k", window_focus!(FocusChange::Direction2D(MoveDir2D::Up, Count::Contextual)) ),
-        ( NVMAP, "<C-W>K", window!(WindowAction::MoveSide(MoveDir2D::Up)) ),
-        ( NVMAP, "<C-W>l", window_focus!(FocusChange::Direction2D(MoveDir2D::Right, Count::Contextual)) ),
-        ( NVMAP, "<C-W>L", window!(WindowAction::MoveSide(MoveDir2D::Right)) ),
-        ( NVMAP, "<C-W>n", window_file!(OpenTarget::Unnamed) ),
-        ( NVMAP, "<C-W>o", window_quit!(WindowTarget::AllBut, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)) ),
-        ( NVMAP, "<C-W>p", window_focus!(FocusChange::PreviouslyFocused) ),
-        ( NVMAP, "<C-W>q", window_quit!(WindowTarget::Single, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)) ),
-        ( NVMAP, "<C-W>r", window!(WindowAction::Rotate(MoveDir1D::Next)) ),
-        ( NVMAP, "<C-W>R", window!(WindowAction::Rotate(MoveDir1D::Previous)) ),
-        ( NVMAP, "<C-W>s", window_split!(Axis::Horizontal) ),
-        ( NVMAP, "<C-W>S", window_split!(Axis::Horizontal) ),
-        ( NVMAP, "<C-W>t", window_focus!(FocusChange::Position(MovePosition::Beginning)) ),
-        ( NVMAP, "<C-W>T", tab!(TabAction::Extract(FocusChange::Current, MoveDir1D::Next), TabAction::Extract(FocusChange::Offset(Count::Contextual, false), MoveDir1D::Previous)) ),
-        ( NVMAP, "<C-W>v", window_split!(Axis::Vertical) ),
-        ( NVMAP, "<C-W>w", window_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), true), FocusChange::Offset(Count::Contextual, true)) ),
-        ( NVMAP, "<C-W>W", window_focus!(FocusChange::Direction1D(MoveDir1D::Previous, Count::Exact(1), true), FocusChange::Offset(Count::Contextual, true)) ),
-        ( NVMAP, "<C-W>x", window_exchange!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), false), FocusChange::Offset(Count::Contextual, false)) ),
+        ( NVMAP, "<C-W>j", window_focus!(FocusChange::Direction2D(MoveDir2D::Down, Count::Contextual)).description("window below") ),
+        ( NVMAP, "<C-W>J", window!(WindowAction::MoveSide(MoveDir2D::Down)).description("move window to bottom") ),
+        ( NVMAP, "<C-W>k", window_focus!(FocusChange::Direction2D(MoveDir2D::Up, Count::Contextual)).description("window above") ),
+        ( NVMAP, "<C-W>K", window!(WindowAction::MoveSide(MoveDir2D::Up)).description("move window to top") ),
+        ( NVMAP, "<C-W>l", window_focus!(FocusChange::Direction2D(MoveDir2D::Right, Count::Contextual)).description("window to the right") ),
+        ( NVMAP, "<C-W>L", window!(WindowAction::MoveSide(MoveDir2D::Right)).description("move window to far right") ),
+        ( NVMAP, "<C-W>n", window_file!(OpenTarget::Unnamed).description("new window") ),
+        ( NVMAP, "<C-W>o", window_quit!(WindowTarget::AllBut, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)).description("close other windows") ),
+        ( NVMAP, "<C-W>p", window_focus!(FocusChange::PreviouslyFocused).description("previous window") ),
+        ( NVMAP, "<C-W>q", window_quit!(WindowTarget::Single, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)).description("quit window") ),
+        ( NVMAP, "<C-W>r", window!(WindowAction::Rotate(MoveDir1D::Next)).description("rotate windows downwards") ),
+        ( NVMAP, "<C-W>R", window!(WindowAction::Rotate(MoveDir1D::Previous)).description("rotate windows upwards") ),
+        ( NVMAP, "<C-W>s", window_split!(Axis::Horizontal).description("split window") ),
+        ( NVMAP, "<C-W>S", window_split!(Axis::Horizontal).description("split window") ),
+        ( NVMAP, "<C-W>t", window_focus!(FocusChange::Position(MovePosition::Beginning)).description("top window") ),
+        ( NVMAP, "<C-W>T", tab!(TabAction::Extract(FocusChange::Current, MoveDir1D::Next), TabAction::Extract(FocusChange::Offset(Count::Contextual, false), MoveDir1D::Previous)).description("move window to new tab") ),
+        ( NVMAP, "<C-W>v", window_split!(Axis::Vertical).description("split window vertically") ),
+        ( NVMAP, "<C-W>w", window_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), true), FocusChange::Offset(Count::Contextual, true)).description("next window") ),
+        ( NVMAP, "<C-W>W", window_focus!(FocusChange::Direction1D(MoveDir1D::Previous, Count::Exact(1), true), FocusChange::Offset(Count::Contextual, true)).description("previous window") ),
+        ( NVMAP, "<C-W>x", window_exchange!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), false), FocusChange::Offset(Count::Contextual, false)).description("exchange with next window") ),
         ( NVMAP, "<C-W>z", unmapped!() ),
-        ( NVMAP, "<C-W>=", window_clear_size!() ),
-        ( NVMAP, "<C-W>-", window_resize!(Axis::Horizontal, SizeChange::Decrease(Count::Contextual)) ),
-        ( NVMAP, "<C-W>+", window_resize!(Axis::Horizontal, SizeChange::Increase(Count::Contextual)) ),
-        ( NVMAP, "<C-W>_", window_resize!(Axis::Horizontal, SizeChange::Exact(Count::Contextual)) ),
-        ( NVMAP, "<C-W><", window_resize!(Axis::Vertical, SizeChange::Decrease(Count::Contextual)) ),
-        ( NVMAP, "<C-W>>", window_resize!(Axis::Vertical, SizeChange::Increase(Count::Contextual)) ),
-        ( NVMAP, "<C-W>|", window_resize!(Axis::Vertical, SizeChange::Exact(Count::Contextual)) ),
-        ( NVMAP, "<C-W><C-B>", window_focus!(FocusChange::Position(MovePosition::End)) ),
-        ( NVMAP, "<C-W><C-C>", normal!() ),
+        ( NVMAP, "<C-W>=", window_clear_size!().description("make windows equal size") ),
+        ( NVMAP, "<C-W>-", window_resize!(Axis::Horizontal, SizeChange::Decrease(Count::Contextual)).description("decrease height") ),
+        ( NVMAP, "<C-W>+", window_resize!(Axis::Horizontal, SizeChange::Increase(Count::Contextual)).description("increase height") ),
+        ( NVMAP, "<C-W>_", window_resize!(Axis::Horizontal, SizeChange::Exact(Count::Contextual)).description("set height") ),
+        ( NVMAP, "<C-W><", window_resize!(Axis::Vertical, SizeChange::Decrease(Count::Contextual)).description("decrease width") ),
+        ( NVMAP, "<C-W>>", window_resize!(Axis::Vertical, SizeChange::Increase(Count::Contextual)).description("increase width") ),
+        ( NVMAP, "<C-W>|", window_resize!(Axis::Vertical, SizeChange::Exact(Count::Contextual)).description("set width") ),
+        ( NVMAP, "<C-W><C-B>", window_focus!(FocusChange::Position(MovePosition::End)).description("bottom window") ),
+        ( NVMAP, "<C-W><C-C>", normal!().description("cancel") ),
         ( NVMAP, "<C-W><C-D>", unmapped!() ),
         ( NVMAP, "<C-W><C-I>", unmapped!() ),
-        ( NVMAP, "<C-W><C-H>", window_focus!(FocusChange::Direction2D(MoveDir2D::Left, Count::Contextual)) ),
-        ( NVMAP, "<C-W><C-J>", window_focus!(FocusChange::Direction2D(MoveDir2D::Down, Count::Contextual)) ),
-        ( NVMAP, "<C-W><C-K>", window_focus!(FocusChange::Direction2D(MoveDir2D::Up, Count::Contextual)) ),
-        ( NVMAP, "<C-W><C-L>", window_focus!(FocusChange::Direction2D(MoveDir2D::Right, Count::Contextual)) ),
-        ( NVMAP, "<C-W><C-N>", window_file!(OpenTarget::Unnamed) ),
-        ( NVMAP, "<C-W><C-O>", window_quit!(WindowTarget::AllBut, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)) ),
-        ( NVMAP, "<C-W><C-Q>", window_quit!(WindowTarget::Single, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)) ),
-        ( NVMAP, "<C-W><C-R>", window!(WindowAction::Rotate(MoveDir1D::Next)) ),
-        ( NVMAP, "<C-W><C-S>", window_split!(Axis::Horizontal) ),
-        ( NVMAP, "<C-W><C-T>", window_focus!(FocusChange::Position(MovePosition::Beginning)) ),
-        ( NVMAP, "<C-W><C-V>", window_split!(Axis::Vertical) ),
-        ( NVMAP, "<C-W><C-W>", window_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), true), FocusChange::Offset(Count::Contextual, true)) ),
-        ( NVMAP, "<C-W><C-X>", window_exchange!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), false), FocusChange::Offset(Count::Contextual, false)) ),
+        ( NVMAP, "<C-W><C-H>", window_focus!(FocusChange::Direction2D(MoveDir2D::Left, Count::Contextual)).description("window to the left") ),
+        ( NVMAP, "<C-W><C-J>", window_focus!(FocusChange::Direction2D(MoveDir2D::Down, Count::Contextual)).description("window below") ),
+        ( NVMAP, "<C-W><C-K>", window_focus!(FocusChange::Direction2D(MoveDir2D::Up, Count::Contextual)).description("window above") ),
+        ( NVMAP, "<C-W><C-L>", window_focus!(FocusChange::Direction2D(MoveDir2D::Right, Count::Contextual)).description("window to the right") ),
+        ( NVMAP, "<C-W><C-N>", window_file!(OpenTarget::Unnamed).description("new window") ),
+        ( NVMAP, "<C-W><C-O>", window_quit!(WindowTarget::AllBut, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)).description("close other windows") ),
+        ( NVMAP, "<C-W><C-Q>", window_quit!(WindowTarget::Single, FocusChange::Current, FocusChange::Offset(Count::Contextual, true)).description("quit window") ),
+        ( NVMAP, "<C-W><C-R>", window!(WindowAction::Rotate(MoveDir1D::Next)).description("rotate windows downwards") ),
+        ( NVMAP, "<C-W><C-S>", window_split!(Axis::Horizontal).description("split window") ),
+        ( NVMAP, "<C-W><C-T>", window_focus!(FocusChange::Position(MovePosition::Beginning)).description("top window") ),
+        ( NVMAP, "<C-W><C-V>", window_split!(Axis::Vertical).description("split window vertically") ),
+        ( NVMAP, "<C-W><C-W>", window_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), true), FocusChange::Offset(Count::Contextual, true)).description("next window") ),
+        ( NVMAP, "<C-W><C-X>", window_exchange!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), false), FocusChange::Offset(Count::Contextual, false)).description("exchange with next window") ),
         ( NVMAP, "<C-W><C-Z>", unmapped!() ),
-        ( NVMAP, "<C-W><Left>", window_focus!(FocusChange::Direction2D(MoveDir2D::Left, Count::Contextual)) ),
-        ( NVMAP, "<C-W><Down>", window_focus!(FocusChange::Direction2D(MoveDir2D::Down, Count::Contextual)) ),
-        ( NVMAP, "<C-W><Up>", window_focus!(FocusChange::Direction2D(MoveDir2D::Up, Count::Contextual)) ),
-        ( NVMAP, "<C-W><Right>", window_focus!(FocusChange::Direction2D(MoveDir2D::Right, Count::Contextual)) ),
+        ( NVMAP, "<C-W><Left>", window_focus!(FocusChange::Direction2D(MoveDir2D::Left, Count::Contextual)).description("window to the left") ),
+        ( NVMAP, "<C-W><Down>", window_focus!(FocusChange::Direction2D(MoveDir2D::Down, Count::Contextual)).description("window below") ),
+        ( NVMAP, "<C-W><Up>", window_focus!(FocusChange::Direction2D(MoveDir2D::Up, Count::Contextual)).description("window above") ),
+        ( NVMAP, "<C-W><Right>", window_focus!(FocusChange::Direction2D(MoveDir2D::Right, Count::Contextual)).description("window to the right") ),
         ( NVMAP, "<C-Y>", scroll2d!(MoveDir2D::Up, ScrollSize::Cell) ),
         ( NVMAP, "<S-Up>", scroll2d!(MoveDir2D::Up, ScrollSize::Page) ),
         ( NVMAP, "<S-Down>", scroll2d!(MoveDir2D::Down, ScrollSize::Page) ),
@@ -1453,45 +1509,45 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( NVMAP, "<PageUp>", scroll2d!(MoveDir2D::Up, ScrollSize::Page) ),
 
         // Normal, Visual mode keys
-        ( NXMAP, "gh", select!(TargetShape::CharWise) ),
-        ( NXMAP, "gH", select!(TargetShape::LineWise) ),
-        ( NXMAP, "gt", tab_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), true), FocusChange::Offset(Count::Contextual, false)) ),
-        ( NXMAP, "gT", tab_focus!(FocusChange::Direction1D(MoveDir1D::Previous, Count::Contextual, true)) ),
-        ( NXMAP, "g<C-H>", select!(TargetShape::BlockWise) ),
+        ( NXMAP, "gh", select!(TargetShape::CharWise).description("Select mode") ),
+        ( NXMAP, "gH", select!(TargetShape::LineWise).description("Select Line mode") ),
+        ( NXMAP, "gt", tab_focus!(FocusChange::Direction1D(MoveDir1D::Next, Count::Exact(1), true), FocusChange::Offset(Count::Contextual, false)).description("next tab") ),
+        ( NXMAP, "gT", tab_focus!(FocusChange::Direction1D(MoveDir1D::Previous, Count::Contextual, true)).description("previous tab") ),
+        ( NXMAP, "g<C-H>", select!(TargetShape::BlockWise).description("Select Block mode") ),
         ( NXMAP, "m{mark}", act!(EditorAction::Mark(Specifier::Contextual)) ),
         ( NXMAP, "q{register}", isv!(vec![], vec![ExternalAction::MacroToggle(false)]) ),
         ( NXMAP, "q", isv!(vec![], vec![ExternalAction::MacroToggle(true)]) ),
         ( NXMAP, "v", visual!(TargetShape::CharWise) ),
         ( NXMAP, "V", visual!(TargetShape::LineWise) ),
-        ( NXMAP, "zb", scrollcpv!(MovePosition::End, false) ),
-        ( NXMAP, "ze", scrollcph!(MovePosition::End) ),
-        ( NXMAP, "zg", unmapped!() ),
-        ( NXMAP, "zG", unmapped!() ),
-        ( NXMAP, "zh", scroll2d!(MoveDir2D::Left, ScrollSize::Cell) ),
-        ( NXMAP, "zH", scroll2d!(MoveDir2D::Left, ScrollSize::HalfPage) ),
-        ( NXMAP, "zl", scroll2d!(MoveDir2D::Right, ScrollSize::Cell) ),
-        ( NXMAP, "zL", scroll2d!(MoveDir2D::Right, ScrollSize::HalfPage) ),
+        ( NXMAP, "za", act!(EditorAction::Fold(FoldAction::Toggle(Count::Contextual))).description("toggle fold") ),
+        ( NXMAP, "zb", scrollcpv!(MovePosition::End, false).description("scroll line to bottom") ),
+        ( NXMAP, "zc", act!(EditorAction::Fold(FoldAction::Close(Count::Contextual))).description("close fold") ),
+        ( NXMAP, "zd", act!(EditorAction::Fold(FoldAction::Delete)).description("delete fold") ),
+        ( NXMAP, "ze", scrollcph!(MovePosition::End).description("scroll cursor to right edge") ),
+        ( NXMAP, "zE", act!(EditorAction::Fold(FoldAction::DeleteAll)).description("delete all folds") ),
+        ( NXMAP, "zh", scroll2d!(MoveDir2D::Left, ScrollSize::Cell).description("scroll left") ),
+        ( NXMAP, "zH", scroll2d!(MoveDir2D::Left, ScrollSize::HalfPage).description("scroll half a screen left") ),
+        ( NXMAP, "zj", edit_end!(MoveType::Fold(MoveDir1D::Next)).description("start of next fold") ),
+        ( NXMAP, "zk", edit_end!(MoveType::Fold(MoveDir1D::Previous)).description("end of previous fold") ),
+        ( NXMAP, "zl", scroll2d!(MoveDir2D::Right, ScrollSize::Cell).description("scroll right") ),
+        ( NXMAP, "zL", scroll2d!(MoveDir2D::Right, ScrollSize::HalfPage).description("scroll half a screen right") ),
+        ( NXMAP, "zM", act!(EditorAction::Fold(FoldAction::CloseAll)).description("close all folds") ),
+        ( NXMAP, "zo", act!(EditorAction::Fold(FoldAction::Open(Count::Contextual))).description("open fold") ),
         ( NXMAP, "zp", unmapped!() ),
         ( NXMAP, "zP", unmapped!() ),
-        ( NXMAP, "zs", scrollcph!(MovePosition::Beginning) ),
-        ( NXMAP, "zt", scrollcpv!(MovePosition::Beginning, false) ),
-        ( NXMAP, "zug", unmapped!() ),
-        ( NXMAP, "zuG", unmapped!() ),
-        ( NXMAP, "zuw", unmapped!() ),
-        ( NXMAP, "zuW", unmapped!() ),
-        ( NXMAP, "zw", unmapped!() ),
-        ( NXMAP, "zW", unmapped!() ),
+        ( NXMAP, "zR", act!(EditorAction::Fold(FoldAction::OpenAll)).description("open all folds") ),
+        ( NXMAP, "zs", scrollcph!(MovePosition::Beginning).description("scroll cursor to left edge") ),
+        ( NXMAP, "zt", scrollcpv!(MovePosition::Beginning, false).description("scroll line to top") ),
         ( NXMAP, "zy", unmapped!() ),
-        ( NXMAP, "zz", scrollcpv!(MovePosition::Middle, false) ),
+        ( NXMAP, "zz", scrollcpv!(MovePosition::Middle, false).description("scroll line to middle") ),
         ( NXMAP, "z+", unmapped!() ),
-        ( NXMAP, "z-", scrollcpv!(MovePosition::End, true) ),
-        ( NXMAP, "z.", scrollcpv!(MovePosition::Middle, true) ),
-        ( NXMAP, "z=", unmapped!() ),
+        ( NXMAP, "z-", scrollcpv!(MovePosition::End, true).description("scroll line to bottom, first non-blank") ),
+        ( NXMAP, "z.", scrollcpv!(MovePosition::Middle, true).description("scroll line to middle, first non-blank") ),
         ( NXMAP, "z^", unmapped!() ),
-        ( NXMAP, "z<Left>", scroll2d!(MoveDir2D::Left, ScrollSize::Cell) ),
-        ( NXMAP, "z<Right>", scroll2d!(MoveDir2D::Right, ScrollSize::Cell) ),
-        ( NXMAP, "z<Enter>", scrollcpv!(MovePosition::Beginning, true) ),
-        ( NXMAP, "z{count}<Enter>", window_resize!(Axis::Horizontal, SizeChange::Exact(Count::Contextual)) ),
+        ( NXMAP, "z<Left>", scroll2d!(MoveDir2D::Left, ScrollSize::Cell).description("scroll left") ),
+        ( NXMAP, "z<Right>", scroll2d!(MoveDir2D::Right, ScrollSize::Cell).description("scroll right") ),
+        ( NXMAP, "z<Enter>", scrollcpv!(MovePosition::Beginning, true).description("scroll line to top, first non-blank") ),
+        ( NXMAP, "z{count}<Enter>", window_resize!(Axis::Horizontal, SizeChange::Exact(Count::Contextual)).description("set window height") ),
         ( NXMAP, ":", cmdbar_focus!(CommandType::Command, VimMode::Normal) ),
 
         // Visual, Operator Pending mode keys
@@ -1545,32 +1601,44 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( NMAP, "D", edit!(EditAction::Delete, MoveType::LinePos(MovePosition::End), Count::MinusOne) ),
         ( NMAP, "ga", unmapped!() ),
         ( NMAP, "gi", unmapped!() ),
-        ( NMAP, "gI", insert!(InsertStyle::Insert, MoveType::LinePos(MovePosition::Beginning), 0) ),
-        ( NMAP, "gJ", edit_lines!(EditAction::Join(JoinStyle::NoChange)) ),
-        ( NMAP, "gn", selection_resize_search!(SelectionResizeStyle::Object, MoveDir1D::Next) ),
-        ( NMAP, "gN", selection_resize_search!(SelectionResizeStyle::Object, MoveDir1D::Previous) ),
-        ( NMAP, "gq", edit_motion!(EditAction::Format) ),
-        ( NMAP, "gqgq", edit_lines!(EditAction::Format) ),
-        ( NMAP, "gqq", edit_lines!(EditAction::Format) ),
-        ( NMAP, "gr", charreplace!(true) ),
+        ( NMAP, "gI", insert!(InsertStyle::Insert, MoveType::LinePos(MovePosition::Beginning), 0).description("insert at start of line") ),
+        ( NMAP, "gJ", edit_lines!(EditAction::Join(JoinStyle::NoChange)).description("join lines without spaces") ),
+        ( NMAP, "gn", selection_resize_search!(SelectionResizeStyle::Object, MoveDir1D::Next).description("select next match") ),
+        ( NMAP, "gN", selection_resize_search!(SelectionResizeStyle::Object, MoveDir1D::Previous).description("select previous match") ),
+        ( NMAP, "gq", edit_motion!(EditAction::Format).description("format text") ),
+        ( NMAP, "gqgq", edit_lines!(EditAction::Format).description("format line") ),
+        ( NMAP, "gqq", edit_lines!(EditAction::Format).description("format line") ),
+        ( NMAP, "gr", charreplace!(true).description("replace virtual character") ),
         ( NMAP, "gR", unmapped!() ),
-        ( NMAP, "gu", edit_motion!(EditAction::ChangeCase(Case::Lower)) ),
-        ( NMAP, "gugu", edit_lines!(EditAction::ChangeCase(Case::Lower)) ),
-        ( NMAP, "guu", edit_lines!(EditAction::ChangeCase(Case::Lower)) ),
-        ( NMAP, "gU", edit_motion!(EditAction::ChangeCase(Case::Upper)) ),
-        ( NMAP, "gUgU", edit_lines!(EditAction::ChangeCase(Case::Upper)) ),
-        ( NMAP, "gUU", edit_lines!(EditAction::ChangeCase(Case::Upper)) ),
+        ( NMAP, "gu", edit_motion!(EditAction::ChangeCase(Case::Lower)).description("make lowercase") ),
+        ( NMAP, "gugu", edit_lines!(EditAction::ChangeCase(Case::Lower)).description("make line lowercase") ),
+        ( NMAP, "guu", edit_lines!(EditAction::ChangeCase(Case::Lower)).description("make line lowercase") ),
+        ( NMAP, "gU", edit_motion!(EditAction::ChangeCase(Case::Upper)).description("make uppercase") ),
+        ( NMAP, "zf", edit_motion!(EditAction::Fold).description("create fold") ),
+        ( NMAP, "zF", edit_lines!(EditAction::Fold).description("create fold over lines") ),
+        ( NMAP, "zg", act!(EditorAction::Spell(SpellAction::Good(false))).description("mark word as good") ),
+        ( NMAP, "zG", act!(EditorAction::Spell(SpellAction::Good(true))).description("mark word as good for this session") ),
+        ( NMAP, "zug", act!(EditorAction::Spell(SpellAction::Undo(false))).description("undo marking word as good") ),
+        ( NMAP, "zuG", act!(EditorAction::Spell(SpellAction::Undo(true))).description("undo marking word as good for this session") ),
+        ( NMAP, "zuw", act!(EditorAction::Spell(SpellAction::Undo(false))).description("undo marking word as bad") ),
+        ( NMAP, "zuW", act!(EditorAction::Spell(SpellAction::Undo(true))).description("undo marking word as bad for this session") ),
+        ( NMAP, "zw", act!(EditorAction::Spell(SpellAction::Bad(false))).description("mark word as bad") ),
+        ( NMAP, "zW", act!(EditorAction::Spell(SpellAction::Bad(true))).description("mark word as bad for this session") ),
+        ( NMAP, "z=", act!(EditorAction::Spell(SpellAction::Suggest(Count::Contextual))).description("suggest spellings") ),
+        ( NMAP, "gUgU", edit_lines!(EditAction::ChangeCase(Case::Upper)).description("make line uppercase") ),
+        ( NMAP, "gUU", edit_lines!(EditAction::ChangeCase(Case::Upper)).description("make line uppercase") ),
         ( NMAP, "gv", unmapped!() ),
-        ( NMAP, "gw", edit_motion!(EditAction::Format) ),
-        ( NMAP, "gwgw", edit_lines!(EditAction::Format) ),
-        ( NMAP, "gww", edit_lines!(EditAction::Format) ),
+        ( NMAP, "gw", edit_motion!(EditAction::Format).description("format text, keep cursor") ),
+        ( NMAP, "gwgw", edit_lines!(EditAction::Format).description("format line, keep cursor") ),
+        ( NMAP, "gww", edit_lines!(EditAction::Format).description("format line, keep cursor") ),
         ( NMAP, "g8", unmapped!() ),
         ( NMAP, "g&", unmapped!() ),
-        ( NMAP, "g~", edit_motion!(EditAction::ChangeCase(Case::Toggle)) ),
-        ( NMAP, "g~~", edit_lines!(EditAction::ChangeCase(Case::Toggle)) ),
-        ( NMAP, "g,", jump!(PositionList::ChangeList, MoveDir1D::Next) ),
-        ( NMAP, "g;", jump!(PositionList::ChangeList, MoveDir1D::Previous) ),
-        ( NMAP, "g<Tab>", tab_focus!(FocusChange::PreviouslyFocused) ),
+        ( NMAP, "g~", edit_motion!(EditAction::ChangeCase(Case::Toggle)).description("toggle case") ),
+        ( NMAP, "g~~", edit_lines!(EditAction::ChangeCase(Case::Toggle)).description("toggle case of line") ),
+        ( NMAP, "g,", jump!(PositionList::ChangeList, MoveDir1D::Next).description("newer change position") ),
+        ( NMAP, "g;", jump!(PositionList::ChangeList, MoveDir1D::Previous).description("older change position") ),
+        ( NMAP, "g<Tab>", tab_focus!(FocusChange::PreviouslyFocused).description("last accessed tab") ),
+        ( NMAP, "g<C-T>", edit!(EditAction::Transpose(TransposeStyle::Char, MoveDir1D::Next), MoveType::Column(MoveDir1D::Next, false), 1).description("transpose characters") ),
         ( NMAP, "i", insert!(InsertStyle::Insert) ),
         ( NMAP, "I", insert!(InsertStyle::Insert, MoveType::FirstWord(MoveDir1D::Next), 0) ),
         ( NMAP, "J", edit_lines!(EditAction::Join(JoinStyle::OneSpace)) ),
@@ -1640,18 +1708,28 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( XMAP, "C", change_selection_nochar!(SelectionCursorChange::Beginning, EditTarget::Motion(MoveType::LinePos(MovePosition::End), Count::Exact(0))) ),
         ( XMAP, "d", edit_selection!(EditAction::Delete) ),
         ( XMAP, "D", delete_selection_nochar!(SelectionCursorChange::Beginning, EditTarget::Motion(MoveType::LinePos(MovePosition::End), Count::Exact(0))) ),
-        ( XMAP, "gf", window_switch!(OpenTarget::Selection) ),
-        ( XMAP, "gJ", edit_selection!(EditAction::Join(JoinStyle::NoChange)) ),
-        ( XMAP, "gn", selection_resize_search!(SelectionResizeStyle::Extend, MoveDir1D::Next) ),
-        ( XMAP, "gN", selection_resize_search!(SelectionResizeStyle::Extend, MoveDir1D::Previous) ),
-        ( XMAP, "gq", edit_selection!(EditAction::Format) ),
-        ( XMAP, "gr", charreplace!(true, EditTarget::Selection) ),
-        ( XMAP, "gu", edit_selection!(EditAction::ChangeCase(Case::Lower)) ),
-        ( XMAP, "gU", edit_selection!(EditAction::ChangeCase(Case::Upper)) ),
-        ( XMAP, "gw", edit_selection!(EditAction::Format) ),
-        ( XMAP, "g~", edit_selection!(EditAction::ChangeCase(Case::Toggle)) ),
-        ( XMAP, "g<C-A>", edit_selection!(EditAction::ChangeNumber(NumberChange::Increase(Count::Contextual), true)) ),
-        ( XMAP, "g<C-X>", edit_selection!(EditAction::ChangeNumber(NumberChange::Decrease(Count::Contextual), true)) ),
+        ( XMAP, "gf", window_switch!(OpenTarget::Selection).description("edit selected file") ),
+        ( XMAP, "gJ", edit_selection!(EditAction::Join(JoinStyle::NoChange)).description("join lines without spaces") ),
+        ( XMAP, "gn", selection_resize_search!(SelectionResizeStyle::Extend, MoveDir1D::Next).description("extend to next match") ),
+        ( XMAP, "gN", selection_resize_search!(SelectionResizeStyle::Extend, MoveDir1D::Previous).description("extend to previous match") ),
+        ( XMAP, "gq", edit_selection!(EditAction::Format).description("format selection") ),
+        ( XMAP, "gr", charreplace!(true, EditTarget::Selection).description("replace virtual characters") ),
+        ( XMAP, "gu", edit_selection!(EditAction::ChangeCase(Case::Lower)).description("make selection lowercase") ),
+        ( XMAP, "gU", edit_selection!(EditAction::ChangeCase(Case::Upper)).description("make selection uppercase") ),
+        ( XMAP, "zf", edit_selection!(EditAction::Fold).description("create fold") ),
+        ( XMAP, "zg", act!(EditorAction::Spell(SpellAction::Good(false)), VimMode::Normal).description("mark word as good") ),
+        ( XMAP, "zG", act!(EditorAction::Spell(SpellAction::Good(true)), VimMode::Normal).description("mark word as good for this session") ),
+        ( XMAP, "zug", act!(EditorAction::Spell(SpellAction::Undo(false)), VimMode::Normal).description("undo marking word as good") ),
+        ( XMAP, "zuG", act!(EditorAction::Spell(SpellAction::Undo(true)), VimMode::Normal).description("undo marking word as good for this session") ),
+        ( XMAP, "zuw", act!(EditorAction::Spell(SpellAction::Undo(false)), VimMode::Normal).description("undo marking word as bad") ),
+        ( XMAP, "zuW", act!(EditorAction::Spell(SpellAction::Undo(true)), VimMode::Normal).description("undo marking word as bad for this session") ),
+        ( XMAP, "zw", act!(EditorAction::Spell(SpellAction::Bad(false)), VimMode::Normal).description("mark word as bad") ),
+        ( XMAP, "zW", act!(EditorAction::Spell(SpellAction::Bad(true)), VimMode::Normal).description("mark word as bad for this session") ),
+        ( XMAP, "z=", unmapped!() ),
+        ( XMAP, "gw", edit_selection!(EditAction::Format).description("format selection, keep cursor") ),
+        ( XMAP, "g~", edit_selection!(EditAction::ChangeCase(Case::Toggle)).description("toggle case of selection") ),
+        ( XMAP, "g<C-A>", edit_selection!(EditAction::ChangeNumber(NumberChange::Increase(Count::Contextual), true)).description("increment numbers progressively") ),
+        ( XMAP, "g<C-X>", edit_selection!(EditAction::ChangeNumber(NumberChange::Decrease(Count::Contextual), true)).description("decrement numbers progressively") ),
         ( XMAP, "I", insert_visual!(SelectionCursorChange::Beginning) ),
         ( XMAP, "J", edit_selection!(EditAction::Join(JoinStyle::OneSpace)) ),
         ( XMAP, "K", act!(Action::KeywordLookup) ),
@@ -1664,8 +1742,6 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( XMAP, "S", change_selection_lines!() ),
         ( XMAP, "u", edit_selection!(EditAction::ChangeCase(Case::Lower)) ),
         ( XMAP, "U", edit_selection!(EditAction::ChangeCase(Case::Upper)) ),
-        ( XMAP, "v", visual!(TargetShape::CharWise) ),
-        ( XMAP, "V", visual!(TargetShape::LineWise) ),
         ( XMAP, "x", edit_selection!(EditAction::Delete) ),
         ( XMAP, "X", delete_selection_nochar!(SelectionCursorChange::Beginning, EditTarget::Selection) ),
         ( XMAP, "y", edit_selection!(EditAction::Yank) ),
@@ -1677,9 +1753,9 @@ fn default_keys<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, InputSt
         ( XMAP, "?", search!(MoveDir1D::Previous, VimMode::Visual) ),
         ( XMAP, "/", search!(MoveDir1D::Next, VimMode::Visual) ),
         ( XMAP, "<C-G>", goto!(VimMode::Select) ),
-        ( XMAP, "<C-W>f", window_file!(OpenTarget::Selection) ),
-        ( XMAP, "<C-W>gf", tab_open!(OpenTarget::Selection, FocusChange::Current) ),
-        ( XMAP, "<C-W><C-F>", window_file!(OpenTarget::Selection) ),
+        ( XMAP, "<C-W>f", window_file!(OpenTarget::Selection).description("split window, edit selected file") ),
+        ( XMAP, "<C-W>gf", tab_open!(OpenTarget::Selection, FocusChange::Current).description("edit selected file in new tab") ),
+        ( XMAP, "<C-W><C-F>", window_file!(OpenTarget::Selection).description("split window, edit selected file") ),
 
         // Select mode
         ( SMAP, "<C-G>", goto!(VimMode::Visual) ),
@@ -1918,10 +1994,10 @@ fn ctrlcd_is_abort<I: ApplicationInfo>() -> Vec<(MappedModes, &'static str, Inpu
 #[rustfmt::skip]
 fn cursor_open<I: ApplicationInfo>(style: WordStyle) -> Vec<(MappedModes, &'static str, InputStep<I>)> {
     [
-        ( NMAP, "gf", window_switch!(OpenTarget::Cursor(style.clone())) ),
-        ( NMAP, "<C-W>f", window_file!(OpenTarget::Cursor(style.clone())) ),
-        ( NMAP, "<C-W>gf", tab_open!(OpenTarget::Cursor(style.clone()), FocusChange::Current) ),
-        ( NMAP, "<C-W><C-F>", window_file!(OpenTarget::Cursor(style)) ),
+        ( NMAP, "gf", window_switch!(OpenTarget::Cursor(style.clone())).description("edit file under cursor") ),
+        ( NMAP, "<C-W>f", window_file!(OpenTarget::Cursor(style.clone())).description("split window, edit file under cursor") ),
+        ( NMAP, "<C-W>gf", tab_open!(OpenTarget::Cursor(style.clone()), FocusChange::Current).description("edit file under cursor in new tab") ),
+        ( NMAP, "<C-W><C-F>", window_file!(OpenTarget::Cursor(style)).description("split window, edit file under cursor") ),
     ].to_vec()
 }
 
@@ -1966,6 +2042,8 @@ pub struct VimBindings<I: ApplicationInfo> {
     search: Vec<(MappedModes, &'static str, InputStep<I>)>,
     ctrlcd: Vec<(MappedModes, &'static str, InputStep<I>)>,
     cursor_open: Vec<(MappedModes, &'static str, InputStep<I>)>,
+    keymap: Vec<(MappedModes, CommonEdgePath, InputStep<I>)>,
+    leaders: Leaders,
 }
 
 impl<I: ApplicationInfo> VimBindings<I> {
@@ -1993,6 +2071,65 @@ impl<I: ApplicationInfo> VimBindings<I> {
         self.cursor_open = cursor_open(style);
         self
     }
+
+    /// Add the mappings in a [Keymap] on top of these bindings.
+    ///
+    /// Modes are named like in Vim's `:map` commands: `n`, `v`, `x`, `s`, `o`, `i` and `c`, or
+    /// `normal`, `visual`, `select`, `operator`, `insert` and `command`. As with `:vmap`, the
+    /// `visual` mode applies to both Visual and Select mode, while `x` is only Visual mode.
+    ///
+    /// Any `<Leader>` or `<LocalLeader>` keys are replaced with the ones currently set with
+    /// [leader](Self::leader) and [local_leader](Self::local_leader).
+    #[cfg(feature = "serde")]
+    pub fn keymap(mut self, keymap: &Keymap<I>) -> Result<Self, KeymapError> {
+        for (modes, evs, act) in keymap.resolve(keymap_mode, &self.leaders)? {
+            let modes = modes.into_iter().fold(MappedModes::empty(), |acc, m| acc | m);
+
+            self.keymap.push((modes, evs, act!(act)));
+        }
+
+        Ok(self)
+    }
+
+    /// Change the key that `<Leader>` stands for in keymaps added after this.
+    ///
+    /// This defaults to `\`.
+    pub fn leader(mut self, key: TerminalKey) -> Self {
+        self.leaders.leader = key;
+        self
+    }
+
+    /// Change the key that `<LocalLeader>` stands for in keymaps added after this.
+    ///
+    /// This defaults to `\`.
+    pub fn local_leader(mut self, key: TerminalKey) -> Self {
+        self.leaders.local_leader = key;
+        self
+    }
+
+    /// Get the keys that `<Leader>` and `<LocalLeader>` currently stand for, for parsing other
+    /// mappings with [parse_with_leaders].
+    ///
+    /// [parse_with_leaders]: crate::env::keyparse::parse_with_leaders
+    pub fn get_leaders(&self) -> &Leaders {
+        &self.leaders
+    }
+}
+
+#[cfg(feature = "serde")]
+fn keymap_mode(name: &str) -> Option<MappedModes> {
+    let modes = match name {
+        "n" | "normal" => NMAP,
+        "v" | "visual" => VMAP,
+        "x" => XMAP,
+        "s" | "select" => SMAP,
+        "o" | "operator" => OMAP,
+        "i" | "insert" => IMAP,
+        "c" | "command" => CMAP,
+        _ => return None,
+    };
+
+    Some(modes)
 }
 
 impl<I: ApplicationInfo> ShellBindings for VimBindings<I> {
@@ -2010,6 +2147,8 @@ impl<I: ApplicationInfo> Default for VimBindings<I> {
             search: default_search(),
             ctrlcd: default_ctrlcd(),
             cursor_open: cursor_open(WordStyle::FilePath),
+            keymap: vec![],
+            leaders: Leaders::default(),
         }
     }
 }
@@ -2039,6 +2178,12 @@ impl<I: ApplicationInfo> InputBindings<TerminalKey, InputStep<I>> for VimBinding
         for (modes, keys, action) in self.cursor_open.iter() {
             add_mapping(machine, modes, keys, action);
         }
+
+        for (modes, evs, action) in self.keymap.iter() {
+            for mode in modes.split() {
+                machine.add_mapping(mode, evs, action);
+            }
+        }
     }
 }
 
@@ -2053,9 +2198,10 @@ pub fn default_vim_keys<I: ApplicationInfo>() -> VimMachine<TerminalKey, I> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::editing::context::EditContext;
-    use crate::keybindings::BindingMachine;
+    use crate::editing::context::{EditContext, MatchedKey};
+    use crate::keybindings::{BindingMachine, EdgeEvent};
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::collections::HashMap;
 
     macro_rules! assert_insert_exit {
         ($mm: expr, $ctx: expr) => {
@@ -3137,6 +3283,133 @@ mod tests {
         assert_normal!(vm, ctx);
     }
 
+    #[test]
+    fn test_transpose_ops() {
+        let mut vm: VimMachine<TerminalKey> = default_vim_keys();
+        let mut ctx = mkctx();
+
+        // "g<C-T>" swaps the character under the cursor with the one after it, like "xp".
+        let op = EditAction::Transpose(TransposeStyle::Char, MoveDir1D::Next);
+        let mov = mvop!(op, MoveType::Column(MoveDir1D::Next, false), 1);
+        vm.input_key(key!('g'));
+        vm.input_key(ctl!('t'));
+        assert_pop1!(vm, mov, ctx);
+        assert_normal!(vm, ctx);
+
+        // Counts are passed through to drag the character further.
+        ctx.count = Some(3);
+        ctx.matched_keys = vec![MatchedKey::Numeric(3)];
+        vm.input_key(key!('3'));
+        vm.input_key(key!('g'));
+        vm.input_key(ctl!('t'));
+        assert_pop1!(vm, mov, ctx);
+
+        ctx.count = None;
+        ctx.matched_keys = vec![];
+        assert_normal!(vm, ctx);
+    }
+
+    #[test]
+    fn test_fold_ops() {
+        let mut vm: VimMachine<TerminalKey> = default_vim_keys();
+        let mut ctx = mkctx();
+
+        // Create a fold over a motion with "zf".
+        let mov = mv!(MoveType::Line(MoveDir1D::Next));
+        ctx.operation = EditAction::Fold;
+        vm.input_key(key!('z'));
+        vm.input_key(key!('f'));
+        vm.input_key(key!('j'));
+        assert_pop1!(vm, mov, ctx);
+        assert_normal!(vm, ctx);
+
+        ctx.operation = EditAction::Motion;
+
+        // Create a fold over [count] lines with "zF".
+        let act = rangeop!(EditAction::Fold, RangeType::Line);
+        vm.input_key(key!('z'));
+        vm.input_key(key!('F'));
+        assert_pop1!(vm, act, ctx);
+        assert_normal!(vm, ctx);
+
+        // Move between folds with "zj" and "zk".
+        let mov = mv!(MoveType::Fold(MoveDir1D::Next));
+        vm.input_key(key!('z'));
+        vm.input_key(key!('j'));
+        assert_pop1!(vm, mov, ctx);
+        assert_normal!(vm, ctx);
+
+        let mov = mv!(MoveType::Fold(MoveDir1D::Previous));
+        vm.input_key(key!('z'));
+        vm.input_key(key!('k'));
+        assert_pop1!(vm, mov, ctx);
+        assert_normal!(vm, ctx);
+
+        // Open, close and delete folds.
+        let keys = [
+            ('o', FoldAction::Open(Count::Contextual)),
+            ('c', FoldAction::Close(Count::Contextual)),
+            ('a', FoldAction::Toggle(Count::Contextual)),
+            ('R', FoldAction::OpenAll),
+            ('M', FoldAction::CloseAll),
+            ('d', FoldAction::Delete),
+            ('E', FoldAction::DeleteAll),
+        ];
+
+        for (key, fold) in keys {
+            let act = Action::from(EditorAction::Fold(fold));
+            vm.input_key(key!('z'));
+            vm.input_key(key!(KeyCode::Char(key)));
+            assert_pop1!(vm, act, ctx);
+            assert_normal!(vm, ctx);
+        }
+    }
+
+    #[test]
+    fn test_spell_ops() {
+        let mut vm: VimMachine<TerminalKey> = default_vim_keys();
+        let mut ctx = mkctx();
+
+        // Move between misspelled words with "]s" and "[s".
+        let mov = mv!(MoveType::Misspelling(MoveDir1D::Next));
+        vm.input_key(key!(']'));
+        vm.input_key(key!('s'));
+        assert_pop1!(vm, mov, ctx);
+        assert_normal!(vm, ctx);
+
+        let mov = mv!(MoveType::Misspelling(MoveDir1D::Previous));
+        vm.input_key(key!('['));
+        vm.input_key(key!('s'));
+        assert_pop1!(vm, mov, ctx);
+        assert_normal!(vm, ctx);
+
+        // Mark words as good or bad.
+        let keys = [
+            ("zg", SpellAction::Good(false)),
+            ("zG", SpellAction::Good(true)),
+            ("zw", SpellAction::Bad(false)),
+            ("zW", SpellAction::Bad(true)),
+            ("zug", SpellAction::Undo(false)),
+            ("zuW", SpellAction::Undo(true)),
+        ];
+
+        for (keys, spell) in keys {
+            let act = Action::from(EditorAction::Spell(spell));
+            for c in keys.chars() {
+                vm.input_key(key!(KeyCode::Char(c)));
+            }
+            assert_pop1!(vm, act, ctx);
+            assert_normal!(vm, ctx);
+        }
+
+        // Show suggestions with "z=".
+        let act = Action::from(EditorAction::Spell(SpellAction::Suggest(Count::Contextual)));
+        vm.input_key(key!('z'));
+        vm.input_key(key!('='));
+        assert_pop1!(vm, act, ctx);
+        assert_normal!(vm, ctx);
+    }
+
     #[test]
     fn test_change_ops() {
         let mut vm: VimMachine<TerminalKey> = default_vim_keys();
@@ -4558,6 +4831,90 @@ mod tests {
         assert_normal!(vm, ctx);
     }
 
+    #[test]
+    fn test_application_operator() {
+        let mut vm: VimMachine<TerminalKey> = default_vim_keys();
+        let mut ctx = mkctx();
+        let op = EditAction::Application("urlencode".into());
+
+        let step = InputStep::new().operator(op.clone(), None);
+        add_mapping(&mut vm, &NMAP, "gz", &step);
+
+        // The operator can be followed by a text object.
+        ctx.operation = op.clone();
+        vm.input_key(key!('g'));
+        vm.input_key(key!('z'));
+        vm.input_key(key!('i'));
+        vm.input_key(key!('w'));
+        assert_pop1!(vm, range!(RangeType::Word(WordStyle::Little)), ctx);
+        assert_normal!(vm, ctx);
+
+        // The operator can be given a count, and followed by a motion.
+        let mov = mv!(MoveType::WordBegin(WordStyle::Little, MoveDir1D::Next));
+        ctx.count = Some(2);
+        ctx.matched_keys = vec![MatchedKey::Numeric(2)];
+        vm.input_key(key!('2'));
+        vm.input_key(key!('g'));
+        vm.input_key(key!('z'));
+        vm.input_key(key!('w'));
+        assert_pop1!(vm, mov, ctx);
+
+        ctx.count = None;
+        ctx.matched_keys = vec![];
+        assert_normal!(vm, ctx);
+
+        // Pressing "." repeats it.
+        ctx.operation = EditAction::Motion;
+        vm.input_key(key!('.'));
+        assert_pop1!(vm, Action::Repeat(RepeatType::EditSequence), ctx);
+
+        ctx.operation = op.clone();
+        ctx.count = Some(2);
+        ctx.matched_keys = vec![MatchedKey::Numeric(2)];
+        vm.repeat(RepeatType::EditSequence, None);
+        assert_pop1!(vm, mov, ctx);
+
+        ctx.operation = EditAction::Motion;
+        ctx.count = None;
+        ctx.matched_keys = vec![];
+        assert_normal!(vm, ctx);
+
+        // The operator can also be mapped in Visual mode, where it acts on the selection.
+        let step = InputStep::new().selection_operator(op.clone(), None);
+        add_mapping(&mut vm, &XMAP, "gz", &step);
+
+        ctx.target_shape = Some(TargetShape::CharWise);
+        vm.input_key(key!('v'));
+        assert_pop2!(vm, CURRENT_POS, ctx);
+        assert_eq!(vm.mode(), VimMode::Visual);
+
+        let col = mv!(MoveType::Column(MoveDir1D::Next, false));
+        vm.input_key(key!('l'));
+        assert_pop2!(vm, col, ctx);
+
+        vm.input_key(key!('g'));
+        vm.input_key(key!('z'));
+        assert_pop1!(vm, selop!(op.clone()), ctx);
+
+        ctx.target_shape = None;
+        assert_pop1!(vm, CURSOR_CLOSE, ctx);
+        assert_pop1!(vm, CURRENT_POS, ctx);
+        assert_normal!(vm, ctx);
+
+        // Pressing "." repeats it on a selection of the same size.
+        vm.input_key(key!('.'));
+        assert_pop1!(vm, Action::Repeat(RepeatType::EditSequence), ctx);
+
+        ctx.target_shape = Some(TargetShape::CharWise);
+        vm.repeat(RepeatType::EditSequence, None);
+        assert_pop1!(vm, col, ctx);
+        assert_pop1!(vm, selop!(op), ctx);
+
+        ctx.target_shape = None;
+        assert_pop1!(vm, CURSOR_CLOSE, ctx);
+        assert_normal!(vm, ctx);
+    }
+
     #[test]
     fn test_edit_repeat_append_line() {
         let mut vm: VimMachine<TerminalKey> = default_vim_keys();
@@ -4620,4 +4977,192 @@ mod tests {
         // Read the Checkpoint from pressing "." earlier.
         assert_normal!(vm, ctx);
     }
+
+    #[test]
+    fn test_describe() {
+        let mut vm: VimMachine<TerminalKey> = default_vim_keys();
+        let descs = |vm: &VimMachine<TerminalKey>| {
+            vm.continuations()
+                .into_iter()
+                .filter_map(|(ev, step)| Some((ev, step?.describe()?.to_string())))
+                .collect::<HashMap<_, _>>()
+        };
+
+        // Nothing is pending at the top of Normal mode.
+        assert!(!vm.is_pending());
+
+        // After ^W, the window commands are described.
+        vm.input_key(ctl!('w'));
+        assert!(vm.is_pending());
+
+        let res = descs(&vm);
+        assert_eq!(res[&EdgeEvent::Key(key!('s'))], "split window");
+        assert_eq!(res[&EdgeEvent::Key(key!('v'))], "split window vertically");
+        assert_eq!(res[&EdgeEvent::Key(ctl!('w'))], "next window");
+
+        // "g" leads to "gu" waiting for a motion, while "gg" is a complete motion.
+        vm.input_key(key!(KeyCode::Esc));
+        let _ = vm.pop();
+        vm.input_key(key!('g'));
+        assert!(vm.is_pending());
+
+        let res = descs(&vm);
+        assert_eq!(res[&EdgeEvent::Key(key!('g'))], "first line");
+        assert_eq!(res[&EdgeEvent::Key(key!('u'))], "make lowercase");
+        assert_eq!(res[&EdgeEvent::Key(key!('t'))], "next tab");
+    }
+
+    #[test]
+    fn test_validate() {
+        use crate::keybindings::{diagnostics::MappingDiagnostic, EdgeRepeat};
+
+        let mut vm: VimMachine<TerminalKey> = default_vim_keys();
+
+        // The only problems are the zero-digit cases of "<C-V>{dec<=3}", which "<C-V>{any}"
+        // always handles instead.
+        for diag in vm.validate() {
+            match diag {
+                MappingDiagnostic::Shadowed { path, .. } => {
+                    assert_eq!(path, vec![(EdgeRepeat::Once, EdgeEvent::Key(ctl!('v')))]);
+                },
+                diag => panic!("unexpected diagnostic: {}", diag),
+            }
+        }
+
+        // Layering a mapping for "xy" on top of the defaults never fires, since "x" runs first.
+        let xy = vec![
+            (EdgeRepeat::Once, EdgeEvent::Key(key!('x'))),
+            (EdgeRepeat::Once, EdgeEvent::Key(key!('y'))),
+        ];
+        let step = act!(Action::KeywordLookup);
+        vm.add_mapping(VimMode::Normal, &xy, &step);
+
+        let conflict = MappingDiagnostic::PrefixConflict {
+            mode: VimMode::Normal,
+            prefix: xy[..1].to_vec(),
+            path: xy,
+        };
+        assert!(vm.validate().contains(&conflict));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_keymap() {
+        use crate::editing::context::Resolve;
+        use crate::env::keymap::{KeymapAction, KeymapEntry, KeymapErrorKind};
+
+        let entry = |modes: &[&str], keys: &str, action| {
+            let modes = modes.iter().map(ToString::to_string).collect();
+
+            KeymapEntry { modes, keys: keys.into(), action }
+        };
+        let keymap = Keymap {
+            mappings: vec![
+                entry(&["n", "visual"], "<C-S>", KeymapAction::Command("write".into())),
+                entry(&["normal"], "Q", KeymapAction::Macro("gqap".into())),
+            ],
+        };
+
+        let mut vm: VimMachine<TerminalKey> = ModalMachine::empty();
+        VimBindings::default().keymap(&keymap).unwrap().setup(&mut vm);
+
+        // The command runs in Normal mode.
+        vm.input_key(ctl!('s'));
+        let (act, _) = vm.pop().unwrap();
+        assert_eq!(act, Action::from(CommandAction::Run("write".into())));
+        assert_eq!(vm.mode(), VimMode::Normal);
+        while vm.pop().is_some() {}
+
+        // Counts given before a macro mapping repeat it.
+        vm.input_key(key!('3'));
+        vm.input_key(key!('Q'));
+        let (act, ctx) = vm.pop().unwrap();
+        assert_eq!(act, Action::from(MacroAction::Run("gqap".into(), Count::Contextual)));
+        assert_eq!(ctx.resolve(&Count::Contextual), 3);
+        while vm.pop().is_some() {}
+
+        // The command also applies in Visual mode.
+        vm.input_key(key!('v'));
+        while vm.pop().is_some() {}
+        assert_eq!(vm.mode(), VimMode::Visual);
+
+        vm.input_key(ctl!('s'));
+        let (act, _) = vm.pop().unwrap();
+        assert_eq!(act, Action::from(CommandAction::Run("write".into())));
+
+        // Emacs mode names aren't valid here.
+        let keymap = Keymap {
+            mappings: vec![entry(
+                &["search"],
+                "Q",
+                KeymapAction::Command("quit".into()),
+            )],
+        };
+        let err = VimBindings::<EmptyInfo>::default().keymap(&keymap).unwrap_err();
+        assert_eq!(err.index, 0);
+        assert_eq!(err.kind, KeymapErrorKind::UnknownMode("search".into()));
+    }
+
+    #[test]
+    fn test_leaders() {
+        use crate::env::keyparse::parse_with_leaders;
+
+        let bindings = VimBindings::<EmptyInfo>::default().leader(key!(' ')).local_leader(key!(','));
+        let leaders = bindings.get_leaders();
+        assert_eq!(leaders.leader, key!(' '));
+        assert_eq!(leaders.local_leader, key!(','));
+
+        // The leaders can be used for mappings added outside of a keymap.
+        let (_, evs) = parse_with_leaders("<Leader>w<LocalLeader>", leaders).unwrap();
+        assert_eq!(evs, parse("<Space>w,").unwrap().1);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_keymap_leader() {
+        use crate::env::keymap::{KeymapAction, KeymapEntry};
+
+        let entry =
+            |keys: &str, action| KeymapEntry { modes: vec!["n".into()], keys: keys.into(), action };
+        let first = Keymap {
+            mappings: vec![
+                entry("<Leader>w", KeymapAction::Macro("<Plug>(save)".into())),
+                entry("<Plug>(save)", KeymapAction::Command("write".into())),
+            ],
+        };
+        let second = Keymap {
+            mappings: vec![entry("<Leader>q", KeymapAction::Macro("<Nop>".into()))],
+        };
+
+        let mut vm: VimMachine<TerminalKey> = ModalMachine::empty();
+        VimBindings::default()
+            .keymap(&first)
+            .unwrap()
+            .leader(key!(KeyCode::F(2)))
+            .keymap(&second)
+            .unwrap()
+            .setup(&mut vm);
+
+        // The leader is the one that was set when the keymap was added.
+        vm.input_key(key!('\\'));
+        vm.input_key(key!('w'));
+        let (act, _) = vm.pop().unwrap();
+        assert_eq!(act, Action::from(MacroAction::Run("<Plug>(save)".into(), Count::Contextual)));
+        while vm.pop().is_some() {}
+
+        vm.input_key(key!(KeyCode::F(2)));
+        vm.input_key(key!('q'));
+        let (act, _) = vm.pop().unwrap();
+        assert_eq!(act, Action::NoOp);
+        while vm.pop().is_some() {}
+
+        // Typing the <Plug> keys reaches the named mapping.
+        for key in
+            <TerminalKey as crate::keybindings::InputKey>::from_macro_str("<Plug>(save)").unwrap()
+        {
+            vm.input_key(key);
+        }
+        let (act, _) = vm.pop().unwrap();
+        assert_eq!(act, Action::from(CommandAction::Run("write".into())));
+    }
 }
@@ -575,9 +575,13 @@ fn register_to_char((reg, append): &(Register, bool)) -> Option<String> {
         Register::Unnamed => '"',
         Register::UnnamedMacro => '@',
         Register::UnnamedCursorGroup => return None,
+        Register::KillRing => return None,
+        Register::KilledRectangle => return None,
+        Register::MacroRing => return None,
         Register::SmallDelete => '-',
         Register::LastCommand(CommandType::Command) => ':',
         Register::LastCommand(CommandType::Search) => '/',
+        Register::LastCommand(CommandType::Prompt) => return None,
         Register::LastInserted => '.',
         Register::LastYanked => '0',
         Register::AltBufName => '#',
@@ -4,16 +4,25 @@
 //!
 //! This module contains code for representing and processing keys.
 //!
+//! Keys are read from the terminal using [crossterm] by default, but can also be converted from
+//! termion and termwiz events with `TerminalKey::from_termion` and `TerminalKey::from_termwiz`
+//! when the `termion` or `termwiz` features are enabled.
+//!
 use std::fmt::{self, Display};
 use std::hash::Hash;
 use std::str::FromStr;
 
 use self::parse::{parse_key_str, parse_macro_str};
 use crate::keybindings::InputKey;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MediaKeyCode};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MediaKeyCode};
 
 pub(crate) mod parse;
 
+#[cfg(feature = "termion")]
+mod termion;
+#[cfg(feature = "termwiz")]
+mod termwiz;
+
 /// Errors that occur while trying to use macros.
 #[derive(thiserror::Error, Debug)]
 #[non_exhaustive]
@@ -56,10 +65,24 @@ pub enum MacroError {
 ///
 /// Additionally, some modifier keys can't be expressed by default: `<S-Enter>` or `<C-Enter>`
 /// cannot always be represented. If you map those, make sure you also provide alternatives!
+///
+/// Terminals that support the [Kitty keyboard protocol] can be asked to report these keys
+/// unambiguously with [crossterm::event::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES].
+/// They can then also report the Super, Meta and Hyper modifiers, which are written as
+/// `<D-...>`, `<T-...>` and `<H-...>`. Since some of the other flags make the terminal report
+/// key releases, and presses of modifier keys on their own, use [TerminalKey::from_event] to
+/// convert the events that should be processed.
+///
+/// [Kitty keyboard protocol]: https://sw.kovidgoyal.net/kitty/keyboard-protocol/
+///
+/// There is also the `<Plug>` pseudo-key, which is never produced by a terminal. Like in Vim, it
+/// can be used to give names to mappings (e.g., `<Plug>(toggle-wrap)`) that can then only be
+/// reached from other mappings and macros.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct TerminalKey {
     code: KeyCode,
     modifiers: KeyModifiers,
+    plug: bool,
 }
 
 impl TerminalKey {
@@ -86,7 +109,35 @@ impl TerminalKey {
             }
         }
 
-        Self { code, modifiers }
+        Self { code, modifiers, plug: false }
+    }
+
+    /// The `<Plug>` pseudo-key.
+    pub(crate) fn plug() -> Self {
+        Self {
+            code: KeyCode::Null,
+            modifiers: KeyModifiers::NONE,
+            plug: true,
+        }
+    }
+
+    /// Whether this is the `<Plug>` pseudo-key.
+    pub fn is_plug(&self) -> bool {
+        self.plug
+    }
+
+    /// Convert a key event read from the terminal, unless it's a key release or the press of a
+    /// modifier key on its own, which shouldn't be treated as input.
+    pub fn from_event(ke: KeyEvent) -> Option<Self> {
+        if ke.kind == KeyEventKind::Release {
+            return None;
+        }
+
+        if let KeyCode::Modifier(_) = ke.code {
+            return None;
+        }
+
+        return Some(ke.into());
     }
 
     pub(crate) fn get_char_mods(&self) -> Option<(char, KeyModifiers)> {
@@ -168,6 +219,18 @@ impl Display for TerminalKey {
                 write!(f, "S-")?;
             }
 
+            if mods.contains(KeyModifiers::SUPER) {
+                write!(f, "D-")?;
+            }
+
+            if mods.contains(KeyModifiers::META) {
+                write!(f, "T-")?;
+            }
+
+            if mods.contains(KeyModifiers::HYPER) {
+                write!(f, "H-")?;
+            }
+
             Ok(())
         };
 
@@ -180,6 +243,10 @@ impl Display for TerminalKey {
         let push_named =
             |f: &mut fmt::Formatter, name: &str| push_named_mods(f, name, self.modifiers);
 
+        if self.plug {
+            return write!(f, "<Plug>");
+        }
+
         match self.code {
             KeyCode::Left => push_named(f, "Left"),
             KeyCode::Right => push_named(f, "Right"),
@@ -204,7 +271,7 @@ impl Display for TerminalKey {
             KeyCode::Tab => push_named(f, "Tab"),
             KeyCode::BackTab => push_named_mods(f, "Tab", self.modifiers | KeyModifiers::SHIFT),
             KeyCode::F(n) => {
-                let n = n.to_string();
+                let n = format!("F{n}");
 
                 push_named(f, n.as_str())
             },
@@ -291,6 +358,10 @@ impl InputKey for TerminalKey {
 
         None
     }
+
+    fn name(&self) -> Option<String> {
+        Some(self.to_string())
+    }
 }
 
 impl From<KeyCode> for TerminalKey {
@@ -344,6 +415,8 @@ mod tests {
         roundtrip("<S-Enter>");
         roundtrip("<A-Enter>");
         roundtrip("<C-Enter>");
+        roundtrip("<F1>");
+        roundtrip("<S-F12>");
         roundtrip("<BS>");
         roundtrip("<S-BS>");
         roundtrip("<A-BS>");
@@ -374,5 +447,66 @@ mod tests {
         // <C-[> is sometimes sent as <Esc>, but we should be able to represent both.
         roundtrip("<C-[>");
         roundtrip("<Esc>");
+
+        // Modifiers that are only reported with the Kitty keyboard protocol.
+        roundtrip("<D-s>");
+        roundtrip("<C-D-S>");
+        roundtrip("<T-x>");
+        roundtrip("<H-Enter>");
+        roundtrip("<A-T-Left>");
+
+        // <Plug> is never the same as any key a terminal can send.
+        roundtrip("<Plug>");
+        assert!(TerminalKey::from_str("<Plug>").unwrap().is_plug());
+        assert_ne!(TerminalKey::from_str("<Plug>").unwrap(), TerminalKey::from(KeyCode::Null));
+        assert!(!TerminalKey::from_str("<Nul>").unwrap().is_plug());
+    }
+
+    #[test]
+    fn test_from_event() {
+        use crossterm::event::{KeyEventState, ModifierKeyCode};
+
+        let event =
+            |code, modifiers, kind| KeyEvent { code, modifiers, kind, state: KeyEventState::NONE };
+
+        // Disambiguated keypresses can be told apart.
+        let ke = event(KeyCode::Enter, KeyModifiers::CONTROL, KeyEventKind::Press);
+        let key = TerminalKey::from_event(ke).unwrap();
+        assert_eq!(key, TerminalKey::from_str("<C-Enter>").unwrap());
+        assert_ne!(key, TerminalKey::from_str("<Enter>").unwrap());
+
+        let ke = event(KeyCode::Char('s'), KeyModifiers::SUPER, KeyEventKind::Press);
+        let key = TerminalKey::from_event(ke).unwrap();
+        assert_eq!(key, TerminalKey::from_str("<D-s>").unwrap());
+
+        // Shifted characters are still normalized.
+        let ke = event(KeyCode::Char('A'), KeyModifiers::SHIFT, KeyEventKind::Repeat);
+        let key = TerminalKey::from_event(ke).unwrap();
+        assert_eq!(key, TerminalKey::from_str("A").unwrap());
+
+        // Releases and lone modifier keys are ignored.
+        let ke = event(KeyCode::Char('a'), KeyModifiers::NONE, KeyEventKind::Release);
+        assert_eq!(TerminalKey::from_event(ke), None);
+
+        let ke = event(
+            KeyCode::Modifier(ModifierKeyCode::LeftShift),
+            KeyModifiers::SHIFT,
+            KeyEventKind::Press,
+        );
+        assert_eq!(TerminalKey::from_event(ke), None);
+    }
+
+    #[test]
+    fn test_nop() {
+        let keys = TerminalKey::from_macro_str("<Nop>").unwrap();
+        assert_eq!(keys, vec![]);
+
+        let keys = TerminalKey::from_macro_str("a<nop>b").unwrap();
+        let a = TerminalKey::from(KeyCode::Char('a'));
+        let b = TerminalKey::from(KeyCode::Char('b'));
+        assert_eq!(keys, vec![a, b]);
+
+        // <Nop> isn't a key by itself.
+        assert!(TerminalKey::from_str("<Nop>").is_err());
     }
 }
@@ -4,9 +4,9 @@ use crossterm::event::{KeyCode, KeyModifiers, MediaKeyCode};
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, tag_no_case},
     character::complete::{anychar, char, digit1},
-    combinator::{eof, map_res, value},
+    combinator::{eof, map, map_res, value},
     multi::{many0, many1},
     IResult,
 };
@@ -17,15 +17,18 @@ fn parse_modifier(input: &str) -> IResult<&str, KeyModifiers> {
     /*
      * Parse the modifier prefixes in things like <C-...>, <S-...>, <A-...>, and <M-...>.
      *
-     * Vim also has <D-...> for Apple's Command key, but terminals don't
-     * understand that, so it's not meaningful to parse it here (unless
-     * we were to map it to be the same as something else like <C-...>).
+     * Terminals using the Kitty keyboard protocol can also report the Super, Meta and Hyper
+     * keys. Like Neovim, we use <D-...> for Super (Apple's Command key) and <T-...> for Meta,
+     * since <M-...> already means Alt. Hyper gets <H-...>, like in Emacs.
      */
     alt((
         value(KeyModifiers::ALT, tag("A-")),
         value(KeyModifiers::ALT, tag("M-")),
         value(KeyModifiers::CONTROL, tag("C-")),
         value(KeyModifiers::SHIFT, tag("S-")),
+        value(KeyModifiers::SUPER, tag("D-")),
+        value(KeyModifiers::META, tag("T-")),
+        value(KeyModifiers::HYPER, tag("H-")),
     ))(input)
 }
 
@@ -156,17 +159,17 @@ fn parse_space(input: &str) -> IResult<&str, KeyCode> {
 }
 
 fn parse_bar(input: &str) -> IResult<&str, KeyCode> {
-    let (input, _) = tag("Bar")(input)?;
+    let (input, _) = tag_no_case("Bar")(input)?;
     Ok((input, KeyCode::Char('|')))
 }
 
 fn parse_bslash(input: &str) -> IResult<&str, KeyCode> {
-    let (input, _) = tag("Bslash")(input)?;
+    let (input, _) = tag_no_case("Bslash")(input)?;
     Ok((input, KeyCode::Char('\\')))
 }
 
 fn parse_lt(input: &str) -> IResult<&str, KeyCode> {
-    let (input, _) = tag("lt")(input)?;
+    let (input, _) = tag_no_case("lt")(input)?;
     Ok((input, KeyCode::Char('<')))
 }
 
@@ -225,7 +228,17 @@ pub fn parse_simple(input: &str) -> IResult<&str, TerminalKey> {
     Ok((input, key))
 }
 
-pub fn parse_special(input: &str) -> IResult<&str, TerminalKey> {
+fn parse_plug(input: &str) -> IResult<&str, TerminalKey> {
+    let (input, _) = tag_no_case("<Plug>")(input)?;
+    Ok((input, TerminalKey::plug()))
+}
+
+/// Parse `<Nop>`, which stands for typing no keys at all.
+pub fn parse_nop(input: &str) -> IResult<&str, ()> {
+    value((), tag_no_case("<Nop>"))(input)
+}
+
+fn parse_modified(input: &str) -> IResult<&str, TerminalKey> {
     let (input, _) = char('<')(input)?;
     let (input, m) = many0(parse_modifier)(input)?;
     let (input, mut k) = alt((parse_keyname, parse_function, parse_anychar))(input)?;
@@ -243,6 +256,10 @@ pub fn parse_special(input: &str) -> IResult<&str, TerminalKey> {
     return Ok((input, key));
 }
 
+pub fn parse_special(input: &str) -> IResult<&str, TerminalKey> {
+    alt((parse_plug, parse_modified))(input)
+}
+
 pub fn parse_key_str(input: &str) -> IResult<&str, TerminalKey> {
     let (input, res) = alt((parse_special, parse_simple))(input)?;
     let (input, _) = eof(input)?;
@@ -251,10 +268,13 @@ pub fn parse_key_str(input: &str) -> IResult<&str, TerminalKey> {
 }
 
 pub fn parse_macro_str(input: &str) -> IResult<&str, Vec<TerminalKey>> {
-    let (input, res) = many1(alt((parse_special, parse_simple)))(input)?;
+    let (input, res) =
+        many1(alt((value(None, parse_nop), map(parse_special, Some), map(parse_simple, Some))))(
+            input,
+        )?;
     let (input, _) = eof(input)?;
 
-    Ok((input, res))
+    Ok((input, res.into_iter().flatten().collect()))
 }
 
 #[cfg(test)]
@@ -267,35 +287,40 @@ mod tests {
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::Left,
-            modifiers: KeyModifiers::SHIFT
+            modifiers: KeyModifiers::SHIFT,
+            plug: false,
         });
 
         let (rem, key) = parse_key_str("<S-Home>").unwrap();
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::Home,
-            modifiers: KeyModifiers::SHIFT
+            modifiers: KeyModifiers::SHIFT,
+            plug: false,
         });
 
         let (rem, key) = parse_key_str("<S-F1>").unwrap();
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::F(1),
-            modifiers: KeyModifiers::SHIFT
+            modifiers: KeyModifiers::SHIFT,
+            plug: false,
         });
 
         let (rem, key) = parse_key_str("<S-Tab>").unwrap();
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::BackTab,
-            modifiers: KeyModifiers::SHIFT
+            modifiers: KeyModifiers::SHIFT,
+            plug: false,
         });
 
         let (rem, key) = parse_key_str("<S-Enter>").unwrap();
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::Enter,
-            modifiers: KeyModifiers::SHIFT
+            modifiers: KeyModifiers::SHIFT,
+            plug: false,
         });
     }
 
@@ -305,35 +330,40 @@ mod tests {
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::Char('A'),
-            modifiers: KeyModifiers::NONE
+            modifiers: KeyModifiers::NONE,
+            plug: false,
         });
 
         let (rem, key) = parse_key_str("<S-:>").unwrap();
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::Char(':'),
-            modifiers: KeyModifiers::NONE
+            modifiers: KeyModifiers::NONE,
+            plug: false,
         });
 
         let (rem, key) = parse_key_str("<S-;>").unwrap();
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::Char(';'),
-            modifiers: KeyModifiers::NONE
+            modifiers: KeyModifiers::NONE,
+            plug: false,
         });
 
         let (rem, key) = parse_key_str("<S-?>").unwrap();
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::Char('?'),
-            modifiers: KeyModifiers::NONE
+            modifiers: KeyModifiers::NONE,
+            plug: false,
         });
 
         let (rem, key) = parse_key_str("<S-/>").unwrap();
         assert_eq!(rem, "");
         assert_eq!(key, TerminalKey {
             code: KeyCode::Char('/'),
-            modifiers: KeyModifiers::NONE
+            modifiers: KeyModifiers::NONE,
+            plug: false,
         });
     }
 }
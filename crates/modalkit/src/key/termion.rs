@@ -0,0 +1,89 @@
+use ::termion::event::Key;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+use super::TerminalKey;
+
+impl TerminalKey {
+    /// Convert a key read using [termion].
+    ///
+    /// termion reports both `<Enter>` and `<C-J>` as a newline, which gets converted to
+    /// `<Enter>`, and reports `<C-Space>` as `<Nul>`.
+    pub fn from_termion(key: Key) -> Option<Self> {
+        let (code, modifiers) = match key {
+            Key::Backspace => (KeyCode::Backspace, KeyModifiers::NONE),
+            Key::Left => (KeyCode::Left, KeyModifiers::NONE),
+            Key::ShiftLeft => (KeyCode::Left, KeyModifiers::SHIFT),
+            Key::AltLeft => (KeyCode::Left, KeyModifiers::ALT),
+            Key::CtrlLeft => (KeyCode::Left, KeyModifiers::CONTROL),
+            Key::Right => (KeyCode::Right, KeyModifiers::NONE),
+            Key::ShiftRight => (KeyCode::Right, KeyModifiers::SHIFT),
+            Key::AltRight => (KeyCode::Right, KeyModifiers::ALT),
+            Key::CtrlRight => (KeyCode::Right, KeyModifiers::CONTROL),
+            Key::Up => (KeyCode::Up, KeyModifiers::NONE),
+            Key::ShiftUp => (KeyCode::Up, KeyModifiers::SHIFT),
+            Key::AltUp => (KeyCode::Up, KeyModifiers::ALT),
+            Key::CtrlUp => (KeyCode::Up, KeyModifiers::CONTROL),
+            Key::Down => (KeyCode::Down, KeyModifiers::NONE),
+            Key::ShiftDown => (KeyCode::Down, KeyModifiers::SHIFT),
+            Key::AltDown => (KeyCode::Down, KeyModifiers::ALT),
+            Key::CtrlDown => (KeyCode::Down, KeyModifiers::CONTROL),
+            Key::Home => (KeyCode::Home, KeyModifiers::NONE),
+            Key::CtrlHome => (KeyCode::Home, KeyModifiers::CONTROL),
+            Key::End => (KeyCode::End, KeyModifiers::NONE),
+            Key::CtrlEnd => (KeyCode::End, KeyModifiers::CONTROL),
+            Key::PageUp => (KeyCode::PageUp, KeyModifiers::NONE),
+            Key::PageDown => (KeyCode::PageDown, KeyModifiers::NONE),
+            Key::BackTab => (KeyCode::BackTab, KeyModifiers::SHIFT),
+            Key::Delete => (KeyCode::Delete, KeyModifiers::NONE),
+            Key::Insert => (KeyCode::Insert, KeyModifiers::NONE),
+            Key::F(n) => (KeyCode::F(n), KeyModifiers::NONE),
+            Key::Char('\n') => (KeyCode::Enter, KeyModifiers::NONE),
+            Key::Char('\t') => (KeyCode::Tab, KeyModifiers::NONE),
+            Key::Char(c) => (KeyCode::Char(c), KeyModifiers::NONE),
+            Key::Alt(c) => (KeyCode::Char(c), KeyModifiers::ALT),
+            Key::Ctrl(c) => (KeyCode::Char(c), KeyModifiers::CONTROL),
+            Key::Null => (KeyCode::Null, KeyModifiers::NONE),
+            Key::Esc => (KeyCode::Esc, KeyModifiers::NONE),
+            _ => return None,
+        };
+
+        return Some(TerminalKey::new(code, modifiers));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn roundtrip(key: Key, s: &str) {
+        let tk = TerminalKey::from_termion(key).expect("can convert");
+        assert_eq!(tk.to_string(), s);
+        assert_eq!(TerminalKey::from_str(s).unwrap(), tk);
+    }
+
+    #[test]
+    fn test_roundtrips() {
+        roundtrip(Key::Char('a'), "a");
+        roundtrip(Key::Char('A'), "A");
+        roundtrip(Key::Char(':'), ":");
+        roundtrip(Key::Char('<'), "<lt>");
+        roundtrip(Key::Char(' '), " ");
+        roundtrip(Key::Char('\n'), "<Enter>");
+        roundtrip(Key::Char('\t'), "<Tab>");
+        roundtrip(Key::Ctrl('w'), "<C-W>");
+        roundtrip(Key::Alt('j'), "<A-j>");
+        roundtrip(Key::Alt('J'), "<A-S-J>");
+        roundtrip(Key::Backspace, "<BS>");
+        roundtrip(Key::Esc, "<Esc>");
+        roundtrip(Key::Null, "<Nul>");
+        roundtrip(Key::BackTab, "<S-Tab>");
+        roundtrip(Key::F(5), "<F5>");
+        roundtrip(Key::Delete, "<Del>");
+        roundtrip(Key::Insert, "<Insert>");
+        roundtrip(Key::ShiftLeft, "<S-Left>");
+        roundtrip(Key::AltUp, "<A-Up>");
+        roundtrip(Key::CtrlEnd, "<C-End>");
+        roundtrip(Key::PageDown, "<PageDown>");
+    }
+}
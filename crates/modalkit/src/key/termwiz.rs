@@ -0,0 +1,159 @@
+use ::termwiz::input::{KeyCode as WizKeyCode, KeyEvent, Modifiers};
+use crossterm::event::{KeyCode, KeyModifiers, MediaKeyCode};
+
+use super::TerminalKey;
+
+fn convert_modifiers(mods: Modifiers) -> KeyModifiers {
+    let mut res = KeyModifiers::NONE;
+
+    if mods.contains(Modifiers::SHIFT) {
+        res |= KeyModifiers::SHIFT;
+    }
+
+    if mods.contains(Modifiers::CTRL) {
+        res |= KeyModifiers::CONTROL;
+    }
+
+    if mods.contains(Modifiers::ALT) {
+        res |= KeyModifiers::ALT;
+    }
+
+    if mods.contains(Modifiers::SUPER) {
+        res |= KeyModifiers::SUPER;
+    }
+
+    return res;
+}
+
+fn convert_code(code: WizKeyCode) -> Option<KeyCode> {
+    let code = match code {
+        WizKeyCode::Char(c) => KeyCode::Char(c),
+
+        // Named keys.
+        WizKeyCode::Backspace => KeyCode::Backspace,
+        WizKeyCode::Tab => KeyCode::Tab,
+        WizKeyCode::Enter => KeyCode::Enter,
+        WizKeyCode::Escape => KeyCode::Esc,
+        WizKeyCode::Menu => KeyCode::Menu,
+        WizKeyCode::Pause => KeyCode::Pause,
+        WizKeyCode::CapsLock => KeyCode::CapsLock,
+        WizKeyCode::NumLock => KeyCode::NumLock,
+        WizKeyCode::ScrollLock => KeyCode::ScrollLock,
+        WizKeyCode::PrintScreen => KeyCode::PrintScreen,
+        WizKeyCode::Insert => KeyCode::Insert,
+        WizKeyCode::Delete => KeyCode::Delete,
+        WizKeyCode::Function(n) => KeyCode::F(n),
+
+        // Movement keys, including their keypad and application mode variants.
+        WizKeyCode::PageUp | WizKeyCode::KeyPadPageUp => KeyCode::PageUp,
+        WizKeyCode::PageDown | WizKeyCode::KeyPadPageDown => KeyCode::PageDown,
+        WizKeyCode::End | WizKeyCode::KeyPadEnd => KeyCode::End,
+        WizKeyCode::Home | WizKeyCode::KeyPadHome => KeyCode::Home,
+        WizKeyCode::LeftArrow | WizKeyCode::ApplicationLeftArrow => KeyCode::Left,
+        WizKeyCode::RightArrow | WizKeyCode::ApplicationRightArrow => KeyCode::Right,
+        WizKeyCode::UpArrow | WizKeyCode::ApplicationUpArrow => KeyCode::Up,
+        WizKeyCode::DownArrow | WizKeyCode::ApplicationDownArrow => KeyCode::Down,
+        WizKeyCode::KeyPadBegin => KeyCode::KeypadBegin,
+
+        // The keypad types the same characters as the rest of the keyboard.
+        WizKeyCode::Numpad0 => KeyCode::Char('0'),
+        WizKeyCode::Numpad1 => KeyCode::Char('1'),
+        WizKeyCode::Numpad2 => KeyCode::Char('2'),
+        WizKeyCode::Numpad3 => KeyCode::Char('3'),
+        WizKeyCode::Numpad4 => KeyCode::Char('4'),
+        WizKeyCode::Numpad5 => KeyCode::Char('5'),
+        WizKeyCode::Numpad6 => KeyCode::Char('6'),
+        WizKeyCode::Numpad7 => KeyCode::Char('7'),
+        WizKeyCode::Numpad8 => KeyCode::Char('8'),
+        WizKeyCode::Numpad9 => KeyCode::Char('9'),
+        WizKeyCode::Multiply => KeyCode::Char('*'),
+        WizKeyCode::Add => KeyCode::Char('+'),
+        WizKeyCode::Separator => KeyCode::Char(','),
+        WizKeyCode::Subtract => KeyCode::Char('-'),
+        WizKeyCode::Decimal => KeyCode::Char('.'),
+        WizKeyCode::Divide => KeyCode::Char('/'),
+
+        // Media keys.
+        WizKeyCode::MediaPlayPause => KeyCode::Media(MediaKeyCode::PlayPause),
+        WizKeyCode::MediaStop => KeyCode::Media(MediaKeyCode::Stop),
+        WizKeyCode::MediaNextTrack => KeyCode::Media(MediaKeyCode::TrackNext),
+        WizKeyCode::MediaPrevTrack => KeyCode::Media(MediaKeyCode::TrackPrevious),
+        WizKeyCode::VolumeDown => KeyCode::Media(MediaKeyCode::LowerVolume),
+        WizKeyCode::VolumeUp => KeyCode::Media(MediaKeyCode::RaiseVolume),
+        WizKeyCode::VolumeMute => KeyCode::Media(MediaKeyCode::MuteVolume),
+
+        // Everything else, including modifier keys pressed on their own, has no equivalent.
+        _ => return None,
+    };
+
+    return Some(code);
+}
+
+impl TerminalKey {
+    /// Convert a key event read using [termwiz].
+    ///
+    /// This returns [None] for keys that have no equivalent here, like the browser keys, and for
+    /// presses of modifier keys on their own.
+    pub fn from_termwiz(ke: &KeyEvent) -> Option<Self> {
+        let mut code = convert_code(ke.key)?;
+        let modifiers = convert_modifiers(ke.modifiers);
+
+        if code == KeyCode::Tab && modifiers.contains(KeyModifiers::SHIFT) {
+            // Represent <S-Tab> the same way as crossterm.
+            code = KeyCode::BackTab;
+        }
+
+        return Some(TerminalKey::new(code, modifiers));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn wiz(key: WizKeyCode, modifiers: Modifiers) -> Option<TerminalKey> {
+        TerminalKey::from_termwiz(&KeyEvent { key, modifiers })
+    }
+
+    fn roundtrip(key: WizKeyCode, modifiers: Modifiers, s: &str) {
+        let tk = wiz(key, modifiers).expect("can convert");
+        assert_eq!(tk.to_string(), s);
+        assert_eq!(TerminalKey::from_str(s).unwrap(), tk);
+    }
+
+    #[test]
+    fn test_roundtrips() {
+        let none = Modifiers::NONE;
+
+        roundtrip(WizKeyCode::Char('a'), none, "a");
+        roundtrip(WizKeyCode::Char('A'), Modifiers::SHIFT, "A");
+        roundtrip(WizKeyCode::Char(':'), Modifiers::SHIFT, ":");
+        roundtrip(WizKeyCode::Char('<'), none, "<lt>");
+        roundtrip(WizKeyCode::Char('w'), Modifiers::CTRL, "<C-W>");
+        roundtrip(WizKeyCode::Char('j'), Modifiers::ALT, "<A-j>");
+        roundtrip(WizKeyCode::Char('s'), Modifiers::SUPER, "<D-s>");
+        roundtrip(WizKeyCode::Enter, none, "<Enter>");
+        roundtrip(WizKeyCode::Enter, Modifiers::CTRL, "<C-Enter>");
+        roundtrip(WizKeyCode::Tab, Modifiers::SHIFT, "<S-Tab>");
+        roundtrip(WizKeyCode::Escape, none, "<Esc>");
+        roundtrip(WizKeyCode::Backspace, none, "<BS>");
+        roundtrip(WizKeyCode::Function(12), none, "<F12>");
+        roundtrip(WizKeyCode::ApplicationLeftArrow, Modifiers::SHIFT, "<S-Left>");
+        roundtrip(WizKeyCode::KeyPadHome, none, "<Home>");
+        roundtrip(WizKeyCode::Numpad7, none, "7");
+        roundtrip(WizKeyCode::MediaPlayPause, none, "<MediaPlayPause>");
+
+        // Position-specific modifiers are ignored.
+        let mods = Modifiers::CTRL | Modifiers::LEFT_CTRL;
+        roundtrip(WizKeyCode::Char('x'), mods, "<C-X>");
+    }
+
+    #[test]
+    fn test_unconvertible() {
+        assert_eq!(wiz(WizKeyCode::BrowserBack, Modifiers::NONE), None);
+        assert_eq!(wiz(WizKeyCode::Help, Modifiers::NONE), None);
+        assert_eq!(wiz(WizKeyCode::LeftShift, Modifiers::SHIFT), None);
+        assert_eq!(wiz(WizKeyCode::Hyper, Modifiers::NONE), None);
+    }
+}
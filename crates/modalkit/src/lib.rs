@@ -5,8 +5,9 @@
 //! This crate allows building terminal applications that support modal input, such as
 //! the Vim text editor.
 //!
-//! The [mod@env] module contains keybindings for Vim and Emacs, which can be used along with
-//! [key::TerminalKey] in the terminal to generate [actions] that can be used to drive [editing].
+//! The [mod@env] module contains keybindings for Vim, Emacs, Kakoune and Helix, which can be used
+//! along with [key::TerminalKey] in the terminal to generate [actions] that can be used to drive
+//! [editing].
 //!
 //! ## Examples
 //!
@@ -2,7 +2,8 @@
 //!
 //! ## Overview
 //!
-//! These types are used to specify the details of [actions].
+//! These types are used to specify the details of [actions]. When the `serde` feature is enabled,
+//! they can be serialized using the format described in [actions].
 //!
 //! [actions]: crate::actions
 use std::fmt::{self, Debug, Display, Formatter};
@@ -28,6 +29,7 @@ use crate::{
 
 /// Specify how to change the case of a string.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Case {
     /// Make the targeted text uppercase.
     Upper,
@@ -42,8 +44,77 @@ pub enum Case {
     Toggle,
 }
 
+/// Specify how to change the delimiters around the targeted text.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum SurroundChange {
+    /// Wrap the targeted text in a new pair of delimiters.
+    Add,
+
+    /// Remove the delimiters at either end of the targeted text.
+    Delete,
+
+    /// Replace the delimiters at either end of the targeted text with a new pair.
+    Replace,
+}
+
+/// Specify how to change the text within a rectangle.
+///
+/// The targeted text is always treated as [blockwise](TargetShape::BlockWise), and lines that end
+/// before the rectangle's left column are left unchanged.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum RectangleChange {
+    /// Insert whitespace the width of the rectangle, pushing its contents to the right.
+    ///
+    /// For example, `C-x r o` in Emacs.
+    Open,
+
+    /// Replace the contents of the rectangle on each line with the text in the
+    /// [register](EditContext::get_register).
+    ///
+    /// For example, `C-x r t` in Emacs.
+    Fill,
+
+    /// Replace the contents of the rectangle with whitespace.
+    ///
+    /// For example, `C-x r c` in Emacs.
+    Clear,
+
+    /// Insert increasing line numbers, starting from 1, at the rectangle's left column.
+    ///
+    /// For example, `C-x r N` in Emacs.
+    Number,
+}
+
+/// The kind of text object to swap when [transposing](crate::actions::EditAction::Transpose).
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TransposeStyle {
+    /// Swap single characters.
+    ///
+    /// For example, `C-t` in Emacs.
+    Char,
+
+    /// Swap runs of characters that match the given [WordStyle].
+    ///
+    /// For example, `M-t` in Emacs.
+    Word(WordStyle),
+
+    /// Swap whole lines.
+    ///
+    /// For example, `C-x C-t` in Emacs.
+    Line,
+
+    /// Swap balanced bracket groups or runs of non-blank, non-bracket characters.
+    ///
+    /// For example, `C-M-t` in Emacs.
+    Sexp,
+}
+
 /// Specify how to join lines together.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum JoinStyle {
     /// Leave whitespace around the join point as-is.
     NoChange,
@@ -58,6 +129,7 @@ pub enum JoinStyle {
 
 /// Specify how to insert register contents into a buffer.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum PasteStyle {
     /// Paste text before the cursor.
     Cursor,
@@ -67,10 +139,17 @@ pub enum PasteStyle {
 
     /// Replace selected text with register contents.
     Replace,
+
+    /// Replace the most recently pasted text with an older ([MoveDir1D::Previous]) or newer
+    /// ([MoveDir1D::Next]) entry from [Register::KillRing].
+    ///
+    /// For example, `M-y` in Emacs.
+    Cycle(MoveDir1D),
 }
 
 /// The source to search for completion candidates.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CompletionScope {
     /// Only use completion candidates from the current buffer.
     Buffer,
@@ -81,6 +160,7 @@ pub enum CompletionScope {
 
 /// What type of phrase we are completing.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CompletionSelection {
     /// Navigate through the list of completion candidates.
     List(MoveDir1D),
@@ -97,6 +177,7 @@ pub enum CompletionSelection {
 
 /// What type of phrase we are completing.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CompletionType {
     /// Determine what to complete by the buffer context.
     Auto,
@@ -113,6 +194,7 @@ pub enum CompletionType {
 
 /// How to display completion candidates.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CompletionDisplay {
     /// Don't display candidates.
     None,
@@ -126,6 +208,7 @@ pub enum CompletionDisplay {
 
 /// Specify what is targeted by an editing action.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum EditTarget {
     /// Move to one of the sides of a range.
@@ -188,6 +271,7 @@ impl From<RangeType> for EditTarget {
 
 /// Determines where to leave the cursor after editing text.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CursorEnd {
     /// Keep the current cursor position as best as possible.
     Keep,
@@ -207,6 +291,7 @@ pub enum CursorEnd {
 
 /// Description of a textual range within a buffer.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct EditRange<Cursor> {
     /// The start of the range.
     pub start: Cursor,
@@ -243,6 +328,7 @@ impl<Cursor: Ord> EditRange<Cursor> {
 
 /// Different action sequences that can be repeated.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum RepeatType {
     /// A sequence of changes made to a buffer.
     EditSequence,
@@ -256,6 +342,7 @@ pub enum RepeatType {
 
 /// Specify a range within the text around the current cursor position.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum SearchType {
     /// Search for the character indicated by [EditContext::get_search_char].
     ///
@@ -297,6 +384,7 @@ impl SearchType {
 
 /// The different ways of grouping a buffer's contents into words.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum WordStyle {
     /// A run of alphanumeric characters.
     AlphaNum,
@@ -307,6 +395,9 @@ pub enum WordStyle {
     Big,
 
     /// A sequence of characters that match a test function.
+    ///
+    /// Functions can't be serialized, so this variant is skipped when using `serde`.
+    #[cfg_attr(feature = "serde", serde(skip))]
     CharSet(fn(char) -> bool),
 
     /// A name of a directory or file.
@@ -573,6 +664,7 @@ impl BoundaryTest for WordStyle {
 
 /// Specify the base for a number.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Radix {
     /// A base 2 number.
     Binary,
@@ -642,6 +734,7 @@ pub trait BoundaryTest {
 
 /// Specify a range within the text around the current cursor position.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum RangeType {
     /// Select from the beginning to the end of a [word](WordStyle).
@@ -682,6 +775,7 @@ pub enum RangeType {
 
 /// Specify a movement away from the current cursor position.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum MoveType {
     /// Move to a line at a position relative to the buffer.
@@ -707,6 +801,10 @@ pub enum MoveType {
     /// Move to the first word [*n* lines](Count) away in [MoveDir1D] direction.
     FirstWord(MoveDir1D),
 
+    /// Move to the start of the next fold, or the end of the previous fold, [*n* times](Count)
+    /// in [MoveDir1D] direction.
+    Fold(MoveDir1D),
+
     /// Move to the matching character of the next item.
     ///
     /// Items are characters like `(`/`)`, `[`/`]`, `{`/`}`, and so on.
@@ -724,6 +822,18 @@ pub enum MoveType {
     /// Move to a column at a position relative to the current line.
     LinePos(MovePosition),
 
+    /// Move into the next list, to just after its opening bracket, or into the previous list,
+    /// to just before its closing bracket, [*n* times](Count) in [MoveDir1D] direction.
+    ListDown(MoveDir1D),
+
+    /// Move out of the enclosing list [*n* times](Count), to just after its closing bracket
+    /// ([MoveDir1D::Next]) or to its opening bracket ([MoveDir1D::Previous]).
+    ListUp(MoveDir1D),
+
+    /// Move to the start of the [*n*<sup>th</sup>](Count) misspelled word in [MoveDir1D]
+    /// direction, wrapping around the buffer.
+    Misspelling(MoveDir1D),
+
     /// Move to the beginning of a word [*n* times](Count) in [MoveDir1D] direction.
     WordBegin(WordStyle, MoveDir1D),
 
@@ -742,6 +852,12 @@ pub enum MoveType {
     /// Move to the end of a section [*n* times](Count) in [MoveDir1D] direction.
     SectionEnd(MoveDir1D),
 
+    /// Move to the end of the next balanced expression, or the beginning of the previous one,
+    /// [*n* times](Count) in [MoveDir1D] direction.
+    ///
+    /// Bracketed groups and strings are treated as single expressions.
+    Sexp(MoveDir1D),
+
     /// Move to the first word of a screen line [*n* times](Count) away in [MoveDir1D] direction.
     ScreenFirstWord(MoveDir1D),
 
@@ -757,6 +873,7 @@ pub enum MoveType {
 
 /// Represent movement along a 1-dimensional line.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum MoveDir1D {
     /// Move backwards, or to a previous point.
     Previous,
@@ -767,6 +884,7 @@ pub enum MoveDir1D {
 
 /// Represent movement along the horizontal or vertical axes.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum MoveDir2D {
     /// Move leftwards.
     Left,
@@ -783,6 +901,7 @@ pub enum MoveDir2D {
 
 /// Represents the two sides of a range that has no meaningful middle.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum MoveTerminus {
     /// The beginning of a range.
     Beginning,
@@ -793,6 +912,7 @@ pub enum MoveTerminus {
 
 /// Represent movement to a position along a 1-dimensional line.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum MovePosition {
     /// Move to the beginning of some range.
     Beginning,
@@ -806,6 +926,7 @@ pub enum MovePosition {
 
 /// Represents a modification of a previous [MoveDir1D] movement.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum MoveDirMod {
     /// Use the same movement previously used.
     Same,
@@ -819,6 +940,7 @@ pub enum MoveDirMod {
 
 /// This represents a selection of a 2-dimensional axis.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Axis {
     /// The horizontal axis.
     Horizontal,
@@ -839,6 +961,7 @@ impl Axis {
 
 /// This represents the units used when scrolling.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum ScrollSize {
     /// Scroll by number of character cells.
     Cell,
@@ -852,6 +975,7 @@ pub enum ScrollSize {
 
 /// This represents the way in which the viewport should be scrolled.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum ScrollStyle {
     /// Scroll the viewport in [MoveDir2D] direction by [ScrollSize] units, [*n* times](Count).
     Direction2D(MoveDir2D, ScrollSize, Count),
@@ -866,6 +990,7 @@ pub enum ScrollStyle {
 /// Place the cursor at a specified position in a visual selection, with the anchor now at the
 /// opposite end.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum SelectionCursorChange {
     /// Place the cursor in the first line of the selection, in the first column of the selection.
     Beginning,
@@ -882,6 +1007,7 @@ pub enum SelectionCursorChange {
 
 /// This represents what UI element is targeted during an Action.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum FocusChange {
     /// Target the currently focused UI element.
     Current,
@@ -910,6 +1036,7 @@ pub enum FocusChange {
 
 /// This represents how to change the size of a window.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum SizeChange<I = Count> {
     /// Make the window and others along the specified axis the same size.
     Equal,
@@ -926,6 +1053,7 @@ pub enum SizeChange<I = Count> {
 
 /// This represents how to change the indentation of a range.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum IndentChange<I = Count> {
     /// Automatically determine indentation level.
     Auto,
@@ -939,6 +1067,7 @@ pub enum IndentChange<I = Count> {
 
 /// This represents how to change a number in text.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum NumberChange {
     /// Decrease the first number in the targeted text by [*n*](Count).
     Decrease(Count),
@@ -952,6 +1081,7 @@ pub enum NumberChange {
 /// [WindowAction::Open]: crate::actions::WindowAction::Open
 /// [WindowAction::Switch]: crate::actions::WindowAction::Switch
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum OpenTarget<W: ApplicationWindowId> {
     /// An alternate window. This is usually the previous window.
     Alternate,
@@ -983,6 +1113,7 @@ pub enum OpenTarget<W: ApplicationWindowId> {
 
 /// This represents what tabs are targeted by a tab command.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum TabTarget {
     /// Close the tab targeted by FocusChange.
     Single(FocusChange),
@@ -996,6 +1127,7 @@ pub enum TabTarget {
 
 /// This represents what windows are targeted by a window command.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum WindowTarget {
     /// Close the window targeted by [FocusChange].
     Single(FocusChange),
@@ -1009,6 +1141,7 @@ pub enum WindowTarget {
 
 /// Target cursors in a cursor group.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CursorCloseTarget {
     /// Target the cursor group's leader.
     Leader,
@@ -1019,6 +1152,7 @@ pub enum CursorCloseTarget {
 
 /// Ways to combine a newer cursor group with an already existing one.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CursorGroupCombineStyle {
     /// Use all of the selections from both groups.
     Append,
@@ -1040,6 +1174,7 @@ impl From<CursorMergeStyle> for CursorGroupCombineStyle {
 
 /// Ways to combine two selections.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum CursorMergeStyle {
     /// Merge the two selections to form one long selection.
     Union,
@@ -1059,6 +1194,7 @@ pub enum CursorMergeStyle {
 
 /// This represents how to determine what count argument should be applied to an action.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Count {
     /// Use the count provided by the user, or 1 if one was not given.
     Contextual,
@@ -1076,6 +1212,7 @@ impl From<usize> for Count {
 
 /// Saved cursor positions.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Mark {
     /// The position of the cursor in the current buffer when it last exited.
     ///
@@ -1134,6 +1271,11 @@ pub enum Mark {
     ///
     /// For example, `']` in Vim.
     LastYankedEnd,
+
+    /// The cursor position where the search currently being typed started.
+    ///
+    /// For example, where `C-s` was typed to start an isearch in Emacs.
+    SearchStart,
 }
 
 impl Mark {
@@ -1152,12 +1294,14 @@ impl Mark {
             Mark::VisualEnd => false,
             Mark::LastYankedBegin => false,
             Mark::LastYankedEnd => false,
+            Mark::SearchStart => false,
         }
     }
 }
 
 /// A value that may not be known now, but is present in the context.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Specifier<T> {
     /// Look for a value of `T` in the [EditContext].
     #[default]
@@ -1176,6 +1320,7 @@ impl<T> From<T> for Specifier<T> {
 bitflags! {
     /// These flags are used to specify the behaviour while writing a window.
     #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
     pub struct WriteFlags: u32 {
         /// No flags set.
         const NONE = 0b00000000;
@@ -1188,6 +1333,7 @@ bitflags! {
 bitflags! {
     /// These flags are used to specify the behaviour while writing a window.
     #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
     pub struct OpenFlags: u32 {
         /// No flags set.
         const NONE = 0b00000000;
@@ -1203,6 +1349,7 @@ bitflags! {
 bitflags! {
     /// These flags are used to specify the behaviour while closing a window.
     #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
     pub struct CloseFlags: u32 {
         /// No flags set.
         const NONE = 0b00000000;
@@ -1226,6 +1373,7 @@ bitflags! {
 
 /// Different ways to expand or trim selections.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum SelectionBoundary {
     /// A selection that starts at the beginning of a line and ends on a newline.
@@ -1264,6 +1412,7 @@ impl BoundaryTest for SelectionBoundary {
 
 /// Different ways to split existing selections into new ones.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum SelectionSplitStyle {
     /// Split a selection into two [TargetShape::CharWise] selections, one at the current cursor
@@ -1286,6 +1435,7 @@ pub enum SelectionSplitStyle {
 
 /// Different ways to change the boundaries of a visual selection.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum SelectionResizeStyle {
     /// Extend (or possibly shrink) the selection by moving the cursor.
@@ -1303,16 +1453,22 @@ pub enum SelectionResizeStyle {
 
 /// When focusing on the command bar, this is the type of command that should be submitted.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[non_exhaustive]
 pub enum CommandType {
     /// Prompt the user for a command.
     Command,
 
     /// Prompt the user for a search query.
     Search,
+
+    /// Prompt the user for text that gets used by another action, like a replacement string.
+    Prompt,
 }
 
 /// This specifies which list of cursors to use when jumping, the change list or the jump list.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum PositionList {
     /// The change list contains positions where changes were previously made.
     ChangeList,
@@ -1322,8 +1478,25 @@ pub enum PositionList {
     JumpList,
 }
 
+/// This specifies which ring of previously set marks to use.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum MarkRing {
+    /// The ring of marks previously set in the current buffer.
+    ///
+    /// For example, `C-u C-SPC` in Emacs.
+    Buffer,
+
+    /// The ring of marks previously set across all buffers, keeping only the most recent one
+    /// set in each buffer visited.
+    ///
+    /// For example, `C-x C-SPC` in Emacs.
+    Global,
+}
+
 /// This specifies the behaviour of entering and backspacing over characters.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum InsertStyle {
     /// This specifies that typed characters should leave existing ones as is, and backspacing
     /// should remove characters.
@@ -1336,6 +1509,7 @@ pub enum InsertStyle {
 
 /// A character.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Char {
     /// An exact character.
     Single(char),
@@ -1355,6 +1529,7 @@ impl From<char> for Char {
 
 /// Locations for temporarily storing text shared between buffers.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[non_exhaustive]
 pub enum Register {
     /// The default register.
@@ -1398,6 +1573,30 @@ pub enum Register {
     /// For eample, `"0` in Vim.
     LastYanked,
 
+    /// A ring of recently killed and copied text.
+    ///
+    /// Reading this register returns the entry at the ring's yank pointer, which can be moved with
+    /// [PasteStyle::Cycle].
+    ///
+    /// For example, the `kill-ring` in Emacs.
+    KillRing,
+
+    /// The most recently killed or copied rectangle.
+    ///
+    /// For example, `killed-rectangle` in Emacs.
+    KilledRectangle,
+
+    /// A ring of recently recorded macros.
+    ///
+    /// Reading this register returns the macro at the front of the ring, and recording into it
+    /// adds a new entry to the front, or extends it when appending. The ring can be rotated with
+    /// [MacroAction::Rotate].
+    ///
+    /// For example, the `kmacro-ring` in Emacs.
+    ///
+    /// [MacroAction::Rotate]: crate::actions::MacroAction::Rotate
+    MacroRing,
+
     /// A register named by `char`.
     ///
     /// The index of the most recent deletion is 0, the second most recent deletion is 1, and so
@@ -1448,6 +1647,9 @@ impl Register {
             Register::LastCommand(_) => false,
             Register::LastInserted => false,
             Register::LastYanked => false,
+            Register::KillRing => false,
+            Register::KilledRectangle => false,
+            Register::MacroRing => false,
             Register::AltBufName => false,
             Register::CurBufName => false,
             Register::SelectionPrimary => false,
@@ -1458,6 +1660,7 @@ impl Register {
 
 /// This specifies either the shape of a visual selection, or a forced motion.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum TargetShape {
     /// A series of characters.
     ///
@@ -1478,6 +1681,7 @@ pub enum TargetShape {
 bitflags! {
     /// Bitmask that specifies what shapes are targeted by an action.
     #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
     pub struct TargetShapeFilter: u32 {
         /// Match no shapes.
         const NONE = 0b00000000;
@@ -1509,6 +1713,7 @@ impl TargetShapeFilter {
 
 /// Methods for determining the start and end of a [RangeSpec].
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum RangeEndingType {
     /// A specific line number.
     Absolute(Count),
@@ -1538,6 +1743,7 @@ pub enum RangeEndingType {
 /// Modifier to a range ending.
 #[non_exhaustive]
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum RangeEndingModifier {
     /// Offset the end of a range by [*n*](Count) lines.
     Offset(MoveDir1D, Count),
@@ -1545,10 +1751,12 @@ pub enum RangeEndingModifier {
 
 /// One of the sides of a range.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct RangeEnding(pub RangeEndingType, pub Vec<RangeEndingModifier>);
 
 /// Position to begin a search in a range.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum RangeSearchInit {
     /// Start from current cursor position.
     Cursor,
@@ -1559,6 +1767,7 @@ pub enum RangeSearchInit {
 
 /// A range specification.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum RangeSpec {
     /// A range specification where only one end of the range was given.
     Single(RangeEnding),
@@ -1772,9 +1981,13 @@ impl MoveType {
             MoveType::BufferLinePercent => false,
             MoveType::Column(_, _) => false,
             MoveType::FirstWord(_) => false,
+            MoveType::Fold(_) => false,
             MoveType::Line(_) => false,
             MoveType::LinePercent => false,
             MoveType::LinePos(_) => false,
+            MoveType::ListDown(_) => false,
+            MoveType::ListUp(_) => false,
+            MoveType::Misspelling(_) => false,
             MoveType::ParagraphBegin(_) => false,
             MoveType::ScreenFirstWord(_) => false,
             MoveType::ScreenLine(_) => false,
@@ -1783,6 +1996,7 @@ impl MoveType {
             MoveType::SectionBegin(_) => false,
             MoveType::SectionEnd(_) => false,
             MoveType::SentenceBegin(_) => false,
+            MoveType::Sexp(_) => false,
             MoveType::WordBegin(_, _) => false,
         }
     }
@@ -1804,14 +2018,19 @@ impl MoveType {
             MoveType::Column(_, _) => false,
             MoveType::FinalNonBlank(_) => false,
             MoveType::FirstWord(_) => false,
+            MoveType::Fold(_) => false,
             MoveType::LineColumnOffset => false,
             MoveType::Line(_) => false,
             MoveType::LinePercent => false,
             MoveType::LinePos(_) => false,
+            MoveType::ListDown(_) => false,
+            MoveType::ListUp(_) => false,
+            MoveType::Misspelling(_) => false,
             MoveType::ScreenFirstWord(_) => false,
             MoveType::ScreenLine(_) => false,
             MoveType::ScreenLinePos(_) => false,
             MoveType::SectionEnd(_) => false,
+            MoveType::Sexp(_) => false,
             MoveType::WordBegin(_, _) => false,
             MoveType::WordEnd(_, _) => false,
         }
@@ -1824,6 +2043,7 @@ impl MoveType {
             MoveType::BufferLinePercent => TargetShape::LineWise,
             MoveType::BufferPos(_) => TargetShape::LineWise,
             MoveType::FirstWord(_) => TargetShape::LineWise,
+            MoveType::Fold(_) => TargetShape::LineWise,
             MoveType::Line(_) => TargetShape::LineWise,
             MoveType::ViewportPos(_) => TargetShape::LineWise,
             MoveType::SectionBegin(_) => TargetShape::LineWise,
@@ -1836,11 +2056,15 @@ impl MoveType {
             MoveType::LineColumnOffset => TargetShape::CharWise,
             MoveType::LinePercent => TargetShape::CharWise,
             MoveType::LinePos(_) => TargetShape::CharWise,
+            MoveType::ListDown(_) => TargetShape::CharWise,
+            MoveType::ListUp(_) => TargetShape::CharWise,
+            MoveType::Misspelling(_) => TargetShape::CharWise,
             MoveType::ParagraphBegin(_) => TargetShape::CharWise,
             MoveType::ScreenFirstWord(_) => TargetShape::CharWise,
             MoveType::ScreenLinePos(_) => TargetShape::CharWise,
             MoveType::ScreenLine(_) => TargetShape::CharWise,
             MoveType::SentenceBegin(_) => TargetShape::CharWise,
+            MoveType::Sexp(_) => TargetShape::CharWise,
             MoveType::WordBegin(_, _) => TargetShape::CharWise,
             MoveType::WordEnd(_, _) => TargetShape::CharWise,
         }
@@ -1866,6 +2090,7 @@ impl From<MoveDir1D> for MoveDirMod {
 
 /// Information to show the user at the bottom of the screen after an action.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum InfoMessage {
     /// Print a simple, informational message on the status line.
     Message(String),
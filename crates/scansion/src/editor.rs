@@ -276,6 +276,10 @@ where
         self.buffer.set_text(text)
     }
 
+    pub fn get_cursor_word(&self, style: &WordStyle) -> Option<String> {
+        self.buffer.get_cursor_word(self.gid, style)
+    }
+
     pub fn redraw(
         &mut self,
         prompt: Option<&str>,
@@ -60,7 +60,14 @@ use regex::Regex;
 
 use crossterm::{
     cursor::{Hide as CursorHide, MoveTo, Show as CursorShow},
-    event::{poll, read, Event},
+    event::{
+        poll,
+        read,
+        Event,
+        KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
+    },
     style::Print,
     terminal::{Clear, ClearType},
     QueueableCommand,
@@ -111,8 +118,8 @@ enum InternalResult<I: ApplicationInfo> {
 macro_rules! focused_mut {
     ($s: expr) => {
         match &$s.ct {
-            Some(CommandType::Command) => &mut $s.cmd,
             Some(CommandType::Search) => &mut $s.search,
+            Some(_) => &mut $s.cmd,
             None => &mut $s.line,
         }
     };
@@ -191,6 +198,7 @@ where
 
     context: EditorContext,
     dimensions: (u16, u16),
+    enhanced: Option<bool>,
 
     ct: Option<CommandType>,
     act: Option<(Action<I>, EditContext)>,
@@ -239,6 +247,7 @@ where
 
             context,
             dimensions,
+            enhanced: None,
 
             ct: None,
             act: None,
@@ -252,7 +261,7 @@ where
 
     /// Prompt the user for input.
     pub fn readline(&mut self, prompt: Option<String>) -> ReadLineResult<I> {
-        crossterm::terminal::enable_raw_mode()?;
+        self.raw_mode_enable()?;
 
         self.init()?;
 
@@ -271,14 +280,14 @@ where
                     Ok(InternalResult::Submitted(res)) => {
                         self.linebreak()?;
 
-                        crossterm::terminal::disable_raw_mode()?;
+                        self.raw_mode_disable()?;
 
                         return Ok(res.to_string());
                     },
                     Err(e) => {
                         self.linebreak()?;
 
-                        crossterm::terminal::disable_raw_mode()?;
+                        self.raw_mode_disable()?;
 
                         return Err(e);
                     },
@@ -287,6 +296,30 @@ where
         }
     }
 
+    fn raw_mode_enable(&mut self) -> Result<(), io::Error> {
+        crossterm::terminal::enable_raw_mode()?;
+
+        let enhanced = *self.enhanced.get_or_insert_with(|| {
+            crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false)
+        });
+
+        if enhanced {
+            // Ask for disambiguated keypresses, so that keys like <C-Enter> can be mapped.
+            let flags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES;
+            self.context.stdout.queue(PushKeyboardEnhancementFlags(flags))?.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn raw_mode_disable(&mut self) -> Result<(), io::Error> {
+        if self.enhanced == Some(true) {
+            self.context.stdout.queue(PopKeyboardEnhancementFlags)?.flush()?;
+        }
+
+        crossterm::terminal::disable_raw_mode()
+    }
+
     fn action_prepend(&mut self, acts: Vec<(Action<I>, EditContext)>) {
         let mut acts = VecDeque::from(acts);
         acts.append(&mut self.actstack);
@@ -311,7 +344,9 @@ where
 
             match read()? {
                 Event::Key(ke) => {
-                    return Ok(ke.into());
+                    if let Some(key) = TerminalKey::from_event(ke) {
+                        return Ok(key);
+                    }
                 },
                 Event::FocusGained | Event::FocusLost => {
                     // Do nothing for now.
@@ -335,7 +370,7 @@ where
 
     fn suspend(&mut self) -> Result<InternalResult<I>, ReadLineError<I>> {
         // Restore old terminal state.
-        crossterm::terminal::disable_raw_mode()?;
+        self.raw_mode_disable()?;
         self.context.stdout.queue(CursorShow)?.flush()?;
 
         // Send SIGTSTP to process.
@@ -347,7 +382,7 @@ where
         }
 
         // Restore application terminal state.
-        crossterm::terminal::enable_raw_mode()?;
+        self.raw_mode_enable()?;
         self.init()?;
 
         Ok(InternalResult::Nothing)
@@ -398,6 +433,21 @@ where
             PromptAction::Recall(dir, count, prefixed) => {
                 self.recall(dir, ctx.resolve(&count), prefixed);
 
+                Ok(InternalResult::Nothing)
+            },
+            PromptAction::InsertCursorWord(style) => {
+                if self.ct.is_none() {
+                    return Ok(InternalResult::Nothing);
+                }
+
+                if let Some(word) = self.line.get_cursor_word(&style) {
+                    let act = InsertTextAction::Transcribe(word, MoveDir1D::Previous, 1.into());
+                    let _ = self.cmd.editor_command(&act.into(), &ctx, &mut self.store)?;
+
+                    // Perform an incremental search if we need to.
+                    self.incsearch(&ctx)?;
+                }
+
                 Ok(InternalResult::Nothing)
             },
         }
@@ -700,6 +750,7 @@ where
             Action::Tab(_) => None,
             Action::Window(_) => None,
             Action::Scroll(_) => None,
+            Action::ClearSearchHighlight => None,
 
             // Handle non-exhaustive pattern.
             act => {